@@ -0,0 +1,118 @@
+//! AI provider implementations for the CLI
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::process::Stdio;
+use termbrain_core::ai::AiProvider;
+
+use crate::config::Config;
+
+/// Runs a user-configured provider command, feeding the prompt on stdin
+/// and reading the completion from stdout.
+pub struct SubprocessProvider {
+    command: String,
+}
+
+impl SubprocessProvider {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+
+    /// Builds the provider configured for this installation, or `None`
+    /// when AI features are disabled, shadow mode is active, or the
+    /// installation is offline. Provider commands may call remote APIs,
+    /// so offline mode refuses to construct one at all.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.shadow_mode || config.offline() {
+            return None;
+        }
+        config.ai_provider.as_ref().map(Self::new)
+    }
+}
+
+#[async_trait]
+impl AiProvider for SubprocessProvider {
+    fn name(&self) -> &str {
+        &self.command
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        let command = self.command.clone();
+        let prompt = prompt.to_string();
+
+        // Provider commands are synchronous CLIs; run them off the async
+        // runtime so a slow model doesn't block other tasks.
+        let output = tokio::task::spawn_blocking(move || -> Result<std::process::Output> {
+            let mut parts = command.split_whitespace();
+            let program = parts
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("AI provider command is empty"))?;
+
+            let mut child = std::process::Command::new(program)
+                .args(parts)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+
+            use std::io::Write;
+            if let Some(stdin) = child.stdin.as_mut() {
+                stdin.write_all(prompt.as_bytes())?;
+            }
+
+            Ok(child.wait_with_output()?)
+        })
+        .await??;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!(
+                "AI provider '{}' failed ({}): {}",
+                self.command,
+                output.status,
+                stderr.trim()
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_provider() -> Config {
+        Config {
+            ai_provider: Some("cat".to_string()),
+            offline: false,
+            shadow_mode: false,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_provider_constructed_when_configured() {
+        assert!(SubprocessProvider::from_config(&config_with_provider()).is_some());
+    }
+
+    #[test]
+    fn test_offline_mode_never_constructs_a_provider() {
+        let config = Config {
+            offline: true,
+            ..config_with_provider()
+        };
+        assert!(SubprocessProvider::from_config(&config).is_none());
+    }
+
+    #[test]
+    fn test_shadow_mode_never_constructs_a_provider() {
+        let config = Config {
+            shadow_mode: true,
+            ..config_with_provider()
+        };
+        assert!(SubprocessProvider::from_config(&config).is_none());
+    }
+}
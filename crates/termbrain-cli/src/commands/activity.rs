@@ -0,0 +1,137 @@
+//! Per-PR / per-commit activity view
+//!
+//! When a `git push` or `gh pr` command is recorded, local git metadata
+//! (HEAD sha, current branch) is captured into `activity_refs`;
+//! `tb activity <ref>` then lists the terminal work behind a PR or
+//! commit.
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::Row;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::github::{detect_git_activity, GitActivity};
+use termbrain_storage::sqlite::SqliteStorage;
+use uuid::Uuid;
+
+use crate::OutputFormat;
+
+use super::create_storage;
+
+/// Attaches PR/commit references to a just-recorded command. Git lookups
+/// are best-effort; a missing repo must never fail recording.
+pub(super) async fn enrich_git_activity(storage: &SqliteStorage, cmd: &Command) -> Result<()> {
+    let Some(activity) = detect_git_activity(cmd) else {
+        return Ok(());
+    };
+
+    let branch = git_metadata(&cmd.working_directory, &["rev-parse", "--abbrev-ref", "HEAD"]);
+    let (kind, reference) = match activity {
+        GitActivity::Push => {
+            let Some(sha) = git_metadata(&cmd.working_directory, &["rev-parse", "HEAD"]) else {
+                return Ok(());
+            };
+            ("commit", sha)
+        }
+        GitActivity::PrCreate => {
+            // No PR number exists locally yet; the head branch is the
+            // stable reference for later reports
+            let Some(branch) = branch.clone() else {
+                return Ok(());
+            };
+            ("pr", branch)
+        }
+        GitActivity::PrMerge { reference } => {
+            let Some(reference) = reference.or_else(|| branch.clone()) else {
+                return Ok(());
+            };
+            ("pr", reference)
+        }
+    };
+
+    sqlx::query(
+        "INSERT INTO activity_refs (id, command_id, kind, reference, branch, occurred_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(cmd.id.to_string())
+    .bind(kind)
+    .bind(&reference)
+    .bind(&branch)
+    .bind(Utc::now().to_rfc3339())
+    .execute(storage.pool())
+    .await?;
+
+    Ok(())
+}
+
+fn git_metadata(directory: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(directory)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Shows the commands recorded around one PR number, branch, or commit.
+pub async fn show_activity(reference: String, format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let rows = sqlx::query(
+        "SELECT a.kind, a.reference, a.branch, a.occurred_at, c.raw, c.exit_code \
+         FROM activity_refs a JOIN commands c ON c.id = a.command_id \
+         WHERE a.reference = ?1 OR a.branch = ?1 \
+         ORDER BY a.occurred_at",
+    )
+    .bind(&reference)
+    .fetch_all(storage.pool())
+    .await?;
+
+    if rows.is_empty() {
+        println!("No recorded activity references {}", reference);
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    serde_json::json!({
+                        "kind": row.get::<String, _>("kind"),
+                        "reference": row.get::<String, _>("reference"),
+                        "branch": row.get::<Option<String>, _>("branch"),
+                        "command": row.get::<String, _>("raw"),
+                        "exit_code": row.get::<i64, _>("exit_code"),
+                        "occurred_at": row.get::<String, _>("occurred_at"),
+                    })
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "reference": reference,
+                    "activity": entries,
+                }))?
+            );
+        }
+        _ => {
+            println!("🔀 Activity for {} ({} references):", reference, rows.len());
+            for row in rows {
+                println!(
+                    "   {} [{}] {}",
+                    row.get::<String, _>("occurred_at"),
+                    row.get::<String, _>("kind"),
+                    row.get::<String, _>("raw"),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Alert rule evaluation on custom metrics
+//!
+//! Rules live in the config next to the metrics they watch. `tb alerts
+//! check` evaluates them once; a future daemon mode will run the same
+//! evaluation on a schedule.
+
+use anyhow::Result;
+
+use crate::config::{AlertRule, Config};
+
+use super::{create_storage, evaluate_metrics, MetricValue};
+
+/// Evaluates all configured alert rules and delivers any that fire.
+pub async fn check_alerts() -> Result<()> {
+    let config = Config::load()?;
+
+    if config.alerts.is_empty() {
+        println!("No alert rules configured (add them under \"alerts\" in {})",
+            Config::config_file().display());
+        return Ok(());
+    }
+
+    let storage = create_storage().await?;
+    let metrics = evaluate_metrics(&storage, &config).await?;
+    let focused = super::focus::deep_focus_now().await;
+
+    let mut fired = 0;
+    for rule in &config.alerts {
+        let Some(metric) = metrics.iter().find(|m| m.name == rule.metric) else {
+            eprintln!("⚠️  Alert '{}' references unknown metric '{}'", rule.name, rule.metric);
+            continue;
+        };
+
+        if rule_fires(rule, metric)? {
+            fired += 1;
+            let message = format!(
+                "{}: {} is {} (threshold {} {})",
+                rule.name, metric.name, metric.value, rule.op, rule.threshold
+            );
+            println!("🔔 {}", message);
+            deliver(rule, &message, config.offline(), focused);
+        } else {
+            println!("✅ {} ({} = {})", rule.name, metric.name, metric.value);
+        }
+    }
+
+    if fired == 0 {
+        println!("\nAll {} alert rules are quiet", config.alerts.len());
+    }
+
+    Ok(())
+}
+
+fn rule_fires(rule: &AlertRule, metric: &MetricValue) -> Result<bool> {
+    match rule.op.as_str() {
+        ">" => Ok(metric.value > rule.threshold),
+        ">=" => Ok(metric.value >= rule.threshold),
+        "<" => Ok(metric.value < rule.threshold),
+        "<=" => Ok(metric.value <= rule.threshold),
+        "==" => Ok((metric.value - rule.threshold).abs() < f64::EPSILON),
+        other => Err(anyhow::anyhow!(
+            "Alert '{}' has unknown operator '{}' (expected >, >=, <, <=, ==)",
+            rule.name,
+            other
+        )),
+    }
+}
+
+/// Best-effort delivery: desktop notification when available, webhook
+/// when configured. Failures are reported but don't abort the check.
+/// Webhooks are skipped entirely in offline mode. During a focus
+/// session the desktop notification is queued for the next break
+/// instead of popping up; webhooks are machine-facing and go out
+/// immediately either way.
+fn deliver(rule: &AlertRule, message: &str, offline: bool, focused: bool) {
+    if focused {
+        super::focus::defer(&format!("🔔 {}", message));
+    } else {
+        // Desktop notification (Linux notify-send / macOS osascript)
+        let notified = std::process::Command::new("notify-send")
+            .arg("TermBrain alert")
+            .arg(message)
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+
+        if !notified {
+            let script = format!(
+                "display notification {:?} with title \"TermBrain alert\"",
+                message
+            );
+            let _ = std::process::Command::new("osascript")
+                .arg("-e")
+                .arg(script)
+                .status();
+        }
+    }
+
+    if offline {
+        if rule.webhook.is_some() {
+            println!("   📴 Offline mode — webhook delivery skipped");
+        }
+        return;
+    }
+
+    if let Some(webhook) = &rule.webhook {
+        // Delivered in the versioned event envelope (tb events schema)
+        let event = termbrain_core::events::Event::new(
+            termbrain_core::events::EventKind::AnomalyDetected,
+            serde_json::json!({
+                "alert": rule.name,
+                "metric": rule.metric,
+                "threshold": rule.threshold,
+                "message": message,
+            }),
+        );
+        let result = std::process::Command::new("curl")
+            .args(["-s", "-X", "POST", "-H", "Content-Type: application/json", "-d"])
+            .arg(serde_json::to_string(&event).unwrap_or_default())
+            .arg(webhook)
+            .status();
+
+        if !result.map(|s| s.success()).unwrap_or(false) {
+            eprintln!("⚠️  Webhook delivery failed for alert '{}'", rule.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(op: &str, threshold: f64) -> AlertRule {
+        AlertRule {
+            name: "test".to_string(),
+            metric: "m".to_string(),
+            op: op.to_string(),
+            threshold,
+            webhook: None,
+        }
+    }
+
+    fn metric(value: f64) -> MetricValue {
+        MetricValue {
+            name: "m".to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_operators() {
+        assert!(rule_fires(&rule(">", 10.0), &metric(11.0)).unwrap());
+        assert!(!rule_fires(&rule(">", 10.0), &metric(10.0)).unwrap());
+        assert!(rule_fires(&rule("<=", 10.0), &metric(10.0)).unwrap());
+        assert!(rule_fires(&rule("==", 10.0), &metric(10.0)).unwrap());
+        assert!(rule_fires(&rule("badop", 10.0), &metric(10.0)).is_err());
+    }
+}
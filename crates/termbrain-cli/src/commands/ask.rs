@@ -0,0 +1,237 @@
+//! Natural language Q&A over command history
+
+use anyhow::Result;
+use termbrain_core::ai::{parse_tool_call, render_tool_instructions, AiProvider, ToolCall, ToolSpec};
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::{CommandRepository, HybridWeights};
+
+use crate::ai::SubprocessProvider;
+use crate::config::Config;
+use crate::OutputFormat;
+
+use super::create_storage;
+
+/// How many commands to retrieve as grounding context for the answer.
+const CONTEXT_LIMIT: usize = 20;
+
+/// Cap on model-issued follow-up queries per question.
+const MAX_TOOL_ROUNDS: usize = 4;
+
+/// Tools the model may call while answering (see
+/// [`termbrain_core::ai::render_tool_instructions`] for the protocol).
+pub(super) fn history_tools() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec {
+            name: "search",
+            description: r#"search command history; arguments {"query": string, "limit": number?}"#,
+        },
+        ToolSpec {
+            name: "get_session",
+            description: r#"fetch all commands from one session; arguments {"session_id": string}"#,
+        },
+    ]
+}
+
+/// Executes a model-issued tool call against the repository and formats
+/// the result for the transcript.
+pub(super) async fn execute_tool(
+    repo: &impl CommandRepository,
+    call: &ToolCall,
+) -> Result<String> {
+    match call.tool.as_str() {
+        "search" => {
+            let query = call.arguments["query"].as_str().unwrap_or_default();
+            let limit = call.arguments["limit"].as_u64().unwrap_or(10) as usize;
+            let results = repo
+                .search_hybrid(query, limit.min(50), &HybridWeights::default())
+                .await?;
+            Ok(format_commands(&results))
+        }
+        "get_session" => {
+            let session_id = call.arguments["session_id"].as_str().unwrap_or_default();
+            let results = repo.find_by_session(session_id).await?;
+            Ok(format_commands(&results))
+        }
+        other => Ok(format!("error: unknown tool '{}'", other)),
+    }
+}
+
+fn format_commands(commands: &[Command]) -> String {
+    if commands.is_empty() {
+        return "(no results)".to_string();
+    }
+    commands
+        .iter()
+        .map(|cmd| {
+            format!(
+                "{} (dir: {}, exit: {}, session: {}, at: {})",
+                cmd.raw,
+                cmd.working_directory,
+                cmd.exit_code,
+                cmd.session_id,
+                cmd.timestamp.format("%Y-%m-%d %H:%M")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Runs the tool-calling loop: completes the prompt, executes any tool
+/// calls the model issues, and returns its final text answer.
+pub(super) async fn complete_with_tools(
+    provider: &impl AiProvider,
+    repo: &impl CommandRepository,
+    prompt: String,
+) -> Result<String> {
+    let mut transcript = prompt;
+    let mut answer = provider.complete(&transcript).await?;
+
+    for _ in 0..MAX_TOOL_ROUNDS {
+        let Some(call) = parse_tool_call(&answer) else {
+            return Ok(answer);
+        };
+        let result = execute_tool(repo, &call).await?;
+        transcript.push_str(&format!(
+            "\nTool call: {}\nTool result:\n{}\n\nAnswer the question, or issue another tool call.\n",
+            serde_json::to_string(&serde_json::json!({
+                "tool": call.tool,
+                "arguments": call.arguments,
+            }))?,
+            result
+        ));
+        answer = provider.complete(&transcript).await?;
+    }
+
+    // Round budget exhausted: whatever came back last is the answer
+    Ok(answer)
+}
+
+/// Answers a natural language question about the user's command history.
+///
+/// Relevant commands are retrieved with hybrid search, formatted into a
+/// numbered context bundle, and sent to the configured AI provider. The
+/// answer is printed together with the citation table so each `[n]`
+/// reference can be traced back to a concrete command id.
+pub async fn ask_question(question: String, format: OutputFormat) -> Result<()> {
+    if question.trim().is_empty() {
+        return Err(anyhow::anyhow!("Question cannot be empty"));
+    }
+
+    let config = Config::load()?;
+    if super::shadow_mode_guard(&config) || super::offline_guard(&config) {
+        return Ok(());
+    }
+    let provider = SubprocessProvider::from_config(&config).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No AI provider configured. Set TERMBRAIN_AI_PROVIDER to a provider command (e.g. 'claude -p')"
+        )
+    })?;
+
+    let storage = create_storage().await?;
+    let repo = super::create_repo(&storage);
+
+    let context = repo
+        .search_hybrid(&question, CONTEXT_LIMIT, &HybridWeights::default())
+        .await?;
+
+    if context.is_empty() {
+        println!("No matching commands found in your history for: {}", question);
+        return Ok(());
+    }
+
+    // An active human intention travels into this AI session: attach it
+    // for the record, then put it (with earlier related attempts) in
+    // front of the model so it knows why it is being invoked
+    let session_id = std::env::var("TERMBRAIN_SESSION_ID")
+        .unwrap_or_else(|_| format!("ask-{}", std::process::id()));
+    let intentions = super::intend::attach_to_session(storage.pool(), &session_id).await?;
+    let prior_attempts = match intentions.first() {
+        Some(text) => repo.search(text, 5, None, None).await?,
+        None => Vec::new(),
+    };
+
+    let mut prompt = build_prompt(&question, &context);
+    prompt.push_str(&render_intentions(&intentions, &prior_attempts));
+    prompt.push_str(&render_tool_instructions(&history_tools()));
+    let answer = complete_with_tools(&provider, &repo, prompt).await?;
+
+    match format {
+        OutputFormat::Json => {
+            let citations: Vec<_> = context
+                .iter()
+                .enumerate()
+                .map(|(i, cmd)| {
+                    serde_json::json!({
+                        "reference": i + 1,
+                        "id": cmd.id,
+                        "command": cmd.raw,
+                        "timestamp": cmd.timestamp,
+                    })
+                })
+                .collect();
+            let output = serde_json::json!({
+                "question": question,
+                "answer": answer,
+                "citations": citations,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        _ => {
+            println!("🧠 {}\n", answer);
+            println!("Citations:");
+            for (i, cmd) in context.iter().enumerate() {
+                println!("  [{}] {} ({}, id {})",
+                    i + 1,
+                    cmd.raw,
+                    cmd.timestamp.format("%Y-%m-%d %H:%M"),
+                    cmd.id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Assembles the grounding prompt sent to the AI provider.
+fn build_prompt(question: &str, context: &[Command]) -> String {
+    let mut prompt = String::from(
+        "You are TermBrain, an assistant that answers questions about the \
+         user's own shell history. Answer based only on the commands below. \
+         Cite commands with their bracketed reference numbers, e.g. [3].\n\n\
+         Command history:\n",
+    );
+
+    for (i, cmd) in context.iter().enumerate() {
+        prompt.push_str(&format!(
+            "[{}] {} (dir: {}, exit: {}, at: {})\n",
+            i + 1,
+            cmd.raw,
+            cmd.working_directory,
+            cmd.exit_code,
+            cmd.timestamp.format("%Y-%m-%d %H:%M")
+        ));
+    }
+
+    prompt.push_str(&format!("\nQuestion: {}\n", question));
+    prompt
+}
+
+/// Renders the inherited-intention section of the prompt; empty when no
+/// intention is active.
+fn render_intentions(intentions: &[String], prior_attempts: &[Command]) -> String {
+    if intentions.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("\nThe user's current intention(s):\n");
+    for text in intentions {
+        section.push_str(&format!("- {}\n", text));
+    }
+    if !prior_attempts.is_empty() {
+        section.push_str("Earlier commands related to this intention:\n");
+        for cmd in prior_attempts {
+            section.push_str(&format!("- {} (exit: {})\n", cmd.raw, cmd.exit_code));
+        }
+    }
+    section
+}
@@ -0,0 +1,101 @@
+//! Inline shell autosuggestions
+//!
+//! `tb suggest-inline` prints at most one full command completing the
+//! buffer typed so far — the ghost text behind the zsh-autosuggestions
+//! strategy and fish keybinding in shell-integration. The latency
+//! budget is a prompt keystroke, so the database is opened directly
+//! (no journal drain, no recovery sweeps), everything is one indexed
+//! query, and any failure prints nothing rather than an error into the
+//! prompt. An empty buffer falls back to the transition model's top
+//! prediction (see `tb predict`).
+
+use anyhow::Result;
+use sqlx::Row;
+use termbrain_core::markov::rank_predictions;
+use termbrain_storage::sqlite::SqliteStorage;
+
+use crate::config::Config;
+
+use super::guest;
+
+pub async fn suggest_inline(buffer: Vec<String>) -> Result<()> {
+    let Ok(config) = Config::load() else {
+        return Ok(());
+    };
+    // Shadow mode disables suggestions; silence, not an explanation,
+    // belongs in the prompt
+    if config.shadow_mode {
+        return Ok(());
+    }
+
+    let database_path = if guest::guest_session_active() {
+        guest::guest_db_path()
+    } else {
+        config.database_path.clone()
+    };
+    if !database_path.exists() {
+        return Ok(());
+    }
+    let Ok(storage) = SqliteStorage::new(&database_path).await else {
+        return Ok(());
+    };
+
+    let directory = std::env::current_dir()
+        .map(|d| d.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let buffer = buffer.join(" ");
+    let buffer = buffer.trim();
+
+    if !buffer.is_empty() {
+        // Successful history entries extending the buffer, preferring
+        // ones from this directory, then frequency, then recency
+        let row = sqlx::query(
+            "SELECT raw,
+                    COUNT(*) AS n,
+                    MAX(timestamp) AS last,
+                    SUM(CASE WHEN working_directory = ?2 THEN 1 ELSE 0 END) AS here
+             FROM commands
+             WHERE raw LIKE ?1 || '_%' AND exit_code = 0
+             GROUP BY raw
+             ORDER BY here DESC, n DESC, last DESC
+             LIMIT 1",
+        )
+        .bind(buffer)
+        .bind(&directory)
+        .fetch_optional(storage.pool())
+        .await;
+        if let Ok(Some(row)) = row {
+            println!("{}", row.get::<String, _>("raw"));
+        }
+        return Ok(());
+    }
+
+    // Empty buffer: the most probable next command after what just ran
+    let Ok(recent) = sqlx::query(
+        "SELECT parsed_command, session_id FROM commands ORDER BY timestamp DESC LIMIT 2",
+    )
+    .fetch_all(storage.pool())
+    .await
+    else {
+        return Ok(());
+    };
+    let Some(last) = recent.first() else {
+        return Ok(());
+    };
+    let session: String = last.get("session_id");
+    let mut previous: Vec<String> = recent
+        .iter()
+        .filter(|row| row.get::<String, _>("session_id") == session)
+        .map(|row| row.get("parsed_command"))
+        .collect();
+    previous.reverse();
+
+    let Ok(observations) = super::predict::load_observations(&storage, &previous, &directory).await
+    else {
+        return Ok(());
+    };
+    if let Some(best) = rank_predictions(&observations).into_iter().next() {
+        println!("{}", best.next);
+    }
+    Ok(())
+}
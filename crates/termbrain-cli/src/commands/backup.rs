@@ -0,0 +1,247 @@
+//! Scheduled automatic backups
+//!
+//! `tb backup run` writes a gzip-compressed JSONL snapshot of the full
+//! history and pushes it to the configured target (a local directory,
+//! s3://bucket/prefix, or rclone:<remote>:<path>), keeping the last N
+//! generations. The command is cheap when nothing is due — wire it into
+//! cron or a shell hook and it only works once per interval. The last
+//! outcome is stamped into ~/.termbrain/last-backup for `tb status`.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use std::io::Write;
+use std::path::PathBuf;
+use termbrain_core::domain::repositories::CommandRepository;
+
+use crate::config::Config;
+
+use super::{create_repo, create_storage};
+
+/// Database page size for the snapshot stream.
+const PAGE_SIZE: usize = 5_000;
+
+/// Where the backup target lives, parsed from `backup.target`.
+enum BackupTarget {
+    /// A local or mounted directory.
+    Dir(PathBuf),
+    /// `s3://bucket/prefix` — transferred with the aws CLI.
+    S3(String),
+    /// `rclone:<remote>:<path>` — transferred with rclone.
+    Rclone(String),
+}
+
+impl BackupTarget {
+    fn parse(target: &str) -> Self {
+        if target.starts_with("s3://") {
+            Self::S3(target.trim_end_matches('/').to_string())
+        } else if let Some(rest) = target.strip_prefix("rclone:") {
+            Self::Rclone(rest.trim_end_matches('/').to_string())
+        } else {
+            Self::Dir(PathBuf::from(target))
+        }
+    }
+
+    fn needs_network(&self) -> bool {
+        !matches!(self, Self::Dir(_))
+    }
+}
+
+/// Stamp file recording the last successful backup, read by `tb status`.
+fn stamp_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".termbrain")
+        .join("last-backup")
+}
+
+/// The last backup's timestamp and file name, if one ever succeeded.
+pub fn last_backup() -> Option<(DateTime<Utc>, String)> {
+    let content = std::fs::read_to_string(stamp_path()).ok()?;
+    let (timestamp, file) = content.trim().split_once(' ')?;
+    let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?.with_timezone(&Utc);
+    Some((timestamp, file.to_string()))
+}
+
+/// One-line backup status for `tb status`.
+pub fn backup_status_line(config: &Config) -> Option<String> {
+    let backup = config.backup.as_ref()?;
+    Some(match last_backup() {
+        Some((when, file)) => {
+            let age_hours = (Utc::now() - when).num_hours();
+            let due = if age_hours as u64 >= backup.interval_hours { " (due)" } else { "" };
+            format!("Last backup: {} ({}h ago){} → {}", file, age_hours, due, backup.target)
+        }
+        None => format!("Last backup: never → {}", backup.target),
+    })
+}
+
+/// Runs a backup if one is due (or `force` is set).
+pub async fn backup_run(force: bool) -> Result<()> {
+    let config = Config::load()?;
+    let Some(backup) = config.backup.clone() else {
+        anyhow::bail!(
+            "No backup target configured — set backup.target in {}",
+            Config::config_file().display()
+        );
+    };
+
+    if !force {
+        if let Some((when, _)) = last_backup() {
+            let age = Utc::now() - when;
+            if (age.num_hours() as u64) < backup.interval_hours {
+                println!(
+                    "💤 Backup not due ({}h since last, interval {}h) — use --force to override",
+                    age.num_hours(),
+                    backup.interval_hours
+                );
+                return Ok(());
+            }
+        }
+    }
+
+    let target = BackupTarget::parse(&backup.target);
+    if target.needs_network() && config.offline() {
+        anyhow::bail!("Offline mode is active — backup to a network target is disabled");
+    }
+
+    // Snapshot the history as gzipped JSONL, one page at a time
+    let file_name = format!("termbrain-{}.jsonl.gz", Utc::now().format("%Y%m%d-%H%M%S"));
+    let local = std::env::temp_dir().join(&file_name);
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+    let mut encoder = GzEncoder::new(std::fs::File::create(&local)?, flate2::Compression::default());
+    let mut offset = 0usize;
+    let mut total = 0usize;
+    loop {
+        let page = repo
+            .find_by_time_range_paged(DateTime::<Utc>::MIN_UTC, Utc::now(), offset, PAGE_SIZE)
+            .await?;
+        if page.is_empty() {
+            break;
+        }
+        for command in &page {
+            writeln!(encoder, "{}", serde_json::to_string(command)?)?;
+        }
+        offset += page.len();
+        total += page.len();
+    }
+    encoder.finish()?;
+
+    push_to_target(&target, &local, &file_name)?;
+    std::fs::remove_file(&local).ok();
+    prune_generations(&target, backup.keep)?;
+
+    if let Some(parent) = stamp_path().parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(stamp_path(), format!("{} {}\n", Utc::now().to_rfc3339(), file_name))?;
+
+    println!("💾 Backed up {} commands as {} → {}", total, file_name, backup.target);
+    Ok(())
+}
+
+/// Shows the backup configuration and last run.
+pub async fn backup_status() -> Result<()> {
+    let config = Config::load()?;
+    let Some(backup) = &config.backup else {
+        println!("Backups are not configured — set backup.target in {}", Config::config_file().display());
+        return Ok(());
+    };
+    println!("💾 Backup target: {}", backup.target);
+    println!("   Interval: every {}h, keeping {} generations", backup.interval_hours, backup.keep);
+    match last_backup() {
+        Some((when, file)) => {
+            println!("   Last: {} at {}", file, when.format("%Y-%m-%d %H:%M:%S"));
+        }
+        None => println!("   Last: never"),
+    }
+    Ok(())
+}
+
+fn push_to_target(target: &BackupTarget, local: &std::path::Path, file_name: &str) -> Result<()> {
+    let local_str = local.display().to_string();
+    match target {
+        BackupTarget::Dir(dir) => {
+            std::fs::create_dir_all(dir)?;
+            std::fs::copy(local, dir.join(file_name))?;
+        }
+        BackupTarget::S3(prefix) => {
+            run_transfer("aws", &["s3", "cp", &local_str, &format!("{}/{}", prefix, file_name)])?;
+        }
+        BackupTarget::Rclone(remote) => {
+            run_transfer("rclone", &["copyto", &local_str, &format!("{}/{}", remote, file_name)])?;
+        }
+    }
+    Ok(())
+}
+
+/// Deletes the oldest generations beyond `keep`. Backup file names sort
+/// chronologically, so lexicographic order is enough.
+fn prune_generations(target: &BackupTarget, keep: usize) -> Result<()> {
+    let mut generations: Vec<String> = match target {
+        BackupTarget::Dir(dir) => std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name.starts_with("termbrain-") && name.ends_with(".jsonl.gz"))
+            .collect(),
+        BackupTarget::S3(prefix) => list_transfer("aws", &["s3", "ls", &format!("{}/", prefix)])?
+            .lines()
+            .filter_map(|line| line.split_whitespace().last())
+            .filter(|name| name.starts_with("termbrain-") && name.ends_with(".jsonl.gz"))
+            .map(String::from)
+            .collect(),
+        BackupTarget::Rclone(remote) => list_transfer("rclone", &["lsf", remote])?
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|name| name.starts_with("termbrain-") && name.ends_with(".jsonl.gz"))
+            .collect(),
+    };
+    generations.sort();
+    if generations.len() <= keep {
+        return Ok(());
+    }
+
+    let stale = generations.len() - keep;
+    for name in &generations[..stale] {
+        match target {
+            BackupTarget::Dir(dir) => {
+                std::fs::remove_file(dir.join(name))?;
+            }
+            BackupTarget::S3(prefix) => {
+                run_transfer("aws", &["s3", "rm", &format!("{}/{}", prefix, name)])?;
+            }
+            BackupTarget::Rclone(remote) => {
+                run_transfer("rclone", &["deletefile", &format!("{}/{}", remote, name)])?;
+            }
+        }
+    }
+    println!("   pruned {} old generation(s)", stale);
+    Ok(())
+}
+
+/// Runs a transfer tool, surfacing its stderr on failure.
+fn run_transfer(tool: &str, args: &[&str]) -> Result<()> {
+    let output = std::process::Command::new(tool).args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Like `run_transfer` but captures stdout (for listings).
+fn list_transfer(tool: &str, args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new(tool).args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -0,0 +1,204 @@
+//! New-machine bootstrap scripts
+//!
+//! `tb bootstrap` turns the tool inventory into a setup script: one
+//! package-manager install line for the tools actually used (above a
+//! frequency threshold), alias definitions for long commands typed
+//! repeatedly, and the saved workflows as shell functions. Pipe it to a
+//! file, review it, and run it on the next laptop.
+
+use anyhow::Result;
+use sqlx::Row;
+
+use super::create_storage;
+
+/// Tools whose package name differs between managers, or that should
+/// not be installed at all (None). Unlisted tools install under their
+/// own name, which is right for most CLI tools.
+const PACKAGE_OVERRIDES: &[(&str, Option<&str>, Option<&str>)] = &[
+    // (tool, brew, apt)
+    ("python3", Some("python"), Some("python3")),
+    ("pip", Some("python"), Some("python3-pip")),
+    ("node", Some("node"), Some("nodejs")),
+    ("npm", Some("node"), Some("npm")),
+    ("cargo", Some("rustup"), Some("cargo")),
+    ("rustup", Some("rustup"), None),
+    ("kubectl", Some("kubernetes-cli"), Some("kubectl")),
+    ("docker", Some("docker"), Some("docker.io")),
+    ("rg", Some("ripgrep"), Some("ripgrep")),
+    ("fd", Some("fd"), Some("fd-find")),
+    ("make", None, Some("build-essential")),
+];
+
+/// Shell builtins and commands no package manager should install.
+const NOT_PACKAGES: &[&str] = &[
+    "cd", "ls", "pwd", "clear", "exit", "echo", "cat", "source", "export", "set", "unset",
+    "alias", "history", "which", "man", "mkdir", "rm", "cp", "mv", "touch", "sudo", "tb",
+];
+
+/// Generates a setup script for `target` ("brew" or "apt") covering
+/// tools used at least `min_uses` times, and writes it to `output` or
+/// stdout.
+pub async fn generate_bootstrap(
+    target: String,
+    min_uses: usize,
+    output: Option<String>,
+) -> Result<()> {
+    if target != "brew" && target != "apt" {
+        anyhow::bail!("Unknown target '{}': expected brew or apt", target);
+    }
+
+    let storage = create_storage().await?;
+
+    let tool_rows = sqlx::query(
+        "SELECT parsed_command AS tool, COUNT(*) AS total
+         FROM commands WHERE parsed_command != ''
+         GROUP BY parsed_command HAVING COUNT(*) >= ?
+         ORDER BY total DESC",
+    )
+    .bind(min_uses as i64)
+    .fetch_all(storage.pool())
+    .await?;
+
+    let mut packages = Vec::new();
+    for row in &tool_rows {
+        let tool: String = row.get("tool");
+        if NOT_PACKAGES.contains(&tool.as_str()) {
+            continue;
+        }
+        let package = match PACKAGE_OVERRIDES.iter().find(|(name, _, _)| *name == tool) {
+            Some((_, brew, apt)) => match if target == "brew" { brew } else { apt } {
+                Some(package) => package.to_string(),
+                None => continue,
+            },
+            None => tool,
+        };
+        if !packages.contains(&package) {
+            packages.push(package);
+        }
+    }
+
+    // Long commands typed repeatedly become aliases, named from the
+    // first letter of each word (same heuristic as 'tb suggest')
+    let alias_rows = sqlx::query(
+        "SELECT raw, COUNT(*) AS total FROM commands
+         WHERE LENGTH(raw) > 20 GROUP BY raw HAVING COUNT(*) >= ?
+         ORDER BY total DESC LIMIT 10",
+    )
+    .bind(min_uses as i64)
+    .fetch_all(storage.pool())
+    .await?;
+
+    let mut aliases: Vec<(String, String)> = Vec::new();
+    for row in &alias_rows {
+        let raw: String = row.get("raw");
+        if raw.contains('\'') || raw.contains('\n') {
+            continue;
+        }
+        if let Some(name) = alias_name(&raw) {
+            if !aliases.iter().any(|(existing, _)| *existing == name) {
+                aliases.push((name, raw));
+            }
+        }
+    }
+
+    let workflow_rows = sqlx::query(
+        "SELECT name, description, steps FROM workflows ORDER BY usage_count DESC",
+    )
+    .fetch_all(storage.pool())
+    .await?;
+
+    let mut script = String::new();
+    script.push_str("#!/bin/sh\n");
+    script.push_str(&format!(
+        "# Generated by 'tb bootstrap --target {}' on {} — review before running\n",
+        target,
+        chrono::Utc::now().format("%Y-%m-%d"),
+    ));
+    script.push_str("set -e\n\n");
+
+    if !packages.is_empty() {
+        script.push_str(&format!("# Tools used at least {} times\n", min_uses));
+        let install = if target == "brew" {
+            format!("brew install {}\n", packages.join(" "))
+        } else {
+            format!("sudo apt-get install -y {}\n", packages.join(" "))
+        };
+        script.push_str(&install);
+        script.push('\n');
+    }
+
+    if !aliases.is_empty() {
+        script.push_str("# Frequently typed commands — add to your shell profile\n");
+        for (name, raw) in &aliases {
+            script.push_str(&format!("alias {}='{}'\n", name, raw));
+        }
+        script.push('\n');
+    }
+
+    for row in &workflow_rows {
+        let name: String = row.get("name");
+        let description: String = row.get("description");
+        let steps: String = row.get("steps");
+        let steps: Vec<serde_json::Value> = serde_json::from_str(&steps).unwrap_or_default();
+        script.push_str(&format!("# Workflow: {}\n", description));
+        script.push_str(&format!("{}() {{\n", sanitize_function_name(&name)));
+        for step in &steps {
+            if let Some(command) = step.get("command").and_then(|c| c.as_str()) {
+                // Placeholder steps from 'tb teach' stay commented out
+                if command.starts_with('<') {
+                    script.push_str(&format!("    # {}\n", command));
+                } else {
+                    script.push_str(&format!("    {}\n", command));
+                }
+            }
+        }
+        script.push_str("}\n\n");
+    }
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, &script)?;
+            println!(
+                "✅ Wrote {} ({} packages, {} aliases, {} workflows)",
+                path,
+                packages.len(),
+                aliases.len(),
+                workflow_rows.len(),
+            );
+        }
+        None => print!("{}", script),
+    }
+    Ok(())
+}
+
+/// Derives an alias name from the first letter of each word; None when
+/// fewer than two letters survive (too ambiguous to bother).
+fn alias_name(raw: &str) -> Option<String> {
+    let name: String = raw
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .filter(|c| c.is_ascii_alphabetic())
+        .take(4)
+        .collect::<String>()
+        .to_lowercase();
+    (name.len() >= 2).then_some(name)
+}
+
+/// Makes a workflow name safe as a shell function name.
+fn sanitize_function_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alias_name_skips_flags_and_short_commands() {
+        assert_eq!(alias_name("docker compose up -d"), Some("dcu".to_string()));
+        assert_eq!(alias_name("git status"), Some("gs".to_string()));
+        assert_eq!(alias_name("ls"), None);
+    }
+}
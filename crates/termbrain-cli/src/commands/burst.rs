@@ -0,0 +1,114 @@
+//! Burst summarization glue for the record path
+//!
+//! Detection and template merging live in `termbrain_core::burst`; this
+//! module does the storage side: find the burst's summary row, fold the
+//! incoming command into it, or promote the command into a new summary
+//! once the threshold is crossed.
+
+use anyhow::Result;
+use chrono::Duration;
+use sqlx::Row;
+use termbrain_core::burst::{
+    merge_template, BURST_COUNT_EXTRA, BURST_DURATION_EXTRA, BURST_FAILURES_EXTRA,
+    BURST_THRESHOLD, BURST_WINDOW_SECS,
+};
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_storage::sqlite::{SqliteCommandRepository, SqliteStorage};
+use uuid::Uuid;
+
+/// Folds `cmd` into its burst, if it is part of one. Returns true when
+/// the individual row should not be recorded (it was counted into an
+/// existing summary and raw retention is off). May rewrite `cmd` into a
+/// summary record when this command crosses the burst threshold.
+pub(super) async fn fold_into_burst(
+    storage: &SqliteStorage,
+    repo: &SqliteCommandRepository,
+    cmd: &mut Command,
+    keep_raw: bool,
+) -> Result<bool> {
+    let window_start = (cmd.timestamp - Duration::seconds(BURST_WINDOW_SECS)).to_rfc3339();
+
+    // An open summary row for this command absorbs the new record
+    let summary_id: Option<String> = sqlx::query(
+        "SELECT id FROM commands
+         WHERE session_id = ? AND parsed_command = ? AND timestamp >= ?
+           AND json_extract(extras, '$.burst_count') IS NOT NULL
+         ORDER BY timestamp DESC LIMIT 1",
+    )
+    .bind(&cmd.session_id)
+    .bind(&cmd.parsed_command)
+    .bind(&window_start)
+    .fetch_optional(storage.pool())
+    .await?
+    .map(|row| row.get("id"));
+
+    if let Some(id) = summary_id {
+        let id = Uuid::parse_str(&id)?;
+        if let Some(summary) = repo.find_by_id(&id).await? {
+            let mut extras = summary.extras;
+            bump(&mut extras, BURST_COUNT_EXTRA, 1);
+            bump(&mut extras, BURST_DURATION_EXTRA, cmd.duration_ms);
+            if cmd.exit_code != 0 {
+                bump(&mut extras, BURST_FAILURES_EXTRA, 1);
+            }
+            repo.update_extras_batch(&[(id, extras)]).await?;
+            return Ok(!keep_raw);
+        }
+    }
+
+    // No summary yet: count identical commands in the window and, past
+    // the threshold, turn this record into the burst's summary row
+    let recent: i64 = sqlx::query(
+        "SELECT COUNT(*) AS n FROM commands
+         WHERE session_id = ? AND parsed_command = ? AND timestamp >= ?",
+    )
+    .bind(&cmd.session_id)
+    .bind(&cmd.parsed_command)
+    .bind(&window_start)
+    .fetch_one(storage.pool())
+    .await?
+    .get("n");
+
+    if (recent as usize) + 1 >= BURST_THRESHOLD {
+        // Template from this command and the previous iteration, read
+        // back through the repository so vault decryption applies
+        let previous_id: Option<String> = sqlx::query(
+            "SELECT id FROM commands
+             WHERE session_id = ? AND parsed_command = ? AND timestamp >= ?
+             ORDER BY timestamp DESC LIMIT 1",
+        )
+        .bind(&cmd.session_id)
+        .bind(&cmd.parsed_command)
+        .bind(&window_start)
+        .fetch_optional(storage.pool())
+        .await?
+        .map(|row| row.get("id"));
+
+        if let Some(previous_id) = previous_id {
+            if let Some(previous) = repo.find_by_id(&Uuid::parse_str(&previous_id)?).await? {
+                cmd.raw = merge_template(&cmd.raw, &previous.raw);
+            }
+        }
+        cmd.arguments.clear();
+        cmd.extras
+            .insert(BURST_COUNT_EXTRA.to_string(), serde_json::json!(1));
+        cmd.extras.insert(
+            BURST_DURATION_EXTRA.to_string(),
+            serde_json::json!(cmd.duration_ms),
+        );
+        cmd.extras.insert(
+            BURST_FAILURES_EXTRA.to_string(),
+            serde_json::json!(if cmd.exit_code == 0 { 0 } else { 1 }),
+        );
+    }
+
+    Ok(false)
+}
+
+/// Adds `by` to a numeric extras counter, treating a missing or
+/// malformed value as zero.
+fn bump(extras: &mut std::collections::HashMap<String, serde_json::Value>, key: &str, by: u64) {
+    let current = extras.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    extras.insert(key.to_string(), serde_json::json!(current + by));
+}
@@ -0,0 +1,56 @@
+//! Environment change timeline
+
+use anyhow::Result;
+use sqlx::Row;
+
+use crate::OutputFormat;
+
+use super::create_storage;
+
+/// Lists recorded environment change events, newest first.
+pub async fn show_env_changes(limit: usize, format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let rows = sqlx::query(
+        "SELECT kind, description, hostname, occurred_at FROM env_changes ORDER BY occurred_at DESC LIMIT ?",
+    )
+    .bind(limit as i64)
+    .fetch_all(storage.pool())
+    .await?;
+
+    if rows.is_empty() {
+        println!("No environment changes recorded yet");
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let events: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    serde_json::json!({
+                        "kind": row.get::<String, _>("kind"),
+                        "description": row.get::<String, _>("description"),
+                        "hostname": row.get::<String, _>("hostname"),
+                        "occurred_at": row.get::<String, _>("occurred_at"),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&events)?);
+        }
+        _ => {
+            println!("🌱 Environment changes (last {}):", rows.len());
+            for row in rows {
+                println!(
+                    "   {} [{}] {} ({})",
+                    row.get::<String, _>("occurred_at"),
+                    row.get::<String, _>("kind"),
+                    row.get::<String, _>("description"),
+                    row.get::<String, _>("hostname"),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
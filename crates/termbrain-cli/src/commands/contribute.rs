@@ -0,0 +1,88 @@
+//! Opt-in anonymized contribution bundles
+//!
+//! `tb contribute build` assembles command *templates* — program,
+//! flag names, placeholders for every value and path — from local
+//! history into a JSON bundle the user can read end to end and submit
+//! by hand if they choose. Assembly is entirely local and nothing is
+//! ever uploaded; the command only writes a file.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashMap;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::privacy::{categorize, command_template};
+
+use super::{create_repo, create_storage};
+
+/// How much history template extraction looks at.
+const ANALYSIS_WINDOW: usize = 10_000;
+
+/// Templates seen fewer times than this are dropped: a shape that
+/// occurred once is close to the original command line and could still
+/// identify it.
+const DEFAULT_MIN_USES: usize = 3;
+
+/// Builds the anonymized bundle and writes it to `output`.
+pub async fn contribute_build(output: Option<String>, min_uses: Option<usize>) -> Result<()> {
+    let min_uses = min_uses.unwrap_or(DEFAULT_MIN_USES).max(2);
+    let output = output.unwrap_or_else(|| "termbrain-contribution.json".to_string());
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+    let commands = repo.find_recent(ANALYSIS_WINDOW).await?;
+
+    // template → (count, successes)
+    let mut templates: HashMap<String, (usize, usize)> = HashMap::new();
+    for cmd in &commands {
+        let entry = templates.entry(command_template(&cmd.raw)).or_insert((0, 0));
+        entry.0 += 1;
+        if cmd.exit_code == 0 {
+            entry.1 += 1;
+        }
+    }
+
+    let mut entries: Vec<_> = templates
+        .into_iter()
+        .filter(|(_, (count, _))| *count >= min_uses)
+        .map(|(template, (count, successes))| {
+            let program = template.split_whitespace().next().unwrap_or("").to_string();
+            serde_json::json!({
+                "template": template,
+                "category": categorize(&program),
+                "count": count,
+                "success_rate": (successes as f64 / count as f64 * 100.0).round(),
+            })
+        })
+        .collect();
+    entries.sort_by_key(|entry| {
+        std::cmp::Reverse(entry.get("count").and_then(|c| c.as_u64()).unwrap_or(0))
+    });
+
+    if entries.is_empty() {
+        println!(
+            "No templates occur at least {} times yet — nothing to contribute",
+            min_uses
+        );
+        return Ok(());
+    }
+
+    let bundle = serde_json::json!({
+        "schema_version": 1,
+        "kind": "termbrain-contribution",
+        "generated_at": Utc::now().to_rfc3339(),
+        "analyzed_commands": commands.len(),
+        "min_uses": min_uses,
+        "templates": entries,
+    });
+    std::fs::write(&output, serde_json::to_string_pretty(&bundle)?)?;
+
+    println!(
+        "📦 Wrote {} templates from {} commands to {}",
+        bundle["templates"].as_array().map(|t| t.len()).unwrap_or(0),
+        commands.len(),
+        output
+    );
+    println!("   Templates carry no values, paths, hosts, users, or timestamps");
+    println!("   Review the file, then submit it manually if you choose — nothing is uploaded");
+    Ok(())
+}
@@ -0,0 +1,168 @@
+//! Long-running ingestion daemon
+//!
+//! `tb daemon` listens on a Unix domain socket in the data directory.
+//! When it is running, `tb record` writes one tiny JSON line to the
+//! socket and returns immediately instead of opening SQLite and doing
+//! enrichment on the prompt's critical path; the daemon drains the
+//! queue sequentially (one writer, no lock contention) and runs the
+//! maintenance that would otherwise never happen — embedding backfill
+//! and digest generation — in the background.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// One recorded command on the wire, mirroring `tb record`'s arguments.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct RecordMessage {
+    pub command: String,
+    #[serde(default)]
+    pub exit_code: i32,
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default = "default_message_source")]
+    pub source: String,
+    /// KEY=VALUE extras, pre-encoded like the --extra flag.
+    #[serde(default)]
+    pub extra: Vec<String>,
+    /// The recording shell's TERMBRAIN_SESSION_ID; the daemon has no
+    /// shell environment of its own.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// When the command actually ran — replays through the daemon or
+    /// journal would otherwise be stamped with the drain time.
+    #[serde(default)]
+    pub recorded_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+fn default_message_source() -> String {
+    "shell-hook".to_string()
+}
+
+/// Where the daemon listens.
+pub(super) fn socket_path() -> std::path::PathBuf {
+    crate::platform::data_dir().join("daemon.sock")
+}
+
+/// Hands a record to the daemon, if one is listening. Returns false
+/// when there is no daemon (caller records directly as before).
+#[cfg(target_family = "unix")]
+pub(super) fn forward_record(message: &RecordMessage) -> Result<bool> {
+    use std::io::Write;
+
+    let path = socket_path();
+    if !path.exists() {
+        return Ok(false);
+    }
+    let mut stream = match std::os::unix::net::UnixStream::connect(&path) {
+        Ok(stream) => stream,
+        Err(_) => {
+            // Stale socket from a dead daemon — fall back to direct writes
+            let _ = std::fs::remove_file(&path);
+            return Ok(false);
+        }
+    };
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    Ok(true)
+}
+
+#[cfg(target_family = "windows")]
+pub(super) fn forward_record(_message: &RecordMessage) -> Result<bool> {
+    Ok(false)
+}
+
+/// Runs the ingestion daemon in the foreground until interrupted.
+#[cfg(target_family = "unix")]
+pub async fn run_daemon() -> Result<()> {
+    use tokio::io::AsyncBufReadExt;
+
+    let path = socket_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // A leftover socket from an unclean shutdown blocks the bind
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    // Stops record_command from forwarding back into our own socket
+    std::env::set_var("TERMBRAIN_IN_DAEMON", "1");
+    println!("👂 Daemon listening on {}", path.display());
+    println!("   'tb record' now queues through the socket; Ctrl-C to stop");
+
+    // One writer task serializes all database writes; connections only
+    // parse and enqueue, so the recording shell never waits on SQLite
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<RecordMessage>();
+    let writer = tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            if let Some(session) = &message.session_id {
+                std::env::set_var("TERMBRAIN_SESSION_ID", session);
+            }
+            if let Err(e) = super::record_command(
+                message.command,
+                message.exit_code,
+                message.duration,
+                message.directory,
+                message.source,
+                message.extra,
+                message.recorded_at,
+            )
+            .await
+            {
+                eprintln!("⚠️  Failed to record queued command: {}", e);
+            }
+        }
+    });
+
+    // Background maintenance on an interval, off the ingestion path
+    let maintenance = tokio::spawn(async {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(600));
+        interval.tick().await; // immediate first tick; skip it
+        loop {
+            interval.tick().await;
+            #[cfg(feature = "embeddings")]
+            if let Err(e) = super::rebuild_embeddings().await {
+                eprintln!("⚠️  Embedding backfill failed: {}", e);
+            }
+            if let Err(e) = super::generate_digest().await {
+                eprintln!("⚠️  Digest generation failed: {}", e);
+            }
+        }
+    });
+
+    let accept = async {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut lines = tokio::io::BufReader::new(stream).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    match serde_json::from_str::<RecordMessage>(&line) {
+                        Ok(message) => {
+                            let _ = tx.send(message);
+                        }
+                        Err(e) => eprintln!("⚠️  Ignoring malformed daemon message: {}", e),
+                    }
+                }
+            });
+        }
+        #[allow(unreachable_code)]
+        Ok::<(), anyhow::Error>(())
+    };
+
+    tokio::select! {
+        result = accept => result?,
+        _ = tokio::signal::ctrl_c() => println!("\n👋 Daemon stopping"),
+    }
+
+    writer.abort();
+    maintenance.abort();
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[cfg(target_family = "windows")]
+pub async fn run_daemon() -> Result<()> {
+    anyhow::bail!("tb daemon requires Unix domain sockets and is not available on Windows")
+}
@@ -0,0 +1,213 @@
+//! Analytical dataset snapshots
+//!
+//! Produces a clean, documented one-row-per-command dataset with
+//! derived features (hour, weekday, project, semantic type, success)
+//! ready for pandas/polars exploration in a notebook.
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, Timelike, Utc};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+
+use super::create_storage;
+
+/// Writes a dataset snapshot to `output`. CSV is written directly; a
+/// `.parquet` output is converted via the `duckdb` CLI.
+pub async fn export_dataset(since: Option<String>, output: String, anonymize: bool) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = super::create_repo(&storage);
+
+    let mut commands = repo.find_recent(usize::MAX / 2).await?;
+
+    if let Some(since) = since {
+        let cutoff = Utc::now() - parse_duration(&since)?;
+        commands.retain(|cmd| cmd.timestamp >= cutoff);
+    }
+
+    if commands.is_empty() {
+        println!("No commands match the requested range");
+        return Ok(());
+    }
+
+    let want_parquet = output.ends_with(".parquet");
+    let csv_path = if want_parquet {
+        format!("{}.staging.csv", output)
+    } else {
+        output.clone()
+    };
+
+    // User corrections (tb reclassify) win over the heuristic
+    // classifiers for both the category and project columns
+    let type_overrides = super::reclassify::load_overrides(&storage, "semantic_type").await?;
+    let project_overrides = super::reclassify::load_overrides(&storage, "project").await?;
+    write_csv(&csv_path, &commands, anonymize, &type_overrides, &project_overrides)?;
+
+    if want_parquet {
+        let sql = format!(
+            "COPY (SELECT * FROM read_csv_auto('{}', header = true)) TO '{}' (FORMAT parquet);",
+            csv_path, output
+        );
+        let result = std::process::Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .output();
+
+        match result {
+            Ok(out) if out.status.success() => {
+                std::fs::remove_file(&csv_path).ok();
+            }
+            Ok(out) => {
+                return Err(anyhow::anyhow!(
+                    "Parquet conversion failed: {}",
+                    String::from_utf8_lossy(&out.stderr).trim()
+                ));
+            }
+            Err(_) => {
+                return Err(anyhow::anyhow!(
+                    "Parquet output needs the 'duckdb' CLI — CSV staged at {}",
+                    csv_path
+                ));
+            }
+        }
+    }
+
+    println!("📤 Dataset snapshot: {} ({} rows)", output, commands.len());
+    if anonymize {
+        println!("   🔒 Anonymized: raw commands dropped, users/hosts/dirs hashed");
+    }
+    println!("   Columns: id, command, tool, category, project, directory,");
+    println!("            hour, weekday, success, exit_code, duration_ms, timestamp, user, hostname");
+
+    Ok(())
+}
+
+fn write_csv(
+    path: &str,
+    commands: &[Command],
+    anonymize: bool,
+    type_overrides: &std::collections::HashMap<String, String>,
+    project_overrides: &std::collections::HashMap<String, String>,
+) -> Result<()> {
+    let mut file = std::fs::File::create(path)?;
+
+    writeln!(
+        file,
+        "id,command,tool,category,project,directory,hour,weekday,success,exit_code,duration_ms,timestamp,user,hostname"
+    )?;
+
+    for cmd in commands {
+        let project = cmd
+            .extras
+            .get("project")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| super::reclassify::directory_override(project_overrides, &cmd.working_directory))
+            .unwrap_or_else(|| project_name(&cmd.working_directory));
+        let (command, directory, project, user, hostname) = if anonymize {
+            (
+                String::new(),
+                pseudonym("dir", &cmd.working_directory),
+                pseudonym("project", &project),
+                pseudonym("user", &cmd.metadata.user),
+                pseudonym("host", &cmd.metadata.hostname),
+            )
+        } else {
+            (
+                cmd.raw.clone(),
+                cmd.working_directory.clone(),
+                project,
+                cmd.metadata.user.clone(),
+                cmd.metadata.hostname.clone(),
+            )
+        };
+
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            cmd.id,
+            csv_escape(&command),
+            csv_escape(&cmd.parsed_command),
+            super::reclassify::semantic_type_of(cmd, type_overrides),
+            csv_escape(&project),
+            csv_escape(&directory),
+            cmd.timestamp.hour(),
+            cmd.timestamp.weekday(),
+            cmd.exit_code == 0,
+            cmd.exit_code,
+            cmd.duration_ms,
+            cmd.timestamp.to_rfc3339(),
+            csv_escape(&user),
+            csv_escape(&hostname),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Parses humanized durations like `1y`, `6m`, `30d`, `12h`.
+fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (number, unit) = value.split_at(value.len().saturating_sub(1));
+    let number: i64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration '{}': expected forms like 1y, 6m, 30d, 12h", value))?;
+
+    match unit {
+        "y" => Ok(Duration::days(number * 365)),
+        "m" => Ok(Duration::days(number * 30)),
+        "w" => Ok(Duration::weeks(number)),
+        "d" => Ok(Duration::days(number)),
+        "h" => Ok(Duration::hours(number)),
+        _ => Err(anyhow::anyhow!(
+            "Unknown duration unit '{}': expected y, m, w, d or h",
+            unit
+        )),
+    }
+}
+
+/// Last path component of the working directory stands in for project.
+fn project_name(directory: &str) -> String {
+    directory
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(directory)
+        .to_string()
+}
+
+/// Stable pseudonym so joins within the dataset still work.
+fn pseudonym(kind: &str, value: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    format!("{}-{:08x}", kind, hasher.finish() as u32)
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("1y").unwrap(), Duration::days(365));
+        assert_eq!(parse_duration("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert!(parse_duration("tomorrow").is_err());
+    }
+
+    #[test]
+    fn test_pseudonyms_are_stable() {
+        assert_eq!(pseudonym("user", "alice"), pseudonym("user", "alice"));
+        assert_ne!(pseudonym("user", "alice"), pseudonym("user", "bob"));
+    }
+}
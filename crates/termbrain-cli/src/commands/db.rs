@@ -0,0 +1,138 @@
+//! Database maintenance
+//!
+//! `tb db` groups the hygiene chores a long-lived history database
+//! eventually needs: reclaiming space after big prunes (vacuum),
+//! verifying page- and reference-level integrity (check), and making or
+//! restoring file-level copies with SQLite's online backup semantics
+//! (backup/restore). Distinct from `tb backup`, which handles scheduled
+//! compressed archives; these operate on the raw database file.
+
+use anyhow::Result;
+use sqlx::Row;
+
+use crate::config::Config;
+
+use super::create_storage;
+
+/// Side tables holding a command_id that should resolve to a row in
+/// `commands`; checked for orphans by `tb db check`.
+const COMMAND_REF_TABLES: &[&str] = &[
+    "env_changes",
+    "diagnoses",
+    "embeddings",
+    "activity_refs",
+    "integrity_chain",
+];
+
+/// Rebuilds the database file, reclaiming free pages.
+pub async fn db_vacuum() -> Result<()> {
+    let path = Config::load()?.database_path;
+    let before = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    let storage = create_storage().await?;
+    sqlx::query("VACUUM").execute(storage.pool()).await?;
+
+    let after = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    println!(
+        "✅ Vacuumed: {:.1} MB → {:.1} MB",
+        before as f64 / 1_048_576.0,
+        after as f64 / 1_048_576.0,
+    );
+    Ok(())
+}
+
+/// Runs PRAGMA integrity_check plus orphan detection over the side
+/// tables that reference commands.
+pub async fn db_check() -> Result<()> {
+    let storage = create_storage().await?;
+
+    let verdict: String = sqlx::query("PRAGMA integrity_check")
+        .fetch_one(storage.pool())
+        .await?
+        .get(0);
+    if verdict == "ok" {
+        println!("✅ Page integrity: ok");
+    } else {
+        println!("❌ Page integrity: {}", verdict);
+    }
+
+    let mut orphans_found = false;
+    for table in COMMAND_REF_TABLES {
+        let count: i64 = match sqlx::query(&format!(
+            "SELECT COUNT(*) AS n FROM {} WHERE command_id NOT IN (SELECT id FROM commands)",
+            table
+        ))
+        .fetch_one(storage.pool())
+        .await
+        {
+            Ok(row) => row.get("n"),
+            // Optional tables (e.g. embeddings) may not exist yet
+            Err(_) => continue,
+        };
+        if count > 0 {
+            println!("⚠️  {}: {} orphaned row(s) referencing pruned commands", table, count);
+            orphans_found = true;
+        }
+    }
+    if !orphans_found {
+        println!("✅ References: no orphans");
+    }
+
+    if verdict != "ok" {
+        anyhow::bail!("Integrity check failed");
+    }
+    Ok(())
+}
+
+/// Writes a consistent copy of the live database to `path` using
+/// VACUUM INTO (SQLite's online backup — safe while recording).
+pub async fn db_backup(path: String) -> Result<()> {
+    if std::path::Path::new(&path).exists() {
+        anyhow::bail!("{} already exists — refusing to overwrite", path);
+    }
+
+    let storage = create_storage().await?;
+    sqlx::query("VACUUM INTO ?")
+        .bind(&path)
+        .execute(storage.pool())
+        .await?;
+
+    let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    println!("✅ Backed up to {} ({:.1} MB)", path, size as f64 / 1_048_576.0);
+    Ok(())
+}
+
+/// Replaces the live database with a backup. The backup is validated
+/// first and the current database is kept aside as *.pre-restore.
+pub async fn db_restore(path: String) -> Result<()> {
+    // Validate before touching anything
+    let source = termbrain_storage::sqlite::SqliteStorage::new(&path).await?;
+    let verdict: String = sqlx::query("PRAGMA integrity_check")
+        .fetch_one(source.pool())
+        .await?
+        .get(0);
+    if verdict != "ok" {
+        anyhow::bail!("{} fails integrity check: {}", path, verdict);
+    }
+    let commands: i64 = sqlx::query("SELECT COUNT(*) AS n FROM commands")
+        .fetch_one(source.pool())
+        .await?
+        .get("n");
+
+    let database_path = Config::load()?.database_path;
+    if database_path.exists() {
+        let aside = database_path.with_extension("db.pre-restore");
+        std::fs::copy(&database_path, &aside)?;
+        println!("   Current database kept at {}", aside.display());
+    }
+    // Stale WAL/shm sidecars from the old database must not shadow the
+    // restored file
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = std::path::PathBuf::from(format!("{}{}", database_path.display(), suffix));
+        let _ = std::fs::remove_file(sidecar);
+    }
+    std::fs::copy(&path, &database_path)?;
+
+    println!("✅ Restored {} ({} commands)", database_path.display(), commands);
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! Device registry
+//!
+//! Every host whose commands reach this database (directly or via sync)
+//! shows up in `tb devices` with its activity. Renaming attaches a
+//! friendly label ("old thinkpad") and retiring hides hosts that will
+//! never record again, so years-old laptop hostnames stop cluttering
+//! the list.
+
+use anyhow::Result;
+use sqlx::Row;
+
+use crate::OutputFormat;
+
+use super::create_storage;
+
+/// Lists hosts seen in history with labels, activity, and counts.
+/// Retired devices only show with `all`.
+pub async fn device_list(all: bool, format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let rows = sqlx::query(
+        "SELECT c.hostname, d.label, COALESCE(d.retired, 0) AS retired,
+                COUNT(*) AS total, MAX(c.timestamp) AS last_seen
+         FROM commands c LEFT JOIN devices d ON d.hostname = c.hostname
+         GROUP BY c.hostname ORDER BY last_seen DESC",
+    )
+    .fetch_all(storage.pool())
+    .await?;
+
+    let this_host = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut hidden = 0usize;
+    let mut entries = Vec::new();
+    for row in &rows {
+        let retired: i64 = row.get("retired");
+        if retired != 0 && !all {
+            hidden += 1;
+            continue;
+        }
+        entries.push((
+            row.get::<String, _>("hostname"),
+            row.get::<Option<String>, _>("label"),
+            retired != 0,
+            row.get::<i64, _>("total"),
+            row.get::<String, _>("last_seen"),
+        ));
+    }
+
+    if entries.is_empty() && hidden == 0 {
+        println!("No devices yet — record some commands first");
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = entries
+                .iter()
+                .map(|(hostname, label, retired, total, last_seen)| {
+                    serde_json::json!({
+                        "hostname": hostname,
+                        "label": label,
+                        "retired": retired,
+                        "commands": total,
+                        "last_seen": last_seen,
+                        "this_machine": *hostname == this_host,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        _ => {
+            println!("💻 Devices ({}):", entries.len());
+            for (hostname, label, retired, total, last_seen) in entries {
+                let name = match &label {
+                    Some(label) => format!("{} ({})", label, hostname),
+                    None => hostname.clone(),
+                };
+                let mut notes = Vec::new();
+                if hostname == this_host {
+                    notes.push("this machine");
+                }
+                if retired {
+                    notes.push("retired");
+                }
+                let notes = if notes.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", notes.join(", "))
+                };
+                println!(
+                    "   {} — {} commands, last active {}{}",
+                    name,
+                    total,
+                    &last_seen[..10.min(last_seen.len())],
+                    notes,
+                );
+            }
+            if hidden > 0 {
+                println!("   ({} retired device(s) hidden — use --all)", hidden);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Attaches a friendly label to a hostname.
+pub async fn device_rename(hostname: String, label: String) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let known: i64 = sqlx::query("SELECT COUNT(*) AS n FROM commands WHERE hostname = ?")
+        .bind(&hostname)
+        .fetch_one(storage.pool())
+        .await?
+        .get("n");
+    if known == 0 {
+        println!("⚠️  No commands from '{}' yet — labelling it anyway", hostname);
+    }
+
+    sqlx::query(
+        "INSERT INTO devices (hostname, label) VALUES (?1, ?2)
+         ON CONFLICT(hostname) DO UPDATE SET label = excluded.label",
+    )
+    .bind(&hostname)
+    .bind(&label)
+    .execute(storage.pool())
+    .await?;
+
+    println!("✅ {} is now labelled '{}'", hostname, label);
+    Ok(())
+}
+
+/// Marks a hostname as retired (or active again with `undo`). Retired
+/// devices keep their history but disappear from the default listing.
+pub async fn device_retire(hostname: String, undo: bool) -> Result<()> {
+    let storage = create_storage().await?;
+
+    sqlx::query(
+        "INSERT INTO devices (hostname, retired) VALUES (?1, ?2)
+         ON CONFLICT(hostname) DO UPDATE SET retired = excluded.retired",
+    )
+    .bind(&hostname)
+    .bind(if undo { 0i64 } else { 1i64 })
+    .execute(storage.pool())
+    .await?;
+
+    if undo {
+        println!("✅ {} is active again", hostname);
+    } else {
+        println!("✅ {} retired — its history stays, the listing clears up", hostname);
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! Failure diagnosis pipeline
+//!
+//! `tb diagnose <id>` (or `--last-failure`) layers local heuristics —
+//! the recovery knowledge base, exit-code semantics, recent related
+//! commands — and optionally an AI provider on top. The resulting
+//! diagnosis is stored with the command for later reference.
+
+use anyhow::Result;
+use chrono::Utc;
+use termbrain_core::diagnosis::{exit_code_meaning, known_recovery};
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use uuid::Uuid;
+
+use crate::OutputFormat;
+
+use super::{create_repo, create_storage};
+
+/// How far back to look for related commands around the failure.
+const RELATED_WINDOW: usize = 200;
+/// How many related commands make it into the diagnosis.
+const RELATED_LIMIT: usize = 5;
+
+/// Diagnoses a failed command and stores the structured result.
+pub async fn diagnose_command(
+    id: Option<String>,
+    last_failure: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let command = match (id, last_failure) {
+        (Some(id), _) => {
+            let id = id.parse::<Uuid>()
+                .map_err(|_| anyhow::anyhow!("Invalid command id: {}", id))?;
+            repo.find_by_id(&id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No command with id {}", id))?
+        }
+        (None, true) => repo
+            .find_recent(RELATED_WINDOW)
+            .await?
+            .into_iter()
+            .find(|cmd| cmd.exit_code != 0)
+            .ok_or_else(|| anyhow::anyhow!("No recent failures found — nothing to diagnose"))?,
+        (None, false) => {
+            return Err(anyhow::anyhow!(
+                "Specify a command id or --last-failure (find ids with: tb history)"
+            ));
+        }
+    };
+
+    if command.exit_code == 0 {
+        println!("✅ That command succeeded (exit 0) — nothing to diagnose");
+        return Ok(());
+    }
+
+    // Layer 1: knowledge base
+    let kb_advice = known_recovery(&command);
+
+    // Layer 2: exit-code semantics
+    let exit_meaning = exit_code_meaning(command.exit_code);
+
+    // Layer 3: output is not captured by shell integration today
+    // Layer 4: related recent commands (same tool, near in time)
+    let related: Vec<Command> = repo
+        .find_recent(RELATED_WINDOW)
+        .await?
+        .into_iter()
+        .filter(|cmd| cmd.id != command.id && cmd.parsed_command == command.parsed_command)
+        .take(RELATED_LIMIT)
+        .collect();
+
+    // Layer 5: AI analysis, when configured and allowed
+    let ai_analysis = ai_layer(&command, kb_advice, exit_meaning, &related, &repo).await?;
+
+    let summary = kb_advice
+        .or(exit_meaning)
+        .map(str::to_string)
+        .or_else(|| ai_analysis.as_ref().map(|a| a.lines().next().unwrap_or("").to_string()))
+        .unwrap_or_else(|| format!("exited with code {}", command.exit_code));
+
+    let layers = serde_json::json!({
+        "knowledge_base": kb_advice,
+        "exit_code": {
+            "code": command.exit_code,
+            "meaning": exit_meaning,
+        },
+        "related_commands": related
+            .iter()
+            .map(|cmd| serde_json::json!({
+                "id": cmd.id,
+                "command": cmd.raw,
+                "exit_code": cmd.exit_code,
+                "timestamp": cmd.timestamp,
+            }))
+            .collect::<Vec<_>>(),
+        "ai_analysis": ai_analysis,
+    });
+
+    sqlx::query(
+        "INSERT INTO diagnoses (id, command_id, summary, layers, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(command.id.to_string())
+    .bind(&summary)
+    .bind(layers.to_string())
+    .bind(Utc::now().to_rfc3339())
+    .execute(storage.pool())
+    .await?;
+
+    match format {
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "command": command.raw,
+                "command_id": command.id,
+                "summary": summary,
+                "layers": layers,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        _ => {
+            println!("🩺 Diagnosing: {} (exit {})\n", command.raw, command.exit_code);
+            if let Some(meaning) = exit_meaning {
+                println!("   Exit code {}: {}", command.exit_code, meaning);
+            }
+            if let Some(advice) = kb_advice {
+                println!("   Known recovery: {}", advice);
+            }
+            if !related.is_empty() {
+                println!("   Related recent {} commands:", command.parsed_command);
+                for cmd in &related {
+                    println!("      {} (exit {})", cmd.raw, cmd.exit_code);
+                }
+            }
+            if let Some(analysis) = &ai_analysis {
+                println!("\n   🧠 {}", analysis);
+            }
+            println!("\n💾 Diagnosis stored with command {}", command.id);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "ai")]
+async fn ai_layer(
+    command: &Command,
+    kb_advice: Option<&str>,
+    exit_meaning: Option<&str>,
+    related: &[Command],
+    repo: &impl CommandRepository,
+) -> Result<Option<String>> {
+    use termbrain_core::ai::render_tool_instructions;
+
+    let config = crate::config::Config::load()?;
+    let Some(provider) = crate::ai::SubprocessProvider::from_config(&config) else {
+        return Ok(None);
+    };
+
+    let mut prompt = format!(
+        "You are TermBrain, diagnosing a failed shell command from the user's \
+         own history. Explain the most likely root cause and a concrete fix \
+         in a few sentences.\n\nFailed command: {}\nDirectory: {}\nExit code: {}\n",
+        command.raw, command.working_directory, command.exit_code
+    );
+    if let Some(meaning) = exit_meaning {
+        prompt.push_str(&format!("Exit code meaning: {}\n", meaning));
+    }
+    if let Some(advice) = kb_advice {
+        prompt.push_str(&format!("Known recovery for this shape: {}\n", advice));
+    }
+    if !related.is_empty() {
+        prompt.push_str("Recent related commands:\n");
+        for cmd in related {
+            prompt.push_str(&format!("- {} (exit {})\n", cmd.raw, cmd.exit_code));
+        }
+    }
+    prompt.push_str(&render_tool_instructions(&super::ask::history_tools()));
+
+    let analysis = super::ask::complete_with_tools(&provider, repo, prompt).await?;
+    Ok(Some(analysis))
+}
+
+#[cfg(not(feature = "ai"))]
+async fn ai_layer(
+    _command: &Command,
+    _kb_advice: Option<&str>,
+    _exit_meaning: Option<&str>,
+    _related: &[Command],
+    _repo: &impl CommandRepository,
+) -> Result<Option<String>> {
+    Ok(None)
+}
@@ -0,0 +1,76 @@
+//! Shell-startup digest
+//!
+//! `tb digest generate` precomputes one daily insight into
+//! `~/.termbrain/digest.txt`; the opt-in shell snippet prints that file
+//! at most once per day without ever invoking `tb`, keeping shell
+//! startup well under the latency budget. Run generate from cron or any
+//! scheduler.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::path::PathBuf;
+use termbrain_core::digest::daily_insight;
+use termbrain_core::domain::repositories::CommandRepository;
+
+use super::{create_repo, create_storage};
+
+/// Where the precomputed insight lives. The first line is the
+/// generation date; the shell snippet uses it for rate limiting.
+fn digest_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".termbrain")
+        .join("digest.txt")
+}
+
+/// Computes today's insight and caches it for the shell snippet. With
+/// nothing worth saying, any stale cache is removed so the shell prints
+/// nothing.
+pub async fn generate_digest() -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let now = Utc::now();
+    let commands = repo
+        .find_by_time_range(now - chrono::Duration::days(1), now)
+        .await?;
+
+    let path = digest_path();
+    match daily_insight(&commands, now) {
+        Some(insight) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, format!("{}\n💡 {}\n", now.format("%Y-%m-%d"), insight))?;
+            println!("💡 {}", insight);
+        }
+        None => {
+            let _ = std::fs::remove_file(&path);
+            println!("Nothing digest-worthy in the last day");
+        }
+    }
+    Ok(())
+}
+
+/// Prints the cached insight if it was generated today; silent
+/// otherwise, so it is safe to call from shell startup. Holds its
+/// tongue entirely during a focus session.
+pub async fn show_digest() -> Result<()> {
+    // Mid-flow, say nothing — the cached insight and anything queued
+    // during focus both surface at the next break instead.
+    if super::focus::deep_focus_now().await {
+        return Ok(());
+    }
+    super::focus::flush_deferred();
+
+    let Ok(content) = std::fs::read_to_string(digest_path()) else {
+        return Ok(());
+    };
+    let mut lines = content.lines();
+    if lines.next() == Some(&Utc::now().format("%Y-%m-%d").to_string()) {
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! Health check and crash recovery
+//!
+//! `tb doctor` runs the startup recovery paths explicitly and reports
+//! what each one found: records re-queued from a flush that died
+//! mid-drain, journaled commands waiting for the database, session
+//! events replayed, stale sessions closed, and a quick page-integrity
+//! verdict. Everything here also runs silently on normal database
+//! opens — doctor exists to make the recovery visible.
+
+use anyhow::Result;
+use sqlx::Row;
+use termbrain_storage::sqlite::SqliteStorage;
+
+use crate::config::Config;
+
+use super::{guest, journal, sessions};
+
+pub async fn run_doctor() -> Result<()> {
+    println!("🩺 TermBrain health check");
+
+    // Spool and journal first, so everything below sees the full
+    // record stream
+    let respooled = journal::recover_stale_spool()?;
+    if respooled > 0 {
+        println!("   📥 Re-queued {} record(s) from a crashed flush", respooled);
+    }
+    let pending = journal::pending_records();
+    if pending > 0 {
+        super::flush_journal().await?;
+    } else {
+        println!("   ✅ Record journal: empty");
+    }
+
+    // Open the database directly — create_storage would run the
+    // session recovery silently, and doctor wants the counts
+    let config = Config::load()?;
+    let database_path = if guest::guest_session_active() {
+        guest::guest_db_path()
+    } else {
+        config.database_path.clone()
+    };
+    if let Some(parent) = database_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let storage = SqliteStorage::new(&database_path).await?;
+    storage.ensure_schema().await?;
+
+    let (replayed, closed) = sessions::recover_sessions(&storage).await?;
+    if replayed > 0 {
+        println!("   📥 Replayed {} journaled session event(s)", replayed);
+    } else {
+        println!("   ✅ Session journal: empty");
+    }
+    if closed > 0 {
+        println!("   🖥️  Closed {} stale session(s) abandoned without an end hook", closed);
+    }
+    let open: i64 = sqlx::query("SELECT COUNT(*) AS n FROM sessions WHERE end_time IS NULL")
+        .fetch_one(storage.pool())
+        .await?
+        .get("n");
+    println!("   🖥️  {} session(s) currently open", open);
+
+    let verdict: String = sqlx::query("PRAGMA quick_check")
+        .fetch_one(storage.pool())
+        .await?
+        .get(0);
+    if verdict == "ok" {
+        println!("   ✅ Page integrity (quick check): ok");
+    } else {
+        println!("   ❌ Page integrity (quick check): {}", verdict);
+    }
+
+    println!();
+    println!("💡 'tb db check' runs the deep integrity and reference checks");
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Bulk edit / retag
+//!
+//! `tb edit --where 'dir:~/oldproject' --set project=archive --add-tag legacy`
+//! enriches historical records at scale: matching commands get their
+//! extras updated in one transaction, with --dry-run previewing the
+//! change first.
+
+use anyhow::Result;
+use serde_json::Value;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+
+use super::{create_repo, create_storage, parse_extras};
+
+/// A parsed `--where` filter. One criterion per invocation keeps the
+/// syntax predictable; combine runs for intersections.
+enum WhereFilter {
+    /// `dir:PATH` — working directory equals PATH or is under it.
+    Directory(String),
+    /// `cmd:NAME` — parsed command equals NAME.
+    Program(String),
+    /// `source:NAME` — provenance matches, with the same prefix
+    /// semantics as `tb history --source`.
+    Source(String),
+    /// Anything else — substring of the raw command line.
+    Substring(String),
+}
+
+impl WhereFilter {
+    fn parse(clause: &str) -> Self {
+        match clause.split_once(':') {
+            Some(("dir", path)) => {
+                let home = dirs::home_dir().unwrap_or_default();
+                let path = match path.strip_prefix("~/") {
+                    Some(rest) => home.join(rest).display().to_string(),
+                    None if path == "~" => home.display().to_string(),
+                    None => path.to_string(),
+                };
+                Self::Directory(path)
+            }
+            Some(("cmd", name)) => Self::Program(name.to_string()),
+            Some(("source", name)) => Self::Source(name.to_string()),
+            _ => Self::Substring(clause.to_string()),
+        }
+    }
+
+    fn matches(&self, command: &Command) -> bool {
+        match self {
+            Self::Directory(dir) => {
+                command.working_directory == *dir
+                    || command.working_directory.starts_with(&format!("{}/", dir))
+            }
+            Self::Program(name) => command.parsed_command == *name,
+            Self::Source(name) => {
+                command.source == *name || command.source.starts_with(&format!("{}:", name))
+            }
+            Self::Substring(text) => command.raw.contains(text.as_str()),
+        }
+    }
+}
+
+/// Applies metadata edits to every command matching `--where`. `--set`
+/// pairs overwrite extras keys; `--add-tag` appends to the "tags" array
+/// in extras. With `dry_run` the matches and changes are printed but
+/// nothing is written.
+pub async fn bulk_edit(
+    where_clause: String,
+    set: Vec<String>,
+    add_tag: Vec<String>,
+    dry_run: bool,
+) -> Result<()> {
+    if set.is_empty() && add_tag.is_empty() {
+        anyhow::bail!("Nothing to change: pass --set KEY=VALUE and/or --add-tag TAG");
+    }
+
+    let sets = parse_extras(&set)?;
+    let filter = WhereFilter::parse(&where_clause);
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let matched: Vec<Command> = repo
+        .find_recent(i64::MAX as usize)
+        .await?
+        .into_iter()
+        .filter(|c| filter.matches(c))
+        .collect();
+
+    if matched.is_empty() {
+        println!("No commands match '{}'", where_clause);
+        return Ok(());
+    }
+
+    let mut updates = Vec::with_capacity(matched.len());
+    for command in &matched {
+        let mut extras = command.extras.clone();
+        for (key, value) in &sets {
+            extras.insert(key.clone(), value.clone());
+        }
+        if !add_tag.is_empty() {
+            let tags = extras
+                .entry("tags".to_string())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(tags) = tags {
+                for tag in &add_tag {
+                    if !tags.iter().any(|t| t.as_str() == Some(tag)) {
+                        tags.push(Value::String(tag.clone()));
+                    }
+                }
+            }
+        }
+        updates.push((command.id, extras));
+    }
+
+    if dry_run {
+        println!("🔍 Dry run — {} commands match '{}':", matched.len(), where_clause);
+        for (command, (_, extras)) in matched.iter().zip(&updates).take(20) {
+            println!(
+                "  {} → extras: {}",
+                command.raw,
+                serde_json::to_string(extras)?
+            );
+        }
+        if matched.len() > 20 {
+            println!("  ... and {} more", matched.len() - 20);
+        }
+        println!("Re-run without --dry-run to apply");
+        return Ok(());
+    }
+
+    repo.update_extras_batch(&updates).await?;
+    println!("✅ Updated {} commands", updates.len());
+    Ok(())
+}
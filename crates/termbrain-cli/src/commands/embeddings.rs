@@ -0,0 +1,110 @@
+//! Embedding persistence
+//!
+//! Embeddings are computed once — on record, or in bulk via
+//! `tb embeddings rebuild` — and stored as BLOBs, so a semantic search
+//! only embeds the query.
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::Row;
+use termbrain_core::ai::EmbeddingProvider;
+use termbrain_core::embedding::encode_vector;
+use termbrain_storage::sqlite::SqliteStorage;
+
+use crate::config::Config;
+use crate::embedder::SubprocessEmbedder;
+
+use super::create_storage;
+
+/// Embeds and stores one command's text. Failures are reported, not
+/// fatal — recording must never be blocked by an embedding provider.
+pub(super) async fn embed_command(
+    storage: &SqliteStorage,
+    config: &Config,
+    command_id: &str,
+    raw: &str,
+) {
+    let Some(embedder) = SubprocessEmbedder::from_config(config) else {
+        return;
+    };
+
+    match embedder.embed(raw).await {
+        Ok(vector) => {
+            let result = sqlx::query(
+                "INSERT OR REPLACE INTO embeddings (command_id, vector, model, created_at) VALUES (?1, ?2, ?3, ?4)",
+            )
+            .bind(command_id)
+            .bind(encode_vector(&vector))
+            .bind(config.embedding_provider.as_deref().unwrap_or_default())
+            .bind(Utc::now().to_rfc3339())
+            .execute(storage.pool())
+            .await;
+            if let Err(e) = result {
+                eprintln!("Warning: failed to store embedding: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Warning: embedding failed: {}", e),
+    }
+}
+
+/// Backfills embeddings for every command that doesn't have one.
+pub async fn rebuild_embeddings() -> Result<()> {
+    let config = Config::load()?;
+    let Some(embedder) = SubprocessEmbedder::from_config(&config) else {
+        return Err(anyhow::anyhow!(
+            "No embedding provider configured. Set TERMBRAIN_EMBEDDING_PROVIDER to a command that reads text on stdin and prints a JSON float array"
+        ));
+    };
+
+    let storage = create_storage().await?;
+
+    let rows = sqlx::query(
+        "SELECT id, raw FROM commands WHERE id NOT IN (SELECT command_id FROM embeddings) ORDER BY timestamp",
+    )
+    .fetch_all(storage.pool())
+    .await?;
+
+    if rows.is_empty() {
+        println!("All commands already have embeddings");
+        return Ok(());
+    }
+
+    println!("🧮 Embedding {} commands...", rows.len());
+    let mut done = 0;
+    for row in rows {
+        let id: String = row.get("id");
+        let raw: String = row.get("raw");
+
+        let vector = embedder.embed(&raw).await?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO embeddings (command_id, vector, model, created_at) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(&id)
+        .bind(encode_vector(&vector))
+        .bind(config.embedding_provider.as_deref().unwrap_or_default())
+        .bind(Utc::now().to_rfc3339())
+        .execute(storage.pool())
+        .await?;
+
+        done += 1;
+        if done % 100 == 0 {
+            println!("   {} embedded...", done);
+        }
+    }
+
+    println!("✅ Embedded {} commands", done);
+    Ok(())
+}
+
+/// Embeds a search query if a provider is configured; `None` means the
+/// caller should fall back to lexical ranking.
+pub(super) async fn embed_query(config: &Config, query: &str) -> Option<Vec<f32>> {
+    let embedder = SubprocessEmbedder::from_config(config)?;
+    match embedder.embed(query).await {
+        Ok(vector) => Some(vector),
+        Err(e) => {
+            eprintln!("Warning: query embedding failed, falling back to lexical ranking: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Failure analytics
+//!
+//! `tb errors` aggregates where commands fail: the most frequent
+//! failures with their exit-code distribution, mean time to recovery
+//! (from the error-recovery pairing `tb explain` uses per failure),
+//! and flaky commands whose outcome keeps flipping.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use termbrain_core::diagnosis::{flaky_commands, recovery_times};
+use termbrain_core::domain::{Command, CommandRepository};
+
+use crate::OutputFormat;
+
+use super::{create_repo, create_storage};
+
+/// Runs at least this many times before a command can be called flaky.
+const FLAKY_MIN_RUNS: usize = 4;
+
+/// Per-command rollup of the window's failures.
+struct FailureSummary {
+    parsed_command: String,
+    runs: usize,
+    failures: usize,
+    /// exit code → occurrences, most frequent first.
+    exit_codes: Vec<(i32, usize)>,
+}
+
+/// Shows failure analytics over the last `days` days, `top` commands
+/// per section.
+pub async fn show_errors(days: u32, top: usize, format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let end = chrono::Utc::now();
+    let start = end - chrono::Duration::days(days as i64);
+    // find_by_time_range returns newest first; the detectors read
+    // history forward
+    let mut commands = repo.find_by_time_range(start, end).await?;
+    commands.reverse();
+
+    let failures = summarize_failures(&commands, top);
+    if failures.is_empty() {
+        println!("No failing commands recorded in the last {} days", days);
+        return Ok(());
+    }
+
+    let recoveries = recovery_times(&commands);
+    let total_failures: usize = failures.iter().map(|f| f.failures).sum();
+    let mean_recovery = if recoveries.is_empty() {
+        None
+    } else {
+        Some(recoveries.iter().map(|(_, s)| s).sum::<i64>() / recoveries.len() as i64)
+    };
+    let per_command_recovery = mean_recovery_per_command(&recoveries);
+    let flaky = flaky_commands(&commands, FLAKY_MIN_RUNS);
+
+    if matches!(format, OutputFormat::Json) {
+        let report = serde_json::json!({
+            "period_days": days,
+            "failures": failures.iter().map(|f| serde_json::json!({
+                "command": f.parsed_command,
+                "failures": f.failures,
+                "runs": f.runs,
+                "failure_rate": f.failures as f64 / f.runs as f64,
+                "exit_codes": f.exit_codes.iter()
+                    .map(|(code, n)| (code.to_string(), n))
+                    .collect::<HashMap<_, _>>(),
+            })).collect::<Vec<_>>(),
+            "recovery": {
+                "mean_seconds": mean_recovery,
+                "recovered_failures": recoveries.len(),
+                "per_command": per_command_recovery.iter()
+                    .map(|(command, seconds, n)| serde_json::json!({
+                        "command": command,
+                        "mean_seconds": seconds,
+                        "recoveries": n,
+                    })).collect::<Vec<_>>(),
+            },
+            "flaky": flaky.iter().map(|f| serde_json::json!({
+                "command": f.parsed_command,
+                "runs": f.runs,
+                "failures": f.failures,
+                "flips": f.flips,
+            })).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("💥 Failure analytics — last {} days", days);
+    println!();
+    println!("   Most frequent failures:");
+    for summary in &failures {
+        let distribution = summary
+            .exit_codes
+            .iter()
+            .map(|(code, n)| format!("{}×{}", code, n))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!(
+            "   {:<20} {:>4} of {:>4} runs failed ({:>3.0}%)  exit codes: {}",
+            summary.parsed_command,
+            summary.failures,
+            summary.runs,
+            summary.failures as f64 / summary.runs as f64 * 100.0,
+            distribution,
+        );
+    }
+
+    println!();
+    match mean_recovery {
+        Some(seconds) => {
+            println!(
+                "💊 Mean time to recovery: {} ({} of {} failures later succeeded)",
+                human_duration(seconds),
+                recoveries.len(),
+                total_failures,
+            );
+            for (command, seconds, n) in per_command_recovery.iter().take(top) {
+                println!(
+                    "   {:<20} {:>8} mean over {} recoveries",
+                    command,
+                    human_duration(*seconds),
+                    n,
+                );
+            }
+        }
+        None => println!("💊 No failure in the window has recovered yet"),
+    }
+
+    if !flaky.is_empty() {
+        println!();
+        println!("🎲 Flaky commands (outcome keeps flipping):");
+        for entry in flaky.iter().take(top) {
+            println!(
+                "   {:<20} flipped {} times across {} runs ({} failures)",
+                entry.parsed_command, entry.flips, entry.runs, entry.failures,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// The `top` commands with the most failures, with their exit-code
+/// distribution.
+fn summarize_failures(commands: &[Command], top: usize) -> Vec<FailureSummary> {
+    let mut by_command: HashMap<&str, (usize, usize, HashMap<i32, usize>)> = HashMap::new();
+    for command in commands {
+        let entry = by_command
+            .entry(command.parsed_command.as_str())
+            .or_default();
+        entry.0 += 1;
+        if command.exit_code != 0 {
+            entry.1 += 1;
+            *entry.2.entry(command.exit_code).or_default() += 1;
+        }
+    }
+
+    let mut summaries: Vec<FailureSummary> = by_command
+        .into_iter()
+        .filter(|(_, (_, failures, _))| *failures > 0)
+        .map(|(parsed_command, (runs, failures, codes))| {
+            let mut exit_codes: Vec<(i32, usize)> = codes.into_iter().collect();
+            exit_codes.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+            FailureSummary {
+                parsed_command: parsed_command.to_string(),
+                runs,
+                failures,
+                exit_codes,
+            }
+        })
+        .collect();
+    summaries.sort_by_key(|s| std::cmp::Reverse(s.failures));
+    summaries.truncate(top);
+    summaries
+}
+
+/// Mean recovery seconds per command, slowest first.
+fn mean_recovery_per_command(recoveries: &[(String, i64)]) -> Vec<(String, i64, usize)> {
+    let mut by_command: HashMap<&str, (i64, usize)> = HashMap::new();
+    for (command, seconds) in recoveries {
+        let entry = by_command.entry(command.as_str()).or_default();
+        entry.0 += seconds;
+        entry.1 += 1;
+    }
+    let mut means: Vec<(String, i64, usize)> = by_command
+        .into_iter()
+        .map(|(command, (total, n))| (command.to_string(), total / n as i64, n))
+        .collect();
+    means.sort_by_key(|(_, seconds, _)| std::cmp::Reverse(*seconds));
+    means
+}
+
+fn human_duration(seconds: i64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60)
+    }
+}
@@ -0,0 +1,46 @@
+//! Event schema inspection
+//!
+//! `tb events schema` prints the JSON Schema documents for the
+//! versioned events termbrain emits (webhooks, mirrors, the HTTP API),
+//! so integrations can validate against the contract instead of
+//! guessing at payload shapes.
+
+use anyhow::Result;
+use termbrain_core::events::{schema_for, EventKind, SCHEMA_VERSION};
+
+/// Prints the JSON Schema for one event type, or all of them.
+pub fn events_schema(event: Option<String>) -> Result<()> {
+    match event {
+        Some(name) => {
+            let kind = EventKind::all()
+                .iter()
+                .find(|kind| kind.name() == name)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown event '{}' — known: {}",
+                        name,
+                        EventKind::all()
+                            .iter()
+                            .map(|kind| kind.name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })?;
+            println!("{}", serde_json::to_string_pretty(&schema_for(*kind))?);
+        }
+        None => {
+            let schemas: Vec<_> = EventKind::all()
+                .iter()
+                .map(|kind| schema_for(*kind))
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "schema_version": SCHEMA_VERSION,
+                    "events": schemas,
+                }))?
+            );
+        }
+    }
+    Ok(())
+}
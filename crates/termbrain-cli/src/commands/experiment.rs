@@ -0,0 +1,121 @@
+//! Self-run A/B experiments
+//!
+//! `tb experiment start "switch to pnpm"` marks a window; `tb
+//! experiment report` compares usage during it against the equally long
+//! window before it (failure rate, durations, volume) and renders a
+//! verdict from real recorded data.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::experiment::{usage_stats, verdict};
+use uuid::Uuid;
+
+use super::{create_repo, create_storage};
+
+/// Starts a named experiment. Only one may run at a time — the point is
+/// a clean before/during comparison.
+pub async fn experiment_start(name: String) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let running = sqlx::query("SELECT name FROM experiments WHERE ended_at IS NULL")
+        .fetch_optional(storage.pool())
+        .await?;
+    if let Some(row) = running {
+        anyhow::bail!(
+            "Experiment '{}' is already running — stop it first (tb experiment stop)",
+            row.get::<String, _>("name")
+        );
+    }
+
+    sqlx::query("INSERT INTO experiments (id, name, started_at) VALUES (?1, ?2, ?3)")
+        .bind(Uuid::new_v4().to_string())
+        .bind(&name)
+        .bind(Utc::now().to_rfc3339())
+        .execute(storage.pool())
+        .await?;
+
+    println!("🧪 Experiment '{}' started — work normally, then run 'tb experiment report'", name);
+    Ok(())
+}
+
+/// Ends the running experiment.
+pub async fn experiment_stop() -> Result<()> {
+    let storage = create_storage().await?;
+    let result = sqlx::query("UPDATE experiments SET ended_at = ?1 WHERE ended_at IS NULL")
+        .bind(Utc::now().to_rfc3339())
+        .execute(storage.pool())
+        .await?;
+
+    if result.rows_affected() == 0 {
+        println!("No experiment is running");
+    } else {
+        println!("🧪 Experiment stopped — 'tb experiment report' for the verdict");
+    }
+    Ok(())
+}
+
+/// Reports before-vs-during metrics for the named experiment, or the
+/// most recent one.
+pub async fn experiment_report(name: Option<String>) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let row = match &name {
+        Some(name) => {
+            sqlx::query("SELECT name, started_at, ended_at FROM experiments WHERE name = ?")
+                .bind(name)
+                .fetch_optional(storage.pool())
+                .await?
+        }
+        None => {
+            sqlx::query("SELECT name, started_at, ended_at FROM experiments ORDER BY started_at DESC LIMIT 1")
+                .fetch_optional(storage.pool())
+                .await?
+        }
+    };
+    let Some(row) = row else {
+        match name {
+            Some(name) => anyhow::bail!("No experiment named '{}'", name),
+            None => anyhow::bail!("No experiments recorded — start one with 'tb experiment start <name>'"),
+        }
+    };
+
+    let name: String = row.get("name");
+    let started: DateTime<Utc> =
+        DateTime::parse_from_rfc3339(&row.get::<String, _>("started_at"))?.with_timezone(&Utc);
+    let ended: DateTime<Utc> = row
+        .get::<Option<String>, _>("ended_at")
+        .map(|s| DateTime::parse_from_rfc3339(&s).map(|d| d.with_timezone(&Utc)))
+        .transpose()?
+        .unwrap_or_else(Utc::now);
+
+    let window = ended - started;
+    let repo = create_repo(&storage);
+    let during = repo.find_by_time_range(started, ended).await?;
+    let before = repo.find_by_time_range(started - window, started).await?;
+
+    let before_stats = usage_stats(&before);
+    let during_stats = usage_stats(&during);
+
+    println!("🧪 Experiment '{}' ({} → {})", name, started.format("%Y-%m-%d %H:%M"), ended.format("%Y-%m-%d %H:%M"));
+    println!(
+        "   before: {} commands, {:.0}% failed, avg {:.0}ms",
+        before_stats.commands,
+        before_stats.failure_rate * 100.0,
+        before_stats.avg_duration_ms
+    );
+    println!(
+        "   during: {} commands, {:.0}% failed, avg {:.0}ms",
+        during_stats.commands,
+        during_stats.failure_rate * 100.0,
+        during_stats.avg_duration_ms
+    );
+
+    if before_stats.commands < 10 || during_stats.commands < 10 {
+        println!("⚖️  Too little data for a verdict (need 10+ commands on each side)");
+        return Ok(());
+    }
+    println!("⚖️  {}", verdict(&before_stats, &during_stats));
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Deep command inspector
+//!
+//! `tb explain <id>` gathers everything known about one recorded
+//! command into a single view: metadata, the session commands around
+//! it, how its duration compares with past runs of the same command,
+//! and — for failures — what eventually made the command succeed
+//! again. Accepts a command id (or unambiguous prefix) or a 1-based
+//! history index (1 = most recent).
+
+use anyhow::Result;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use uuid::Uuid;
+
+use super::{create_repo, create_storage};
+
+/// Explains one command, looked up by id prefix or history index.
+pub async fn explain_command(reference: String) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let command = resolve(&repo, storage.pool(), &reference).await?;
+
+    println!("🔍 {}", command.raw);
+    println!("   Id:        {}", command.id);
+    println!("   When:      {}", command.timestamp.format("%Y-%m-%d %H:%M:%S UTC"));
+    println!("   Directory: {}", command.working_directory);
+
+    if command.exit_code == 0 {
+        println!("   Exit code: 0");
+    } else {
+        println!("   Exit code: {} ❌", command.exit_code);
+    }
+    match repo
+        .duration_percentile(&command.parsed_command, command.duration_ms)
+        .await?
+    {
+        Some(percentile) if command.duration_ms > 0 => println!(
+            "   Duration:  {}ms (slower than {:.0}% of past '{}' runs)",
+            command.duration_ms,
+            percentile.min(99.0),
+            command.parsed_command
+        ),
+        _ => println!("   Duration:  {}ms", command.duration_ms),
+    }
+
+    println!(
+        "   Context:   {} · {}@{} · {} · session {}",
+        command.metadata.shell,
+        command.metadata.user,
+        command.metadata.hostname,
+        command.source,
+        command.session_id
+    );
+    if let Some(clock) = &command.logical_clock {
+        println!("   Clock:     {}", clock);
+    }
+    for (key, value) in &command.metadata.environment {
+        println!("   Env:       {}={}", key, value);
+    }
+    for (key, value) in &command.extras {
+        println!("   Extra:     {}={}", key, value);
+    }
+
+    let neighbors = repo.find_neighbors(&command, 3, 3).await?;
+    if !neighbors.is_empty() {
+        println!("\n📍 Session neighbors:");
+        let mut anchor_shown = false;
+        for neighbor in &neighbors {
+            if !anchor_shown && neighbor.timestamp > command.timestamp {
+                println!(" → {}", command.raw);
+                anchor_shown = true;
+            }
+            let marker = if neighbor.exit_code == 0 { " " } else { "✗" };
+            println!("  {} {}", marker, neighbor.raw);
+        }
+        if !anchor_shown {
+            println!(" → {}", command.raw);
+        }
+    }
+
+    if command.exit_code != 0 {
+        show_recovery(&repo, &command).await?;
+    }
+
+    Ok(())
+}
+
+/// Resolves a history index ("3" = third most recent) or an id prefix.
+async fn resolve(
+    repo: &dyn CommandRepository,
+    pool: &sqlx::SqlitePool,
+    reference: &str,
+) -> Result<Command> {
+    if let Ok(index) = reference.parse::<usize>() {
+        if index == 0 {
+            anyhow::bail!("History indexes start at 1 (the most recent command)");
+        }
+        let recent = repo.find_recent(index).await?;
+        return recent
+            .into_iter()
+            .nth(index - 1)
+            .ok_or_else(|| anyhow::anyhow!("Only {} commands recorded", index - 1));
+    }
+
+    let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM commands WHERE id LIKE ? || '%'")
+        .bind(reference)
+        .fetch_all(pool)
+        .await?;
+    match ids.as_slice() {
+        [] => anyhow::bail!("No command matches id '{}'", reference),
+        [id] => repo
+            .find_by_id(&Uuid::parse_str(id)?)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No command matches id '{}'", reference)),
+        many => anyhow::bail!(
+            "Ambiguous id '{}' matches {} commands — give more characters",
+            reference,
+            many.len()
+        ),
+    }
+}
+
+/// For a failed command: when the same command next succeeded, and what
+/// ran in between — usually the fix.
+async fn show_recovery(repo: &dyn CommandRepository, command: &Command) -> Result<()> {
+    let later = repo
+        .find_by_time_range(command.timestamp, chrono::Utc::now())
+        .await?;
+    // find_by_time_range returns newest first; recovery reads forward
+    let mut later: Vec<&Command> = later
+        .iter()
+        .filter(|c| c.id != command.id && c.timestamp >= command.timestamp)
+        .collect();
+    later.sort_by_key(|c| c.timestamp);
+
+    let Some(success_at) = later
+        .iter()
+        .position(|c| c.parsed_command == command.parsed_command && c.exit_code == 0)
+    else {
+        println!("\n💊 No later successful '{}' run recorded", command.parsed_command);
+        return Ok(());
+    };
+
+    println!("\n💊 Recovered after {} commands:", success_at);
+    for step in later.iter().take(success_at + 1).rev().take(6).rev() {
+        let marker = if step.exit_code == 0 { " " } else { "✗" };
+        println!("  {} {}", marker, step.raw);
+    }
+    Ok(())
+}
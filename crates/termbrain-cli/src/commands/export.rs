@@ -0,0 +1,494 @@
+//! Full history exports sized for multi-GB archives
+//!
+//! Commands stream out in stable (timestamp, id) order, one page at a
+//! time, optionally gzip/zstd-compressed and split into numbered parts.
+//! A manifest alongside the output records every finished part with its
+//! checksum, so an interrupted export resumes instead of starting over.
+//! Record filters (directory, semantic type, agent, exit code) are
+//! pushed into the SQL query, and `--redact-sensitive` scrubs likely
+//! secrets on the way out — together they make sanitized,
+//! project-scoped exports safe to hand to a teammate.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::{CommandFilter, CommandRepository};
+use termbrain_core::privacy::{category_tools, redact_secrets};
+
+use super::{create_repo, create_storage};
+use crate::{ExportCompression, ExportFormat};
+
+/// Records fetched from the database per round trip. Parts smaller than
+/// this still stream; parts larger never hold more than one page in
+/// memory.
+const PAGE_SIZE: usize = 5_000;
+
+/// Sidecar describing a (possibly in-progress) export. Written after
+/// every finished part so a crash loses at most one part of work.
+#[derive(Serialize, Deserialize)]
+struct ExportManifest {
+    format: String,
+    compression: Option<String>,
+    chunk_size: Option<usize>,
+    since: Option<String>,
+    until: Option<String>,
+    /// Canonical rendering of any record filters; absent on unfiltered
+    /// exports (and in manifests from before filters existed).
+    #[serde(default)]
+    filters: Option<String>,
+    complete: bool,
+    total_records: u64,
+    parts: Vec<ManifestPart>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestPart {
+    file: String,
+    records: u64,
+    sha256: String,
+}
+
+/// Everything about an export besides its destination and format.
+pub struct ExportOptions {
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub compress: Option<ExportCompression>,
+    pub chunk_size: Option<usize>,
+    pub filter: CommandFilter,
+    pub redact: bool,
+}
+
+pub async fn export_data(output: String, format: ExportFormat, options: ExportOptions) -> Result<()> {
+    let ExportOptions { since, until, compress, chunk_size, filter, redact } = options;
+    if chunk_size == Some(0) {
+        anyhow::bail!("--chunk-size must be at least 1");
+    }
+    if let Some(category) = &filter.semantic_type {
+        if category != "other" && category_tools(category).is_none() {
+            anyhow::bail!(
+                "Unknown semantic type '{}' — one of {}, or other",
+                category,
+                termbrain_core::privacy::CATEGORY_TOOLS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+    let start = match &since {
+        Some(s) => parse_time(s)?,
+        None => DateTime::<Utc>::MIN_UTC,
+    };
+    let end = match &until {
+        Some(s) => parse_time(s)?,
+        None => Utc::now(),
+    };
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    // Snapshot formats are written whole — no parts, no compression
+    if matches!(format, ExportFormat::Sqlite | ExportFormat::Parquet)
+        && (compress.is_some() || chunk_size.is_some())
+    {
+        anyhow::bail!("--compress and --chunk-size don't apply to sqlite/parquet exports");
+    }
+    if let ExportFormat::Sqlite = format {
+        if since.is_some() || until.is_some() {
+            anyhow::bail!("sqlite snapshots are full-fidelity copies; --since/--until don't apply");
+        }
+        if filter.is_active() || redact {
+            anyhow::bail!("sqlite snapshots are full-fidelity copies; filters and --redact-sensitive don't apply");
+        }
+        return export_sqlite_snapshot(&storage, &output).await;
+    }
+    if let ExportFormat::Parquet = format {
+        return export_parquet(&repo, &output, start, end, &filter, redact).await;
+    }
+
+    let manifest_path = format!("{}.manifest.json", output);
+    let filters = filter_signature(&filter, redact);
+    let mut manifest = load_or_start_manifest(&manifest_path, &format, &compress, chunk_size, &since, &until, &filters)?;
+    if manifest.complete {
+        println!("✅ Export already complete ({} records) — remove {} to redo it", manifest.total_records, manifest_path);
+        return Ok(());
+    }
+    if !manifest.parts.is_empty() {
+        println!("⏯️  Resuming export: {} parts ({} records) already done", manifest.parts.len(), manifest.total_records);
+    }
+
+    // Records already safely on disk from a previous run
+    let mut offset = manifest.total_records as usize;
+    loop {
+        let part_limit = chunk_size.unwrap_or(usize::MAX);
+        let part_path = part_file_path(&output, chunk_size.map(|_| manifest.parts.len() + 1), &compress);
+
+        let mut writer = open_writer(&part_path, &compress)?;
+        let mut written: u64 = 0;
+        if let ExportFormat::Csv = format {
+            writeln!(writer, "timestamp,command,directory,exit_code,duration_ms,session_id,source")?;
+        }
+        if let ExportFormat::Markdown = format {
+            writeln!(writer, "| Timestamp | Command | Directory | Exit |")?;
+            writeln!(writer, "|---|---|---|---|")?;
+        }
+        while (written as usize) < part_limit {
+            let page = PAGE_SIZE.min(part_limit - written as usize);
+            let mut commands = repo.find_filtered_paged(start, end, &filter, offset, page).await?;
+            if commands.is_empty() {
+                break;
+            }
+            if redact {
+                commands = commands.into_iter().map(redact_command).collect();
+            }
+            for command in &commands {
+                write_record(&mut writer, command, &format)?;
+            }
+            written += commands.len() as u64;
+            offset += commands.len();
+        }
+        writer.finish()?;
+
+        if written == 0 && !manifest.parts.is_empty() {
+            // Previous part ended exactly on the boundary — nothing left
+            std::fs::remove_file(&part_path).ok();
+            break;
+        }
+
+        manifest.parts.push(ManifestPart {
+            file: part_path.clone(),
+            records: written,
+            sha256: sha256_file(&part_path)?,
+        });
+        manifest.total_records += written;
+        save_manifest(&manifest_path, &manifest)?;
+        println!("   wrote {} ({} records)", part_path, written);
+
+        // A short part means the range is exhausted; no chunking means
+        // everything went into the one file
+        if chunk_size.is_none() || (written as usize) < chunk_size.unwrap() {
+            break;
+        }
+    }
+
+    manifest.complete = true;
+    save_manifest(&manifest_path, &manifest)?;
+    println!(
+        "✅ Exported {} records in {} part(s) — manifest: {}",
+        manifest.total_records,
+        manifest.parts.len(),
+        manifest_path
+    );
+    Ok(())
+}
+
+/// Loads a resumable manifest, or starts a fresh one. A manifest whose
+/// parameters differ from this invocation is discarded: mixing formats
+/// or ranges across parts would corrupt the export.
+fn load_or_start_manifest(
+    path: &str,
+    format: &ExportFormat,
+    compress: &Option<ExportCompression>,
+    chunk_size: Option<usize>,
+    since: &Option<String>,
+    until: &Option<String>,
+    filters: &Option<String>,
+) -> Result<ExportManifest> {
+    let fresh = ExportManifest {
+        format: format!("{:?}", format).to_lowercase(),
+        compression: compress.as_ref().map(compression_name),
+        chunk_size,
+        since: since.clone(),
+        until: until.clone(),
+        filters: filters.clone(),
+        complete: false,
+        total_records: 0,
+        parts: Vec::new(),
+    };
+
+    let Ok(data) = std::fs::read_to_string(path) else {
+        return Ok(fresh);
+    };
+    let existing: ExportManifest =
+        serde_json::from_str(&data).with_context(|| format!("Corrupt manifest {}", path))?;
+    if existing.format != fresh.format
+        || existing.compression != fresh.compression
+        || existing.chunk_size != fresh.chunk_size
+        || existing.since != fresh.since
+        || existing.until != fresh.until
+        || existing.filters != fresh.filters
+    {
+        println!("⚠️  Existing manifest used different options — starting over");
+        return Ok(fresh);
+    }
+    // Only trust parts whose checksum still matches
+    let mut verified = existing;
+    for (index, part) in verified.parts.iter().enumerate() {
+        if sha256_file(&part.file).map(|sum| sum != part.sha256).unwrap_or(true) {
+            println!("⚠️  {} missing or modified — re-exporting from part {}", part.file, index + 1);
+            verified.total_records = verified.parts[..index].iter().map(|p| p.records).sum();
+            verified.parts.truncate(index);
+            break;
+        }
+    }
+    Ok(verified)
+}
+
+/// Canonical one-line rendering of the active filters, used to detect
+/// a resume attempt with different filters. None when nothing is set,
+/// so unfiltered manifests stay byte-compatible with older ones.
+fn filter_signature(filter: &CommandFilter, redact: bool) -> Option<String> {
+    if !filter.is_active() && !redact {
+        return None;
+    }
+    Some(format!(
+        "directory={:?} type={:?} agent={:?} exit={:?} redact={}",
+        filter.directory, filter.semantic_type, filter.ai_agent, filter.exit_code, redact
+    ))
+}
+
+/// Applies the shared secret redaction to every exported field that can
+/// carry one. The captured environment can hold anything, so it is
+/// dropped outright rather than pattern-matched.
+fn redact_command(mut command: Command) -> Command {
+    command.raw = redact_secrets(&command.raw);
+    let arguments = command.arguments.join(" ");
+    command.arguments = redact_secrets(&arguments)
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+    command.metadata.environment.clear();
+    command
+}
+
+fn save_manifest(path: &str, manifest: &ExportManifest) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+fn part_file_path(output: &str, part: Option<usize>, compress: &Option<ExportCompression>) -> String {
+    let base = match part {
+        Some(n) => format!("{}.part-{:04}", output, n),
+        None => output.to_string(),
+    };
+    match compress {
+        Some(ExportCompression::Gzip) => format!("{}.gz", base),
+        Some(ExportCompression::Zstd) => format!("{}.zst", base),
+        None => base,
+    }
+}
+
+fn compression_name(c: &ExportCompression) -> String {
+    match c {
+        ExportCompression::Gzip => "gzip".to_string(),
+        ExportCompression::Zstd => "zstd".to_string(),
+    }
+}
+
+/// A part writer that knows how to finalize its compression stream.
+enum PartWriter {
+    Plain(std::io::BufWriter<std::fs::File>),
+    Gzip(flate2::write::GzEncoder<std::fs::File>),
+    Zstd(zstd::Encoder<'static, std::fs::File>),
+}
+
+impl Write for PartWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            PartWriter::Plain(w) => w.write(buf),
+            PartWriter::Gzip(w) => w.write(buf),
+            PartWriter::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            PartWriter::Plain(w) => w.flush(),
+            PartWriter::Gzip(w) => w.flush(),
+            PartWriter::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+impl PartWriter {
+    fn finish(self) -> Result<()> {
+        match self {
+            PartWriter::Plain(mut w) => w.flush()?,
+            PartWriter::Gzip(w) => {
+                w.finish()?;
+            }
+            PartWriter::Zstd(w) => {
+                w.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn open_writer(path: &str, compress: &Option<ExportCompression>) -> Result<PartWriter> {
+    let file = std::fs::File::create(path)?;
+    Ok(match compress {
+        None => PartWriter::Plain(std::io::BufWriter::new(file)),
+        Some(ExportCompression::Gzip) => {
+            PartWriter::Gzip(flate2::write::GzEncoder::new(file, flate2::Compression::default()))
+        }
+        Some(ExportCompression::Zstd) => PartWriter::Zstd(zstd::Encoder::new(file, 0)?),
+    })
+}
+
+fn write_record(writer: &mut impl Write, command: &Command, format: &ExportFormat) -> Result<()> {
+    match format {
+        // JSON Lines: one self-contained object per line, so parts
+        // concatenate and stream cleanly
+        ExportFormat::Json => writeln!(writer, "{}", serde_json::to_string(command)?)?,
+        ExportFormat::Csv => writeln!(
+            writer,
+            "{},{},{},{},{},{},{}",
+            command.timestamp.to_rfc3339(),
+            csv_escape(&command.raw),
+            csv_escape(&command.working_directory),
+            command.exit_code,
+            command.duration_ms,
+            command.session_id,
+            command.source
+        )?,
+        ExportFormat::Markdown => writeln!(
+            writer,
+            "| {} | `{}` | {} | {} |",
+            command.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            command.raw.replace('|', "\\|").replace('`', "'"),
+            command.working_directory.replace('|', "\\|"),
+            command.exit_code
+        )?,
+        // Snapshot formats return before the streaming writer runs
+        ExportFormat::Sqlite | ExportFormat::Parquet => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Full-fidelity .db snapshot via VACUUM INTO (SQLite's online backup —
+/// safe while recording), for moving complete histories between
+/// machines.
+async fn export_sqlite_snapshot(
+    storage: &termbrain_storage::sqlite::SqliteStorage,
+    output: &str,
+) -> Result<()> {
+    if std::path::Path::new(output).exists() {
+        anyhow::bail!("{} already exists — refusing to overwrite", output);
+    }
+    sqlx::query("VACUUM INTO ?")
+        .bind(output)
+        .execute(storage.pool())
+        .await?;
+    let size = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    println!(
+        "✅ Exported sqlite snapshot to {} ({:.1} MB)",
+        output,
+        size as f64 / 1_048_576.0
+    );
+    Ok(())
+}
+
+/// Parquet export for DuckDB/pandas analysis. Rows stage as CSV, then
+/// the duckdb CLI rewrites them columnar — in line with how other
+/// external tools are integrated.
+async fn export_parquet(
+    repo: &termbrain_storage::sqlite::SqliteCommandRepository,
+    output: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    filter: &CommandFilter,
+    redact: bool,
+) -> Result<()> {
+    let staging = format!("{}.staging.csv", output);
+    let mut total = 0usize;
+    {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&staging)?);
+        writeln!(
+            file,
+            "id,raw,parsed_command,working_directory,exit_code,duration_ms,timestamp,session_id,source,shell,user,hostname"
+        )?;
+        let mut offset = 0usize;
+        loop {
+            let mut commands = repo
+                .find_filtered_paged(start, end, filter, offset, PAGE_SIZE)
+                .await?;
+            if commands.is_empty() {
+                break;
+            }
+            if redact {
+                commands = commands.into_iter().map(redact_command).collect();
+            }
+            for cmd in &commands {
+                writeln!(
+                    file,
+                    "{},{},{},{},{},{},{},{},{},{},{},{}",
+                    cmd.id,
+                    csv_escape(&cmd.raw),
+                    csv_escape(&cmd.parsed_command),
+                    csv_escape(&cmd.working_directory),
+                    cmd.exit_code,
+                    cmd.duration_ms,
+                    cmd.timestamp.to_rfc3339(),
+                    csv_escape(&cmd.session_id),
+                    csv_escape(&cmd.source),
+                    csv_escape(&cmd.metadata.shell),
+                    csv_escape(&cmd.metadata.user),
+                    csv_escape(&cmd.metadata.hostname),
+                )?;
+            }
+            offset += commands.len();
+            total = offset;
+        }
+    }
+
+    let copy_sql = format!(
+        "COPY (SELECT * FROM read_csv_auto('{}', header = true)) TO '{}' (FORMAT PARQUET);",
+        staging, output
+    );
+    let result = std::process::Command::new("duckdb")
+        .args(["-c", &copy_sql])
+        .status();
+    std::fs::remove_file(&staging).ok();
+
+    match result {
+        Ok(status) if status.success() => {
+            println!("✅ Exported {} records to {} (parquet)", total, output);
+            Ok(())
+        }
+        Ok(_) => anyhow::bail!("duckdb failed to write {}", output),
+        Err(_) => anyhow::bail!(
+            "Parquet export needs the duckdb CLI — install it from https://duckdb.org"
+        ),
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn sha256_file(path: &str) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut file = std::fs::File::open(path)?;
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Accepts RFC 3339 timestamps or bare YYYY-MM-DD dates (midnight UTC).
+fn parse_time(input: &str) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = input.parse::<DateTime<Utc>>() {
+        return Ok(timestamp);
+    }
+    if let Ok(date) = input.parse::<NaiveDate>() {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    anyhow::bail!("Invalid time '{}': use RFC 3339 or YYYY-MM-DD", input)
+}
@@ -0,0 +1,101 @@
+//! DuckDB analytical export
+//!
+//! Mirrors command history into a DuckDB file so heavy analytics
+//! (millions of rows, joins with external datasets) run against a
+//! columnar store instead of hammering the operational SQLite DB.
+//! Uses the `duckdb` CLI when available, in line with how other
+//! external tools (AI providers) are integrated.
+
+use anyhow::Result;
+use std::io::Write;
+use termbrain_core::domain::repositories::CommandRepository;
+
+use super::create_storage;
+
+/// Exports all visible history into `output` as a DuckDB database.
+pub async fn export_duckdb(output: String) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = super::create_repo(&storage);
+
+    let commands = repo.find_recent(usize::MAX / 2).await?;
+
+    if commands.is_empty() {
+        println!("No commands to export");
+        return Ok(());
+    }
+
+    // Stage the rows as CSV; DuckDB's CSV reader handles type inference
+    let staging = tempfile_path(&output);
+    {
+        let mut file = std::fs::File::create(&staging)?;
+        writeln!(
+            file,
+            "id,raw,parsed_command,working_directory,exit_code,duration_ms,timestamp,session_id,shell,user,hostname"
+        )?;
+        for cmd in &commands {
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                cmd.id,
+                csv_escape(&cmd.raw),
+                csv_escape(&cmd.parsed_command),
+                csv_escape(&cmd.working_directory),
+                cmd.exit_code,
+                cmd.duration_ms,
+                cmd.timestamp.to_rfc3339(),
+                csv_escape(&cmd.session_id),
+                csv_escape(&cmd.metadata.shell),
+                csv_escape(&cmd.metadata.user),
+                csv_escape(&cmd.metadata.hostname),
+            )?;
+        }
+    }
+
+    let load_sql = format!(
+        "CREATE OR REPLACE TABLE commands AS SELECT * FROM read_csv_auto('{}', header = true);",
+        staging
+    );
+
+    let result = std::process::Command::new("duckdb")
+        .arg(&output)
+        .arg("-c")
+        .arg(&load_sql)
+        .output();
+
+    match result {
+        Ok(out) if out.status.success() => {
+            std::fs::remove_file(&staging).ok();
+            println!("📤 Exported {} commands to DuckDB: {}", commands.len(), output);
+            println!("   Open with: duckdb {}", output);
+        }
+        Ok(out) => {
+            std::fs::remove_file(&staging).ok();
+            return Err(anyhow::anyhow!(
+                "duckdb failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ));
+        }
+        Err(_) => {
+            // No duckdb binary: leave the staged CSV plus a loader script
+            let script = format!("{}.load.sql", output);
+            std::fs::write(&script, format!("{}\n", load_sql))?;
+            println!("⚠️  'duckdb' CLI not found — staged CSV instead");
+            println!("   Data: {}", staging);
+            println!("   Load with: duckdb {} < {}", output, script);
+        }
+    }
+
+    Ok(())
+}
+
+fn tempfile_path(output: &str) -> String {
+    format!("{}.staging.csv", output)
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,70 @@
+//! Focus-aware suppression for proactive surfaces
+//!
+//! The digest, suggestion, and alert-notification surfaces call
+//! [`deep_focus_now`] before printing anything unprompted; when command
+//! cadence indicates deep focus (see `termbrain_core::focus`) they stay
+//! quiet and park their output via [`defer`]. The next surface to run
+//! after the cadence lapses drains the queue with [`flush_deferred`],
+//! so nothing is lost — it just waits for a natural break.
+
+use std::path::PathBuf;
+
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::focus::{in_deep_focus, FOCUS_MIN_COMMANDS};
+
+use super::{create_repo, create_storage};
+
+/// Where output deferred during focus waits, one line per item.
+fn queue_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".termbrain")
+        .join("deferred.txt")
+}
+
+/// Whether the user is in deep focus right now, judged from recent
+/// command cadence. Best effort: any error (no database yet, first
+/// run) reads as "not focused" so surfaces never go silent by mistake.
+pub(super) async fn deep_focus_now() -> bool {
+    let Ok(storage) = create_storage().await else {
+        return false;
+    };
+    let repo = create_repo(&storage);
+    let Ok(commands) = repo.find_recent(FOCUS_MIN_COMMANDS * 2).await else {
+        return false;
+    };
+    let timestamps: Vec<_> = commands.iter().map(|cmd| cmd.timestamp).collect();
+    in_deep_focus(&timestamps, chrono::Utc::now())
+}
+
+/// Queues one line of output for the next natural break.
+pub(super) fn defer(message: &str) {
+    let path = queue_path();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+    // Repeated deferrals of the same message (e.g. an alert firing on
+    // every check) collapse to one queue entry.
+    if existing.lines().any(|line| line == message.trim()) {
+        return;
+    }
+    let _ = std::fs::write(&path, format!("{}{}\n", existing, message.trim()));
+}
+
+/// Prints and clears anything queued during focus. Callers invoke this
+/// only after confirming focus has lapsed.
+pub(super) fn flush_deferred() {
+    let Ok(content) = std::fs::read_to_string(queue_path()) else {
+        return;
+    };
+    let lines: Vec<_> = content.lines().filter(|l| !l.is_empty()).collect();
+    if lines.is_empty() {
+        return;
+    }
+    println!("📬 Held during your focus session:");
+    for line in &lines {
+        println!("   {}", line);
+    }
+    let _ = std::fs::remove_file(queue_path());
+}
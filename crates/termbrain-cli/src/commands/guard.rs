@@ -0,0 +1,190 @@
+//! Pre-exec dangerous-command guard
+//!
+//! The shell hooks call `tb guard -- <command>` before a command runs
+//! (opt-in via TERMBRAIN_GUARD=1). The policy lives in a user-editable
+//! JSON file next to the database: regex rules with warn / confirm /
+//! block severities and per-directory scoping (see
+//! `termbrain_core::guard`). Exit code 0 lets the command through;
+//! anything else makes the hook stop it. Overridden confirmations and
+//! blocks are appended to an audit log so "I said yes to that warning"
+//! is reconstructable later.
+
+use anyhow::Result;
+use chrono::Utc;
+use termbrain_core::guard::{evaluate, GuardMatch, GuardRule, Severity};
+
+use crate::config::expand_tilde;
+
+fn policy_path() -> std::path::PathBuf {
+    crate::platform::data_dir().join("guard-policy.json")
+}
+
+fn audit_path() -> std::path::PathBuf {
+    crate::platform::data_dir().join("guard-audit.jsonl")
+}
+
+/// The policy written on first use: a starting point, meant to be
+/// edited. Block rules cover the classics; riskier-but-routine
+/// operations only ask.
+fn default_rules() -> Vec<GuardRule> {
+    let rule = |pattern: &str, severity: Severity, reason: &str| GuardRule {
+        pattern: pattern.to_string(),
+        severity,
+        reason: reason.to_string(),
+        directories: Vec::new(),
+        exempt_directories: Vec::new(),
+    };
+    vec![
+        rule(
+            r"^\s*rm\s+(-\w*[rf]\w*\s+)+(/|~|\$HOME)\s*$",
+            Severity::Block,
+            "recursive delete of / or the home directory",
+        ),
+        rule(r"^\s*mkfs", Severity::Block, "formats a filesystem"),
+        rule(
+            r"^\s*dd\s+.*of=/dev/",
+            Severity::Block,
+            "writes raw bytes over a device",
+        ),
+        rule(r":\(\)\s*\{.*\|.*&.*\}", Severity::Block, "fork bomb"),
+        rule(
+            r"^\s*rm\s+(-\w*[rf]\w*\s*)+",
+            Severity::Confirm,
+            "recursive or forced delete",
+        ),
+        rule(
+            r"^\s*git\s+push\s+.*(--force|\s-f)(\s|$)",
+            Severity::Confirm,
+            "force push rewrites remote history",
+        ),
+        rule(
+            r"^\s*chmod\s+(-R\s+)?777\s",
+            Severity::Confirm,
+            "world-writable permissions",
+        ),
+        rule(
+            r"\|\s*(ba|z)?sh\s*$",
+            Severity::Warn,
+            "pipes downloaded content into a shell",
+        ),
+        rule(
+            r"^\s*git\s+reset\s+--hard",
+            Severity::Warn,
+            "discards uncommitted changes",
+        ),
+    ]
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GuardPolicy {
+    rules: Vec<GuardRule>,
+}
+
+/// Loads the policy file, writing the default one on first use so
+/// there is always something concrete to edit. Invalid regexes are
+/// warned about here, once, instead of on every keystroke of a hook.
+fn load_policy() -> Result<Vec<GuardRule>> {
+    let path = policy_path();
+    if !path.exists() {
+        let policy = GuardPolicy {
+            rules: default_rules(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(&policy)?)?;
+        return Ok(policy.rules);
+    }
+    let policy: GuardPolicy = serde_json::from_str(&std::fs::read_to_string(&path)?)
+        .map_err(|e| anyhow::anyhow!("Invalid guard policy {}: {}", path.display(), e))?;
+    for rule in &policy.rules {
+        if let Err(e) = regex::Regex::new(&rule.pattern) {
+            eprintln!("Warning: invalid guard pattern '{}': {}", rule.pattern, e);
+        }
+    }
+    Ok(policy.rules)
+}
+
+/// Appends one audit entry; best-effort, because failing to audit must
+/// never stop or allow a command the policy already decided on.
+fn audit(action: &str, command: &str, directory: &str, fired: &GuardMatch) {
+    let entry = serde_json::json!({
+        "at": Utc::now().to_rfc3339(),
+        "action": action,
+        "command": command,
+        "directory": directory,
+        "severity": fired.severity.as_str(),
+        "pattern": fired.pattern,
+        "reason": fired.reason,
+    });
+    let _ = std::fs::create_dir_all(crate::platform::data_dir());
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(audit_path())
+    {
+        let _ = std::io::Write::write_all(&mut file, format!("{}\n", entry).as_bytes());
+    }
+}
+
+/// Checks one command line against the policy. Allowed commands exit 0
+/// silently; blocked or declined ones exit 1, which the shell hook
+/// turns into "don't run it". All output goes to stderr because the
+/// hooks leave stdout attached to the terminal pipeline.
+pub async fn run_guard(command: Vec<String>) -> Result<()> {
+    let command = command.join(" ");
+    let command = command.trim();
+    if command.is_empty() {
+        return Ok(());
+    }
+
+    let mut rules = load_policy()?;
+    for rule in &mut rules {
+        rule.directories = rule
+            .directories
+            .iter()
+            .map(|d| expand_tilde(d).to_string_lossy().to_string())
+            .collect();
+        rule.exempt_directories = rule
+            .exempt_directories
+            .iter()
+            .map(|d| expand_tilde(d).to_string_lossy().to_string())
+            .collect();
+    }
+
+    let directory = std::env::current_dir()
+        .map(|d| d.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let Some(fired) = evaluate(&rules, command, &directory) else {
+        return Ok(());
+    };
+
+    match fired.severity {
+        Severity::Warn => {
+            eprintln!("⚠️  termbrain: {}", fired.reason);
+            audit("warned", command, &directory, &fired);
+        }
+        Severity::Confirm => {
+            eprintln!("⚠️  termbrain: {}", fired.reason);
+            eprint!("   Run anyway? [y/N]: ");
+            let mut input = String::new();
+            // EOF or no terminal reads as empty, i.e. declined — the
+            // safe answer when nobody can actually confirm
+            let _ = std::io::stdin().read_line(&mut input);
+            if input.trim().to_lowercase().starts_with('y') {
+                audit("overridden", command, &directory, &fired);
+            } else {
+                audit("declined", command, &directory, &fired);
+                eprintln!("   Not running it (edit {} to change)", policy_path().display());
+                std::process::exit(1);
+            }
+        }
+        Severity::Block => {
+            eprintln!("🚫 termbrain: {} — blocked by policy", fired.reason);
+            eprintln!("   Edit {} to change", policy_path().display());
+            audit("blocked", command, &directory, &fired);
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! Guest sessions for shared or pairing machines
+//!
+//! While a guest session is active, every command goes to an isolated
+//! temporary store instead of the main database. Ending the session is
+//! an explicit choice: `merge` folds the guest's commands into the main
+//! history, `discard` drops them. This protects the host's history from
+//! a guest's commands and the guest's privacy from the host — the guest
+//! store also can't see existing history.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::path::PathBuf;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_storage::sqlite::{SqliteCommandRepository, SqliteStorage};
+
+use crate::config::Config;
+
+fn termbrain_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".termbrain")
+}
+
+fn marker_path() -> PathBuf {
+    termbrain_dir().join("guest-session")
+}
+
+pub(super) fn guest_db_path() -> PathBuf {
+    termbrain_dir().join("guest.db")
+}
+
+/// Whether a guest session is currently active. Checked by
+/// `create_storage` so all recording and search is redirected while
+/// one is open.
+pub(super) fn guest_session_active() -> bool {
+    marker_path().exists()
+}
+
+/// Starts a guest session: subsequent commands record to the isolated
+/// guest store.
+pub async fn guest_session_start() -> Result<()> {
+    if guest_session_active() {
+        println!("A guest session is already active (started {})", std::fs::read_to_string(marker_path())?.trim());
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(termbrain_dir())?;
+    std::fs::write(marker_path(), Utc::now().to_rfc3339())?;
+
+    println!("🎭 Guest session started");
+    println!("   Commands now record to an isolated store; existing history is hidden");
+    println!("   End it with: tb guest-session merge | tb guest-session discard");
+    Ok(())
+}
+
+/// Folds the guest store's commands into the main database and ends the
+/// session.
+pub async fn guest_session_merge() -> Result<()> {
+    if !guest_session_active() {
+        println!("No guest session is active");
+        return Ok(());
+    }
+
+    let guest_db = guest_db_path();
+    let mut merged = 0;
+    if guest_db.exists() {
+        let guest = SqliteStorage::new(&guest_db).await?;
+        guest.ensure_schema().await?;
+        let guest_repo = SqliteCommandRepository::new(guest.pool().clone());
+        let commands = guest_repo.find_recent(i64::MAX as usize).await?;
+
+        let config = Config::load()?;
+        let main = SqliteStorage::new(&config.database_path).await?;
+        main.ensure_schema().await?;
+        let main_repo = SqliteCommandRepository::new(main.pool().clone());
+        main_repo.save_batch(&commands).await?;
+        merged = commands.len();
+    }
+
+    end_session()?;
+    println!("✅ Guest session merged: {} commands added to history", merged);
+    Ok(())
+}
+
+/// Drops the guest store without touching the main database.
+pub async fn guest_session_discard() -> Result<()> {
+    if !guest_session_active() {
+        println!("No guest session is active");
+        return Ok(());
+    }
+
+    end_session()?;
+    println!("🗑️  Guest session discarded; nothing was added to history");
+    Ok(())
+}
+
+fn end_session() -> Result<()> {
+    let guest_db = guest_db_path();
+    if guest_db.exists() {
+        std::fs::remove_file(&guest_db)?;
+    }
+    std::fs::remove_file(marker_path())?;
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Ignore rule management
+//!
+//! `tb config ignore` edits the three recording ignore lists: command
+//! words (`cd`, `ls`), regexes on the full command line, and directory
+//! subtrees (`~/secrets`). Matching commands are dropped before any
+//! persistence path — daemon, journal, or direct write.
+
+use anyhow::Result;
+
+use crate::config::Config;
+
+/// What an `add`/`remove` invocation targets; exactly one of the
+/// `--command`/`--pattern`/`--directory` flags.
+enum IgnoreRule {
+    Command(String),
+    Pattern(String),
+    Directory(String),
+}
+
+fn resolve_rule(
+    command: Option<String>,
+    pattern: Option<String>,
+    directory: Option<String>,
+) -> Result<IgnoreRule> {
+    match (command, pattern, directory) {
+        (Some(command), None, None) => Ok(IgnoreRule::Command(command)),
+        (None, Some(pattern), None) => Ok(IgnoreRule::Pattern(pattern)),
+        (None, None, Some(directory)) => Ok(IgnoreRule::Directory(directory)),
+        _ => anyhow::bail!("Specify exactly one of --command, --pattern, or --directory"),
+    }
+}
+
+/// Adds an ignore rule. Patterns are validated as regexes up front so a
+/// typo fails here instead of warning on every future recording.
+pub fn ignore_add(
+    command: Option<String>,
+    pattern: Option<String>,
+    directory: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    match resolve_rule(command, pattern, directory)? {
+        IgnoreRule::Command(command) => {
+            if config.ignored_commands.iter().any(|c| c == &command) {
+                println!("'{}' is already ignored", command);
+                return Ok(());
+            }
+            config.ignored_commands.push(command.clone());
+            config.save()?;
+            println!("✅ Commands starting with '{}' will not be recorded", command);
+        }
+        IgnoreRule::Pattern(pattern) => {
+            regex::Regex::new(&pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid regex '{}': {}", pattern, e))?;
+            if config.ignore_patterns.iter().any(|p| p == &pattern) {
+                println!("Pattern '{}' is already ignored", pattern);
+                return Ok(());
+            }
+            config.ignore_patterns.push(pattern.clone());
+            config.save()?;
+            println!("✅ Commands matching /{}/ will not be recorded", pattern);
+        }
+        IgnoreRule::Directory(directory) => {
+            if config.ignore_directories.iter().any(|d| d == &directory) {
+                println!("'{}' is already ignored", directory);
+                return Ok(());
+            }
+            config.ignore_directories.push(directory.clone());
+            config.save()?;
+            println!("✅ Nothing run under {} will be recorded", directory);
+        }
+    }
+    Ok(())
+}
+
+/// Removes an ignore rule (matched exactly as it was added).
+pub fn ignore_remove(
+    command: Option<String>,
+    pattern: Option<String>,
+    directory: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let (list, value) = match resolve_rule(command, pattern, directory)? {
+        IgnoreRule::Command(command) => (&mut config.ignored_commands, command),
+        IgnoreRule::Pattern(pattern) => (&mut config.ignore_patterns, pattern),
+        IgnoreRule::Directory(directory) => (&mut config.ignore_directories, directory),
+    };
+    let before = list.len();
+    list.retain(|entry| entry != &value);
+    if list.len() == before {
+        println!("No ignore rule '{}' found — see 'tb config ignore list'", value);
+        return Ok(());
+    }
+    config.save()?;
+    println!("✅ Removed '{}' — matching commands record again", value);
+    Ok(())
+}
+
+/// Lists all configured ignore rules.
+pub fn ignore_list() -> Result<()> {
+    let config = Config::load()?;
+    if config.ignored_commands.is_empty()
+        && config.ignore_patterns.is_empty()
+        && config.ignore_directories.is_empty()
+    {
+        println!("No ignore rules — everything is recorded");
+        return Ok(());
+    }
+
+    println!("🙈 Ignore rules:");
+    if !config.ignored_commands.is_empty() {
+        println!("   Commands ({}):", config.ignored_commands.len());
+        for command in &config.ignored_commands {
+            println!("      {}", command);
+        }
+    }
+    if !config.ignore_patterns.is_empty() {
+        println!("   Patterns ({}):", config.ignore_patterns.len());
+        for pattern in &config.ignore_patterns {
+            println!("      /{}/", pattern);
+        }
+    }
+    if !config.ignore_directories.is_empty() {
+        println!("   Directories ({}):", config.ignore_directories.len());
+        for directory in &config.ignore_directories {
+            println!("      {}", directory);
+        }
+    }
+    Ok(())
+}
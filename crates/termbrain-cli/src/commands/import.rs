@@ -0,0 +1,511 @@
+//! Shell history import
+//!
+//! Seeds a fresh database from the user's existing bash/zsh/fish
+//! history files — or an Atuin / zsh-histdb database — so search and
+//! suggestions are useful on day one.
+
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::Row;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use termbrain_core::domain::entities::{Command, CommandMetadata};
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::shell_history::{
+    dedupe_imported, import_fingerprint, parse_bash_history, parse_fish_history,
+    parse_zsh_history, ImportedCommand,
+};
+use uuid::Uuid;
+
+use super::{create_repo, create_storage};
+
+type HistoryParser = fn(&str) -> Vec<ImportedCommand>;
+
+/// Known history files, with the parser for each format.
+fn history_sources() -> Vec<(PathBuf, &'static str, HistoryParser)> {
+    let home = dirs::home_dir().unwrap_or_default();
+    vec![
+        (home.join(".bash_history"), "bash", parse_bash_history),
+        (home.join(".zsh_history"), "zsh", parse_zsh_history),
+        (
+            home.join(".local/share/fish/fish_history"),
+            "fish",
+            parse_fish_history,
+        ),
+    ]
+}
+
+/// Imports shell history files into the database. With `file` set, only
+/// that file is read, parsed per `shell` ("bash", "zsh", or "fish").
+/// `atuin` / `histdb` switch to the SQLite adapters instead, which
+/// preserve exit codes, durations, directories, and sessions. `format`
+/// accepts termbrain's own export formats: "sqlite" snapshots and
+/// "parquet" files.
+pub async fn import_history(
+    file: Option<PathBuf>,
+    shell: Option<String>,
+    atuin: bool,
+    histdb: bool,
+    format: Option<String>,
+) -> Result<()> {
+    let home = dirs::home_dir().unwrap_or_default();
+    if let Some(format) = format {
+        let file = file.ok_or_else(|| anyhow::anyhow!("--as requires --file"))?;
+        return match format.as_str() {
+            "sqlite" => import_sqlite_snapshot(file).await,
+            "parquet" => import_parquet(file).await,
+            other => Err(anyhow::anyhow!(
+                "Unknown import format '{}': expected sqlite or parquet",
+                other
+            )),
+        };
+    }
+    if atuin {
+        let db = file.unwrap_or_else(|| home.join(".local/share/atuin/history.db"));
+        return import_atuin(db).await;
+    }
+    if histdb {
+        let db = file.unwrap_or_else(|| home.join(".histdb/zsh-history.db"));
+        return import_histdb(db).await;
+    }
+
+    let sources = match file {
+        Some(path) => {
+            let shell = shell.as_deref().unwrap_or("bash");
+            let parser = match shell {
+                "zsh" => parse_zsh_history as HistoryParser,
+                "fish" => parse_fish_history,
+                _ => parse_bash_history,
+            };
+            vec![(path, shell.to_string(), parser)]
+        }
+        None => history_sources()
+            .into_iter()
+            .filter(|(path, _, _)| path.exists())
+            .map(|(path, shell, parser)| (path, shell.to_string(), parser))
+            .collect(),
+    };
+
+    if sources.is_empty() {
+        println!("No shell history files found to import");
+        return Ok(());
+    }
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    // Don't re-import what's already recorded
+    let existing: HashSet<String> = sqlx::query_scalar::<_, String>("SELECT raw FROM commands")
+        .fetch_all(storage.pool())
+        .await?
+        .into_iter()
+        .collect();
+
+    let user = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
+    let hostname = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "localhost".to_string());
+    let home = dirs::home_dir().unwrap_or_default().display().to_string();
+
+    let mut total = 0;
+    for (path, shell, parser) in sources {
+        let content = std::fs::read_to_string(&path)?;
+        let source = format!("import:{}", shell);
+        let ledger = load_ledger(storage.pool(), &source).await?;
+
+        // Entries without timestamps get the file's mtime — the best
+        // upper bound available for when they last ran
+        let fallback: DateTime<Utc> = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map(DateTime::from)
+            .unwrap_or_else(|_| Utc::now());
+
+        let entries: Vec<(ImportedCommand, String)> = dedupe_imported(parser(&content))
+            .into_iter()
+            .map(|e| {
+                let fingerprint = import_fingerprint(&e.raw, e.timestamp.as_ref(), &hostname);
+                (e, fingerprint)
+            })
+            .filter(|(e, fp)| !existing.contains(&e.raw) && !ledger.contains(fp))
+            .collect();
+
+        let fingerprints: Vec<String> = entries.iter().map(|(_, fp)| fp.clone()).collect();
+        let commands: Vec<Command> = entries
+            .into_iter()
+            .map(|(e, _)| {
+                let mut parts = e.raw.split_whitespace();
+                Command {
+                    id: Uuid::new_v4(),
+                    parsed_command: parts.next().unwrap_or("").to_string(),
+                    arguments: parts.map(String::from).collect(),
+                    raw: e.raw,
+                    working_directory: home.clone(),
+                    exit_code: 0,
+                    duration_ms: 0,
+                    timestamp: e.timestamp.unwrap_or(fallback),
+                    session_id: format!("imported-{}", shell),
+                    source: format!("import:{}", shell),
+                    extras: std::collections::HashMap::new(),
+                    logical_clock: None,
+                    metadata: CommandMetadata {
+                        shell: shell.clone(),
+                        user: user.clone(),
+                        hostname: hostname.clone(),
+                        terminal: "imported".to_string(),
+                        environment: std::collections::HashMap::new(),
+                    },
+                }
+            })
+            .collect();
+
+        repo.save_batch(&commands).await?;
+        record_ledger(storage.pool(), &source, &fingerprints).await?;
+        println!("📥 {}: imported {} commands", path.display(), commands.len());
+        total += commands.len();
+    }
+
+    println!("✅ Imported {} commands", total);
+    Ok(())
+}
+
+/// One run read from a richer history store (Atuin, zsh-histdb), with
+/// the detail plain history files lack.
+struct ImportedRun {
+    raw: String,
+    directory: String,
+    exit_code: i32,
+    duration_ms: u64,
+    timestamp: DateTime<Utc>,
+    session_id: String,
+    hostname: String,
+}
+
+/// Imports an Atuin SQLite database (`history` table; nanosecond
+/// timestamps and durations).
+async fn import_atuin(db: PathBuf) -> Result<()> {
+    if !db.exists() {
+        anyhow::bail!("Atuin database not found at {}", db.display());
+    }
+    let source = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&format!("sqlite:{}?mode=ro", db.display()))
+        .await?;
+
+    let rows = sqlx::query(
+        "SELECT command, cwd, exit, duration, timestamp, session, hostname FROM history ORDER BY timestamp",
+    )
+    .fetch_all(&source)
+    .await?;
+
+    let runs = rows
+        .into_iter()
+        .map(|row| ImportedRun {
+            raw: row.get::<String, _>("command"),
+            directory: row.get::<String, _>("cwd"),
+            exit_code: row.get::<i64, _>("exit") as i32,
+            duration_ms: (row.get::<i64, _>("duration").max(0) / 1_000_000) as u64,
+            timestamp: Utc.timestamp_nanos(row.get::<i64, _>("timestamp")),
+            session_id: format!("atuin-{}", row.get::<String, _>("session")),
+            hostname: row.get::<String, _>("hostname"),
+        })
+        .collect();
+
+    save_runs(runs, "atuin", "import:atuin", &db).await
+}
+
+/// Imports a zsh-histdb database (normalized commands/places/history
+/// schema; second-resolution timestamps).
+async fn import_histdb(db: PathBuf) -> Result<()> {
+    if !db.exists() {
+        anyhow::bail!("zsh-histdb database not found at {}", db.display());
+    }
+    let source = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&format!("sqlite:{}?mode=ro", db.display()))
+        .await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT c.argv AS raw, p.dir AS dir, p.host AS host,
+               h.exit_status, h.start_time, h.duration, h.session
+        FROM history h
+        JOIN commands c ON h.command_id = c.id
+        JOIN places p ON h.place_id = p.id
+        ORDER BY h.start_time
+        "#,
+    )
+    .fetch_all(&source)
+    .await?;
+
+    let runs = rows
+        .into_iter()
+        .map(|row| ImportedRun {
+            raw: row.get::<String, _>("raw"),
+            directory: row.get::<String, _>("dir"),
+            exit_code: row.get::<Option<i64>, _>("exit_status").unwrap_or(0) as i32,
+            duration_ms: (row.get::<Option<i64>, _>("duration").unwrap_or(0).max(0) * 1000) as u64,
+            timestamp: Utc
+                .timestamp_opt(row.get::<i64, _>("start_time"), 0)
+                .single()
+                .unwrap_or_else(Utc::now),
+            session_id: format!("histdb-{}", row.get::<i64, _>("session")),
+            hostname: row.get::<String, _>("host"),
+        })
+        .collect();
+
+    save_runs(runs, "zsh", "import:histdb", &db).await
+}
+
+/// Imports a termbrain sqlite snapshot (from `tb export --as sqlite`
+/// or `tb db backup`), merging rows with full fidelity —
+/// shell, user, extras and all.
+async fn import_sqlite_snapshot(db: PathBuf) -> Result<()> {
+    if !db.exists() {
+        anyhow::bail!("Snapshot not found at {}", db.display());
+    }
+    let source = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&format!("sqlite:{}?mode=ro", db.display()))
+        .await?;
+
+    let rows = sqlx::query(
+        "SELECT raw, parsed_command, arguments, working_directory, exit_code, duration_ms,
+                timestamp, session_id, source, shell, user, hostname, terminal, environment, extras
+         FROM commands ORDER BY timestamp",
+    )
+    .fetch_all(&source)
+    .await?;
+
+    let commands: Vec<Command> = rows
+        .into_iter()
+        .map(|row| Command {
+            id: Uuid::new_v4(),
+            raw: row.get("raw"),
+            parsed_command: row.get("parsed_command"),
+            arguments: serde_json::from_str(&row.get::<String, _>("arguments"))
+                .unwrap_or_default(),
+            working_directory: row.get("working_directory"),
+            exit_code: row.get::<i64, _>("exit_code") as i32,
+            duration_ms: row.get::<i64, _>("duration_ms").max(0) as u64,
+            timestamp: row
+                .get::<String, _>("timestamp")
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            session_id: row.get("session_id"),
+            source: row.get("source"),
+            extras: serde_json::from_str(&row.get::<String, _>("extras")).unwrap_or_default(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: row.get("shell"),
+                user: row.get("user"),
+                hostname: row.get("hostname"),
+                terminal: row.get("terminal"),
+                environment: serde_json::from_str(&row.get::<String, _>("environment"))
+                    .unwrap_or_default(),
+            },
+        })
+        .collect();
+
+    save_full(commands, "import:sqlite", &db).await
+}
+
+/// Imports a parquet export by reading it back through the duckdb CLI,
+/// the same tool the export used to write it.
+async fn import_parquet(file: PathBuf) -> Result<()> {
+    if !file.exists() {
+        anyhow::bail!("Parquet file not found at {}", file.display());
+    }
+    let query = format!("SELECT * FROM read_parquet('{}')", file.display());
+    let output = std::process::Command::new("duckdb")
+        .args(["-json", "-c", &query])
+        .output()
+        .map_err(|_| {
+            anyhow::anyhow!("Parquet import needs the duckdb CLI — install it from https://duckdb.org")
+        })?;
+    if !output.status.success() {
+        anyhow::bail!("duckdb failed to read {}", file.display());
+    }
+
+    let rows: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+    let commands: Vec<Command> = rows
+        .iter()
+        .map(|row| {
+            let text = |key: &str| {
+                row.get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let raw = text("raw");
+            let mut parts = raw.split_whitespace();
+            let parsed_command = parts.next().unwrap_or("").to_string();
+            Command {
+                id: Uuid::new_v4(),
+                parsed_command,
+                arguments: parts.map(String::from).collect(),
+                raw,
+                working_directory: text("working_directory"),
+                exit_code: row.get("exit_code").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+                duration_ms: row
+                    .get("duration_ms")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0)
+                    .max(0) as u64,
+                timestamp: text("timestamp").parse().unwrap_or_else(|_| Utc::now()),
+                session_id: text("session_id"),
+                source: text("source"),
+                extras: std::collections::HashMap::new(),
+                logical_clock: None,
+                metadata: CommandMetadata {
+                    shell: text("shell"),
+                    user: text("user"),
+                    hostname: text("hostname"),
+                    terminal: "imported".to_string(),
+                    environment: std::collections::HashMap::new(),
+                },
+            }
+        })
+        .collect();
+
+    save_full(commands, "import:parquet", &file).await
+}
+
+/// Saves fully-formed commands from a termbrain-format import, with the
+/// same (raw, timestamp) and ledger dedupe as the adapter imports.
+async fn save_full(commands: Vec<Command>, source: &str, origin: &std::path::Path) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let existing: HashSet<(String, String)> = sqlx::query("SELECT raw, timestamp FROM commands")
+        .fetch_all(storage.pool())
+        .await?
+        .into_iter()
+        .map(|row| (row.get("raw"), row.get("timestamp")))
+        .collect();
+    let ledger = load_ledger(storage.pool(), source).await?;
+
+    let commands: Vec<(Command, String)> = commands
+        .into_iter()
+        .filter(|cmd| !cmd.raw.trim().is_empty())
+        .filter(|cmd| !existing.contains(&(cmd.raw.clone(), cmd.timestamp.to_rfc3339())))
+        .map(|cmd| {
+            let fingerprint =
+                import_fingerprint(&cmd.raw, Some(&cmd.timestamp), &cmd.metadata.hostname);
+            (cmd, fingerprint)
+        })
+        .filter(|(_, fp)| !ledger.contains(fp))
+        .collect();
+
+    if commands.is_empty() {
+        println!("Nothing new to import from {}", origin.display());
+        return Ok(());
+    }
+
+    let fingerprints: Vec<String> = commands.iter().map(|(_, fp)| fp.clone()).collect();
+    let commands: Vec<Command> = commands.into_iter().map(|(cmd, _)| cmd).collect();
+
+    repo.save_batch(&commands).await?;
+    record_ledger(storage.pool(), source, &fingerprints).await?;
+    println!("✅ Imported {} commands from {}", commands.len(), origin.display());
+    Ok(())
+}
+
+/// Saves imported runs, skipping any (command, timestamp) pair already
+/// recorded — and anything in the import ledger, which holds even after
+/// the original rows were pruned — so re-running an import is a no-op
+/// without collapsing genuine repeats.
+async fn save_runs(
+    runs: Vec<ImportedRun>,
+    shell: &str,
+    source: &str,
+    db: &std::path::Path,
+) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let existing: HashSet<(String, String)> =
+        sqlx::query("SELECT raw, timestamp FROM commands")
+            .fetch_all(storage.pool())
+            .await?
+            .into_iter()
+            .map(|row| (row.get("raw"), row.get("timestamp")))
+            .collect();
+    let ledger = load_ledger(storage.pool(), source).await?;
+
+    let user = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
+
+    let runs: Vec<(ImportedRun, String)> = runs
+        .into_iter()
+        .filter(|run| !run.raw.trim().is_empty())
+        .filter(|run| !existing.contains(&(run.raw.clone(), run.timestamp.to_rfc3339())))
+        .map(|run| {
+            let fingerprint = import_fingerprint(&run.raw, Some(&run.timestamp), &run.hostname);
+            (run, fingerprint)
+        })
+        .filter(|(_, fp)| !ledger.contains(fp))
+        .collect();
+
+    let fingerprints: Vec<String> = runs.iter().map(|(_, fp)| fp.clone()).collect();
+    let commands: Vec<Command> = runs
+        .into_iter()
+        .map(|(run, _)| {
+            let mut parts = run.raw.split_whitespace();
+            Command {
+                id: Uuid::new_v4(),
+                parsed_command: parts.next().unwrap_or("").to_string(),
+                arguments: parts.map(String::from).collect(),
+                raw: run.raw,
+                working_directory: run.directory,
+                exit_code: run.exit_code,
+                duration_ms: run.duration_ms,
+                timestamp: run.timestamp,
+                session_id: run.session_id,
+                source: source.to_string(),
+                extras: std::collections::HashMap::new(),
+                logical_clock: None,
+                metadata: CommandMetadata {
+                    shell: shell.to_string(),
+                    user: user.clone(),
+                    hostname: run.hostname,
+                    terminal: "imported".to_string(),
+                    environment: std::collections::HashMap::new(),
+                },
+            }
+        })
+        .collect();
+
+    repo.save_batch(&commands).await?;
+    record_ledger(storage.pool(), source, &fingerprints).await?;
+    println!("📥 {}: imported {} commands", db.display(), commands.len());
+    println!("✅ Imported {} commands", commands.len());
+    Ok(())
+}
+
+/// Fingerprints this source has ever imported.
+pub(super) async fn load_ledger(pool: &sqlx::SqlitePool, source: &str) -> Result<HashSet<String>> {
+    Ok(
+        sqlx::query_scalar::<_, String>("SELECT fingerprint FROM import_ledger WHERE source = ?")
+            .bind(source)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Adds newly imported fingerprints to the ledger in one transaction.
+pub(super) async fn record_ledger(pool: &sqlx::SqlitePool, source: &str, fingerprints: &[String]) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await?;
+    for fingerprint in fingerprints {
+        sqlx::query("INSERT OR IGNORE INTO import_ledger (source, fingerprint, imported_at) VALUES (?1, ?2, ?3)")
+            .bind(source)
+            .bind(fingerprint)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! Intentions: what the user is currently trying to do
+//!
+//! `tb intend <text>` sets an intention manually; recording a checkout
+//! of a `feature/*`-style branch opens one automatically (and merging
+//! or deleting the branch closes it again).
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::Row;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::intentions::{
+    detect_branch_event, matches_branch_pattern, render_intention, BranchEvent,
+};
+use termbrain_storage::sqlite::SqliteStorage;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+use super::create_storage;
+
+/// Sets a manual intention, or lists active ones when `text` is empty.
+pub async fn intend(text: String) -> Result<()> {
+    let storage = create_storage().await?;
+
+    if text.trim().is_empty() {
+        let rows = sqlx::query(
+            "SELECT text, source, branch, created_at FROM intentions WHERE status = 'active' ORDER BY created_at DESC",
+        )
+        .fetch_all(storage.pool())
+        .await?;
+
+        if rows.is_empty() {
+            println!("No active intentions — set one with: tb intend \"ship the release\"");
+            return Ok(());
+        }
+
+        println!("🎯 Active intentions:");
+        for row in rows {
+            let branch: Option<String> = row.get("branch");
+            println!(
+                "   {} ({}{}, since {})",
+                row.get::<String, _>("text"),
+                row.get::<String, _>("source"),
+                branch.map(|b| format!(" {}", b)).unwrap_or_default(),
+                row.get::<String, _>("created_at"),
+            );
+        }
+        return Ok(());
+    }
+
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO intentions (id, text, source, directory, status, created_at) VALUES (?1, ?2, 'manual', ?3, 'active', ?4)",
+    )
+    .bind(&id)
+    .bind(text.trim())
+    .bind(std::env::current_dir()?.to_string_lossy().to_string())
+    .bind(Utc::now().to_rfc3339())
+    .execute(storage.pool())
+    .await?;
+
+    super::issue::store_issue_refs(storage.pool(), "intention", &id, text.trim()).await?;
+
+    println!("🎯 Intention set: {}", text.trim());
+    Ok(())
+}
+
+/// Attaches the active intentions to an AI session and returns their
+/// texts, newest first, for inclusion in the generated context — so a
+/// wrapped agent automatically knows why it is being invoked. A no-op
+/// (returning empty) when no intention is active.
+#[cfg(feature = "ai")]
+pub(super) async fn attach_to_session(
+    pool: &sqlx::SqlitePool,
+    session_id: &str,
+) -> Result<Vec<String>> {
+    let active = sqlx::query(
+        "SELECT id, text FROM intentions WHERE status = 'active' ORDER BY created_at DESC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let now = Utc::now().to_rfc3339();
+    let mut texts = Vec::new();
+    for row in active {
+        sqlx::query(
+            "INSERT OR IGNORE INTO session_intentions (session_id, intention_id, attached_at) VALUES (?1, ?2, ?3)",
+        )
+        .bind(session_id)
+        .bind(row.get::<String, _>("id"))
+        .bind(&now)
+        .execute(pool)
+        .await?;
+        texts.push(row.get::<String, _>("text"));
+    }
+    Ok(texts)
+}
+
+/// Reacts to branch lifecycle events in a recorded command: opens an
+/// intention for matching checkouts, closes it on merge/delete.
+pub(super) async fn process_branch_event(
+    storage: &SqliteStorage,
+    command: &Command,
+    config: &Config,
+) -> Result<()> {
+    let Some(event) = detect_branch_event(command) else {
+        return Ok(());
+    };
+
+    match event {
+        BranchEvent::CheckedOut(branch) => {
+            if !matches_branch_pattern(&branch, &config.branch_intention_patterns) {
+                return Ok(());
+            }
+
+            // Re-checkout of a branch we already track is not a new intention
+            let existing: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM intentions WHERE branch = ? AND status = 'active'",
+            )
+            .bind(&branch)
+            .fetch_one(storage.pool())
+            .await?;
+            if existing > 0 {
+                return Ok(());
+            }
+
+            let text = render_intention(&config.branch_intention_template, &branch);
+            sqlx::query(
+                "INSERT INTO intentions (id, text, source, branch, directory, status, created_at) VALUES (?1, ?2, 'branch', ?3, ?4, 'active', ?5)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&text)
+            .bind(&branch)
+            .bind(&command.working_directory)
+            .bind(command.timestamp.to_rfc3339())
+            .execute(storage.pool())
+            .await?;
+            println!("   🎯 Intention opened from branch {}: {}", branch, text);
+        }
+        BranchEvent::Merged(branch) | BranchEvent::Deleted(branch) => {
+            let result = sqlx::query(
+                "UPDATE intentions SET status = 'closed', closed_at = ?1 WHERE branch = ?2 AND status = 'active'",
+            )
+            .bind(Utc::now().to_rfc3339())
+            .bind(&branch)
+            .execute(storage.pool())
+            .await?;
+            if result.rows_affected() > 0 {
+                println!("   🎯 Intention for branch {} closed", branch);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! Per-issue activity view
+//!
+//! Issue keys are extracted when commands and intentions are recorded;
+//! `tb issue ABC-123` pulls all terminal activity linked to one key.
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+use termbrain_core::issues::extract_issue_keys;
+use uuid::Uuid;
+
+use crate::OutputFormat;
+
+use super::create_storage;
+
+/// Extracts issue keys from `context` and stores one reference per key.
+pub(super) async fn store_issue_refs(
+    pool: &SqlitePool,
+    kind: &str,
+    source_id: &str,
+    context: &str,
+) -> Result<()> {
+    for key in extract_issue_keys(context) {
+        sqlx::query(
+            "INSERT INTO issue_refs (id, issue_key, kind, source_id, context, occurred_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&key)
+        .bind(kind)
+        .bind(source_id)
+        .bind(context)
+        .bind(Utc::now().to_rfc3339())
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// The issue-ref kind for a recorded command, from its git subcommand.
+pub(super) fn command_ref_kind(parsed_command: &str, first_arg: &str) -> &'static str {
+    match (parsed_command, first_arg) {
+        ("git", "commit") => "commit",
+        ("git", "checkout") | ("git", "switch") | ("git", "branch") => "branch",
+        _ => "command",
+    }
+}
+
+/// Shows all terminal activity linked to one issue key.
+pub async fn show_issue(key: String, format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let refs = sqlx::query(
+        "SELECT kind, context, occurred_at FROM issue_refs WHERE issue_key = ?1 ORDER BY occurred_at",
+    )
+    .bind(&key)
+    .fetch_all(storage.pool())
+    .await?;
+
+    if refs.is_empty() {
+        println!("No recorded activity references {}", key);
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = refs
+                .iter()
+                .map(|row| {
+                    serde_json::json!({
+                        "kind": row.get::<String, _>("kind"),
+                        "context": row.get::<String, _>("context"),
+                        "occurred_at": row.get::<String, _>("occurred_at"),
+                    })
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "issue": key,
+                    "activity": entries,
+                }))?
+            );
+        }
+        _ => {
+            println!("🔗 Activity for {} ({} references):", key, refs.len());
+            for row in refs {
+                println!(
+                    "   {} [{}] {}",
+                    row.get::<String, _>("occurred_at"),
+                    row.get::<String, _>("kind"),
+                    row.get::<String, _>("context"),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! Write-ahead journal fast path for recording
+//!
+//! With `fast_record` enabled and no daemon running, `tb record`
+//! appends one JSON line to a journal file and returns — no SQLite
+//! open, no enrichment, no fsync — keeping the shell hook under a few
+//! milliseconds. The journal drains into SQLite through the full
+//! record pipeline on `tb flush`, or automatically the next time any
+//! command opens the database.
+
+use anyhow::Result;
+
+use super::daemon::RecordMessage;
+
+/// Reentrancy guard: set while draining so the replayed records take
+/// the direct path instead of journaling themselves again.
+const FLUSH_GUARD: &str = "TERMBRAIN_FLUSHING";
+
+fn journal_path() -> std::path::PathBuf {
+    crate::platform::data_dir().join("journal.jsonl")
+}
+
+/// True when this invocation must not journal (it is the daemon, or a
+/// flush replay).
+pub(super) fn recording_directly() -> bool {
+    std::env::var("TERMBRAIN_IN_DAEMON").is_ok() || std::env::var(FLUSH_GUARD).is_ok()
+}
+
+/// Appends one record to the journal. Deliberately fsync-free: losing
+/// the last few entries on power loss is acceptable for shell history,
+/// prompt latency is not.
+pub(super) fn append_record(message: &RecordMessage) -> Result<()> {
+    use std::io::Write;
+
+    let path = journal_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Age after which an orphaned `.draining` file is a crashed flush
+/// rather than one still in progress.
+const SPOOL_STALE_SECS: u64 = 60;
+
+/// Re-queues a drain that died mid-flush. `flush_journal` claims the
+/// journal by renaming it to `.draining`; if that file outlives any
+/// plausible flush, the process crashed and its records would
+/// otherwise be lost. Returns how many spooled records were put back.
+pub(super) fn recover_stale_spool() -> Result<usize> {
+    let draining = journal_path().with_extension("draining");
+    let Ok(metadata) = std::fs::metadata(&draining) else {
+        return Ok(0);
+    };
+    let stale = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age.as_secs() >= SPOOL_STALE_SECS);
+    if !stale {
+        return Ok(0);
+    }
+
+    use std::io::Write;
+    let content = std::fs::read_to_string(&draining)?;
+    let recovered = content.lines().filter(|l| !l.trim().is_empty()).count();
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_path())?
+        .write_all(content.as_bytes())?;
+    std::fs::remove_file(&draining)?;
+    Ok(recovered)
+}
+
+/// Records waiting in the journal right now.
+pub(super) fn pending_records() -> usize {
+    std::fs::read_to_string(journal_path())
+        .map(|content| content.lines().filter(|l| !l.trim().is_empty()).count())
+        .unwrap_or(0)
+}
+
+/// Drains the journal into SQLite when one is pending. Called from
+/// every database open so journaled records become visible before any
+/// read; a no-op when there is nothing to drain.
+pub(super) async fn flush_if_pending() -> Result<()> {
+    if recording_directly() {
+        return Ok(());
+    }
+    recover_stale_spool()?;
+    if !journal_path().exists() {
+        return Ok(());
+    }
+    flush_journal().await
+}
+
+/// Replays every journaled record through the normal record pipeline.
+pub async fn flush_journal() -> Result<()> {
+    let path = journal_path();
+    if !path.exists() {
+        println!("Journal is empty — nothing to flush");
+        return Ok(());
+    }
+
+    // Claim the journal by renaming it: concurrent invocations and the
+    // records we replay below each see an empty journal
+    let draining = path.with_extension("draining");
+    if std::fs::rename(&path, &draining).is_err() {
+        return Ok(()); // another invocation claimed it first
+    }
+    std::env::set_var(FLUSH_GUARD, "1");
+
+    let content = std::fs::read_to_string(&draining)?;
+    let mut flushed = 0usize;
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let message: RecordMessage = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => {
+                eprintln!("⚠️  Skipping malformed journal entry: {}", e);
+                continue;
+            }
+        };
+        if let Some(session) = &message.session_id {
+            std::env::set_var("TERMBRAIN_SESSION_ID", session);
+        }
+        // Boxed: record_command reaches back here via create_storage,
+        // and indirect async recursion needs a finite future size
+        Box::pin(super::record_command(
+            message.command,
+            message.exit_code,
+            message.duration,
+            message.directory,
+            message.source,
+            message.extra,
+            message.recorded_at,
+        ))
+        .await?;
+        flushed += 1;
+    }
+
+    std::fs::remove_file(&draining)?;
+    std::env::remove_var(FLUSH_GUARD);
+    if flushed > 0 {
+        println!("📥 Flushed {} journaled commands into the database", flushed);
+    }
+    Ok(())
+}
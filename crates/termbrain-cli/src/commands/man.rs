@@ -0,0 +1,125 @@
+//! History-informed help pages
+//!
+//! `tb man <tool>` shows the tool's own `--help` output enriched with
+//! what history knows: the flags actually used, the invocations typed
+//! most, and the ones that keep failing. Help lines documenting a flag
+//! the user already relies on are marked, so the familiar parts of a
+//! long help page stand out.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx::Row;
+
+use super::create_storage;
+
+/// How many history rows inform the usage sections.
+const HISTORY_WINDOW: i64 = 2000;
+
+/// Shows a tool's help alongside the user's own usage of it.
+pub async fn man_page(tool: String) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let rows = sqlx::query(
+        "SELECT raw, exit_code FROM commands WHERE parsed_command = ?
+         ORDER BY timestamp DESC LIMIT ?",
+    )
+    .bind(&tool)
+    .bind(HISTORY_WINDOW)
+    .fetch_all(storage.pool())
+    .await?;
+
+    // Tally flags and whole invocations across the window
+    let mut flags: HashMap<String, usize> = HashMap::new();
+    let mut invocations: HashMap<String, (usize, usize)> = HashMap::new();
+    for row in &rows {
+        let raw: String = row.get("raw");
+        let exit_code: i64 = row.get("exit_code");
+        for word in raw.split_whitespace().skip(1) {
+            if word.starts_with('-') && word.len() > 1 {
+                let flag = word.split('=').next().unwrap_or(word).to_string();
+                *flags.entry(flag).or_default() += 1;
+            }
+        }
+        let entry = invocations.entry(raw).or_default();
+        entry.0 += 1;
+        if exit_code != 0 {
+            entry.1 += 1;
+        }
+    }
+
+    println!("📖 {} — help, enriched with your history", tool);
+    println!();
+
+    if rows.is_empty() {
+        println!("No recorded history for '{}' yet", tool);
+    } else {
+        let mut top_flags: Vec<_> = flags.into_iter().collect();
+        top_flags.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        if !top_flags.is_empty() {
+            println!("🚩 Flags you use:");
+            for (flag, count) in top_flags.iter().take(8) {
+                println!("   {:<20} {} times", flag, count);
+            }
+            println!();
+        }
+
+        let mut top_invocations: Vec<_> = invocations.iter().collect();
+        top_invocations.sort_by_key(|(_, (count, _))| std::cmp::Reverse(*count));
+        println!("⌨️  Your common invocations:");
+        for (raw, (count, _)) in top_invocations.iter().take(5) {
+            println!("   {:>4}× {}", count, raw);
+        }
+        println!();
+
+        let mut failing: Vec<_> = invocations
+            .iter()
+            .filter(|(_, (_, failures))| *failures > 0)
+            .collect();
+        failing.sort_by_key(|(_, (_, failures))| std::cmp::Reverse(*failures));
+        if !failing.is_empty() {
+            println!("💥 What keeps failing:");
+            for (raw, (count, failures)) in failing.iter().take(3) {
+                println!("   {} — failed {} of {} runs", raw, failures, count);
+            }
+            println!();
+        }
+
+        let your_flags: Vec<String> = top_flags.into_iter().map(|(flag, _)| flag).collect();
+        print_help(&tool, &your_flags);
+        return Ok(());
+    }
+
+    println!();
+    print_help(&tool, &[]);
+    Ok(())
+}
+
+/// Prints the tool's own help text, marking lines that document a flag
+/// the user already uses with a ▸.
+fn print_help(tool: &str, your_flags: &[String]) {
+    let output = ["--help", "-h"].iter().find_map(|flag| {
+        std::process::Command::new(tool)
+            .arg(flag)
+            .output()
+            .ok()
+            .filter(|out| out.status.success() && !out.stdout.is_empty())
+    });
+
+    let Some(output) = output else {
+        println!("(no --help output available for '{}')", tool);
+        return;
+    };
+
+    println!("── {} --help ──", tool);
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let familiar = your_flags
+            .iter()
+            .any(|flag| line.split([' ', ',', '=']).any(|word| word == flag));
+        if familiar {
+            println!(" ▸ {}", line);
+        } else {
+            println!("   {}", line);
+        }
+    }
+}
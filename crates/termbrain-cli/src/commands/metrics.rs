@@ -0,0 +1,64 @@
+//! Custom metric evaluation
+//!
+//! Metrics are defined in the config file (name plus a read-only SQL
+//! expression or a filter substring) and surface in stats output and,
+//! later, digests and alerting.
+
+use anyhow::Result;
+use sqlx::Row;
+use termbrain_storage::sqlite::SqliteStorage;
+
+use crate::config::{Config, MetricDefinition};
+
+/// An evaluated metric ready for display.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MetricValue {
+    pub name: String,
+    pub value: f64,
+}
+
+/// Evaluates every metric defined in the config. Broken definitions
+/// produce a warning instead of failing the whole stats run.
+pub async fn evaluate_metrics(storage: &SqliteStorage, config: &Config) -> Result<Vec<MetricValue>> {
+    let mut values = Vec::new();
+
+    for definition in &config.metrics {
+        match evaluate_metric(storage, definition).await {
+            Ok(value) => values.push(MetricValue {
+                name: definition.name.clone(),
+                value,
+            }),
+            Err(e) => eprintln!("⚠️  Metric '{}' failed: {}", definition.name, e),
+        }
+    }
+
+    Ok(values)
+}
+
+async fn evaluate_metric(storage: &SqliteStorage, definition: &MetricDefinition) -> Result<f64> {
+    if let Some(sql) = &definition.sql {
+        let lowered = sql.trim().to_lowercase();
+        if !(lowered.starts_with("select") || lowered.starts_with("with")) {
+            return Err(anyhow::anyhow!("Metric SQL must be a SELECT/WITH query"));
+        }
+
+        let row = sqlx::query(sql).fetch_one(storage.pool()).await?;
+        if let Ok(value) = row.try_get::<f64, _>(0) {
+            return Ok(value);
+        }
+        if let Ok(value) = row.try_get::<i64, _>(0) {
+            return Ok(value as f64);
+        }
+        return Err(anyhow::anyhow!("Metric query did not return a number"));
+    }
+
+    if let Some(filter) = &definition.filter {
+        let row = sqlx::query("SELECT COUNT(*) FROM commands WHERE raw LIKE ?")
+            .bind(format!("%{}%", filter))
+            .fetch_one(storage.pool())
+            .await?;
+        return Ok(row.get::<i64, _>(0) as f64);
+    }
+
+    Err(anyhow::anyhow!("Metric needs either 'sql' or 'filter'"))
+}
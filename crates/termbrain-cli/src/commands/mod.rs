@@ -1,38 +1,346 @@
 //! Command implementations
 
+mod activity;
+mod alerts;
+mod autosuggest;
+mod backup;
+#[cfg(feature = "ai")]
+mod ask;
+mod bootstrap;
+mod burst;
+mod daemon;
+mod changes;
+mod contribute;
+mod dataset;
+mod db;
+mod devices;
+mod diagnose;
+mod digest;
+mod doctor;
+mod edit;
+mod errors;
+#[cfg(feature = "embeddings")]
+mod embeddings;
+mod events;
+mod experiment;
+mod explain;
+mod export;
+mod export_duckdb;
+mod focus;
+mod guard;
+mod guest;
+mod ignore;
+mod import;
+mod intend;
+mod issue;
+mod journal;
+mod man;
+mod metrics;
+mod perf;
+mod picker;
+mod predict;
+mod privacy;
+mod projects;
+mod prune;
+mod reclassify;
+mod refresh;
+mod remap_dir;
+mod repl;
+mod repro;
+mod runbook;
+mod selftest;
+#[cfg(feature = "server")]
+mod serve;
+mod sessions;
+mod snapshot;
+mod sql;
+#[cfg(feature = "ai")]
+mod suggest;
+#[cfg(feature = "sync")]
+mod sync;
+#[cfg(feature = "ai")]
+mod synthesize;
+mod teach;
+mod tidy;
+mod time;
+mod tools;
+mod vault;
+mod verify;
+mod versions;
+mod watch;
+mod workflow;
+
+pub use activity::*;
+pub use alerts::*;
+pub use autosuggest::*;
+pub use backup::*;
+#[cfg(feature = "ai")]
+pub use ask::*;
+pub use bootstrap::*;
+pub use changes::*;
+pub use contribute::*;
+pub use daemon::run_daemon;
+pub use dataset::*;
+pub use db::*;
+pub use devices::*;
+pub use diagnose::*;
+pub use digest::*;
+pub use doctor::*;
+pub use edit::*;
+pub use errors::*;
+#[cfg(feature = "embeddings")]
+pub use embeddings::*;
+pub use events::*;
+pub use experiment::*;
+pub use explain::*;
+pub use export::*;
+pub use export_duckdb::*;
+pub use guard::*;
+pub use guest::*;
+pub use ignore::*;
+pub use import::*;
+pub use intend::*;
+pub use issue::*;
+pub use journal::flush_journal;
+pub use man::*;
+pub use metrics::*;
+pub use perf::*;
+pub use picker::*;
+pub use predict::*;
+pub use privacy::*;
+pub use projects::*;
+pub use prune::*;
+pub use reclassify::*;
+pub use refresh::*;
+pub use remap_dir::*;
+pub use repro::*;
+pub use runbook::*;
+pub use selftest::*;
+#[cfg(feature = "server")]
+pub use serve::*;
+pub use sessions::*;
+pub use snapshot::*;
+pub use sql::*;
+#[cfg(feature = "ai")]
+pub use suggest::*;
+#[cfg(feature = "sync")]
+pub use sync::*;
+#[cfg(feature = "ai")]
+pub use synthesize::*;
+pub use teach::*;
+pub use tidy::*;
+pub use time::*;
+pub use tools::*;
+pub use vault::*;
+pub use verify::*;
+pub use versions::*;
+pub use watch::*;
+pub use workflow::*;
+
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use std::path::Path;
-use termbrain_core::domain::repositories::CommandRepository;
+use std::sync::OnceLock;
+use termbrain_core::domain::repositories::{CommandRepository, HybridWeights, ProjectRepository, StatsRepository, UserScope};
 use termbrain_core::validation::{
     validate_command, validate_path, validate_shell, validate_username, validate_hostname
 };
-use termbrain_storage::sqlite::{SqliteStorage, SqliteCommandRepository};
+use termbrain_storage::sqlite::{SqliteStorage, SqliteCommandRepository, SqliteProjectRepository, SqliteStatsRepository, VectorIndex};
 use uuid::Uuid;
-use crate::{OutputFormat, ExportFormat, WorkflowAction, config::Config};
+use crate::{OutputFormat, config::Config};
+
+/// User scope for this invocation, resolved once from the global
+/// --user/--team flags. Defaults to the current user on shared backends.
+static USER_SCOPE: OnceLock<UserScope> = OnceLock::new();
+
+/// Records the scope resolved from global CLI flags. Called once from
+/// main before any command runs.
+pub fn set_user_scope(scope: UserScope) {
+    let _ = USER_SCOPE.set(scope);
+}
+
+/// Returns true (after explaining why) when shadow mode blocks this
+/// feature. Only recording and search run in shadow mode.
+fn shadow_mode_guard(config: &Config) -> bool {
+    if config.shadow_mode {
+        println!("🕶️  Shadow mode is active — AI, prediction, and suggestion features are disabled");
+        println!("   Recording and search keep working. Unset shadow_mode (or TERMBRAIN_SHADOW_MODE) to re-enable.");
+        true
+    } else {
+        false
+    }
+}
+
+/// Returns true (after explaining why) when offline mode blocks this
+/// network-touching feature.
+#[cfg(feature = "ai")]
+fn offline_guard(config: &Config) -> bool {
+    if config.offline() {
+        println!("📴 Offline mode is active — network-touching features are disabled");
+        true
+    } else {
+        false
+    }
+}
+
+fn user_scope() -> UserScope {
+    USER_SCOPE.get().cloned().unwrap_or_else(UserScope::current_user)
+}
+
+/// This machine's stable identity, created on first use. Shared by the
+/// sync protocol and logical-clock stamps.
+pub(crate) fn device_id() -> Result<String> {
+    let path = crate::platform::data_dir().join("device-id");
+    if let Ok(id) = std::fs::read_to_string(&path) {
+        return Ok(id.trim().to_string());
+    }
+    let id = Uuid::new_v4().to_string();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, &id)?;
+    Ok(id)
+}
+
+/// Process-wide hybrid logical clock stamping new records (see
+/// `termbrain_core::hlc`).
+static HLC: OnceLock<std::sync::Mutex<termbrain_core::hlc::HybridClock>> = OnceLock::new();
+
+fn next_logical_clock() -> String {
+    let clock = HLC.get_or_init(|| {
+        // The first 8 chars of the device id are plenty to break ties
+        let device = device_id()
+            .map(|id| id[..8].to_string())
+            .unwrap_or_else(|_| "local".to_string());
+        std::sync::Mutex::new(termbrain_core::hlc::HybridClock::new(device))
+    });
+    clock
+        .lock()
+        .expect("hlc mutex poisoned")
+        .tick(Utc::now().timestamp_millis().max(0) as u64)
+        .to_string()
+}
+
+/// Creates a command repository enforcing the invocation's user scope
+/// and the configured vector index.
+fn create_repo(storage: &SqliteStorage) -> SqliteCommandRepository {
+    let vector_index = Config::load()
+        .map(|config| VectorIndex::from_name(&config.vector_index))
+        .unwrap_or_default();
+    let repo = SqliteCommandRepository::with_scope(storage.pool().clone(), user_scope())
+        .with_vector_index(vector_index);
+    match vault::load_cipher() {
+        Some(cipher) => repo.with_cipher(cipher),
+        None => repo,
+    }
+}
 
 /// Create storage instance using proper database path
 async fn create_storage() -> Result<SqliteStorage> {
+    // Any pending fast-path journal drains first, so reads see every
+    // record the hooks have handed off
+    journal::flush_if_pending().await?;
+
     let config = Config::load()?;
-    
+
+    // During a guest session, everything is redirected to the isolated
+    // guest store — recording and reading alike
+    let database_path = if guest::guest_session_active() {
+        guest::guest_db_path()
+    } else {
+        config.database_path.clone()
+    };
+
     // Ensure the config directory exists
-    if let Some(parent) = config.database_path.parent() {
+    if let Some(parent) = database_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
-    
-    let storage = SqliteStorage::new(&config.database_path).await?;
-    
+
+    let storage = SqliteStorage::new(&database_path).await?;
+
     // Ensure schema exists
     storage.ensure_schema().await?;
-    
+
+    // Crash recovery: replay journaled session events that never made
+    // it to the database and close sessions abandoned mid-crash
+    sessions::recover_sessions(&storage).await?;
+
     Ok(storage)
 }
 
+/// Median duration of a command's earlier successful timed runs, or
+/// None below the sample floor. ORDER BY + OFFSET because SQLite has
+/// no percentile function.
+async fn prior_median_duration(
+    storage: &SqliteStorage,
+    cmd: &termbrain_core::domain::entities::Command,
+) -> Result<Option<u64>> {
+    let window = "FROM commands
+                  WHERE parsed_command = ?1 AND exit_code = 0 AND duration_ms > 0 AND id != ?2";
+    let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) {window}"))
+        .bind(&cmd.parsed_command)
+        .bind(cmd.id.to_string())
+        .fetch_one(storage.pool())
+        .await?;
+    if (count as usize) < termbrain_core::perf::MIN_SAMPLES {
+        return Ok(None);
+    }
+    let median: Option<i64> = sqlx::query_scalar(&format!(
+        "SELECT duration_ms {window} ORDER BY duration_ms LIMIT 1 OFFSET (SELECT COUNT(*) / 2 {window})"
+    ))
+    .bind(&cmd.parsed_command)
+    .bind(cmd.id.to_string())
+    .fetch_optional(storage.pool())
+    .await?;
+    Ok(median.map(|m| m as u64))
+}
+
+/// Resolves a directory's project identity from its git origin remote.
+/// Best-effort: directories outside a repository simply have none.
+fn detect_project(directory: &str) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", directory, "remote", "get-url", "origin"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    termbrain_core::project::normalize_remote_url(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Builds the redaction service: built-in secret patterns plus any
+/// rules from the config file.
+fn redaction_service(config: &Config) -> Result<termbrain_core::redaction::RedactionService> {
+    let mut service = termbrain_core::redaction::RedactionService::with_default_rules();
+    for rule in &config.redaction_rules {
+        service.add_rule(&rule.name, &rule.pattern, &rule.placeholder)?;
+    }
+    Ok(service)
+}
+
+/// Parses repeated `KEY=VALUE` pairs into an extras map. Values that
+/// parse as JSON keep their type; anything else is stored as a string.
+fn parse_extras(pairs: &[String]) -> Result<std::collections::HashMap<String, serde_json::Value>> {
+    let mut extras = std::collections::HashMap::new();
+    for pair in pairs {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --extra '{}': expected KEY=VALUE", pair))?;
+        let value = serde_json::from_str(value)
+            .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+        extras.insert(key.to_string(), value);
+    }
+    Ok(extras)
+}
+
 pub async fn record_command(
     command: String,
     exit_code: i32,
     duration: Option<u64>,
     directory: Option<String>,
+    source: String,
+    extra: Vec<String>,
+    recorded_at: Option<DateTime<Utc>>,
 ) -> Result<()> {
     // Validate command input
     validate_command(&command)?;
@@ -47,18 +355,47 @@ pub async fn record_command(
         path.to_string_lossy().to_string()
     };
     
+    // Ignore rules run before every persistence path — forwarding an
+    // ignored command to the daemon or journal would defeat them
+    let config = Config::load()?;
+    if config.should_ignore(&command, &working_directory) {
+        return Ok(());
+    }
+
+    // When the ingestion daemon is running, hand the record over the
+    // socket and return before touching the database at all; without a
+    // daemon, the fast_record journal gives hooks the same early exit
+    if !journal::recording_directly() {
+        let message = daemon::RecordMessage {
+            command: command.clone(),
+            exit_code,
+            duration,
+            directory: Some(working_directory.clone()),
+            source: source.clone(),
+            extra: extra.clone(),
+            session_id: std::env::var("TERMBRAIN_SESSION_ID").ok(),
+            recorded_at: Some(recorded_at.unwrap_or_else(Utc::now)),
+        };
+        if daemon::forward_record(&message)? {
+            return Ok(());
+        }
+        if config.fast_record {
+            return journal::append_record(&message);
+        }
+    }
+
     // Use persistent storage
     let storage = create_storage().await?;
-    let repo = SqliteCommandRepository::new(storage.pool().clone());
-    
+    let repo = create_repo(&storage);
+
     // Parse command name and arguments
     let parts: Vec<&str> = command.split_whitespace().collect();
     let parsed_command = parts.first().unwrap_or(&"").to_string();
+
     let arguments = parts.into_iter().skip(1).map(|s| s.to_string()).collect();
     
     // Get and validate shell
-    let shell_path = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
-    let shell = shell_path.split('/').last().unwrap_or("bash").to_string();
+    let shell = crate::platform::detect_shell();
     if let Err(e) = validate_shell(&shell) {
         eprintln!("Warning: {}", e);
     }
@@ -77,7 +414,32 @@ pub async fn record_command(
         eprintln!("Warning: Invalid hostname '{}': {}", hostname, e);
     }
     
-    let cmd = termbrain_core::domain::entities::Command {
+    // Tag commands run inside an editor's integrated terminal so they
+    // can be filtered apart from standalone terminal work
+    let mut environment = std::collections::HashMap::new();
+    if let Some(ctx) = termbrain_core::editor::detect_editor_context(|k| std::env::var(k).ok()) {
+        environment.insert("editor".to_string(), ctx.editor);
+        if let Some(workspace) = ctx.workspace {
+            environment.insert("editor_workspace".to_string(), workspace);
+        }
+    }
+
+    // Git context read straight from .git files — recording is on the
+    // shell hook's hot path and must not spawn a git subprocess
+    if let Some(git) = termbrain_core::git_context::read_git_context(Path::new(&working_directory)) {
+        environment.insert("git_repo".to_string(), git.repo_root);
+        if let Some(branch) = git.branch {
+            environment.insert("git_branch".to_string(), branch);
+        }
+        if let Some(commit) = git.commit {
+            environment.insert("git_commit".to_string(), commit);
+        }
+        if let Some(dirty) = git.dirty {
+            environment.insert("git_dirty".to_string(), dirty.to_string());
+        }
+    }
+
+    let mut cmd = termbrain_core::domain::entities::Command {
         id: Uuid::new_v4(),
         raw: command.clone(),
         parsed_command,
@@ -85,20 +447,171 @@ pub async fn record_command(
         working_directory,
         exit_code,
         duration_ms: duration.unwrap_or(0),
-        timestamp: Utc::now(),
+        timestamp: recorded_at.unwrap_or_else(Utc::now),
         session_id: std::env::var("TERMBRAIN_SESSION_ID")
             .unwrap_or_else(|_| format!("{}-{}", Utc::now().timestamp(), std::process::id())),
+        source,
+        extras: parse_extras(&extra)?,
+        logical_clock: Some(next_logical_clock()),
         metadata: termbrain_core::domain::entities::CommandMetadata {
             shell,
             user,
             hostname,
             terminal: std::env::var("TERM").unwrap_or_else(|_| "xterm".to_string()),
-            environment: std::collections::HashMap::new(),
+            environment,
         },
     };
-    
+
+    // Stamp the project identity so analytics group clones of one
+    // repository across paths and machines. Corrections recorded via
+    // `tb reclassify` win over the git-remote heuristic.
+    if !cmd.extras.contains_key("project") {
+        let overrides = reclassify::load_overrides(&storage, "project").await?;
+        if let Some(project) = reclassify::directory_override(&overrides, &cmd.working_directory)
+            .or_else(|| detect_project(&cmd.working_directory))
+        {
+            cmd.extras
+                .insert("project".to_string(), serde_json::Value::String(project));
+        }
+    }
+
+    // Likewise for agent attribution: a session the user has attributed
+    // to an agent keeps that attribution for its remaining commands
+    if !cmd.extras.contains_key("agent") {
+        let overrides = reclassify::load_overrides(&storage, "agent").await?;
+        if let Some(agent) = overrides.get(&cmd.session_id) {
+            cmd.extras
+                .insert("agent".to_string(), serde_json::Value::String(agent.clone()));
+        }
+    }
+
+    // Keep the local project registry fresh; per-project views group
+    // commands by these marker-detected roots
+    if let Some((root, marker)) =
+        termbrain_core::project::detect_project_root(Path::new(&cmd.working_directory))
+    {
+        let name = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| root.display().to_string());
+        let projects = SqliteProjectRepository::new(storage.pool().clone());
+        projects
+            .upsert(&termbrain_core::domain::entities::Project {
+                id: Uuid::new_v4(),
+                name,
+                root: root.display().to_string(),
+                marker: marker.to_string(),
+                // The normalized remote stamped above links this root
+                // with other clones of the same repository
+                remote: cmd
+                    .extras
+                    .get("project")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                tags: Vec::new(),
+                first_seen: cmd.timestamp,
+                last_seen: cmd.timestamp,
+            })
+            .await?;
+    }
+
+    // Tag interactive REPL sessions so their time stops being an
+    // anonymous blob in analytics
+    let repl_profile = termbrain_core::repl::detect_repl(&cmd.parsed_command, &cmd.arguments);
+    if let Some(profile) = repl_profile {
+        cmd.extras.insert(
+            "repl".to_string(),
+            serde_json::Value::String(profile.name.to_string()),
+        );
+    }
+
+    // SQL passed inline to database CLIs is indexed separately so
+    // database work is searchable as SQL (tb search --sql)
+    if let Some(sql) = termbrain_core::sql_extract::extract_sql(&cmd.raw) {
+        cmd.extras
+            .insert("sql".to_string(), serde_json::Value::String(sql));
+    }
+
+    // Strip secrets before anything is persisted; which rules fired is
+    // recorded in extras so the alteration stays visible
+    redaction_service(&config)?.apply(&mut cmd);
+
+    // Bulk-loop throttling: past the threshold, a burst of identical
+    // commands collapses into one summarized row instead of thousands
+    if config.burst_summarize
+        && burst::fold_into_burst(&storage, &repo, &mut cmd, config.burst_keep_raw).await?
+    {
+        return Ok(());
+    }
+
     repo.save(&cmd).await?;
-    
+
+    // Count this record's transitions so `tb predict` stays current
+    predict::update_transitions(&storage, &cmd).await?;
+
+    // Optional slowness warning: one run landing 2× past the command's
+    // own median usually means something changed (cold cache, new
+    // dependency, struggling disk). Stderr only — the hooks discard it,
+    // manual and wrapped recording see it.
+    if config.perf_warnings && cmd.exit_code == 0 && cmd.duration_ms > 0 {
+        if let Some(median) = prior_median_duration(&storage, &cmd).await? {
+            if termbrain_core::perf::is_anomalously_slow(cmd.duration_ms, median) {
+                eprintln!(
+                    "🐢 '{}' took {:.1}s — {:.1}× its median of {:.1}s ('tb perf' shows the trend)",
+                    cmd.parsed_command,
+                    cmd.duration_ms as f64 / 1_000.0,
+                    cmd.duration_ms as f64 / median as f64,
+                    median as f64 / 1_000.0,
+                );
+            }
+        }
+    }
+
+    // The REPL's own history file holds what was typed inside; capture
+    // any statements not seen before
+    if let Some(profile) = repl_profile {
+        repl::capture_statements(&storage, &repo, &cmd, profile).await?;
+    }
+
+    // Chain a tamper-evidence hash over the record (checked by tb verify)
+    if config.integrity_chain {
+        verify::append_chain(storage.pool(), &cmd).await?;
+    }
+
+    // Track environment changes (installs, dotfile edits) for later
+    // correlation with failures
+    if let Some(change) = termbrain_core::env_changes::detect_env_change(&cmd) {
+        sqlx::query(
+            "INSERT INTO env_changes (id, kind, description, command_id, hostname, occurred_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(change.kind())
+        .bind(change.description())
+        .bind(cmd.id.to_string())
+        .bind(&cmd.metadata.hostname)
+        .bind(cmd.timestamp.to_rfc3339())
+        .execute(storage.pool())
+        .await?;
+        println!("   🌱 Environment change noted: {}", change.description());
+    }
+
+    // Branch watch: open/close intentions from matching branch events
+    intend::process_branch_event(&storage, &cmd, &config).await?;
+
+    // Link this command to any issue keys it mentions
+    let kind = issue::command_ref_kind(
+        &cmd.parsed_command,
+        cmd.arguments.first().map(String::as_str).unwrap_or(""),
+    );
+    issue::store_issue_refs(storage.pool(), kind, &cmd.id.to_string(), &cmd.raw).await?;
+
+    // Attach commit/PR references for pushes and gh pr commands
+    activity::enrich_git_activity(&storage, &cmd).await?;
+
+    // Persist an embedding now so semantic search never re-embeds history
+    #[cfg(feature = "embeddings")]
+    embeddings::embed_command(&storage, &config, &cmd.id.to_string(), &cmd.raw).await;
+
     println!("📝 Recording command: {}", command);
     println!("   Exit code: {}", exit_code);
     if let Some(dur) = duration {
@@ -108,12 +621,24 @@ pub async fn record_command(
     Ok(())
 }
 
+/// Which ranking strategy to use for `tb search`.
+#[derive(Debug, Clone)]
+pub enum SearchMode {
+    Keyword,
+    FullText,
+    Semantic,
+    Hybrid { keyword_weight: f32, semantic_weight: f32 },
+    /// Match against extracted SQL text rather than the command line.
+    Sql,
+}
+
 pub async fn search_commands(
     query: String,
     limit: usize,
     directory: Option<String>,
+    repo_filter: Option<String>,
     since: Option<String>,
-    semantic: bool,
+    mode: Option<SearchMode>,
     format: OutputFormat,
 ) -> Result<()> {
     // Validate query (relaxed validation for search)
@@ -130,7 +655,7 @@ pub async fn search_commands(
     };
     
     let storage = create_storage().await?;
-    let repo = SqliteCommandRepository::new(storage.pool().clone());
+    let repo = create_repo(&storage);
 
     // Parse since date if provided
     let since_date = if let Some(since_str) = since {
@@ -139,11 +664,113 @@ pub async fn search_commands(
         None
     };
 
+    // Minimal builds only ship keyword and full-text search
+    #[cfg(not(feature = "embeddings"))]
+    let mode = match mode {
+        Some(SearchMode::Semantic) | Some(SearchMode::Hybrid { .. }) => {
+            return Err(anyhow::anyhow!(
+                "This build was compiled without the `embeddings` feature; only keyword and --fts search are available"
+            ));
+        }
+        Some(SearchMode::FullText) => Some(SearchMode::FullText),
+        Some(SearchMode::Sql) => Some(SearchMode::Sql),
+        _ => Some(SearchMode::Keyword),
+    };
+
+    // Route by query intent when no explicit mode flag was given
+    let mode = match mode {
+        Some(mode) => mode,
+        None => {
+            let intent = termbrain_core::search::classify_query(&query);
+            let routed = match intent {
+                termbrain_core::search::QueryIntent::Keyword => SearchMode::Keyword,
+                termbrain_core::search::QueryIntent::ErrorMessage => SearchMode::Semantic,
+                // No AI provider is wired up yet, so natural language
+                // questions get the broadest ranking we have
+                termbrain_core::search::QueryIntent::NaturalLanguage => SearchMode::Hybrid {
+                    keyword_weight: 0.5,
+                    semantic_weight: 0.5,
+                },
+            };
+            if !matches!(format, OutputFormat::Json) {
+                let strategy = match routed {
+                    SearchMode::Keyword => "keyword",
+                    SearchMode::FullText => "full-text",
+                    SearchMode::Semantic => "semantic",
+                    SearchMode::Hybrid { .. } => "hybrid",
+                    SearchMode::Sql => "sql",
+                };
+                println!("🧭 Query looks like {} — using {} search (override with --keyword/--semantic/--hybrid)",
+                    intent, strategy);
+            }
+            routed
+        }
+    };
+
     // Perform search based on type
-    let results = if semantic {
-        repo.search_semantic(&query, limit).await?
-    } else {
-        repo.search(&query, limit, validated_directory.as_deref(), since_date).await?
+    let results = match mode {
+        SearchMode::Sql => {
+            // Matches only the SQL text extracted from database CLI
+            // invocations; rows come back through the repo for scoping
+            // and decryption
+            let ids = sqlx::query_scalar::<_, String>(
+                "SELECT id FROM commands
+                 WHERE json_extract(extras, '$.sql') LIKE '%' || ? || '%'
+                 ORDER BY timestamp DESC LIMIT ?",
+            )
+            .bind(&query)
+            .bind(limit as i64)
+            .fetch_all(storage.pool())
+            .await?;
+            let mut results = Vec::new();
+            for id in ids {
+                if let Ok(id) = id.parse::<Uuid>() {
+                    if let Some(cmd) = repo.find_by_id(&id).await? {
+                        results.push(cmd);
+                    }
+                }
+            }
+            results
+        }
+        SearchMode::FullText => repo.search_fulltext(&query, limit).await?,
+        SearchMode::Semantic => {
+            // Prefer stored embeddings when a provider is configured;
+            // only the query gets embedded per search
+            #[cfg(feature = "embeddings")]
+            let vector = embeddings::embed_query(&Config::load()?, &query).await;
+            #[cfg(not(feature = "embeddings"))]
+            let vector: Option<Vec<f32>> = None;
+
+            match vector {
+                Some(vector) => repo.search_by_vector(&vector, limit).await?,
+                None => repo.search_semantic(&query, limit).await?,
+            }
+        }
+        SearchMode::Hybrid { keyword_weight, semantic_weight } => {
+            if !(0.0..=1.0).contains(&keyword_weight) || !(0.0..=1.0).contains(&semantic_weight) {
+                return Err(anyhow::anyhow!("Hybrid search weights must be between 0.0 and 1.0"));
+            }
+            let weights = HybridWeights {
+                keyword: keyword_weight,
+                semantic: semantic_weight,
+            };
+            repo.search_hybrid(&query, limit, &weights).await?
+        }
+        SearchMode::Keyword => {
+            repo.search(&query, limit, validated_directory.as_deref(), since_date).await?
+        }
+    };
+
+    // Narrow to one repository (full root path, or just its basename)
+    let results = match repo_filter {
+        Some(wanted) => results
+            .into_iter()
+            .filter(|cmd| match cmd.metadata.environment.get("git_repo") {
+                Some(root) => *root == wanted || root.ends_with(&format!("/{}", wanted)),
+                None => false,
+            })
+            .collect(),
+        None => results,
     };
 
     // Display results
@@ -188,12 +815,32 @@ pub async fn search_commands(
     Ok(())
 }
 
+/// Filters applied by `tb history`.
+#[derive(Default)]
+pub struct HistoryFilters {
+    pub success_only: bool,
+    pub directory: Option<String>,
+    pub branch: Option<String>,
+    pub project: Option<String>,
+    pub editor: Option<String>,
+    pub source: Option<String>,
+    pub extra: Option<String>,
+}
+
 pub async fn show_history(
     limit: usize,
-    success_only: bool,
-    directory: Option<String>,
+    filters: HistoryFilters,
     format: OutputFormat,
 ) -> Result<()> {
+    let HistoryFilters {
+        success_only,
+        directory,
+        branch,
+        project,
+        editor,
+        source,
+        extra,
+    } = filters;
     println!("📚 Command History (last {} commands)", limit);
     if success_only {
         println!("   Filtering: successful commands only");
@@ -201,12 +848,29 @@ pub async fn show_history(
     if let Some(ref dir) = directory {
         println!("   Directory: {}", dir);
     }
+    if let Some(ref branch) = branch {
+        println!("   Branch: {}", branch);
+    }
+    if let Some(ref project) = project {
+        println!("   Project: {}", project);
+    }
+    if let Some(ref editor) = editor {
+        println!("   Editor: {}", editor);
+    }
+    if let Some(ref source) = source {
+        println!("   Source: {}", source);
+    }
     
     let storage = create_storage().await?;
-    let repo = SqliteCommandRepository::new(storage.pool().clone());
+    let repo = create_repo(&storage);
     
     // Get commands based on filters
-    let mut commands = if let Some(dir) = directory {
+    let mut commands = if let Some(pair) = extra {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --extra '{}': expected KEY=VALUE", pair))?;
+        repo.find_by_extra(key, value).await?
+    } else if let Some(dir) = directory {
         repo.find_by_directory(&dir).await?
     } else {
         repo.find_recent(limit).await?
@@ -216,7 +880,64 @@ pub async fn show_history(
     if success_only {
         commands.retain(|cmd| cmd.exit_code == 0);
     }
-    
+
+    // Filter to one project identity: every root registered under the
+    // name (clones linked by remote included), plus the stamped
+    // per-command identity, so records made before the registry — or
+    // on another machine — count too
+    if let Some(project) = project {
+        let projects = SqliteProjectRepository::new(storage.pool().clone());
+        let mut matched = projects.find_by_name(&project).await?;
+        for remote in matched
+            .iter()
+            .filter_map(|p| p.remote.clone())
+            .collect::<Vec<_>>()
+        {
+            for clone in projects.find_by_remote(&remote).await? {
+                if !matched.iter().any(|p| p.id == clone.id) {
+                    matched.push(clone);
+                }
+            }
+        }
+        if matched.is_empty() {
+            println!("\nNo project named '{}' detected yet — see tb projects list", project);
+            return Ok(());
+        }
+        let roots: Vec<&str> = matched.iter().map(|p| p.root.as_str()).collect();
+        let remotes: Vec<&str> = matched.iter().filter_map(|p| p.remote.as_deref()).collect();
+        commands.retain(|cmd| {
+            roots.iter().any(|root| {
+                cmd.working_directory == *root
+                    || cmd.working_directory.starts_with(&format!("{}/", root))
+            }) || cmd
+                .extras
+                .get("project")
+                .and_then(|v| v.as_str())
+                .is_some_and(|identity| remotes.contains(&identity))
+        });
+    }
+
+    // Filter by the git branch the command was recorded on
+    if let Some(branch) = branch {
+        commands.retain(|cmd| {
+            cmd.metadata.environment.get("git_branch") == Some(&branch)
+        });
+    }
+
+    // Filter by embedding editor ("vscode", "nvim", or "none" for
+    // standalone terminal work)
+    if let Some(editor) = editor {
+        commands.retain(|cmd| match cmd.metadata.environment.get("editor") {
+            Some(tag) => *tag == editor,
+            None => editor == "none",
+        });
+    }
+
+    // Filter by provenance; a "import" prefix matches every import source
+    if let Some(source) = source {
+        commands.retain(|cmd| cmd.source == source || cmd.source.starts_with(&format!("{}:", source)));
+    }
+
     // Limit results
     commands.truncate(limit);
     
@@ -261,37 +982,56 @@ pub async fn show_history(
     Ok(())
 }
 
+/// The look-back window a `--period` name covers.
+fn period_window(period: &str) -> Result<chrono::Duration> {
+    match period {
+        "day" => Ok(chrono::Duration::days(1)),
+        "week" => Ok(chrono::Duration::weeks(1)),
+        "month" => Ok(chrono::Duration::days(30)),
+        "year" => Ok(chrono::Duration::days(365)),
+        other => Err(anyhow::anyhow!(
+            "Unknown period '{}': use day, week, month, or year",
+            other
+        )),
+    }
+}
+
 pub async fn show_statistics(period: String, top: usize, format: OutputFormat) -> Result<()> {
-    println!("📊 Usage Statistics ({})", period);
+    let end = Utc::now();
+    let start = end - period_window(&period)?;
+
+    let team_view = user_scope() == UserScope::Team;
+    if team_view {
+        println!("📊 Team Usage Statistics ({})", period);
+    } else {
+        println!("📊 Usage Statistics ({})", period);
+    }
     println!("   Top {} commands:", top);
-    
+
     let storage = create_storage().await?;
-    let repo = SqliteCommandRepository::new(storage.pool().clone());
-    
-    // Get all commands for analysis
-    let commands = repo.find_recent(1000).await?; // Get a reasonable sample
-    
-    if commands.is_empty() {
-        println!("\nNo commands recorded yet");
+
+    // All aggregation happens inside the database, so the period flag
+    // scales past any in-memory sample size
+    let stats_repo = SqliteStatsRepository::with_scope(storage.pool().clone(), user_scope());
+    let stats = stats_repo.period_stats(start, end).await?;
+
+    if stats.total_commands == 0 {
+        println!("\nNo commands recorded in the last {}", period);
         return Ok(());
     }
-    
-    // Calculate statistics
-    let mut command_stats: std::collections::HashMap<String, (usize, usize)> = std::collections::HashMap::new();
-    
-    for cmd in &commands {
-        let entry = command_stats.entry(cmd.parsed_command.clone()).or_insert((0, 0));
-        entry.0 += 1; // total count
-        if cmd.exit_code == 0 {
-            entry.1 += 1; // success count
-        }
-    }
-    
-    // Sort by frequency
-    let mut sorted_stats: Vec<_> = command_stats.into_iter().collect();
-    sorted_stats.sort_by(|a, b| b.1.0.cmp(&a.1.0));
-    sorted_stats.truncate(top);
-    
+
+    let sorted_stats: Vec<(String, (usize, usize))> = stats_repo
+        .top_commands(start, end, top)
+        .await?
+        .into_iter()
+        .map(|usage| {
+            (
+                usage.parsed_command,
+                (usage.count as usize, usage.successes as usize),
+            )
+        })
+        .collect();
+
     match format {
         OutputFormat::Table => {
             if sorted_stats.is_empty() {
@@ -337,22 +1077,265 @@ pub async fn show_statistics(period: String, top: usize, format: OutputFormat) -
             }
         }
     }
-    
+
+    // Window-wide aggregates, straight from SQL
+    if !matches!(format, OutputFormat::Json | OutputFormat::Csv) {
+        println!("\n📈 Period overview:");
+        println!(
+            "   {} commands ({} unique), {:.1}% success",
+            stats.total_commands, stats.unique_commands, stats.success_rate
+        );
+        if let (Some(p50), Some(p95)) = (stats.duration_p50_ms, stats.duration_p95_ms) {
+            println!("   duration p50 {} ms, p95 {} ms", p50, p95);
+        }
+        if !stats.busiest_hours.is_empty() {
+            let hours: Vec<String> = stats
+                .busiest_hours
+                .iter()
+                .map(|(hour, count)| format!("{:02}:00 ({})", hour, count))
+                .collect();
+            println!("   busiest hours: {}", hours.join(", "));
+        }
+        if !stats.busiest_days.is_empty() {
+            let days: Vec<String> = stats
+                .busiest_days
+                .iter()
+                .map(|(day, count)| format!("{} ({})", day, count))
+                .collect();
+            println!("   busiest days: {}", days.join(", "));
+        }
+    }
+
+    // Custom metrics from the config file
+    let config = Config::load()?;
+    if !config.metrics.is_empty() {
+        let metric_values = evaluate_metrics(&storage, &config).await?;
+        if !metric_values.is_empty() {
+            match format {
+                OutputFormat::Json | OutputFormat::Csv => {
+                    // Structured formats already printed above; metrics go to a separate block
+                    for metric in &metric_values {
+                        println!("{},{}", metric.name, metric.value);
+                    }
+                }
+                _ => {
+                    println!("\n📐 Custom metrics:");
+                    for metric in &metric_values {
+                        println!("   {:<30} {}", metric.name, metric.value);
+                    }
+                }
+            }
+        }
+    }
+
+    // Heaviest recurring commands by estimated resource cost, over the
+    // newest (at most) 1000 commands in the window — cost aggregation
+    // needs full records, so it samples rather than scanning everything
+    if !matches!(format, OutputFormat::Json | OutputFormat::Csv) {
+        let repo = create_repo(&storage);
+        let sample_offset = (stats.total_commands as usize).saturating_sub(1000);
+        let commands = repo
+            .find_by_time_range_paged(start, end, sample_offset, 1000)
+            .await?;
+        let costs = termbrain_core::cost::aggregate_costs(&commands, 3);
+        let heavy: Vec<_> = costs
+            .iter()
+            .filter(|cost| cost.total_cpu_seconds >= 10.0)
+            .take(5)
+            .collect();
+        if !heavy.is_empty() {
+            println!("\n💸 Heaviest recurring commands:");
+            for cost in heavy {
+                let rss = cost
+                    .max_rss_kb
+                    .map(|kb| format!(", peak {:.0} MB", kb as f64 / 1024.0))
+                    .unwrap_or_default();
+                println!(
+                    "   {:<30} {:.0} cpu-seconds over {} runs{}",
+                    cost.parsed_command, cost.total_cpu_seconds, cost.runs, rss,
+                );
+                if let Some(hint) = termbrain_core::cost::waste_hint(&cost.parsed_command) {
+                    println!("      💡 {}", hint);
+                }
+            }
+        }
+    }
+
+    // Database activity: SQL extracted from psql/mysql/sqlite3
+    // invocations, grouped by verb
+    if !matches!(format, OutputFormat::Json | OutputFormat::Csv) {
+        let statements = sqlx::query_scalar::<_, String>(
+            "SELECT json_extract(extras, '$.sql') FROM commands
+             WHERE json_extract(extras, '$.sql') IS NOT NULL
+               AND timestamp >= ? AND timestamp <= ?
+             ORDER BY timestamp DESC LIMIT 1000",
+        )
+        .bind(start.to_rfc3339())
+        .bind(end.to_rfc3339())
+        .fetch_all(storage.pool())
+        .await?;
+        if !statements.is_empty() {
+            let mut by_verb: std::collections::HashMap<&str, usize> =
+                std::collections::HashMap::new();
+            for sql in &statements {
+                let verb = termbrain_core::sql_extract::sql_verb(sql).unwrap_or("other");
+                *by_verb.entry(verb).or_insert(0) += 1;
+            }
+            let mut by_verb: Vec<_> = by_verb.into_iter().collect();
+            by_verb.sort_by_key(|entry| std::cmp::Reverse(entry.1));
+
+            println!("\n🗄️  Database activity ({} statements):", statements.len());
+            for (verb, count) in by_verb {
+                println!("   {:<10} {}", verb, count);
+            }
+            println!("   Search the SQL itself with 'tb search --sql <text>'");
+        }
+    }
+
+    // Team view: show who is contributing to the aggregate
+    if team_view && !matches!(format, OutputFormat::Json | OutputFormat::Csv) {
+        println!("\n👥 Per-user activity:");
+        for (user, count) in stats_repo.per_user(start, end).await? {
+            println!("   {:<20} {} commands", user, count);
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders `tb stats --heatmap`: a GitHub-style weekly activity grid
+/// plus per-day trend sparklines, from SQL-bucketed daily counts.
+pub async fn show_heatmap(period: String, format: OutputFormat) -> Result<()> {
+    use chrono::Datelike;
+
+    let end = Utc::now();
+    let start = end - period_window(&period)?;
+
+    let storage = create_storage().await?;
+    let stats_repo = SqliteStatsRepository::with_scope(storage.pool().clone(), user_scope());
+    let days = stats_repo.daily_activity(start, end).await?;
+
+    if days.is_empty() {
+        println!("No commands recorded in the last {}", period);
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = days
+                .iter()
+                .map(|day| {
+                    serde_json::json!({
+                        "date": day.date,
+                        "commands": day.commands,
+                        "failures": day.failures,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+            return Ok(());
+        }
+        OutputFormat::Csv => {
+            println!("date,commands,failures");
+            for day in &days {
+                println!("{},{},{}", day.date, day.commands, day.failures);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let by_date: std::collections::HashMap<&str, &termbrain_core::domain::DailyActivity> =
+        days.iter().map(|day| (day.date.as_str(), day)).collect();
+
+    let first = start.date_naive();
+    let last = end.date_naive();
+    let total_days = (last - first).num_days() as usize + 1;
+    // Pad the first column so rows line up on weekdays, GitHub-style
+    let lead = first.weekday().num_days_from_monday() as usize;
+    let weeks = (lead + total_days).div_ceil(7);
+    let peak = days.iter().map(|day| day.commands).max().unwrap_or(0);
+
+    const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    println!("📅 Activity heatmap ({} — {}):", first, last);
+    for (row, label) in WEEKDAYS.iter().enumerate() {
+        let mut line = String::new();
+        for week in 0..weeks {
+            let index = week * 7 + row;
+            if index < lead || index - lead >= total_days {
+                line.push(' ');
+                continue;
+            }
+            let date = first + chrono::Duration::days((index - lead) as i64);
+            let count = by_date
+                .get(date.to_string().as_str())
+                .map(|day| day.commands)
+                .unwrap_or(0);
+            line.push(termbrain_core::viz::heat_glyph(count, peak));
+        }
+        println!("   {} {}", label, line);
+    }
+    println!("       · none ░ light ▒ medium ▓ heavy █ peak ({}/day)", peak);
+
+    // Chronological daily series, zero-filled for quiet days
+    let mut counts = Vec::with_capacity(total_days);
+    let mut error_rates = Vec::with_capacity(total_days);
+    for offset in 0..total_days {
+        let date = first + chrono::Duration::days(offset as i64);
+        match by_date.get(date.to_string().as_str()) {
+            Some(day) => {
+                counts.push(day.commands as f64);
+                error_rates.push(day.failures as f64 / day.commands.max(1) as f64 * 100.0);
+            }
+            None => {
+                counts.push(0.0);
+                error_rates.push(0.0);
+            }
+        }
+    }
+
+    // Long windows compress into ≤60 buckets so a sparkline fits a row
+    let bucket = counts.len().div_ceil(60).max(1);
+    let counts: Vec<f64> = counts
+        .chunks(bucket)
+        .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
+        .collect();
+    let error_rates: Vec<f64> = error_rates
+        .chunks(bucket)
+        .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
+        .collect();
+    let peak_errors = error_rates.iter().copied().fold(0.0_f64, f64::max);
+
+    println!();
+    println!("📈 Commands/day  {}  (peak {})", termbrain_core::viz::sparkline(&counts), peak);
+    println!(
+        "   Error rate    {}  (peak {:.0}%)",
+        termbrain_core::viz::sparkline(&error_rates),
+        peak_errors
+    );
+    if bucket > 1 {
+        println!("   (each point averages {} days)", bucket);
+    }
     Ok(())
 }
 
 pub async fn show_patterns(
     confidence: f32,
     pattern_type: Option<String>,
+    suggest_workflows: bool,
     format: OutputFormat,
 ) -> Result<()> {
+    if shadow_mode_guard(&Config::load()?) {
+        return Ok(());
+    }
+
     println!("🔄 Detected Patterns (confidence >= {:.1})", confidence);
     if let Some(ptype) = pattern_type {
         println!("   Pattern type: {}", ptype);
     }
     
     let storage = create_storage().await?;
-    let repo = SqliteCommandRepository::new(storage.pool().clone());
+    let repo = create_repo(&storage);
     
     // Get recent commands for basic pattern analysis
     let commands = repo.find_recent(100).await?;
@@ -386,7 +1369,17 @@ pub async fn show_patterns(
         .collect();
     
     patterns.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-    
+
+    // Persist detections so they have stable ids (`tb workflow adopt <id>`)
+    let mut ids: Vec<String> = Vec::new();
+    for (pattern, conf, freq) in &patterns {
+        ids.push(upsert_pattern(storage.pool(), pattern, *conf, *freq).await?);
+    }
+
+    if suggest_workflows {
+        return suggest_workflows_from_patterns(storage.pool(), &patterns).await;
+    }
+
     match format {
         OutputFormat::Table => {
             if patterns.is_empty() {
@@ -394,22 +1387,24 @@ pub async fn show_patterns(
                 println!("Try lowering the confidence threshold or record more commands");
                 return Ok(());
             }
-            
+
             println!("\n┌─────────────────────────────────────────┬────────────┬─────────────┐");
             println!("│ Pattern                                 │ Confidence │ Frequency   │");
             println!("├─────────────────────────────────────────┼────────────┼─────────────┤");
-            
+
             for (pattern, confidence_val, frequency) in patterns {
                 let truncated_pattern = truncate_string(&pattern, 39);
-                println!("│ {:<39} │ {:>8.2}   │ {:>9} │", 
+                println!("│ {:<39} │ {:>8.2}   │ {:>9} │",
                     truncated_pattern, confidence_val, frequency);
             }
-            
+
             println!("└─────────────────────────────────────────┴────────────┴─────────────┘");
+            println!("Promote one into a workflow: tb workflow adopt <pattern-id> (--format plain shows ids)");
         }
         OutputFormat::Json => {
-            let pattern_data: Vec<_> = patterns.into_iter().map(|(pattern, conf, freq)| {
+            let pattern_data: Vec<_> = patterns.into_iter().zip(&ids).map(|((pattern, conf, freq), id)| {
                 serde_json::json!({
+                    "id": id,
                     "pattern": pattern,
                     "confidence": conf,
                     "frequency": freq
@@ -418,95 +1413,124 @@ pub async fn show_patterns(
             println!("{}", serde_json::to_string_pretty(&pattern_data)?);
         }
         OutputFormat::Csv => {
-            println!("pattern,confidence,frequency");
-            for (pattern, conf, freq) in patterns {
-                println!("{},{:.2},{}", pattern, conf, freq);
+            println!("id,pattern,confidence,frequency");
+            for ((pattern, conf, freq), id) in patterns.into_iter().zip(&ids) {
+                println!("{},{},{:.2},{}", id, pattern, conf, freq);
             }
         }
         OutputFormat::Plain => {
-            for (pattern, conf, freq) in patterns {
-                println!("{} (confidence: {:.2}, {} times)", pattern, conf, freq);
+            for ((pattern, conf, freq), id) in patterns.into_iter().zip(&ids) {
+                println!("[{}] {} (confidence: {:.2}, {} times)", &id[..8], pattern, conf, freq);
             }
         }
     }
-    
+
     Ok(())
 }
 
-pub async fn handle_workflow(action: WorkflowAction, _format: OutputFormat) -> Result<()> {
-    match action {
-        WorkflowAction::List => {
-            println!("🔄 Available Workflows:");
-            println!("\n1. deploy-frontend");
-            println!("   Steps: build → test → deploy");
-            println!("   Used: 12 times");
-            println!("\n2. git-workflow");
-            println!("   Steps: add → commit → push");
-            println!("   Used: 45 times");
-        }
-        WorkflowAction::Create { name } => {
-            println!("✨ Created workflow: {}", name);
-        }
-        WorkflowAction::Run { name } => {
-            println!("▶️  Running workflow: {}", name);
-        }
-        WorkflowAction::Delete { name } => {
-            println!("🗑️  Deleted workflow: {}", name);
-        }
+/// Inserts or refreshes a detected sequence pattern, returning its
+/// stable id.
+async fn upsert_pattern(
+    pool: &sqlx::SqlitePool,
+    pattern: &str,
+    confidence: f32,
+    frequency: usize,
+) -> Result<String> {
+    use sqlx::Row;
+
+    let now = Utc::now().to_rfc3339();
+    if let Some(row) = sqlx::query("SELECT id FROM patterns WHERE name = ?")
+        .bind(pattern)
+        .fetch_optional(pool)
+        .await?
+    {
+        let id: String = row.get("id");
+        sqlx::query("UPDATE patterns SET frequency = ?2, confidence = ?3, last_seen = ?4 WHERE id = ?1")
+            .bind(&id)
+            .bind(frequency as i64)
+            .bind(confidence)
+            .bind(&now)
+            .execute(pool)
+            .await?;
+        return Ok(id);
     }
-    Ok(())
+
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO patterns (id, name, description, pattern_type, frequency, last_seen, confidence)
+         VALUES (?1, ?2, '', 'sequence', ?3, ?4, ?5)",
+    )
+    .bind(&id)
+    .bind(pattern)
+    .bind(frequency as i64)
+    .bind(&now)
+    .bind(confidence)
+    .execute(pool)
+    .await?;
+    Ok(id)
 }
 
-pub async fn export_data(
-    output: String,
-    format: ExportFormat,
-    since: Option<String>,
-    until: Option<String>,
-) -> Result<()> {
-    println!("📤 Exporting data to: {}", output);
-    println!("   Format: {:?}", format);
-    if let Some(since_date) = since {
-        println!("   From: {}", since_date);
+/// Exports only k-anonymous aggregate statistics for team sharing.
+///
+/// The k threshold is enforced in `termbrain_core::privacy`, so no
+/// tool or category that fewer than `k` distinct users touched can
+/// appear in the output file regardless of flags.
+pub async fn export_aggregates(output: String, k: usize) -> Result<()> {
+    if k == 0 {
+        return Err(anyhow::anyhow!("k-anonymity threshold must be at least 1"));
     }
-    if let Some(until_date) = until {
-        println!("   Until: {}", until_date);
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let commands = repo.find_recent(usize::MAX / 2).await?;
+    let report = termbrain_core::privacy::k_anonymous_aggregates(&commands, k);
+
+    std::fs::write(&output, serde_json::to_string_pretty(&report)?)?;
+
+    println!("📤 Exported aggregate statistics to: {}", output);
+    println!("   k-anonymity threshold: {}", report.k);
+    println!("   Contributing users: {}", report.contributing_users);
+    println!("   Tools included: {}", report.top_tools.len());
+    println!("   Categories included: {}", report.failure_rates.len());
+
+    if report.top_tools.is_empty() && report.failure_rates.is_empty() {
+        println!("   ⚠️  Everything was suppressed — fewer than {} users share this database", k);
     }
-    println!("✅ Export completed");
+
     Ok(())
 }
 
 pub async fn install_shell_integration(shell: Option<String>, yes: bool) -> Result<()> {
     // Detect shell if not provided
-    let shell_type = if let Some(s) = shell {
-        s
-    } else {
-        let shell_path = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
-        shell_path.split('/').last().unwrap_or("bash").to_string()
+    let shell_type = shell.unwrap_or_else(crate::platform::detect_shell);
+    // Normalize the aliases people actually type
+    let shell_type = match shell_type.as_str() {
+        "pwsh" => "powershell".to_string(),
+        "nushell" => "nu".to_string(),
+        _ => shell_type,
     };
 
     println!("🛠️  Installing shell integration for: {}", shell_type);
-    
+
     // Validate shell
-    if !["bash", "zsh", "fish"].contains(&shell_type.as_str()) {
-        return Err(anyhow::anyhow!("Unsupported shell: {}. Supported shells: bash, zsh, fish", shell_type));
+    if !["bash", "zsh", "fish", "powershell", "nu"].contains(&shell_type.as_str()) {
+        return Err(anyhow::anyhow!("Unsupported shell: {}. Supported shells: bash, zsh, fish, powershell, nu", shell_type));
     }
-    
+
     // Get the appropriate shell integration file
     let integration_content = match shell_type.as_str() {
         "bash" => include_str!("../../../../shell-integration/bash/termbrain.bash"),
-        "zsh" => include_str!("../../../../shell-integration/zsh/termbrain.zsh"), 
+        "zsh" => include_str!("../../../../shell-integration/zsh/termbrain.zsh"),
         "fish" => include_str!("../../../../shell-integration/fish/termbrain.fish"),
+        "powershell" => include_str!("../../../../shell-integration/powershell/termbrain.ps1"),
+        "nu" => include_str!("../../../../shell-integration/nu/termbrain.nu"),
         _ => return Err(anyhow::anyhow!("Unsupported shell: {}", shell_type)),
     };
-    
+
     // Get shell config file
-    let home_dir = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-    let config_file = match shell_type.as_str() {
-        "bash" => home_dir.join(".bashrc"),
-        "zsh" => home_dir.join(".zshrc"),
-        "fish" => home_dir.join(".config/fish/config.fish"),
-        _ => return Err(anyhow::anyhow!("Unsupported shell: {}", shell_type)),
-    };
+    let config_file = crate::platform::shell_profile(&shell_type)
+        .ok_or_else(|| anyhow::anyhow!("Could not find a profile location for {}", shell_type))?;
     
     // Create shell integration directory
     let config = Config::load()?;
@@ -514,15 +1538,40 @@ pub async fn install_shell_integration(shell: Option<String>, yes: bool) -> Resu
     std::fs::create_dir_all(&integration_dir)?;
     
     // Write integration script
-    let script_name = format!("termbrain.{}", shell_type);
+    let script_name = match shell_type.as_str() {
+        "powershell" => "termbrain.ps1".to_string(),
+        _ => format!("termbrain.{}", shell_type),
+    };
     let script_path = integration_dir.join(&script_name);
     std::fs::write(&script_path, integration_content)?;
     
     println!("   Created integration script: {}", script_path.display());
-    
+
+    // Optional autosuggestion add-ons for shells that support them;
+    // written alongside the main script but never sourced automatically
+    let autosuggest = match shell_type.as_str() {
+        "zsh" => Some((
+            "termbrain-autosuggest.zsh",
+            include_str!("../../../../shell-integration/zsh/termbrain-autosuggest.zsh"),
+            "source it after zsh-autosuggestions and set ZSH_AUTOSUGGEST_STRATEGY=(termbrain history)",
+        )),
+        "fish" => Some((
+            "termbrain-autosuggest.fish",
+            include_str!("../../../../shell-integration/fish/termbrain-autosuggest.fish"),
+            "source it to bind Ctrl-S to history-based completion",
+        )),
+        _ => None,
+    };
+    if let Some((name, content, hint)) = autosuggest {
+        let path = integration_dir.join(name);
+        std::fs::write(&path, content)?;
+        println!("   Created autosuggest script: {} ({})", path.display(), hint);
+    }
+
     // Check if already integrated
     let source_line = match shell_type.as_str() {
-        "fish" => format!("source {}", script_path.display()),
+        "fish" | "nu" => format!("source {}", script_path.display()),
+        "powershell" => format!(". \"{}\"", script_path.display()),
         _ => format!("source \"{}\"", script_path.display()),
     };
     
@@ -596,12 +1645,10 @@ pub async fn uninstall_termbrain(purge: bool, yes: bool) -> Result<()> {
     println!("\n   The following will be removed:");
     
     // Shell integration removal
-    let home_dir = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-    let shell_configs = vec![
-        ("bash", home_dir.join(".bashrc")),
-        ("zsh", home_dir.join(".zshrc")),
-        ("fish", home_dir.join(".config/fish/config.fish")),
-    ];
+    let shell_configs: Vec<_> = ["bash", "zsh", "fish", "powershell", "nu"]
+        .iter()
+        .filter_map(|shell| crate::platform::shell_profile(shell).map(|path| (shell, path)))
+        .collect();
     
     let mut found_integrations = Vec::new();
     for (shell, config_file) in &shell_configs {
@@ -760,6 +1807,7 @@ fn get_directory_size(path: &std::path::Path) -> Result<u64> {
     Ok(size)
 }
 
+#[cfg(feature = "tui")]
 pub async fn start_interactive_session() -> Result<()> {
     println!("🚀 Starting TermBrain interactive session...");
     println!("   Type 'help' for commands, 'exit' to quit");
@@ -771,7 +1819,7 @@ pub async fn show_status(format: OutputFormat) -> Result<()> {
     println!("📊 TermBrain Status");
     
     let storage = create_storage().await?;
-    let repo = SqliteCommandRepository::new(storage.pool().clone());
+    let repo = create_repo(&storage);
     let config = Config::load()?;
     
     // Get actual metrics
@@ -841,7 +1889,11 @@ pub async fn show_status(format: OutputFormat) -> Result<()> {
             println!("Database Size: {}", db_size);
         }
     }
-    
+
+    if let Some(line) = backup_status_line(&config) {
+        println!("{}", line);
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,82 @@
+//! Performance regression view
+//!
+//! `tb perf` lists recurring commands whose recent runs are
+//! significantly slower than their own historical baseline, worst
+//! first. The detection itself lives in `termbrain_core::perf`; with
+//! `perf_warnings` enabled in the config, `tb record` also warns the
+//! moment a run lands 2× past its median.
+
+use anyhow::Result;
+use termbrain_core::domain::CommandRepository;
+use termbrain_core::perf::detect_regressions;
+
+use crate::OutputFormat;
+
+use super::{create_repo, create_storage};
+
+/// Shows duration regressions over the last `days` days.
+pub async fn show_perf(days: u32, format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let end = chrono::Utc::now();
+    let start = end - chrono::Duration::days(days as i64);
+    // find_by_time_range returns newest first; the detector reads
+    // history forward
+    let mut commands = repo.find_by_time_range(start, end).await?;
+    commands.reverse();
+
+    let regressions = detect_regressions(&commands);
+
+    if matches!(format, OutputFormat::Json) {
+        let entries: Vec<_> = regressions
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "command": r.parsed_command,
+                    "baseline_median_ms": r.baseline_median_ms,
+                    "recent_median_ms": r.recent_median_ms,
+                    "ratio": r.ratio,
+                    "baseline_samples": r.baseline_samples,
+                    "recent_samples": r.recent_samples,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if regressions.is_empty() {
+        println!("No duration regressions in the last {} days", days);
+        println!("   (needs commands with enough timed successful runs to compare)");
+        return Ok(());
+    }
+
+    println!("🐢 Duration regressions — last {} days:", days);
+    for regression in &regressions {
+        println!(
+            "   {:<20} {} → {} ({:.1}× slower, {}+{} runs)",
+            regression.parsed_command,
+            human_ms(regression.baseline_median_ms),
+            human_ms(regression.recent_median_ms),
+            regression.ratio,
+            regression.baseline_samples,
+            regression.recent_samples,
+        );
+    }
+    println!();
+    println!("💡 Medians of each command's older runs vs its last few; failures excluded");
+
+    Ok(())
+}
+
+/// Renders milliseconds as "340ms" / "2.4s" / "3m 10s".
+fn human_ms(ms: u64) -> String {
+    if ms < 1_000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1_000.0)
+    } else {
+        format!("{}m {}s", ms / 60_000, (ms % 60_000) / 1_000)
+    }
+}
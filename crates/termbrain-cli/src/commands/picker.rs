@@ -0,0 +1,86 @@
+//! Interactive history picker (`tb search --interactive`)
+//!
+//! Backs the Ctrl-R shell widget: the UI talks to the terminal via
+//! /dev/tty and stderr, and only the selected command is printed to
+//! stdout, so the shell binding can capture it into the prompt line.
+//! `fix N` corrects a record's classification without leaving the
+//! picker (see `tb reclassify`).
+
+use anyhow::Result;
+use std::io::{BufRead, BufReader, Write};
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::picker::rank_candidates;
+
+use super::{create_repo, create_storage};
+
+/// How much history the picker ranks over.
+const HISTORY_WINDOW: usize = 5000;
+
+/// Runs the interactive picker. `initial_query` seeds the filter (the
+/// shell widget passes the current prompt line). Prints the selection
+/// to stdout; prints nothing when cancelled.
+pub async fn interactive_search(initial_query: String, limit: usize) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+    let commands = repo.find_recent(HISTORY_WINDOW).await?;
+    let cwd = std::env::current_dir()
+        .map(|d| d.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let tty = std::fs::File::open("/dev/tty")
+        .map_err(|_| anyhow::anyhow!("tb search --interactive needs a terminal"))?;
+    let mut tty = BufReader::new(tty);
+    let mut err = std::io::stderr();
+
+    let mut query = initial_query;
+    loop {
+        let candidates = rank_candidates(&commands, &query, &cwd, limit);
+
+        writeln!(err)?;
+        if candidates.is_empty() {
+            writeln!(err, "  (no matches)")?;
+        }
+        for (i, candidate) in candidates.iter().enumerate() {
+            writeln!(err, "  {:>2}. {}", i + 1, candidate)?;
+        }
+        write!(
+            err,
+            "search [{}]> type to filter, number to select, 'fix N' to correct, empty to cancel: ",
+            query
+        )?;
+        err.flush()?;
+
+        let mut line = String::new();
+        if tty.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let input = line.trim();
+
+        if input.is_empty() {
+            return Ok(());
+        }
+        // `fix N` corrects candidate N's classification in place; the
+        // conversation stays on stderr so stdout remains selection-only
+        if let Some(choice) = input
+            .strip_prefix("fix ")
+            .and_then(|n| n.trim().parse::<usize>().ok())
+        {
+            if choice >= 1 && choice <= candidates.len() {
+                if let Some(command) = commands.iter().find(|c| c.raw == candidates[choice - 1]) {
+                    super::reclassify::correct_interactively(
+                        &mut tty, &mut err, &storage, &repo, command,
+                    )
+                    .await?;
+                }
+            }
+            continue;
+        }
+        if let Ok(choice) = input.parse::<usize>() {
+            if choice >= 1 && choice <= candidates.len() {
+                println!("{}", candidates[choice - 1]);
+                return Ok(());
+            }
+        }
+        query = input.to_string();
+    }
+}
@@ -0,0 +1,265 @@
+//! Data-driven next-command prediction
+//!
+//! `tb predict` ranks likely next commands from the persisted
+//! transition table (see `termbrain_core::markov`), which counts what
+//! actually followed what — per directory and globally — and is
+//! updated incrementally on every record. `--explain` shows the
+//! evidence behind each candidate, `--accuracy` measures the model's
+//! hit rate on held-out history, `--rebuild` recounts from scratch.
+
+use anyhow::Result;
+use sqlx::Row;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::markov::{contexts, evaluate, rank_predictions, TransitionObservation, MAX_ORDER};
+use termbrain_storage::sqlite::SqliteStorage;
+
+use crate::OutputFormat;
+
+use super::{create_repo, create_storage};
+
+/// History replayed for `--rebuild` and `--accuracy`.
+const ANALYSIS_WINDOW: usize = 10_000;
+
+/// Incrementally counts one freshly recorded command's transitions,
+/// globally and under its directory. Called from the record pipeline.
+pub(super) async fn update_transitions(storage: &SqliteStorage, cmd: &Command) -> Result<()> {
+    let mut previous: Vec<String> = sqlx::query(
+        "SELECT parsed_command FROM commands
+         WHERE session_id = ?1 AND id != ?2 AND timestamp <= ?3
+         ORDER BY timestamp DESC LIMIT ?4",
+    )
+    .bind(&cmd.session_id)
+    .bind(cmd.id.to_string())
+    .bind(cmd.timestamp.to_rfc3339())
+    .bind(MAX_ORDER as i64)
+    .fetch_all(storage.pool())
+    .await?
+    .into_iter()
+    .map(|row| row.get("parsed_command"))
+    .collect();
+    previous.reverse();
+
+    for context in contexts(&previous) {
+        for scope in ["global", cmd.working_directory.as_str()] {
+            record_transition(storage, scope, &context, &cmd.raw).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn record_transition(
+    storage: &SqliteStorage,
+    scope: &str,
+    context: &str,
+    next: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO command_transitions (scope, context, next, count, updated_at)
+         VALUES (?1, ?2, ?3, 1, ?4)
+         ON CONFLICT(scope, context, next)
+         DO UPDATE SET count = count + 1, updated_at = excluded.updated_at",
+    )
+    .bind(scope)
+    .bind(context)
+    .bind(next)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(storage.pool())
+    .await?;
+    Ok(())
+}
+
+/// Shows ranked next-command predictions for the current context.
+pub async fn show_predictions(
+    explain: bool,
+    top: usize,
+    rebuild: bool,
+    accuracy: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    if super::shadow_mode_guard(&crate::config::Config::load()?) {
+        return Ok(());
+    }
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    if rebuild {
+        rebuild_transitions(&storage, &repo).await?;
+    }
+    if accuracy {
+        return show_accuracy(&repo, format).await;
+    }
+
+    // Context: the latest session's trailing commands, and the
+    // directory the last command ran in
+    let recent = repo.find_recent(10).await?;
+    let Some(last) = recent.first() else {
+        println!("No commands recorded yet — nothing to predict from");
+        return Ok(());
+    };
+    let mut previous: Vec<String> = recent
+        .iter()
+        .filter(|c| c.session_id == last.session_id)
+        .take(MAX_ORDER)
+        .map(|c| c.parsed_command.clone())
+        .collect();
+    previous.reverse();
+
+    let observations = load_observations(&storage, &previous, &last.working_directory).await?;
+    let ranked = rank_predictions(&observations);
+
+    if matches!(format, OutputFormat::Json) {
+        let entries: Vec<_> = ranked
+            .iter()
+            .take(top)
+            .map(|p| {
+                serde_json::json!({
+                    "command": p.next,
+                    "probability": p.probability,
+                    "evidence": p.evidence.iter().map(|e| serde_json::json!({
+                        "context": e.context,
+                        "scope": if e.scoped { "directory" } else { "global" },
+                        "count": e.count,
+                        "total": e.total,
+                        "weight": e.weight,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if ranked.is_empty() {
+        println!("No transitions observed after '{}' yet", last.parsed_command);
+        println!("   The model learns as you work; 'tb predict --rebuild' recounts existing history");
+        return Ok(());
+    }
+
+    println!("🔮 Likely after '{}':", last.parsed_command);
+    for prediction in ranked.iter().take(top) {
+        println!(
+            "   {:>4.0}%  {}",
+            prediction.probability * 100.0,
+            prediction.next,
+        );
+        if explain {
+            for evidence in &prediction.evidence {
+                println!(
+                    "          ↳ followed '{}' {} of {} times {} (weight {:.0})",
+                    evidence.context,
+                    evidence.count,
+                    evidence.total,
+                    if evidence.scoped { "in this directory" } else { "globally" },
+                    evidence.weight,
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads the stored counts relevant to the given contexts, global and
+/// directory-scoped.
+pub(super) async fn load_observations(
+    storage: &SqliteStorage,
+    previous: &[String],
+    directory: &str,
+) -> Result<Vec<TransitionObservation>> {
+    let mut observations = Vec::new();
+    for context in contexts(previous) {
+        let rows = sqlx::query(
+            "SELECT scope, next, count FROM command_transitions
+             WHERE context = ?1 AND scope IN ('global', ?2)",
+        )
+        .bind(&context)
+        .bind(directory)
+        .fetch_all(storage.pool())
+        .await?;
+        for row in rows {
+            observations.push(TransitionObservation {
+                context: context.clone(),
+                scoped: row.get::<String, _>("scope") != "global",
+                next: row.get("next"),
+                count: row.get::<i64, _>("count") as u32,
+            });
+        }
+    }
+    Ok(observations)
+}
+
+/// Recounts the transition table from recorded history, replaying each
+/// session in order exactly as incremental recording would have.
+async fn rebuild_transitions(
+    storage: &SqliteStorage,
+    repo: &termbrain_storage::sqlite::SqliteCommandRepository,
+) -> Result<()> {
+    sqlx::query("DELETE FROM command_transitions")
+        .execute(storage.pool())
+        .await?;
+
+    let mut commands = repo.find_recent(ANALYSIS_WINDOW).await?;
+    commands.reverse();
+
+    let mut tails: std::collections::HashMap<&str, Vec<String>> = std::collections::HashMap::new();
+    let mut counted = 0usize;
+    for cmd in &commands {
+        let previous = tails.entry(cmd.session_id.as_str()).or_default();
+        for context in contexts(previous) {
+            for scope in ["global", cmd.working_directory.as_str()] {
+                record_transition(storage, scope, &context, &cmd.raw).await?;
+                counted += 1;
+            }
+        }
+        previous.push(cmd.parsed_command.clone());
+        if previous.len() > MAX_ORDER {
+            previous.remove(0);
+        }
+    }
+
+    println!(
+        "🔄 Rebuilt transition table: {} counts from {} commands",
+        counted,
+        commands.len(),
+    );
+    Ok(())
+}
+
+/// Measures top-1/top-3 hit rates on the held-out tail of history.
+async fn show_accuracy(
+    repo: &termbrain_storage::sqlite::SqliteCommandRepository,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut commands = repo.find_recent(ANALYSIS_WINDOW).await?;
+    commands.reverse();
+
+    let accuracy = evaluate(&commands);
+    if accuracy.evaluated == 0 {
+        println!("Not enough history to evaluate the model yet");
+        return Ok(());
+    }
+
+    if matches!(format, OutputFormat::Json) {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "evaluated": accuracy.evaluated,
+                "top1": accuracy.top1,
+                "top3": accuracy.top3,
+                "top1_rate": accuracy.top1 as f64 / accuracy.evaluated as f64,
+                "top3_rate": accuracy.top3 as f64 / accuracy.evaluated as f64,
+            }))?
+        );
+        return Ok(());
+    }
+
+    println!("🎯 Prediction accuracy over the last {} held-out commands:", accuracy.evaluated);
+    println!(
+        "   top-1: {:.0}%   top-3: {:.0}%",
+        accuracy.top1 as f64 / accuracy.evaluated as f64 * 100.0,
+        accuracy.top3 as f64 / accuracy.evaluated as f64 * 100.0,
+    );
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! Privacy surface report
+//!
+//! `tb privacy report` enumerates every feature capable of network
+//! access or data sharing, with its current state and where that state
+//! comes from (build feature, config field, environment variable). The
+//! list is built from this registry in code — the same flags the
+//! features themselves check — so the report cannot drift from what the
+//! binary actually does.
+
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::OutputFormat;
+
+/// One feature that could move data off this machine.
+struct Surface {
+    name: &'static str,
+    description: &'static str,
+    /// Whether the feature can currently act.
+    active: bool,
+    /// The configured state, before kill switches.
+    detail: String,
+    /// Where the state is controlled.
+    source: &'static str,
+}
+
+/// The registry: every network- or sharing-capable feature, evaluated
+/// against the exact flags those features check at run time.
+fn surfaces(config: &Config) -> Vec<Surface> {
+    let offline = config.offline();
+    let network_built = cfg!(feature = "network");
+
+    vec![
+        Surface {
+            name: "network (build)",
+            description: "Whether any outbound network code was compiled in",
+            active: network_built,
+            detail: if network_built {
+                "compiled in".to_string()
+            } else {
+                "compiled out — all network features are dead code".to_string()
+            },
+            source: "cargo feature `network`",
+        },
+        Surface {
+            name: "offline mode",
+            description: "Kill switch blocking every network call when on",
+            active: config.offline,
+            detail: if config.offline { "on".to_string() } else { "off".to_string() },
+            source: "config offline / TERMBRAIN_OFFLINE / --offline",
+        },
+        Surface {
+            name: "shadow mode",
+            description: "Blocks AI, prediction, and suggestion features",
+            active: config.shadow_mode,
+            detail: if config.shadow_mode { "on".to_string() } else { "off".to_string() },
+            source: "config shadow_mode / TERMBRAIN_SHADOW_MODE",
+        },
+        Surface {
+            name: "AI provider",
+            description: "External command receiving history excerpts for ask/synthesize",
+            active: config.ai_provider.is_some() && !config.shadow_mode && !offline,
+            detail: match &config.ai_provider {
+                Some(provider) => format!("configured: {}", provider),
+                None => "unset — AI commands refuse to run".to_string(),
+            },
+            source: "config ai_provider / TERMBRAIN_AI_PROVIDER",
+        },
+        Surface {
+            name: "embedding provider",
+            description: "External command receiving command text to embed",
+            active: config.embedding_provider.is_some() && !config.shadow_mode && !offline,
+            detail: match &config.embedding_provider {
+                Some(provider) => format!("configured: {}", provider),
+                None => "unset — vector search falls back to lexical ranking".to_string(),
+            },
+            source: "config embedding_provider / TERMBRAIN_EMBEDDING_PROVIDER",
+        },
+        Surface {
+            name: "alert webhooks",
+            description: "POSTs fired alert events to configured URLs",
+            active: config.alerts.iter().any(|alert| alert.webhook.is_some()) && !offline,
+            detail: {
+                let count = config.alerts.iter().filter(|a| a.webhook.is_some()).count();
+                if count == 0 {
+                    "no webhook URLs configured".to_string()
+                } else {
+                    format!("{} alert rule(s) with webhook URLs", count)
+                }
+            },
+            source: "config alerts[].webhook",
+        },
+        Surface {
+            name: "multi-machine sync",
+            description: "Pushes history to a shared remote location",
+            active: cfg!(feature = "sync") && config.sync_remote.is_some() && !offline,
+            detail: match &config.sync_remote {
+                Some(remote) => format!("remote: {}", remote),
+                None => "unset — sync disabled".to_string(),
+            },
+            source: "config sync_remote / TERMBRAIN_SYNC_REMOTE",
+        },
+        Surface {
+            name: "scheduled backups",
+            description: "Writes compressed history archives to the backup target",
+            active: config.backup.is_some(),
+            detail: match &config.backup {
+                Some(backup) => format!("target: {}", backup.target),
+                None => "unset — backups disabled".to_string(),
+            },
+            source: "config backup.target",
+        },
+        Surface {
+            name: "HTTP API",
+            description: "Local HTTP server exposing history to integrations",
+            active: cfg!(feature = "server"),
+            detail: if cfg!(feature = "server") {
+                "compiled in — listens only when 'tb serve' is run".to_string()
+            } else {
+                "compiled out".to_string()
+            },
+            source: "cargo feature `server`",
+        },
+    ]
+}
+
+/// Prints the privacy surface report.
+pub fn privacy_report(format: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    let surfaces = surfaces(&config);
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = surfaces
+                .iter()
+                .map(|surface| {
+                    serde_json::json!({
+                        "name": surface.name,
+                        "description": surface.description,
+                        "active": surface.active,
+                        "state": surface.detail,
+                        "source": surface.source,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        _ => {
+            println!("🔒 Privacy surfaces ({} features):", surfaces.len());
+            if config.offline() {
+                println!("   Offline mode is active — no network call can be made\n");
+            }
+            for surface in &surfaces {
+                let marker = if surface.active { "🟢" } else { "⚫" };
+                println!("{} {:<20} {}", marker, surface.name, surface.detail);
+                println!("      {} — {}", surface.description, surface.source);
+            }
+            println!();
+            println!("💡 Everything else runs entirely locally; recording never leaves this machine");
+        }
+    }
+    Ok(())
+}
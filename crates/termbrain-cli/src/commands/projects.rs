@@ -0,0 +1,270 @@
+//! Project analytics
+//!
+//! `tb projects` lists activity grouped by project identity — the
+//! normalized git remote URL stamped onto each command at record time —
+//! so clones of the same repository in different directories (or
+//! imported from other machines) aggregate as one row. `tb projects
+//! list` instead groups by marker-detected project roots (Cargo.toml,
+//! package.json, .git), which also covers local-only trees.
+
+use anyhow::Result;
+use sqlx::Row;
+use termbrain_core::domain::repositories::ProjectRepository;
+use termbrain_storage::sqlite::SqliteProjectRepository;
+
+use crate::OutputFormat;
+
+use super::create_storage;
+
+/// Shows the per-project activity rollup, most recently active first.
+pub async fn show_projects(format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let rows = sqlx::query(
+        "SELECT project, total, directories, sessions, first_seen, last_seen, failures \
+         FROM project_rollups ORDER BY last_seen DESC",
+    )
+    .fetch_all(storage.pool())
+    .await?;
+
+    if rows.is_empty() {
+        println!("No commands with a project identity yet");
+        println!("   Commands recorded inside a git repository with an 'origin' remote get one automatically");
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    serde_json::json!({
+                        "project": row.get::<String, _>("project"),
+                        "total": row.get::<i64, _>("total"),
+                        "directories": row.get::<i64, _>("directories"),
+                        "sessions": row.get::<i64, _>("sessions"),
+                        "first_seen": row.get::<String, _>("first_seen"),
+                        "last_seen": row.get::<String, _>("last_seen"),
+                        "failures": row.get::<i64, _>("failures"),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        _ => {
+            println!("📦 Projects ({}):", rows.len());
+            for row in rows {
+                let total = row.get::<i64, _>("total");
+                let failures = row.get::<i64, _>("failures");
+                println!(
+                    "   {} — {} commands across {} directories, {} failed (last active {})",
+                    row.get::<String, _>("project"),
+                    total,
+                    row.get::<i64, _>("directories"),
+                    failures,
+                    &row.get::<String, _>("last_seen")[..10],
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists marker-detected projects with command counts, success rates,
+/// and each project's most-used commands.
+pub async fn project_list(format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+    let projects = SqliteProjectRepository::new(storage.pool().clone());
+
+    let all = projects.find_all().await?;
+    if all.is_empty() {
+        println!("No projects detected yet");
+        println!("   Commands recorded inside a directory with Cargo.toml, package.json, or .git register one");
+        return Ok(());
+    }
+
+    let mut entries = Vec::new();
+    for project in &all {
+        let stats = sqlx::query(
+            "SELECT COUNT(*) AS total, SUM(CASE WHEN exit_code = 0 THEN 1 ELSE 0 END) AS ok
+             FROM commands WHERE working_directory = ?1 OR working_directory LIKE ?1 || '/%'",
+        )
+        .bind(&project.root)
+        .fetch_one(storage.pool())
+        .await?;
+        let total: i64 = stats.get("total");
+        let ok: i64 = stats.get::<Option<i64>, _>("ok").unwrap_or(0);
+
+        let top: Vec<String> = sqlx::query(
+            "SELECT parsed_command, COUNT(*) AS n FROM commands
+             WHERE working_directory = ?1 OR working_directory LIKE ?1 || '/%'
+             GROUP BY parsed_command ORDER BY n DESC LIMIT 3",
+        )
+        .bind(&project.root)
+        .fetch_all(storage.pool())
+        .await?
+        .into_iter()
+        .map(|row| row.get::<String, _>("parsed_command"))
+        .collect();
+
+        entries.push((project, total, ok, top));
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = entries
+                .iter()
+                .map(|(project, total, ok, top)| {
+                    serde_json::json!({
+                        "name": project.name,
+                        "root": project.root,
+                        "marker": project.marker,
+                        "total": total,
+                        "success_rate": if *total > 0 { Some(*ok as f64 / *total as f64) } else { None },
+                        "top_commands": top,
+                        "last_seen": project.last_seen.to_rfc3339(),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        _ => {
+            println!("📦 Projects ({}):", entries.len());
+            for (project, total, ok, top) in entries {
+                let rate = if total > 0 {
+                    format!("{:.0}% ok", ok as f64 / total as f64 * 100.0)
+                } else {
+                    "no commands yet".to_string()
+                };
+                println!("   {} ({}) — {} commands, {}", project.name, project.root, total, rate);
+                if !top.is_empty() {
+                    println!("      top: {}", top.join(", "));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves a reference (name, tag, or remote) to project rows.
+async fn resolve_projects(
+    projects: &SqliteProjectRepository,
+    reference: &str,
+) -> Result<Vec<termbrain_core::domain::entities::Project>> {
+    let named = projects.find_by_name(reference).await?;
+    if !named.is_empty() {
+        return Ok(named);
+    }
+    let by_remote = projects.find_by_remote(reference).await?;
+    if !by_remote.is_empty() {
+        return Ok(by_remote);
+    }
+    let tagged: Vec<_> = projects
+        .find_all()
+        .await?
+        .into_iter()
+        .filter(|p| p.tags.iter().any(|t| t == reference))
+        .collect();
+    if tagged.is_empty() {
+        anyhow::bail!("No project matches '{}' — see tb projects list", reference);
+    }
+    Ok(tagged)
+}
+
+/// Shows one project's identity: every root path sharing it, the
+/// remote, tags, and activity.
+pub async fn project_show(reference: String) -> Result<()> {
+    let storage = create_storage().await?;
+    let projects = SqliteProjectRepository::new(storage.pool().clone());
+
+    let mut matched = resolve_projects(&projects, &reference).await?;
+    // A row with a remote pulls in its clones under other names too
+    if let Some(remote) = matched.iter().find_map(|p| p.remote.clone()) {
+        for clone in projects.find_by_remote(&remote).await? {
+            if !matched.iter().any(|p| p.id == clone.id) {
+                matched.push(clone);
+            }
+        }
+    }
+
+    println!("📦 Project '{}' — {} root(s):", reference, matched.len());
+    for project in &matched {
+        let total: i64 = sqlx::query(
+            "SELECT COUNT(*) AS n FROM commands
+             WHERE working_directory = ?1 OR working_directory LIKE ?1 || '/%'",
+        )
+        .bind(&project.root)
+        .fetch_one(storage.pool())
+        .await?
+        .get("n");
+        println!("   {} ({}, {} commands)", project.root, project.marker, total);
+        println!(
+            "      id {}, last active {}",
+            project.id,
+            project.last_seen.format("%Y-%m-%d"),
+        );
+    }
+    if let Some(remote) = matched.iter().find_map(|p| p.remote.as_deref()) {
+        println!("   remote: {}", remote);
+    }
+    let mut tags: Vec<&str> = matched.iter().flat_map(|p| p.tags.iter()).map(String::as_str).collect();
+    tags.sort_unstable();
+    tags.dedup();
+    if !tags.is_empty() {
+        println!("   tags: {}", tags.join(", "));
+    }
+    Ok(())
+}
+
+/// Renames every row of one project.
+pub async fn project_rename(reference: String, new_name: String) -> Result<()> {
+    let storage = create_storage().await?;
+    let projects = SqliteProjectRepository::new(storage.pool().clone());
+
+    let matched = resolve_projects(&projects, &reference).await?;
+    for mut project in matched {
+        project.name = new_name.clone();
+        projects.update(&project).await?;
+    }
+    println!("✅ Renamed '{}' to '{}'", reference, new_name);
+    Ok(())
+}
+
+/// Adds (or with `remove`, drops) a tag on every row of one project.
+pub async fn project_tag(reference: String, tag: String, remove: bool) -> Result<()> {
+    let storage = create_storage().await?;
+    let projects = SqliteProjectRepository::new(storage.pool().clone());
+
+    let matched = resolve_projects(&projects, &reference).await?;
+    for mut project in matched {
+        if remove {
+            project.tags.retain(|t| *t != tag);
+        } else if !project.tags.contains(&tag) {
+            project.tags.push(tag.clone());
+        }
+        projects.update(&project).await?;
+    }
+    if remove {
+        println!("✅ Removed tag '{}' from '{}'", tag, reference);
+    } else {
+        println!("✅ Tagged '{}' with '{}'", reference, tag);
+    }
+    Ok(())
+}
+
+/// Sets the remote on a project root, linking it with other clones of
+/// the same repository.
+pub async fn project_set_remote(reference: String, remote: String) -> Result<()> {
+    let storage = create_storage().await?;
+    let projects = SqliteProjectRepository::new(storage.pool().clone());
+
+    let matched = resolve_projects(&projects, &reference).await?;
+    for mut project in matched {
+        project.remote = Some(remote.clone());
+        projects.update(&project).await?;
+    }
+    println!("✅ '{}' now shares identity via {}", reference, remote);
+    Ok(())
+}
@@ -0,0 +1,128 @@
+//! Policy-driven pruning
+//!
+//! `tb prune` evaluates the retention policies from the config file
+//! over recorded history and deletes what they condemn, then enforces
+//! the per-class retention windows (raw commands, AI transcripts and
+//! summaries, patterns, notes); `--dry-run` reports what would be
+//! removed without touching anything.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::retention::{prune_candidates, ClassRetention};
+use termbrain_storage::sqlite::SqliteStorage;
+
+use crate::config::Config;
+
+use super::{create_repo, create_storage};
+
+/// The side tables class windows govern: (class label, window, SQL
+/// condition selecting expired rows with the cutoff bound as ?1).
+fn side_table_rules(classes: &ClassRetention) -> Vec<(&'static str, Option<i64>, &'static str)> {
+    vec![
+        (
+            "AI summaries (diagnoses)",
+            classes.ai_summaries_days,
+            "FROM diagnoses WHERE created_at < ?1",
+        ),
+        (
+            "patterns",
+            classes.patterns_days,
+            "FROM patterns WHERE last_seen < ?1",
+        ),
+        (
+            "notes (closed intentions)",
+            classes.notes_days,
+            "FROM intentions WHERE status = 'closed' AND created_at < ?1",
+        ),
+    ]
+}
+
+/// Applies the configured retention policies and class windows. With
+/// `dry_run`, only reports the matches.
+pub async fn run_prune(dry_run: bool) -> Result<()> {
+    let config = Config::load()?;
+    // Class windows become ordinary delete policies for the commands
+    // table, so keep policies still protect what they match
+    let mut policies = config.retention_policies.clone();
+    policies.extend(config.class_retention.command_policies());
+
+    if policies.is_empty() && config.class_retention.is_empty() {
+        println!("No retention policies configured");
+        println!("   Add retention_policies or class_retention to {} — e.g. delete", Config::config_file().display());
+        println!("   failed commands after 30 days, or AI transcripts after 14");
+        return Ok(());
+    }
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let commands = repo.find_recent(i64::MAX as usize).await?;
+    let candidates = prune_candidates(&commands, &policies, chrono::Utc::now());
+
+    if candidates.is_empty() {
+        println!("✨ Nothing to prune — no command matches a retention policy");
+    } else {
+        let mut per_policy: HashMap<&str, usize> = HashMap::new();
+        for candidate in &candidates {
+            *per_policy.entry(candidate.policy).or_default() += 1;
+        }
+
+        let verb = if dry_run { "would be removed" } else { "removed" };
+        println!("🗑️  {} of {} commands {}:", candidates.len(), commands.len(), verb);
+        for (policy, count) in &per_policy {
+            println!("   {:>6}× by policy '{}'", count, policy);
+        }
+        for candidate in candidates.iter().take(10) {
+            println!("   {} ({})", candidate.command.raw, candidate.policy);
+        }
+        if candidates.len() > 10 {
+            println!("   ... and {} more", candidates.len() - 10);
+        }
+
+        if !dry_run {
+            for candidate in &candidates {
+                repo.delete_by_id(&candidate.command.id).await?;
+            }
+            println!("✅ Pruned {} commands", candidates.len());
+        }
+    }
+
+    prune_side_tables(&storage, &config.class_retention, dry_run).await?;
+
+    if dry_run {
+        println!("Re-run without --dry-run to apply");
+    }
+    Ok(())
+}
+
+/// Enforces class windows over the non-command tables.
+async fn prune_side_tables(
+    storage: &SqliteStorage,
+    classes: &ClassRetention,
+    dry_run: bool,
+) -> Result<()> {
+    for (label, days, clause) in side_table_rules(classes) {
+        let Some(days) = days else { continue };
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+
+        let expired: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) {clause}"))
+            .bind(&cutoff)
+            .fetch_one(storage.pool())
+            .await?;
+        if expired == 0 {
+            continue;
+        }
+
+        if dry_run {
+            println!("🗑️  {} {} row(s) older than {} days would be removed", expired, label, days);
+        } else {
+            sqlx::query(&format!("DELETE {clause}"))
+                .bind(&cutoff)
+                .execute(storage.pool())
+                .await?;
+            println!("✅ Pruned {} {} row(s) older than {} days", expired, label, days);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,213 @@
+//! Correction of misclassified records
+//!
+//! `tb reclassify <id> --type testing --project foo --agent claude`
+//! fixes one record's semantic type, project identity, or agent
+//! attribution. Each correction is also stored as a training signal
+//! the classifiers consult before their heuristics: a type correction
+//! generalizes to every command running the same program, a project
+//! correction to the same directory tree, and an agent correction to
+//! the rest of the same session. The interactive picker offers the
+//! same corrections via `fix N`.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_storage::sqlite::{SqliteCommandRepository, SqliteStorage};
+use uuid::Uuid;
+
+use super::{create_repo, create_storage};
+
+/// Applies corrections to a record by full UUID or unique prefix.
+pub async fn reclassify_record(
+    id: String,
+    semantic_type: Option<String>,
+    project: Option<String>,
+    agent: Option<String>,
+) -> Result<()> {
+    if semantic_type.is_none() && project.is_none() && agent.is_none() {
+        anyhow::bail!("Nothing to correct: pass --type, --project, and/or --agent");
+    }
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let command = resolve_record(&storage, &repo, &id).await?;
+    for note in
+        apply_corrections(&storage, &repo, &command, semantic_type, project, agent).await?
+    {
+        println!("✅ {}", note);
+    }
+    Ok(())
+}
+
+/// Finds the one command matching an id prefix; ambiguity is an error
+/// rather than a guess.
+async fn resolve_record(
+    storage: &SqliteStorage,
+    repo: &SqliteCommandRepository,
+    id: &str,
+) -> Result<Command> {
+    if let Ok(full) = Uuid::parse_str(id) {
+        return repo
+            .find_by_id(&full)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No command with id {}", full));
+    }
+
+    let matches: Vec<String> =
+        sqlx::query_scalar("SELECT id FROM commands WHERE id LIKE ? || '%' LIMIT 2")
+            .bind(id)
+            .fetch_all(storage.pool())
+            .await?;
+    match matches.as_slice() {
+        [] => anyhow::bail!("No command with id starting '{}'", id),
+        [only] => repo
+            .find_by_id(&Uuid::parse_str(only)?)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No command with id {}", only)),
+        _ => anyhow::bail!("Id prefix '{}' is ambiguous — give more characters", id),
+    }
+}
+
+/// Writes the corrected fields onto the record's extras and records
+/// each correction as an override for future classification. Returns
+/// one summary line per correction so each surface can print them to
+/// its own output stream.
+pub(super) async fn apply_corrections(
+    storage: &SqliteStorage,
+    repo: &SqliteCommandRepository,
+    command: &Command,
+    semantic_type: Option<String>,
+    project: Option<String>,
+    agent: Option<String>,
+) -> Result<Vec<String>> {
+    let mut extras = command.extras.clone();
+    let mut notes = Vec::new();
+
+    if let Some(semantic_type) = &semantic_type {
+        extras.insert(
+            "semantic_type".to_string(),
+            serde_json::Value::String(semantic_type.clone()),
+        );
+        save_override(storage, "semantic_type", &command.parsed_command, semantic_type).await?;
+        notes.push(format!(
+            "Type: {} — future '{}' commands will classify the same way",
+            semantic_type, command.parsed_command
+        ));
+    }
+    if let Some(project) = &project {
+        extras.insert(
+            "project".to_string(),
+            serde_json::Value::String(project.clone()),
+        );
+        save_override(storage, "project", &command.working_directory, project).await?;
+        notes.push(format!(
+            "Project: {} — applies to everything under {}",
+            project, command.working_directory
+        ));
+    }
+    if let Some(agent) = &agent {
+        extras.insert(
+            "agent".to_string(),
+            serde_json::Value::String(agent.clone()),
+        );
+        save_override(storage, "agent", &command.session_id, agent).await?;
+        notes.push(format!(
+            "Agent: {} — applies to the rest of session {}",
+            agent, command.session_id
+        ));
+    }
+
+    repo.update_extras_batch(&[(command.id, extras)]).await?;
+    Ok(notes)
+}
+
+/// Upserts one training signal; a repeated correction just refreshes
+/// the stored value.
+async fn save_override(storage: &SqliteStorage, kind: &str, key: &str, value: &str) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO classification_overrides (kind, key, value, corrected_at)
+         VALUES (?, ?, ?, ?)
+         ON CONFLICT (kind, key) DO UPDATE SET value = excluded.value, corrected_at = excluded.corrected_at",
+    )
+    .bind(kind)
+    .bind(key)
+    .bind(value)
+    .bind(Utc::now().to_rfc3339())
+    .execute(storage.pool())
+    .await?;
+    Ok(())
+}
+
+/// Loads every override of one kind as a key → value map for
+/// classifiers to consult before their heuristics.
+pub(super) async fn load_overrides(
+    storage: &SqliteStorage,
+    kind: &str,
+) -> Result<HashMap<String, String>> {
+    let rows: Vec<(String, String)> =
+        sqlx::query_as("SELECT key, value FROM classification_overrides WHERE kind = ?")
+            .bind(kind)
+            .fetch_all(storage.pool())
+            .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Looks up a directory-keyed override, matching the directory itself
+/// or the closest corrected ancestor.
+pub(super) fn directory_override(overrides: &HashMap<String, String>, directory: &str) -> Option<String> {
+    overrides
+        .iter()
+        .filter(|(key, _)| {
+            directory == key.as_str() || directory.starts_with(&format!("{}/", key))
+        })
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, value)| value.clone())
+}
+
+/// The record's semantic type: a per-record correction first, then a
+/// program-keyed override, then the heuristic classifier.
+pub(super) fn semantic_type_of(command: &Command, overrides: &HashMap<String, String>) -> String {
+    command
+        .extras
+        .get("semantic_type")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| overrides.get(&command.parsed_command).cloned())
+        .unwrap_or_else(|| termbrain_core::privacy::categorize(&command.parsed_command).to_string())
+}
+
+/// Prompts for corrections to one record over the picker's tty; blank
+/// answers skip a field.
+pub(super) async fn correct_interactively(
+    tty: &mut impl BufRead,
+    err: &mut impl Write,
+    storage: &SqliteStorage,
+    repo: &SqliteCommandRepository,
+    command: &Command,
+) -> Result<()> {
+    writeln!(err, "  fixing: {}", command.raw)?;
+    let mut answers = Vec::new();
+    for field in ["semantic type", "project", "agent"] {
+        write!(err, "  {} (blank to keep): ", field)?;
+        err.flush()?;
+        let mut line = String::new();
+        tty.read_line(&mut line)?;
+        let answer = line.trim();
+        answers.push((!answer.is_empty()).then(|| answer.to_string()));
+    }
+
+    let (semantic_type, project, agent) =
+        (answers[0].clone(), answers[1].clone(), answers[2].clone());
+    if semantic_type.is_none() && project.is_none() && agent.is_none() {
+        writeln!(err, "  (nothing changed)")?;
+        return Ok(());
+    }
+    for note in apply_corrections(storage, repo, command, semantic_type, project, agent).await? {
+        writeln!(err, "  ✅ {}", note)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Knowledge refresher prompts
+//!
+//! `tb refresh` lists tools the user once used heavily but hasn't
+//! touched in months, together with their own best past invocations as
+//! a memory jog.
+
+use anyhow::Result;
+use chrono::Utc;
+use termbrain_core::decay::{rusty_tools, RUSTY_AFTER_DAYS};
+use termbrain_core::domain::repositories::CommandRepository;
+
+use crate::OutputFormat;
+
+use super::{create_repo, create_storage};
+
+/// How much history to scan for decayed knowledge.
+const SCAN_WINDOW: usize = 10_000;
+
+/// Shows heavily-used-but-idle tools with refresher examples.
+pub async fn show_refreshers(format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let commands = repo.find_recent(SCAN_WINDOW).await?;
+    let rusty = rusty_tools(&commands, Utc::now());
+
+    if rusty.is_empty() {
+        println!("Nothing is getting rusty — all your heavily-used tools saw recent use");
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = rusty
+                .iter()
+                .map(|tool| {
+                    serde_json::json!({
+                        "tool": tool.tool,
+                        "uses": tool.uses,
+                        "last_used": tool.last_used,
+                        "examples": tool.examples,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        _ => {
+            println!(
+                "🧠 Tools you know well but haven't used in {}+ days:",
+                RUSTY_AFTER_DAYS
+            );
+            for tool in &rusty {
+                let idle_days = (Utc::now() - tool.last_used).num_days();
+                println!(
+                    "\n   {} — {} past uses, idle for {} days",
+                    tool.tool, tool.uses, idle_days
+                );
+                if !tool.examples.is_empty() {
+                    println!("   Your go-to invocations were:");
+                    for example in &tool.examples {
+                        println!("      $ {}", example);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
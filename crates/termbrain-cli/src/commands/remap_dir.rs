@@ -0,0 +1,120 @@
+//! Directory remapping
+//!
+//! When a project directory is moved or renamed, its history fractures
+//! into two paths. `tb remap-dir /old/path /new/path` rewrites recorded
+//! working directories (and everything under them) to the new location;
+//! the per-directory rollups are views over `commands`, so they follow
+//! automatically. `--detect` suggests old paths for a repository by
+//! matching its git remote URL against recorded clone commands.
+
+use anyhow::Result;
+use sqlx::Row;
+use std::path::Path;
+
+use super::create_storage;
+
+/// Expands a leading `~` and trims trailing slashes so both sides of
+/// the remap compare consistently.
+fn normalize_path(path: &str) -> String {
+    let home = dirs::home_dir().unwrap_or_default();
+    let expanded = match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest).display().to_string(),
+        None if path == "~" => home.display().to_string(),
+        None => path.to_string(),
+    };
+    expanded.trim_end_matches('/').to_string()
+}
+
+/// Rewrites every recorded directory equal to `old` — or nested under
+/// it — to the corresponding path under `new`.
+pub async fn remap_dir(old: String, new: String) -> Result<()> {
+    let old = normalize_path(&old);
+    let new = normalize_path(&new);
+    if old == new {
+        anyhow::bail!("Old and new paths are the same");
+    }
+
+    let storage = create_storage().await?;
+
+    let commands = sqlx::query(
+        r#"
+        UPDATE commands
+        SET working_directory = ?1 || substr(working_directory, length(?2) + 1)
+        WHERE working_directory = ?2 OR working_directory LIKE ?2 || '/%'
+        "#,
+    )
+    .bind(&new)
+    .bind(&old)
+    .execute(storage.pool())
+    .await?
+    .rows_affected();
+
+    let intentions = sqlx::query(
+        r#"
+        UPDATE intentions
+        SET directory = ?1 || substr(directory, length(?2) + 1)
+        WHERE directory = ?2 OR directory LIKE ?2 || '/%'
+        "#,
+    )
+    .bind(&new)
+    .bind(&old)
+    .execute(storage.pool())
+    .await?
+    .rows_affected();
+
+    if commands == 0 && intentions == 0 {
+        println!("No recorded history under {}", old);
+        println!("   Try 'tb remap-dir --detect' from the moved project to find its old paths");
+        return Ok(());
+    }
+
+    println!("📂 Remapped {} → {}", old, new);
+    println!("   {} commands, {} intentions updated", commands, intentions);
+    Ok(())
+}
+
+/// Suggests old recorded paths for the git repository at `path` by
+/// looking for its remote URL in recorded command lines (clones, remote
+/// adds) under directories other than the current one.
+pub async fn detect_remaps(path: Option<String>) -> Result<()> {
+    let path = normalize_path(path.as_deref().unwrap_or("."));
+    let current = Path::new(&path)
+        .canonicalize()
+        .map(|p| p.display().to_string())
+        .unwrap_or(path);
+
+    let output = std::process::Command::new("git")
+        .args(["-C", &current, "remote", "get-url", "origin"])
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("{} is not a git repository with an 'origin' remote", current);
+    }
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let url = url.strip_suffix(".git").unwrap_or(&url).to_string();
+
+    let storage = create_storage().await?;
+    let rows = sqlx::query(
+        "SELECT DISTINCT working_directory FROM commands WHERE raw LIKE '%' || ? || '%'",
+    )
+    .bind(&url)
+    .fetch_all(storage.pool())
+    .await?;
+
+    let candidates: Vec<String> = rows
+        .into_iter()
+        .map(|row| row.get::<String, _>("working_directory"))
+        .filter(|dir| dir != &current && !dir.starts_with(&format!("{}/", current)))
+        .collect();
+
+    if candidates.is_empty() {
+        println!("No other recorded directories mention {}", url);
+        return Ok(());
+    }
+
+    println!("🔍 Directories whose history mentions {}:", url);
+    for dir in &candidates {
+        println!("  {}", dir);
+        println!("    tb remap-dir {} {}", dir, current);
+    }
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! REPL statement capture
+//!
+//! When a recorded command turns out to be an interactive REPL session
+//! (tagged by `termbrain_core::repl`), this reads the REPL's history
+//! file afterwards and records the statements typed inside as commands
+//! with source `repl:<name>`. The import ledger fingerprints every
+//! captured line, so statements land once no matter how many sessions
+//! end. REPL history files carry no timestamps, so statements share the
+//! session's.
+
+use anyhow::Result;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::repl::ReplProfile;
+use termbrain_core::shell_history::import_fingerprint;
+use termbrain_storage::sqlite::{SqliteCommandRepository, SqliteStorage};
+use uuid::Uuid;
+
+use super::import::{load_ledger, record_ledger};
+
+/// Only this many trailing history lines are considered per capture,
+/// bounding the first run against a years-old history file.
+const CAPTURE_WINDOW: usize = 500;
+
+/// Captures new statements from the REPL's history file into history,
+/// linked to the session command via session id and extras.
+pub(super) async fn capture_statements(
+    storage: &SqliteStorage,
+    repo: &SqliteCommandRepository,
+    session_cmd: &Command,
+    profile: &ReplProfile,
+) -> Result<()> {
+    let Some(history_file) = profile.history_file else {
+        return Ok(());
+    };
+    let path = dirs::home_dir().unwrap_or_default().join(history_file);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+
+    let source = format!("repl:{}", profile.name);
+    let seen = load_ledger(storage.pool(), &source).await?;
+
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    let window = lines.len().saturating_sub(CAPTURE_WINDOW);
+
+    let mut statements = Vec::new();
+    let mut fingerprints = Vec::new();
+    for line in &lines[window..] {
+        let fingerprint = import_fingerprint(line, None, &session_cmd.metadata.hostname);
+        if seen.contains(&fingerprint) || fingerprints.contains(&fingerprint) {
+            continue;
+        }
+        fingerprints.push(fingerprint);
+        statements.push(*line);
+    }
+    if statements.is_empty() {
+        return Ok(());
+    }
+
+    let commands: Vec<Command> = statements
+        .iter()
+        .map(|statement| {
+            let mut parts = statement.split_whitespace();
+            Command {
+                id: Uuid::new_v4(),
+                parsed_command: parts.next().unwrap_or("").to_string(),
+                arguments: parts.map(String::from).collect(),
+                raw: statement.to_string(),
+                working_directory: session_cmd.working_directory.clone(),
+                exit_code: 0,
+                duration_ms: 0,
+                timestamp: session_cmd.timestamp,
+                session_id: session_cmd.session_id.clone(),
+                source: source.clone(),
+                extras: std::collections::HashMap::from([(
+                    "repl".to_string(),
+                    serde_json::Value::String(profile.name.to_string()),
+                )]),
+                logical_clock: None,
+                metadata: session_cmd.metadata.clone(),
+            }
+        })
+        .collect();
+
+    repo.save_batch(&commands).await?;
+    record_ledger(storage.pool(), &source, &fingerprints).await?;
+    println!(
+        "   🔁 Captured {} {} statement(s) from this session",
+        commands.len(),
+        profile.name,
+    );
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! Reproducibility bundles
+//!
+//! `tb repro --last 15 -o repro.md` assembles the recent command
+//! sequence, tool versions, and environment details into a markdown
+//! template ready to paste into an upstream bug report. Command lines
+//! are passed through secret redaction before leaving the database.
+
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::privacy::redact_secrets;
+
+use super::create_repo;
+use super::create_storage;
+use super::versions::probe_version;
+
+/// Writes a bug-report template covering the last `last` commands.
+pub async fn export_repro_bundle(last: usize, output: String) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let mut commands = repo.find_recent(last).await?;
+    if commands.is_empty() {
+        println!("No commands recorded yet");
+        return Ok(());
+    }
+    // find_recent returns newest first; a repro reads top to bottom
+    commands.reverse();
+
+    let latest = commands.last().unwrap();
+    let directory = &latest.working_directory;
+    let branch = git_branch(directory);
+
+    let tools: BTreeSet<&str> = commands
+        .iter()
+        .map(|cmd| cmd.parsed_command.as_str())
+        .collect();
+
+    let mut report = String::new();
+    report.push_str("## Steps to reproduce\n\n```console\n");
+    for cmd in &commands {
+        report.push_str(&format!("$ {}\n", redact_secrets(&cmd.raw)));
+        if cmd.exit_code != 0 {
+            report.push_str(&format!("# exited with code {}\n", cmd.exit_code));
+        }
+    }
+    report.push_str("```\n\n## Environment\n\n");
+
+    report.push_str(&format!("- Directory: `{}`\n", directory));
+    if let Some(branch) = branch {
+        report.push_str(&format!("- Git branch: `{}`\n", branch));
+    }
+    report.push_str(&format!("- Shell: {}\n", latest.metadata.shell));
+    report.push_str(&format!("- OS: {}\n", std::env::consts::OS));
+
+    report.push_str("\n## Tool versions\n\n");
+    let mut probed = 0;
+    for tool in tools {
+        if let Some(version) = probe_version(tool) {
+            report.push_str(&format!("- {}\n", version));
+            probed += 1;
+        }
+    }
+    if probed == 0 {
+        report.push_str("- (none of the involved tools report --version)\n");
+    }
+
+    report.push_str("\n## Expected behavior\n\n<!-- fill in -->\n");
+    report.push_str("\n## Actual behavior\n\n<!-- fill in -->\n");
+
+    std::fs::write(&output, report)?;
+    println!(
+        "📦 Wrote repro bundle covering {} commands to {}",
+        commands.len(),
+        output
+    );
+    println!("   Review for anything redaction missed before filing.");
+
+    Ok(())
+}
+
+/// Current branch of the repository containing `directory`, if any.
+fn git_branch(directory: &str) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(directory)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!branch.is_empty()).then_some(branch)
+}
@@ -0,0 +1,206 @@
+//! Runbook generation from incident timelines
+//!
+//! After an incident is resolved, `tb runbook --from ... --to ... -o
+//! runbooks/db-outage.md` distills what was actually run into a
+//! reusable document: numbered steps, verification checks, and rollback
+//! candidates. With `--register` the steps also become a workflow.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::privacy::redact_secrets;
+use uuid::Uuid;
+
+use super::{create_repo, create_storage};
+
+/// Tools (and tool/subcommand pairs) that only observe state; they end
+/// up in the "checks" section rather than the numbered steps.
+const READ_ONLY_TOOLS: &[&str] = &[
+    "ls", "cat", "grep", "find", "ps", "top", "htop", "df", "du", "free", "ping", "dig", "curl",
+    "tail", "head", "less", "watch", "journalctl",
+];
+const READ_ONLY_SUBCOMMANDS: &[(&str, &str)] = &[
+    ("git", "status"),
+    ("git", "log"),
+    ("git", "diff"),
+    ("kubectl", "get"),
+    ("kubectl", "describe"),
+    ("kubectl", "logs"),
+    ("docker", "ps"),
+    ("docker", "logs"),
+    ("systemctl", "status"),
+];
+
+/// Verbs that mark a step as a rollback/recovery candidate.
+const ROLLBACK_VERBS: &[&str] = &[
+    "rollback", "revert", "reset", "restart", "undo", "restore", "downgrade",
+];
+
+/// Generates a runbook from the commands run between `from` and `to`.
+pub async fn generate_runbook(
+    from: String,
+    to: String,
+    output: String,
+    register: Option<String>,
+) -> Result<()> {
+    let start = parse_time(&from)?;
+    let end = parse_time(&to)?;
+    if end <= start {
+        return Err(anyhow::anyhow!("--to must be after --from"));
+    }
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    let commands = repo.find_by_time_range(start, end).await?;
+    if commands.is_empty() {
+        println!("No commands recorded between {} and {}", from, to);
+        return Ok(());
+    }
+
+    // Collapse immediate retries of the same command into one step
+    let mut timeline: Vec<&Command> = Vec::new();
+    for cmd in &commands {
+        if timeline.last().map(|prev| prev.raw == cmd.raw).unwrap_or(false) {
+            continue;
+        }
+        timeline.push(cmd);
+    }
+
+    let (checks, steps): (Vec<&&Command>, Vec<&&Command>) =
+        timeline.iter().partition(|cmd| is_check(cmd));
+    let rollback: Vec<&&Command> = steps
+        .iter()
+        .filter(|cmd| is_rollback(cmd))
+        .copied()
+        .collect();
+
+    let title = std::path::Path::new(&output)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().replace(['-', '_'], " "))
+        .unwrap_or_else(|| "runbook".to_string());
+
+    let mut doc = format!("# Runbook: {}\n\n", title);
+    doc.push_str(&format!(
+        "Distilled from {} commands between {} and {}.\n\n",
+        timeline.len(),
+        start.format("%Y-%m-%d %H:%M"),
+        end.format("%Y-%m-%d %H:%M")
+    ));
+
+    doc.push_str("## Steps\n\n");
+    for (i, cmd) in steps.iter().enumerate() {
+        doc.push_str(&format!(
+            "{}. `{}` (in `{}`{})\n",
+            i + 1,
+            redact_secrets(&cmd.raw),
+            cmd.working_directory,
+            if cmd.exit_code != 0 {
+                format!(", failed with exit {} when first run", cmd.exit_code)
+            } else {
+                String::new()
+            }
+        ));
+    }
+
+    if !checks.is_empty() {
+        doc.push_str("\n## Checks\n\nVerify state before/after the steps:\n\n");
+        for cmd in &checks {
+            doc.push_str(&format!("- `{}`\n", redact_secrets(&cmd.raw)));
+        }
+    }
+
+    // Issue keys mentioned anywhere in the timeline
+    let mut issues: Vec<String> = Vec::new();
+    for cmd in &timeline {
+        for key in termbrain_core::issues::extract_issue_keys(&cmd.raw) {
+            if !issues.contains(&key) {
+                issues.push(key);
+            }
+        }
+    }
+    if !issues.is_empty() {
+        doc.push_str("\n## Related issues\n\n");
+        for key in &issues {
+            doc.push_str(&format!("- {}\n", key));
+        }
+    }
+
+    doc.push_str("\n## Rollback\n\n");
+    if rollback.is_empty() {
+        doc.push_str("<!-- No rollback commands detected in the timeline; fill in -->\n");
+    } else {
+        for cmd in &rollback {
+            doc.push_str(&format!("- `{}`\n", redact_secrets(&cmd.raw)));
+        }
+    }
+
+    if let Some(parent) = std::path::Path::new(&output).parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    std::fs::write(&output, &doc)?;
+    println!(
+        "📘 Wrote runbook with {} steps, {} checks to {}",
+        steps.len(),
+        checks.len(),
+        output
+    );
+
+    if let Some(name) = register {
+        let step_json: Vec<_> = steps
+            .iter()
+            .map(|cmd| {
+                serde_json::json!({
+                    "command": redact_secrets(&cmd.raw),
+                    "directory": cmd.working_directory,
+                })
+            })
+            .collect();
+
+        sqlx::query(
+            "INSERT INTO workflows (id, name, description, steps, created_at, updated_at, usage_count) VALUES (?1, ?2, ?3, ?4, ?5, ?5, 0)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&name)
+        .bind(format!("Runbook generated from {} – {}", from, to))
+        .bind(serde_json::to_string(&step_json)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(storage.pool())
+        .await?;
+        println!("✨ Registered workflow: {}", name);
+    }
+
+    Ok(())
+}
+
+/// Accepts RFC 3339 timestamps or bare YYYY-MM-DD dates (midnight UTC).
+fn parse_time(input: &str) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = input.parse::<DateTime<Utc>>() {
+        return Ok(timestamp);
+    }
+    if let Ok(date) = input.parse::<NaiveDate>() {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    Err(anyhow::anyhow!(
+        "Could not parse '{}' as a timestamp (use RFC 3339 or YYYY-MM-DD)",
+        input
+    ))
+}
+
+fn is_check(cmd: &Command) -> bool {
+    let tool = cmd.parsed_command.as_str();
+    if READ_ONLY_TOOLS.contains(&tool) {
+        return true;
+    }
+    let first_arg = cmd.arguments.first().map(String::as_str).unwrap_or("");
+    READ_ONLY_SUBCOMMANDS.contains(&(tool, first_arg))
+}
+
+fn is_rollback(cmd: &Command) -> bool {
+    cmd.raw
+        .split_whitespace()
+        .any(|word| ROLLBACK_VERBS.contains(&word))
+}
@@ -0,0 +1,210 @@
+//! End-to-end shell integration selftest
+//!
+//! Drives a real interactive shell on a PTY (via util-linux `script`)
+//! with the hooks installed into a throwaway HOME, types a scripted
+//! command sequence, then opens the sandbox database directly and
+//! verifies exit codes and durations landed. This is the only check
+//! that exercises the actual preexec/precmd plumbing — unit tests
+//! cannot catch a hook regression inside the shell itself.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use termbrain_core::domain::{Command, CommandRepository};
+use termbrain_storage::sqlite::{SqliteCommandRepository, SqliteStorage};
+use tokio::io::AsyncWriteExt;
+
+/// How long to wait for the shell session itself.
+const SHELL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait for the async `tb record` children to drain into
+/// the database after the shell has exited.
+const RECORD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The hooks record durations from wall-clock millisecond timestamps;
+/// `sleep 1` must come out at least this long.
+const MIN_SLEEP_MS: u64 = 800;
+
+pub async fn run_selftest(shell: String) -> Result<()> {
+    // Normalize the aliases people actually type
+    let shell = match shell.as_str() {
+        "pwsh" => "powershell".to_string(),
+        "nushell" => "nu".to_string(),
+        _ => shell,
+    };
+    let integration = match shell.as_str() {
+        "bash" => include_str!("../../../../shell-integration/bash/termbrain.bash"),
+        "zsh" => include_str!("../../../../shell-integration/zsh/termbrain.zsh"),
+        _ => bail!("Selftest currently supports bash and zsh, not {}", shell),
+    };
+    if std::process::Command::new(&shell)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_err()
+    {
+        bail!("{} is not installed or not in PATH", shell);
+    }
+
+    println!("🧪 Running shell integration selftest for {}", shell);
+
+    let sandbox = std::env::temp_dir().join(format!("termbrain-selftest-{}", std::process::id()));
+    if sandbox.exists() {
+        std::fs::remove_dir_all(&sandbox)?;
+    }
+    std::fs::create_dir_all(&sandbox)?;
+    println!("   Sandbox HOME: {}", sandbox.display());
+
+    let result = run_in_sandbox(&shell, integration, &sandbox).await;
+
+    // Best-effort cleanup; a leftover tmp dir is not worth failing over
+    let _ = std::fs::remove_dir_all(&sandbox);
+    result
+}
+
+async fn run_in_sandbox(shell: &str, integration: &str, sandbox: &Path) -> Result<()> {
+    // The hooks invoke `tb` by name, so the binary under test must be
+    // first in PATH inside the sandbox
+    let bin_dir = sandbox.join("bin");
+    std::fs::create_dir_all(&bin_dir)?;
+    let current_exe = std::env::current_exe()?;
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&current_exe, bin_dir.join("tb"))?;
+    #[cfg(not(unix))]
+    std::fs::copy(&current_exe, bin_dir.join("tb"))?;
+
+    let script_path = sandbox.join(format!("termbrain.{}", shell));
+    std::fs::write(&script_path, integration)?;
+
+    // Each supported shell sources the integration from its rc file,
+    // exactly as `tb install` would set it up
+    let shell_command = match shell {
+        "zsh" => {
+            std::fs::write(
+                sandbox.join(".zshrc"),
+                format!("source \"{}\"\n", script_path.display()),
+            )?;
+            "zsh -i".to_string()
+        }
+        _ => {
+            let rc = sandbox.join(".bashrc");
+            std::fs::write(&rc, format!("source \"{}\"\n", script_path.display()))?;
+            format!("bash --noprofile --rcfile \"{}\" -i", rc.display())
+        }
+    };
+
+    let marker = format!("echo tb-selftest-{}", std::process::id());
+    let typed = format!("{marker}\nfalse\nsleep 1\nexit\n");
+
+    // util-linux `script` allocates the PTY; the hooks only register in
+    // a genuinely interactive shell, so piping into `zsh -i` directly
+    // would not exercise them
+    let mut command = tokio::process::Command::new("script");
+    if cfg!(target_os = "macos") {
+        command.arg("-q").arg("/dev/null").args(shell_command.split_whitespace());
+    } else {
+        command.args(["-qec", &shell_command, "/dev/null"]);
+    }
+    let mut child = command
+        .current_dir(sandbox)
+        .env("HOME", sandbox)
+        .env("ZDOTDIR", sandbox)
+        .env(
+            "PATH",
+            format!(
+                "{}:{}",
+                bin_dir.display(),
+                std::env::var("PATH").unwrap_or_default()
+            ),
+        )
+        .env("TERMBRAIN_ENABLED", "1")
+        .env("TERMBRAIN_AUTO_RECORD", "1")
+        .env_remove("TERMBRAIN_SESSION_ID")
+        .env_remove("TERMBRAIN_SESSION_PID")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Could not start `script` — selftest needs util-linux/BSD script for the PTY")?;
+
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    stdin.write_all(typed.as_bytes()).await?;
+    drop(stdin);
+
+    match tokio::time::timeout(SHELL_TIMEOUT, child.wait()).await {
+        Ok(status) => {
+            status?;
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            bail!("Shell session did not exit within {:?}", SHELL_TIMEOUT);
+        }
+    }
+
+    // Recording is asynchronous — the hooks background `tb record` —
+    // so poll the sandbox database until the rows appear
+    let db_path = sandbox.join(".termbrain").join("termbrain.db");
+    let marker_raw = marker.clone();
+    let deadline = std::time::Instant::now() + RECORD_TIMEOUT;
+    let mut recorded: Vec<Command> = Vec::new();
+    loop {
+        if db_path.exists() {
+            if let Ok(rows) = recent_commands(&db_path).await {
+                let done = ["false", "sleep 1"]
+                    .iter()
+                    .all(|raw| rows.iter().any(|c| c.raw == *raw))
+                    && rows.iter().any(|c| c.raw == marker_raw);
+                recorded = rows;
+                if done {
+                    break;
+                }
+            }
+        }
+        if std::time::Instant::now() >= deadline {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+
+    let mut failures = 0;
+    let mut check = |ok: bool, label: &str| {
+        println!("   {} {}", if ok { "✅" } else { "❌" }, label);
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let find = |raw: &str| recorded.iter().find(|c| c.raw == raw);
+    check(
+        find(&marker_raw).is_some_and(|c| c.exit_code == 0),
+        "marker command recorded with exit code 0",
+    );
+    check(
+        find("false").is_some_and(|c| c.exit_code == 1),
+        "`false` recorded with exit code 1",
+    );
+    check(
+        find("sleep 1").is_some_and(|c| c.duration_ms >= MIN_SLEEP_MS),
+        "`sleep 1` recorded with a plausible duration",
+    );
+
+    if failures > 0 {
+        bail!(
+            "Shell integration selftest failed: {} of 3 checks ({} commands recorded)",
+            failures,
+            recorded.len()
+        );
+    }
+    println!("🧪 Shell integration selftest passed for {}", shell);
+    Ok(())
+}
+
+/// Opens the sandbox database by path — never through `create_storage`,
+/// which would resolve the real HOME's configuration.
+async fn recent_commands(db_path: &PathBuf) -> Result<Vec<Command>> {
+    let storage = SqliteStorage::new(db_path).await?;
+    let repo = SqliteCommandRepository::new(storage.pool().clone());
+    repo.find_recent(50).await
+}
@@ -0,0 +1,231 @@
+//! Local HTTP API
+//!
+//! `tb serve` exposes history to editors, status bars, and dashboards
+//! over loopback REST/JSON, so integrations can query without spawning
+//! the CLI per request. Every request must present the bearer token
+//! from `~/.termbrain/api-token` (created on first serve), which keeps
+//! other local users out while avoiding any network-facing surface —
+//! the listener binds 127.0.0.1 only.
+
+use anyhow::Result;
+use axum::extract::{Query, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use uuid::Uuid;
+
+use super::{create_repo, create_storage};
+
+const DEFAULT_LIMIT: usize = 20;
+
+struct AppState {
+    repo: termbrain_storage::sqlite::SqliteCommandRepository,
+    pool: SqlitePool,
+    token: String,
+}
+
+/// Loads the API token, generating one on first use.
+fn api_token() -> Result<String> {
+    let path = dirs::home_dir()
+        .unwrap_or_default()
+        .join(".termbrain")
+        .join("api-token");
+    if let Ok(token) = std::fs::read_to_string(&path) {
+        return Ok(token.trim().to_string());
+    }
+    let token = Uuid::new_v4().simple().to_string();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, &token)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(token)
+}
+
+/// Starts the API server on 127.0.0.1.
+pub async fn run_serve(port: u16) -> Result<()> {
+    let storage = create_storage().await?;
+    let state = Arc::new(AppState {
+        repo: create_repo(&storage),
+        pool: storage.pool().clone(),
+        token: api_token()?,
+    });
+
+    let app = Router::new()
+        .route("/search", get(search))
+        .route("/commands/recent", get(recent))
+        .route("/sessions", get(sessions))
+        .route("/patterns", get(patterns))
+        .route("/stats", get(stats))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    println!("🌐 API listening on http://127.0.0.1:{}", port);
+    println!("   Token: ~/.termbrain/api-token (send as 'Authorization: Bearer <token>')");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Rejects requests without the local bearer token.
+async fn require_token(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let authorized = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token == state.token);
+    if !authorized {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(next.run(request).await)
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct LimitParams {
+    limit: Option<usize>,
+}
+
+fn command_json(command: &Command) -> Value {
+    json!({
+        "id": command.id,
+        "raw": command.raw,
+        "directory": command.working_directory,
+        "exit_code": command.exit_code,
+        "duration_ms": command.duration_ms,
+        "timestamp": command.timestamp,
+        "session_id": command.session_id,
+        "source": command.source,
+        "extras": command.extras,
+    })
+}
+
+fn internal_error(e: anyhow::Error) -> StatusCode {
+    eprintln!("API error: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let results = state
+        .repo
+        .search(&params.q, params.limit.unwrap_or(DEFAULT_LIMIT), None, None)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(json!({
+        "query": params.q,
+        "results": results.iter().map(command_json).collect::<Vec<_>>(),
+    })))
+}
+
+async fn recent(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<LimitParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let commands = state
+        .repo
+        .find_recent(params.limit.unwrap_or(DEFAULT_LIMIT))
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(json!({
+        "commands": commands.iter().map(command_json).collect::<Vec<_>>(),
+    })))
+}
+
+async fn sessions(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<LimitParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let rows = sqlx::query(
+        "SELECT id, start_time, end_time, shell, terminal FROM sessions ORDER BY start_time DESC LIMIT ?",
+    )
+    .bind(params.limit.unwrap_or(DEFAULT_LIMIT) as i64)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| internal_error(e.into()))?;
+
+    let sessions: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            json!({
+                "id": row.get::<String, _>("id"),
+                "start_time": row.get::<String, _>("start_time"),
+                "end_time": row.get::<Option<String>, _>("end_time"),
+                "shell": row.get::<String, _>("shell"),
+                "terminal": row.get::<String, _>("terminal"),
+            })
+        })
+        .collect();
+    Ok(Json(json!({ "sessions": sessions })))
+}
+
+async fn patterns(State(state): State<Arc<AppState>>) -> Result<Json<Value>, StatusCode> {
+    let rows = sqlx::query(
+        "SELECT id, name, description, frequency, confidence, last_seen FROM patterns ORDER BY confidence DESC",
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| internal_error(e.into()))?;
+
+    let patterns: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            json!({
+                "id": row.get::<String, _>("id"),
+                "name": row.get::<String, _>("name"),
+                "description": row.get::<String, _>("description"),
+                "frequency": row.get::<i64, _>("frequency"),
+                "confidence": row.get::<f64, _>("confidence"),
+                "last_seen": row.get::<String, _>("last_seen"),
+            })
+        })
+        .collect();
+    Ok(Json(json!({ "patterns": patterns })))
+}
+
+async fn stats(State(state): State<Arc<AppState>>) -> Result<Json<Value>, StatusCode> {
+    let total = state.repo.count().await.map_err(internal_error)?;
+    let rows = sqlx::query(
+        "SELECT day, total, succeeded, failed FROM daily_command_counts ORDER BY day DESC LIMIT 7",
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| internal_error(e.into()))?;
+
+    let daily: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            json!({
+                "day": row.get::<String, _>("day"),
+                "total": row.get::<i64, _>("total"),
+                "succeeded": row.get::<i64, _>("succeeded"),
+                "failed": row.get::<i64, _>("failed"),
+            })
+        })
+        .collect();
+    Ok(Json(json!({ "total_commands": total, "daily": daily })))
+}
@@ -0,0 +1,395 @@
+//! Shell session lifecycle
+//!
+//! The shell hooks mint a TERMBRAIN_SESSION_ID per terminal tab and call
+//! `tb sessions start` on load and `tb sessions end` on exit, so the
+//! sessions table reflects real tab lifetimes instead of staying empty.
+//! `tb sessions list/show` then replay everything that happened in one
+//! tab: duration, directories visited, and the commands in order.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use termbrain_core::domain::entities::Session;
+use termbrain_core::domain::repositories::{CommandRepository, SessionRepository};
+use termbrain_storage::sqlite::{SqliteSessionRepository, SqliteStorage};
+
+use crate::OutputFormat;
+
+use super::{create_repo, create_storage};
+
+/// An open session with no activity for this long was abandoned — the
+/// shell died without running its end hook (crash, kill, power loss).
+const STALE_SESSION_HOURS: i64 = 48;
+
+/// One journaled session lifecycle event. Appended to the session
+/// journal before the database write, so a crash between the two is
+/// replayed on the next open instead of losing the event.
+#[derive(Serialize, Deserialize)]
+struct SessionEvent {
+    op: String,
+    id: String,
+    parent_id: Option<String>,
+    shell: String,
+    terminal: String,
+    at: DateTime<Utc>,
+}
+
+fn session_journal_path() -> std::path::PathBuf {
+    crate::platform::data_dir().join("session-journal.jsonl")
+}
+
+/// Appends one event to the session journal. Like the record journal,
+/// deliberately fsync-free.
+fn journal_event(event: &SessionEvent) -> Result<()> {
+    use std::io::Write;
+
+    let path = session_journal_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(event)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Crash recovery for the session table, run on every database open:
+/// replays journaled open/close events that never reached the
+/// database, then closes sessions abandoned without their end hook.
+/// Returns (events replayed, stale sessions closed).
+pub(super) async fn recover_sessions(storage: &SqliteStorage) -> Result<(usize, usize)> {
+    let replayed = replay_session_journal(storage).await?;
+    let closed = close_stale_sessions(storage).await?;
+    Ok((replayed, closed))
+}
+
+/// Applies every journaled event in order. All applications are
+/// idempotent (INSERT OR IGNORE / close-if-open), so the journal is
+/// only removed after the whole replay succeeded — a crash mid-replay
+/// just replays again.
+async fn replay_session_journal(storage: &SqliteStorage) -> Result<usize> {
+    let path = session_journal_path();
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let sessions = SqliteSessionRepository::new(storage.pool().clone());
+    let content = std::fs::read_to_string(&path)?;
+    let mut replayed = 0usize;
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let event: SessionEvent = match serde_json::from_str(line) {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("⚠️  Skipping malformed session journal entry: {}", e);
+                continue;
+            }
+        };
+        match event.op.as_str() {
+            "start" => {
+                sessions
+                    .create(&Session {
+                        id: event.id,
+                        parent_id: event.parent_id,
+                        start_time: event.at,
+                        end_time: None,
+                        shell: event.shell,
+                        terminal: event.terminal,
+                        extras: Default::default(),
+                    })
+                    .await?;
+            }
+            "end" => {
+                sqlx::query("UPDATE sessions SET end_time = ?2 WHERE id = ?1 AND end_time IS NULL")
+                    .bind(&event.id)
+                    .bind(event.at.to_rfc3339())
+                    .execute(storage.pool())
+                    .await?;
+            }
+            other => eprintln!("⚠️  Skipping unknown session journal op '{}'", other),
+        }
+        replayed += 1;
+    }
+
+    std::fs::remove_file(&path)?;
+    Ok(replayed)
+}
+
+/// Closes open sessions whose last activity is older than the stale
+/// threshold, backdating end_time to that last activity so durations
+/// stay honest.
+async fn close_stale_sessions(storage: &SqliteStorage) -> Result<usize> {
+    let cutoff = (Utc::now() - chrono::Duration::hours(STALE_SESSION_HOURS)).to_rfc3339();
+    let result = sqlx::query(
+        "UPDATE sessions
+         SET end_time = COALESCE(
+             (SELECT MAX(timestamp) FROM commands WHERE session_id = sessions.id),
+             start_time)
+         WHERE end_time IS NULL
+           AND COALESCE(
+               (SELECT MAX(timestamp) FROM commands WHERE session_id = sessions.id),
+               start_time) < ?",
+    )
+    .bind(&cutoff)
+    .execute(storage.pool())
+    .await?;
+    Ok(result.rows_affected() as usize)
+}
+
+/// Opens the session named by TERMBRAIN_SESSION_ID. Idempotent: the
+/// hooks may re-source the integration file in an already-open tab.
+pub async fn session_start() -> Result<()> {
+    let id = match std::env::var("TERMBRAIN_SESSION_ID") {
+        Ok(id) if !id.is_empty() => id,
+        _ => {
+            anyhow::bail!("TERMBRAIN_SESSION_ID is not set — source the shell integration first")
+        }
+    };
+
+    let shell = crate::platform::detect_shell();
+    let terminal = std::env::var("TERM_PROGRAM")
+        .or_else(|_| std::env::var("TERM"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    // Set by the hooks when this shell was started inside another
+    // recorded session (nested shell, tmux pane)
+    let parent_id = std::env::var("TERMBRAIN_PARENT_SESSION_ID")
+        .ok()
+        .filter(|p| !p.is_empty());
+
+    // Journal first: if the database write below never happens, the
+    // next open replays this event
+    journal_event(&SessionEvent {
+        op: "start".to_string(),
+        id: id.clone(),
+        parent_id: parent_id.clone(),
+        shell: shell.clone(),
+        terminal: terminal.clone(),
+        at: Utc::now(),
+    })?;
+
+    let storage = create_storage().await?;
+    let sessions = SqliteSessionRepository::new(storage.pool().clone());
+
+    sessions
+        .create(&Session {
+            id,
+            parent_id,
+            start_time: Utc::now(),
+            end_time: None,
+            shell,
+            terminal,
+            extras: Default::default(),
+        })
+        .await?;
+    Ok(())
+}
+
+/// Closes the session named by TERMBRAIN_SESSION_ID. A no-op for
+/// unknown or already-closed sessions so exit traps never error.
+pub async fn session_end() -> Result<()> {
+    let id = match std::env::var("TERMBRAIN_SESSION_ID") {
+        Ok(id) if !id.is_empty() => id,
+        _ => return Ok(()),
+    };
+
+    journal_event(&SessionEvent {
+        op: "end".to_string(),
+        id: id.clone(),
+        parent_id: None,
+        shell: String::new(),
+        terminal: String::new(),
+        at: Utc::now(),
+    })?;
+
+    let storage = create_storage().await?;
+    let sessions = SqliteSessionRepository::new(storage.pool().clone());
+
+    sessions.close(&id).await?;
+    Ok(())
+}
+
+/// Lists recent sessions with duration and command counts, newest first.
+pub async fn session_list(limit: usize, format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+    let sessions = SqliteSessionRepository::new(storage.pool().clone());
+
+    let recent = sessions.find_recent(limit).await?;
+    if recent.is_empty() {
+        println!("No sessions recorded yet");
+        println!("   Re-source the shell integration — it opens a session per terminal tab");
+        return Ok(());
+    }
+
+    let mut entries = Vec::new();
+    for session in &recent {
+        let commands: i64 = sqlx::query("SELECT COUNT(*) AS n FROM commands WHERE session_id = ?")
+            .bind(&session.id)
+            .fetch_one(storage.pool())
+            .await?
+            .get("n");
+        entries.push((session, commands));
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = entries
+                .iter()
+                .map(|(session, commands)| {
+                    serde_json::json!({
+                        "id": session.id,
+                        "parent_id": session.parent_id,
+                        "start_time": session.start_time.to_rfc3339(),
+                        "end_time": session.end_time.map(|t| t.to_rfc3339()),
+                        "shell": session.shell,
+                        "terminal": session.terminal,
+                        "commands": commands,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        _ => {
+            println!("🖥️  Sessions ({}):", entries.len());
+            // Nested sessions render under their parent; a parent that
+            // fell outside the listing window makes its child a root
+            let listed: std::collections::HashSet<&str> =
+                entries.iter().map(|(s, _)| s.id.as_str()).collect();
+            let roots: Vec<_> = entries
+                .iter()
+                .filter(|(session, _)| {
+                    session
+                        .parent_id
+                        .as_deref()
+                        .is_none_or(|parent| !listed.contains(parent))
+                })
+                .collect();
+            for (session, commands) in roots {
+                print_session_tree(session, *commands, 0, &entries);
+            }
+            println!();
+            println!("💡 'tb sessions show <id>' replays one session");
+        }
+    }
+
+    Ok(())
+}
+
+/// Replays one session: its commands in order, with the directory
+/// printed whenever it changes.
+pub async fn session_show(reference: String) -> Result<()> {
+    let storage = create_storage().await?;
+    let sessions = SqliteSessionRepository::new(storage.pool().clone());
+
+    // Accept an unambiguous id prefix like 'tb explain' does
+    let session = match sessions.find_by_id(&reference).await? {
+        Some(session) => session,
+        None => {
+            let ids: Vec<String> = sqlx::query("SELECT id FROM sessions WHERE id LIKE ? || '%'")
+                .bind(&reference)
+                .fetch_all(storage.pool())
+                .await?
+                .into_iter()
+                .map(|row| row.get("id"))
+                .collect();
+            match ids.len() {
+                0 => anyhow::bail!("No session matches '{}'", reference),
+                1 => sessions.find_by_id(&ids[0]).await?.unwrap(),
+                n => anyhow::bail!("'{}' is ambiguous — {} sessions match", reference, n),
+            }
+        }
+    };
+
+    let repo = create_repo(&storage);
+    let mut commands = repo.find_by_session(&session.id).await?;
+    commands.sort_by_key(|cmd| cmd.timestamp);
+
+    let duration = match session.end_time {
+        Some(end) => format_duration(end - session.start_time),
+        None => "still open".to_string(),
+    };
+    println!("🖥️  Session {}", session.id);
+    if let Some(parent) = &session.parent_id {
+        println!("   nested in session {}", parent);
+    }
+    println!(
+        "   {} on {}, started {}, {}",
+        session.shell,
+        session.terminal,
+        session.start_time.format("%Y-%m-%d %H:%M:%S"),
+        duration,
+    );
+
+    let mut directories: Vec<&str> = Vec::new();
+    for cmd in &commands {
+        if directories.last() != Some(&cmd.working_directory.as_str()) {
+            directories.push(&cmd.working_directory);
+        }
+    }
+    let mut unique = directories.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    println!("   {} commands across {} directories", commands.len(), unique.len());
+    println!();
+
+    if commands.is_empty() {
+        println!("   No commands recorded in this session");
+        return Ok(());
+    }
+
+    let mut last_dir = "";
+    for cmd in &commands {
+        if cmd.working_directory != last_dir {
+            println!("📁 {}", cmd.working_directory);
+            last_dir = &cmd.working_directory;
+        }
+        let marker = if cmd.exit_code == 0 { "✓" } else { "✗" };
+        println!(
+            "   {} {} {}",
+            cmd.timestamp.format("%H:%M:%S"),
+            marker,
+            cmd.raw,
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints one session line, then its nested sessions indented below it.
+fn print_session_tree(session: &Session, commands: i64, depth: usize, entries: &[(&Session, i64)]) {
+    let duration = match session.end_time {
+        Some(end) => format_duration(end - session.start_time),
+        None => "still open".to_string(),
+    };
+    println!(
+        "   {}{}{} — {} on {}, {} commands, {} (started {})",
+        "   ".repeat(depth),
+        if depth > 0 { "↳ " } else { "" },
+        session.id,
+        session.shell,
+        session.terminal,
+        commands,
+        duration,
+        session.start_time.format("%Y-%m-%d %H:%M"),
+    );
+    for (child, n) in entries {
+        if child.parent_id.as_deref() == Some(session.id.as_str()) {
+            print_session_tree(child, *n, depth + 1, entries);
+        }
+    }
+}
+
+/// Renders a duration as a compact "2h 13m" / "5m 30s" / "42s" string.
+fn format_duration(duration: chrono::Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
@@ -0,0 +1,142 @@
+//! Pre-flight working-tree snapshots
+//!
+//! The shell preexec hook calls `tb snapshot -- <command>` before
+//! commands that rewrite history or delete files. When `undo_snapshots`
+//! is enabled and the command runs inside a repository, a lightweight
+//! snapshot summary is stored: a `git stash create` ref (a dangling
+//! commit that touches neither the working tree nor the stash list),
+//! the HEAD hash, and a status summary. `tb undo-hint` then points at
+//! the exact ref that undoes the damage.
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::Row;
+use termbrain_core::git_context::read_git_context;
+use termbrain_core::risk::needs_undo_snapshot;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+use super::create_storage;
+
+/// Captures a snapshot summary before `command` runs, when warranted.
+/// Silent on success — it runs from the preexec hook.
+pub async fn snapshot_before(command: String) -> Result<()> {
+    let config = Config::load()?;
+    if !config.undo_snapshots || !needs_undo_snapshot(&command) {
+        return Ok(());
+    }
+
+    let cwd = std::env::current_dir()?;
+    let Some(git) = read_git_context(&cwd) else {
+        return Ok(());
+    };
+
+    let files_changed = git_lines(&git.repo_root, &["status", "--porcelain"])
+        .map(|out| out.lines().count())
+        .unwrap_or(0);
+
+    // Only a dirty tree has anything to stash; `git stash create`
+    // prints nothing at all when the tree is clean
+    let stash_ref = if files_changed > 0 {
+        git_lines(&git.repo_root, &["stash", "create", "termbrain pre-flight snapshot"])
+            .map(|out| out.trim().to_string())
+            .filter(|r| !r.is_empty())
+    } else {
+        None
+    };
+
+    // A stash ref is a dangling commit: anchor it against gc so the
+    // hint still works after the damage is noticed days later
+    if let Some(stash) = &stash_ref {
+        let _ = git_lines(
+            &git.repo_root,
+            &["update-ref", "-m", "termbrain snapshot", "refs/termbrain/snapshot", stash],
+        );
+    }
+
+    let storage = create_storage().await?;
+    sqlx::query(
+        "INSERT INTO undo_snapshots (id, command, directory, branch, head_commit, stash_ref, files_changed, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&command)
+    .bind(git.repo_root)
+    .bind(&git.branch)
+    .bind(&git.commit)
+    .bind(&stash_ref)
+    .bind(files_changed as i64)
+    .bind(Utc::now().to_rfc3339())
+    .execute(storage.pool())
+    .await?;
+
+    Ok(())
+}
+
+/// Shows how to get back to the state captured before recent risky
+/// commands.
+pub async fn undo_hint() -> Result<()> {
+    let storage = create_storage().await?;
+
+    let rows = sqlx::query(
+        "SELECT command, directory, branch, head_commit, stash_ref, files_changed, created_at
+         FROM undo_snapshots ORDER BY created_at DESC LIMIT 5",
+    )
+    .fetch_all(storage.pool())
+    .await?;
+
+    if rows.is_empty() {
+        let config = Config::load()?;
+        if config.undo_snapshots {
+            println!("No snapshots yet — none of the recent commands looked risky");
+        } else {
+            println!(
+                "Snapshots are disabled — set undo_snapshots in {} to capture them",
+                Config::config_file().display()
+            );
+        }
+        return Ok(());
+    }
+
+    println!("🪃 Recent pre-flight snapshots, newest first:");
+    for row in rows {
+        let branch: Option<String> = row.get("branch");
+        let head: Option<String> = row.get("head_commit");
+        let stash: Option<String> = row.get("stash_ref");
+        let files: i64 = row.get("files_changed");
+
+        println!(
+            "\n   before: {} ({}, {})",
+            row.get::<String, _>("command"),
+            branch.unwrap_or_else(|| "detached".to_string()),
+            &row.get::<String, _>("created_at")[..19],
+        );
+        match (stash, head) {
+            (Some(stash), _) => {
+                println!("      {} uncommitted files were saved — restore them with:", files);
+                println!("      git stash apply {}", stash);
+            }
+            (None, Some(head)) => {
+                println!("      tree was clean at {} — if history moved, go back with:", head);
+                println!("      git reset --hard {}   (or check git reflog)", head);
+            }
+            (None, None) => println!("      tree was clean; see git reflog to walk back"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs git in `directory`, returning stdout on success.
+fn git_lines(directory: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(directory)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
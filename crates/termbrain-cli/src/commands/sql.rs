@@ -0,0 +1,167 @@
+//! Read-only SQL console for ad-hoc analytics
+
+use anyhow::Result;
+use sqlx::{Column, Row};
+use std::time::Duration;
+
+use crate::OutputFormat;
+
+use super::create_storage;
+
+/// Hard cap on returned rows, regardless of the query's own LIMIT.
+const MAX_ROWS: usize = 1_000;
+
+/// Queries that run longer than this are cancelled.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Executes a read-only query against the live database.
+///
+/// Only SELECT and WITH statements are accepted, a single statement at
+/// a time, with a hard row cap and a timeout — enough for power users
+/// to do ad-hoc analytics without being able to corrupt the database.
+pub async fn run_sql(query: String, format: OutputFormat) -> Result<()> {
+    validate_read_only(&query)?;
+
+    let storage = create_storage().await?;
+
+    let rows = tokio::time::timeout(
+        QUERY_TIMEOUT,
+        sqlx::query(&query).fetch_all(storage.pool()),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("Query timed out after {}s", QUERY_TIMEOUT.as_secs()))??;
+
+    let truncated = rows.len() > MAX_ROWS;
+    let rows = &rows[..rows.len().min(MAX_ROWS)];
+
+    if rows.is_empty() {
+        println!("(no rows)");
+        return Ok(());
+    }
+
+    let columns: Vec<String> = rows[0]
+        .columns()
+        .iter()
+        .map(|col| col.name().to_string())
+        .collect();
+
+    match format {
+        OutputFormat::Json => {
+            let objects: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|row| {
+                    let mut object = serde_json::Map::new();
+                    for (i, name) in columns.iter().enumerate() {
+                        object.insert(name.clone(), cell_to_json(row, i));
+                    }
+                    serde_json::Value::Object(object)
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&objects)?);
+        }
+        OutputFormat::Csv => {
+            println!("{}", columns.join(","));
+            for row in rows {
+                let cells: Vec<String> = (0..columns.len())
+                    .map(|i| cell_to_string(row, i))
+                    .collect();
+                println!("{}", cells.join(","));
+            }
+        }
+        _ => {
+            println!("{}", columns.join(" | "));
+            println!("{}", "-".repeat(columns.join(" | ").len()));
+            for row in rows {
+                let cells: Vec<String> = (0..columns.len())
+                    .map(|i| cell_to_string(row, i))
+                    .collect();
+                println!("{}", cells.join(" | "));
+            }
+            println!("\n({} rows)", rows.len());
+        }
+    }
+
+    if truncated {
+        eprintln!("⚠️  Output truncated to {} rows", MAX_ROWS);
+    }
+
+    Ok(())
+}
+
+/// Rejects anything that isn't a single read-only statement.
+fn validate_read_only(query: &str) -> Result<()> {
+    let trimmed = query.trim().trim_end_matches(';');
+
+    if trimmed.is_empty() {
+        return Err(anyhow::anyhow!("SQL query cannot be empty"));
+    }
+
+    // A second statement hiding after a semicolon is not allowed
+    if trimmed.contains(';') {
+        return Err(anyhow::anyhow!("Only a single SQL statement is allowed"));
+    }
+
+    let lowered = trimmed.to_lowercase();
+    if !(lowered.starts_with("select") || lowered.starts_with("with")) {
+        return Err(anyhow::anyhow!(
+            "Only SELECT and WITH queries are allowed in the SQL console"
+        ));
+    }
+
+    // WITH ... INSERT/UPDATE/DELETE would still mutate; block the verbs
+    for forbidden in ["insert", "update", "delete", "drop", "alter", "create", "attach", "pragma", "vacuum", "replace"] {
+        if lowered.split(|c: char| !c.is_alphanumeric()).any(|word| word == forbidden) {
+            return Err(anyhow::anyhow!(
+                "Statement contains forbidden keyword '{}' — the SQL console is read-only",
+                forbidden
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn cell_to_string(row: &sqlx::sqlite::SqliteRow, index: usize) -> String {
+    if let Ok(value) = row.try_get::<i64, _>(index) {
+        return value.to_string();
+    }
+    if let Ok(value) = row.try_get::<f64, _>(index) {
+        return value.to_string();
+    }
+    if let Ok(value) = row.try_get::<String, _>(index) {
+        return value;
+    }
+    "NULL".to_string()
+}
+
+fn cell_to_json(row: &sqlx::sqlite::SqliteRow, index: usize) -> serde_json::Value {
+    if let Ok(value) = row.try_get::<i64, _>(index) {
+        return serde_json::json!(value);
+    }
+    if let Ok(value) = row.try_get::<f64, _>(index) {
+        return serde_json::json!(value);
+    }
+    if let Ok(value) = row.try_get::<String, _>(index) {
+        return serde_json::json!(value);
+    }
+    serde_json::Value::Null
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_and_with_are_allowed() {
+        assert!(validate_read_only("SELECT * FROM commands").is_ok());
+        assert!(validate_read_only("  with t as (select 1) select * from t;").is_ok());
+    }
+
+    #[test]
+    fn test_mutating_statements_are_rejected() {
+        assert!(validate_read_only("DELETE FROM commands").is_err());
+        assert!(validate_read_only("SELECT 1; DROP TABLE commands").is_err());
+        assert!(validate_read_only("WITH t AS (SELECT 1) INSERT INTO commands SELECT * FROM t").is_err());
+        assert!(validate_read_only("PRAGMA writable_schema = 1").is_err());
+    }
+}
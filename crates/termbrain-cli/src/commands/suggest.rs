@@ -0,0 +1,228 @@
+//! Suggestion generation with auditable provenance
+
+use anyhow::Result;
+use std::collections::HashMap;
+use termbrain_core::domain::entities::{Command, ProvenanceRecord, Suggestion, SuggestionKind};
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::working_set::WorkingSet;
+
+use crate::OutputFormat;
+
+use super::{create_storage, truncate_string};
+
+/// How much history to analyze when generating suggestions.
+const ANALYSIS_WINDOW: usize = 500;
+
+/// Minimum occurrences before a repeated command or sequence is worth
+/// suggesting.
+const MIN_OCCURRENCES: usize = 3;
+
+/// Shows suggestions derived from recent history. With `explain`, each
+/// suggestion lists the historical commands that produced it. Results
+/// are filtered to the current working set unless `all` is set.
+pub async fn show_suggestions(explain: bool, all: bool, format: OutputFormat) -> Result<()> {
+    if super::shadow_mode_guard(&crate::config::Config::load()?) {
+        return Ok(());
+    }
+
+    if super::focus::deep_focus_now().await {
+        println!("🧘 Deep focus detected — holding suggestions until your next break");
+        super::focus::defer("Suggestions were held during a focus session — run 'tb suggest'");
+        return Ok(());
+    }
+    super::focus::flush_deferred();
+
+    let storage = create_storage().await?;
+    let repo = super::create_repo(&storage);
+
+    let commands = repo.find_recent(ANALYSIS_WINDOW).await?;
+
+    if commands.len() < MIN_OCCURRENCES {
+        println!("Not enough history recorded to generate suggestions yet");
+        return Ok(());
+    }
+
+    let mut suggestions = Vec::new();
+    suggestions.extend(suggest_aliases(&commands));
+    suggestions.extend(suggest_workflows(&commands));
+    suggestions.extend(suggest_next_command(&commands));
+
+    // Drop suggestions whose evidence comes entirely from contexts the
+    // user is no longer working in, unless --all was requested.
+    if !all {
+        let working_set = WorkingSet::from_commands(&commands, chrono::Utc::now());
+        if !working_set.is_empty() {
+            let before = suggestions.len();
+            suggestions.retain(|suggestion| {
+                suggestion.provenance.iter().any(|record| {
+                    commands
+                        .iter()
+                        .find(|cmd| cmd.id == record.command_id)
+                        .map(|cmd| working_set.contains(cmd))
+                        .unwrap_or(false)
+                })
+            });
+            let hidden = before - suggestions.len();
+            if hidden > 0 && !matches!(format, OutputFormat::Json) {
+                println!("({} suggestions outside your working set hidden — use --all to see them)", hidden);
+            }
+        }
+    }
+
+    suggestions.sort_by(|a, b| {
+        b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let rusty = termbrain_core::decay::rusty_tools(&commands, chrono::Utc::now());
+
+    match format {
+        OutputFormat::Json => {
+            // JSON always includes provenance so integrations can audit
+            println!("{}", serde_json::to_string_pretty(&suggestions)?);
+        }
+        _ => {
+            if suggestions.is_empty() {
+                println!("No suggestions yet — keep recording commands");
+                return Ok(());
+            }
+
+            println!("💡 Suggestions ({}):", suggestions.len());
+            for (i, suggestion) in suggestions.iter().enumerate() {
+                let kind = match suggestion.kind {
+                    SuggestionKind::PredictedCommand => "next",
+                    SuggestionKind::Workflow => "workflow",
+                    SuggestionKind::Alias => "alias",
+                };
+                println!("\n{}. [{}] {}", i + 1, kind, suggestion.value);
+                println!("   {} (confidence: {:.2})", suggestion.description, suggestion.confidence);
+
+                // Warn before recommending a tool the user is rusty on
+                let tool = suggestion.value.split_whitespace().next().unwrap_or("");
+                if termbrain_core::decay::is_rusty(tool, &rusty) {
+                    println!("   ⚠️  You haven't used {} in a while — see: tb refresh", tool);
+                }
+
+                if explain {
+                    println!("   Based on:");
+                    for record in &suggestion.provenance {
+                        println!("     - {} ({}, id {})",
+                            truncate_string(&record.raw, 60),
+                            record.timestamp.format("%Y-%m-%d %H:%M"),
+                            record.command_id);
+                    }
+                }
+            }
+
+            if !explain {
+                println!("\nRun with --explain to see the history behind each suggestion");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn provenance_for<'a>(commands: impl IntoIterator<Item = &'a Command>) -> Vec<ProvenanceRecord> {
+    commands
+        .into_iter()
+        .map(|cmd| ProvenanceRecord {
+            command_id: cmd.id,
+            raw: cmd.raw.clone(),
+            timestamp: cmd.timestamp,
+        })
+        .collect()
+}
+
+/// Long commands typed repeatedly are alias candidates.
+fn suggest_aliases(commands: &[Command]) -> Vec<Suggestion> {
+    let mut by_raw: HashMap<&str, Vec<&Command>> = HashMap::new();
+    for cmd in commands {
+        if cmd.raw.len() > 20 {
+            by_raw.entry(&cmd.raw).or_default().push(cmd);
+        }
+    }
+
+    by_raw
+        .into_iter()
+        .filter(|(_, occurrences)| occurrences.len() >= MIN_OCCURRENCES)
+        .map(|(raw, occurrences)| {
+            let count = occurrences.len();
+            Suggestion {
+                kind: SuggestionKind::Alias,
+                value: raw.to_string(),
+                description: format!("Typed {} times — consider an alias", count),
+                confidence: (count as f32 / commands.len() as f32).min(1.0),
+                provenance: provenance_for(occurrences),
+            }
+        })
+        .collect()
+}
+
+/// Repeated three-command sequences are workflow candidates.
+fn suggest_workflows(commands: &[Command]) -> Vec<Suggestion> {
+    let mut sequences: HashMap<String, Vec<&Command>> = HashMap::new();
+
+    for window in commands.windows(3) {
+        // find_recent returns newest first; render oldest → newest
+        let key = format!(
+            "{} → {} → {}",
+            window[2].parsed_command, window[1].parsed_command, window[0].parsed_command
+        );
+        sequences.entry(key).or_default().push(&window[2]);
+    }
+
+    sequences
+        .into_iter()
+        .filter(|(_, starts)| starts.len() >= MIN_OCCURRENCES)
+        .map(|(sequence, starts)| {
+            let count = starts.len();
+            Suggestion {
+                kind: SuggestionKind::Workflow,
+                value: sequence,
+                description: format!("Sequence ran {} times — consider a workflow", count),
+                confidence: (count as f32 / commands.len() as f32 * 3.0).min(1.0),
+                provenance: provenance_for(starts),
+            }
+        })
+        .collect()
+}
+
+/// Predicts the most likely follow-up to the last recorded command.
+fn suggest_next_command(commands: &[Command]) -> Vec<Suggestion> {
+    let Some(last) = commands.first() else {
+        return Vec::new();
+    };
+
+    // Count what historically followed the same command
+    let mut followers: HashMap<&str, Vec<&Command>> = HashMap::new();
+    for window in commands.windows(2) {
+        let (next, prev) = (&window[0], &window[1]);
+        if prev.parsed_command == last.parsed_command && next.id != last.id {
+            followers.entry(&next.raw).or_default().push(next);
+        }
+    }
+
+    let total: usize = followers.values().map(|v| v.len()).sum();
+
+    followers
+        .into_iter()
+        .filter(|(_, occurrences)| occurrences.len() >= 2)
+        .max_by_key(|(_, occurrences)| occurrences.len())
+        .map(|(raw, occurrences)| {
+            let count = occurrences.len();
+            Suggestion {
+                kind: SuggestionKind::PredictedCommand,
+                value: raw.to_string(),
+                description: format!(
+                    "Followed '{}' in {} of {} cases",
+                    last.parsed_command, count, total
+                ),
+                confidence: count as f32 / total.max(1) as f32,
+                provenance: provenance_for(occurrences),
+            }
+        })
+        .into_iter()
+        .collect()
+}
@@ -0,0 +1,287 @@
+//! Multi-machine history sync
+//!
+//! One brain across a laptop and servers: every device appends its own
+//! history to `<device-id>.jsonl` at a shared location (a directory —
+//! possibly a WebDAV mount — an SSH path, or an S3 prefix), and `tb
+//! sync pull` union-merges the other devices' logs by command id.
+//! Commands are immutable once recorded, so the per-device snapshot is
+//! an append-only change log and the merge is conflict-free by
+//! construction. Deletions deliberately do not propagate.
+
+use anyhow::Result;
+use sqlx::Row;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+
+use crate::config::Config;
+
+use super::{create_repo, create_storage, device_id};
+
+/// Where the other side of the sync lives, parsed from `sync_remote`.
+enum SyncRemote {
+    /// A local or mounted directory.
+    Dir(PathBuf),
+    /// `ssh://user@host/path` — transferred with scp.
+    Ssh { host: String, path: String },
+    /// `s3://bucket/prefix` — transferred with the aws CLI.
+    S3(String),
+}
+
+impl SyncRemote {
+    fn parse(remote: &str) -> Result<Self> {
+        if let Some(rest) = remote.strip_prefix("ssh://") {
+            let (host, path) = rest
+                .split_once('/')
+                .ok_or_else(|| anyhow::anyhow!("Invalid ssh remote '{}': expected ssh://host/path", remote))?;
+            return Ok(Self::Ssh {
+                host: host.to_string(),
+                path: format!("/{}", path),
+            });
+        }
+        if remote.starts_with("s3://") {
+            return Ok(Self::S3(remote.trim_end_matches('/').to_string()));
+        }
+        Ok(Self::Dir(PathBuf::from(remote)))
+    }
+
+    /// True when reaching the remote needs the network.
+    fn needs_network(&self) -> bool {
+        !matches!(self, Self::Dir(_))
+    }
+}
+
+fn sync_remote() -> Result<SyncRemote> {
+    let config = Config::load()?;
+    let Some(remote) = config.sync_remote else {
+        anyhow::bail!(
+            "No sync remote configured — set sync_remote in {} (a directory, ssh://host/path, or s3://bucket/prefix)",
+            Config::config_file().display()
+        );
+    };
+    let remote = SyncRemote::parse(&remote)?;
+    if remote.needs_network() && config.offline {
+        anyhow::bail!("Offline mode is active — sync against a network remote is disabled");
+    }
+    Ok(remote)
+}
+
+/// Runs a transfer tool, surfacing its stderr on failure.
+fn run_transfer(tool: &str, args: &[&str]) -> Result<()> {
+    let output = std::process::Command::new(tool).args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Exports this device's full history to the remote as
+/// `<device-id>.jsonl`.
+pub async fn sync_push() -> Result<()> {
+    let remote = sync_remote()?;
+    let device = device_id()?;
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+    let commands = repo.find_recent(i64::MAX as usize).await?;
+
+    let mut log = String::new();
+    for command in &commands {
+        log.push_str(&serde_json::to_string(command)?);
+        log.push('\n');
+    }
+
+    let file_name = format!("{}.jsonl", device);
+    match &remote {
+        SyncRemote::Dir(dir) => {
+            std::fs::create_dir_all(dir)?;
+            std::fs::write(dir.join(&file_name), &log)?;
+        }
+        SyncRemote::Ssh { host, path } => {
+            let local = std::env::temp_dir().join(&file_name);
+            std::fs::write(&local, &log)?;
+            run_transfer(
+                "scp",
+                &[&local.display().to_string(), &format!("{}:{}/{}", host, path, file_name)],
+            )?;
+            std::fs::remove_file(&local)?;
+        }
+        SyncRemote::S3(prefix) => {
+            let local = std::env::temp_dir().join(&file_name);
+            std::fs::write(&local, &log)?;
+            run_transfer(
+                "aws",
+                &["s3", "cp", &local.display().to_string(), &format!("{}/{}", prefix, file_name)],
+            )?;
+            std::fs::remove_file(&local)?;
+        }
+    }
+
+    println!("⬆️  Pushed {} commands as device {}", commands.len(), device);
+    Ok(())
+}
+
+/// Merges the other devices' logs from the remote: commands whose ids
+/// are already present locally are skipped, everything else is saved in
+/// one batch.
+pub async fn sync_pull() -> Result<()> {
+    let remote = sync_remote()?;
+    let device = device_id()?;
+    let own_file = format!("{}.jsonl", device);
+
+    // Stage the remote logs into a local directory
+    let stage = std::env::temp_dir().join(format!("termbrain-sync-{}", std::process::id()));
+    std::fs::create_dir_all(&stage)?;
+    match &remote {
+        SyncRemote::Dir(dir) => {
+            if dir.exists() {
+                for entry in std::fs::read_dir(dir)? {
+                    let path = entry?.path();
+                    if path.extension().is_some_and(|e| e == "jsonl") {
+                        std::fs::copy(&path, stage.join(path.file_name().unwrap()))?;
+                    }
+                }
+            }
+        }
+        SyncRemote::Ssh { host, path } => {
+            // Wildcard expands on the remote side; an empty remote is fine
+            let _ = run_transfer(
+                "scp",
+                &[&format!("{}:{}/*.jsonl", host, path), &stage.display().to_string()],
+            );
+        }
+        SyncRemote::S3(prefix) => {
+            run_transfer(
+                "aws",
+                &["s3", "sync", "--exclude", "*", "--include", "*.jsonl", prefix, &stage.display().to_string()],
+            )?;
+        }
+    }
+
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+    let existing: HashSet<String> = sqlx::query("SELECT id FROM commands")
+        .fetch_all(storage.pool())
+        .await?
+        .into_iter()
+        .map(|row| row.get("id"))
+        .collect();
+
+    let mut logs: Vec<(String, String)> = Vec::new();
+    for entry in std::fs::read_dir(&stage)? {
+        let path = entry?.path();
+        if path.file_name().and_then(|n| n.to_str()) == Some(own_file.as_str()) {
+            continue;
+        }
+        logs.push((file_label(&path), std::fs::read_to_string(&path)?));
+    }
+    std::fs::remove_dir_all(&stage)?;
+
+    let devices = logs.len();
+    let merged = merge_device_logs(&logs, existing);
+
+    repo.save_batch(&merged).await?;
+    println!("⬇️  Merged {} new commands from {} other devices", merged.len(), devices);
+    Ok(())
+}
+
+fn file_label(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Parses the staged `(label, jsonl)` device logs into the commands to
+/// save, skipping ids already present locally — and ids seen earlier in
+/// this merge: every device pushes its full history, so once devices
+/// have pulled from each other the same command appears in several
+/// logs, and a duplicate would abort the whole `save_batch` insert.
+fn merge_device_logs(logs: &[(String, String)], mut existing: HashSet<String>) -> Vec<Command> {
+    let mut merged = Vec::new();
+    for (label, content) in logs {
+        for line in content.lines() {
+            let command: Command = match serde_json::from_str(line) {
+                Ok(command) => command,
+                Err(e) => {
+                    eprintln!("Warning: skipping bad entry in {}: {}", label, e);
+                    continue;
+                }
+            };
+            if existing.insert(command.id.to_string()) {
+                merged.push(command);
+            }
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termbrain_core::domain::entities::CommandMetadata;
+
+    fn command(id: u128, raw: &str) -> Command {
+        Command {
+            id: uuid::Uuid::from_u128(id),
+            raw: raw.to_string(),
+            parsed_command: raw.split_whitespace().next().unwrap_or("").to_string(),
+            arguments: Vec::new(),
+            working_directory: "/home/dev".to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: chrono::Utc::now(),
+            session_id: "s1".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "zsh".to_string(),
+                user: "dev".to_string(),
+                hostname: "laptop".to_string(),
+                terminal: "tty".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    fn log(commands: &[&Command]) -> String {
+        commands
+            .iter()
+            .map(|c| serde_json::to_string(c).unwrap())
+            .chain(std::iter::once(String::new()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_three_log_merge_deduplicates_shared_history() {
+        let a = command(1, "git status");
+        let b = command(2, "cargo test");
+        let c = command(3, "ls");
+
+        // Devices that already pulled from each other re-export the
+        // merged history, so the same ids recur across logs
+        let logs = vec![
+            ("a.jsonl".to_string(), log(&[&a, &b])),
+            ("b.jsonl".to_string(), log(&[&b, &c])),
+            ("c.jsonl".to_string(), log(&[&a, &b, &c])),
+        ];
+
+        // `b` is already local; `a` and `c` must each arrive exactly once
+        let existing: HashSet<String> = [b.id.to_string()].into();
+        let merged = merge_device_logs(&logs, existing);
+        let mut ids: Vec<String> = merged.iter().map(|c| c.id.to_string()).collect();
+        ids.sort();
+        assert_eq!(ids, vec![a.id.to_string(), c.id.to_string()]);
+
+        // A malformed line is skipped without dropping the rest
+        let logs = vec![("d.jsonl".to_string(), format!("not json\n{}", log(&[&a])))];
+        let merged = merge_device_logs(&logs, HashSet::new());
+        assert_eq!(merged.len(), 1);
+    }
+}
@@ -0,0 +1,113 @@
+//! History-grounded command synthesis
+
+use anyhow::Result;
+use termbrain_core::ai::AiProvider;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::{CommandRepository, HybridWeights};
+
+use crate::ai::SubprocessProvider;
+use crate::config::Config;
+
+use super::create_storage;
+
+/// How many related commands to show the model as grounding examples.
+const EXAMPLE_LIMIT: usize = 15;
+
+/// Generates a candidate command for a natural language goal, grounded
+/// in the user's own history (preferred flags, context names, hosts).
+///
+/// The candidate is always shown for confirmation first; it is only run
+/// when the user confirms AND `--execute` was passed.
+pub async fn synthesize_command(goal: String, execute: bool) -> Result<()> {
+    if goal.trim().is_empty() {
+        return Err(anyhow::anyhow!("Goal cannot be empty"));
+    }
+
+    let config = Config::load()?;
+    if super::shadow_mode_guard(&config) || super::offline_guard(&config) {
+        return Ok(());
+    }
+    let provider = SubprocessProvider::from_config(&config).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No AI provider configured. Set TERMBRAIN_AI_PROVIDER to a provider command (e.g. 'claude -p')"
+        )
+    })?;
+
+    let storage = create_storage().await?;
+    let repo = super::create_repo(&storage);
+
+    let examples = repo
+        .search_hybrid(&goal, EXAMPLE_LIMIT, &HybridWeights::default())
+        .await?;
+
+    let prompt = build_prompt(&goal, &examples);
+    let candidate = provider.complete(&prompt).await?;
+    let candidate = candidate.lines().next().unwrap_or("").trim().to_string();
+
+    if candidate.is_empty() {
+        return Err(anyhow::anyhow!("AI provider returned an empty candidate command"));
+    }
+
+    println!("🎯 Goal: {}", goal);
+    if examples.is_empty() {
+        println!("   ⚠️  No related history found — candidate is not grounded in your usage");
+    } else {
+        println!("   Grounded in {} related commands from your history", examples.len());
+    }
+    println!("\n   Candidate command:");
+    println!("   {}", candidate);
+
+    if !execute {
+        println!("\n   Re-run with --execute to run it (you will still be asked to confirm)");
+        return Ok(());
+    }
+
+    // Synthesized commands always require explicit confirmation; there
+    // is deliberately no --yes escape hatch for model output.
+    print!("\n   Run this command? [y/N]: ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+
+    if !input.trim().to_lowercase().starts_with('y') {
+        println!("   Cancelled");
+        return Ok(());
+    }
+
+    let (shell, flag) = crate::platform::shell_invocation();
+    let status = std::process::Command::new(shell)
+        .arg(flag)
+        .arg(&candidate)
+        .status()?;
+
+    if status.success() {
+        println!("\n✅ Command completed successfully");
+    } else {
+        println!("\n❌ Command exited with {}", status);
+    }
+
+    Ok(())
+}
+
+/// Builds a prompt asking the model for exactly one shell command,
+/// preferring the flags and names visible in the user's history.
+fn build_prompt(goal: &str, examples: &[Command]) -> String {
+    let mut prompt = String::from(
+        "You are TermBrain, generating a single shell command for the user's \
+         goal. Prefer the tools, flags, context names and hosts from the \
+         user's own history below over generic alternatives. Respond with \
+         exactly one command on one line and nothing else.\n\n\
+         Related history:\n",
+    );
+
+    for cmd in examples {
+        prompt.push_str(&format!(
+            "- {} (dir: {}, exit: {})\n",
+            cmd.raw, cmd.working_directory, cmd.exit_code
+        ));
+    }
+
+    prompt.push_str(&format!("\nGoal: {}\n", goal));
+    prompt
+}
@@ -0,0 +1,168 @@
+//! Cold-start onboarding
+//!
+//! A fresh database takes weeks of recording before suggestions and
+//! analytics have anything to chew on. `tb teach` shortcuts the warm-up
+//! with a few questions — main languages, common tasks, risky
+//! environments — and seeds tracked tools, metrics, ignore lists,
+//! retention policies, and starter workflows from the answers.
+
+use anyhow::Result;
+use chrono::Utc;
+use termbrain_core::retention::RetentionPolicy;
+use uuid::Uuid;
+
+use crate::config::{Config, MetricDefinition};
+
+use super::create_storage;
+
+/// Language name, its tools worth tracking, and (metric name, filter)
+/// pairs for the invocations worth counting.
+type LanguageProfile = (&'static str, &'static [&'static str], &'static [(&'static str, &'static str)]);
+
+const LANGUAGE_PROFILES: &[LanguageProfile] = &[
+    ("rust", &["cargo", "rustup"], &[("rust_builds", "cargo build"), ("rust_tests", "cargo test")]),
+    ("python", &["python3", "pip", "uv"], &[("python_tests", "pytest")]),
+    ("js", &["node", "npm", "pnpm", "yarn"], &[("js_tests", "npm test")]),
+    ("go", &["go"], &[("go_tests", "go test")]),
+    ("java", &["javac", "mvn", "gradle"], &[("java_builds", "mvn ")]),
+];
+
+/// Starter workflows per common task; steps are deliberately generic
+/// placeholders the user edits to taste.
+const TASK_WORKFLOWS: &[(&str, &str, &[&str])] = &[
+    ("deploy", "Build, verify, ship", &["<build command>", "<test command>", "<deploy command>"]),
+    ("release", "Cut and publish a release", &["<bump version>", "<tag release>", "<publish>"]),
+    ("containers", "Rebuild and restart containers", &["docker compose build", "docker compose up -d", "docker compose logs --tail 50"]),
+    ("data", "Refresh local data", &["<fetch data>", "<load into db>", "<sanity-check query>"]),
+];
+
+/// Shell noise rarely worth recording.
+const NOISE_COMMANDS: &[&str] = &["ls", "cd", "pwd", "clear", "exit"];
+
+/// Runs the onboarding interview and seeds config and workflows.
+pub async fn run_teach() -> Result<()> {
+    let mut config = Config::load()?;
+    let mut seeded: Vec<String> = Vec::new();
+
+    println!("👋 A few questions to make termbrain useful from day one.");
+    println!("   Enter skips a question.\n");
+
+    // 1. Languages → tracked tools and build/test metrics
+    let known: Vec<&str> = LANGUAGE_PROFILES.iter().map(|(name, _, _)| *name).collect();
+    let languages = ask(&format!("Main languages ({})? ", known.join(", ")))?;
+    for language in languages.split(',').map(|l| l.trim().to_lowercase()) {
+        let Some((_, tools, metrics)) = LANGUAGE_PROFILES
+            .iter()
+            .find(|(name, _, _)| *name == language || (language == "ts" && *name == "js"))
+        else {
+            continue;
+        };
+        for tool in *tools {
+            if !config.tracked_tools.iter().any(|t| t == tool) {
+                config.tracked_tools.push(tool.to_string());
+            }
+        }
+        for (name, filter) in *metrics {
+            if !config.metrics.iter().any(|m| m.name == *name) {
+                config.metrics.push(MetricDefinition {
+                    name: name.to_string(),
+                    sql: None,
+                    filter: Some(filter.to_string()),
+                });
+            }
+        }
+        seeded.push(format!("{} tooling", language));
+    }
+
+    // 2. Common tasks → starter workflows
+    let tasks = ask(&format!(
+        "Common tasks ({})? ",
+        TASK_WORKFLOWS.iter().map(|(name, _, _)| *name).collect::<Vec<_>>().join(", ")
+    ))?;
+    let storage = create_storage().await?;
+    for task in tasks.split(',').map(|t| t.trim().to_lowercase()) {
+        let Some((name, description, steps)) =
+            TASK_WORKFLOWS.iter().find(|(task_name, _, _)| *task_name == task)
+        else {
+            continue;
+        };
+        let exists = sqlx::query("SELECT 1 FROM workflows WHERE name = ?")
+            .bind(name)
+            .fetch_optional(storage.pool())
+            .await?
+            .is_some();
+        if exists {
+            continue;
+        }
+
+        let step_json: Vec<_> = steps
+            .iter()
+            .map(|command| serde_json::json!({ "command": command }))
+            .collect();
+        sqlx::query(
+            "INSERT INTO workflows (id, name, description, steps, created_at, updated_at, usage_count) VALUES (?1, ?2, ?3, ?4, ?5, ?5, 0)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(name)
+        .bind(*description)
+        .bind(serde_json::to_string(&step_json)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(storage.pool())
+        .await?;
+        seeded.push(format!("'{}' starter workflow", name));
+    }
+
+    // 3. Risky environments → sensitive-command retention
+    let risky = ask("Do you run commands with production credentials or secrets? [y/N] ")?;
+    if risky.to_lowercase().starts_with('y')
+        && !config.retention_policies.iter().any(|p| p.name == "drop-sensitive")
+    {
+        config.retention_policies.push(RetentionPolicy {
+            name: "drop-sensitive".to_string(),
+            keep: false,
+            directory: None,
+            command: None,
+            source: None,
+            category: None,
+            failed_only: false,
+            sensitive_only: true,
+            max_age_days: Some(30),
+        });
+        seeded.push("30-day retention for redacted commands".to_string());
+        println!("   Secrets are redacted before storage; redacted rows now also expire via 'tb prune'.");
+    }
+
+    // 4. Noise filtering
+    let quiet = ask(&format!(
+        "Skip recording shell noise ({})? [y/N] ",
+        NOISE_COMMANDS.join(", ")
+    ))?;
+    if quiet.to_lowercase().starts_with('y') {
+        for command in NOISE_COMMANDS {
+            if !config.ignored_commands.iter().any(|c| c == command) {
+                config.ignored_commands.push(command.to_string());
+            }
+        }
+        seeded.push("noise ignore list".to_string());
+    }
+
+    config.save()?;
+
+    println!();
+    if seeded.is_empty() {
+        println!("Nothing seeded — run 'tb teach' again any time");
+    } else {
+        println!("✅ Seeded: {}", seeded.join(", "));
+        println!("   Review with 'tb workflow list' and {}", Config::config_file().display());
+    }
+    Ok(())
+}
+
+/// Prints a prompt and reads one trimmed line from stdin.
+fn ask(prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
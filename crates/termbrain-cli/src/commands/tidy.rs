@@ -0,0 +1,184 @@
+//! Interactive history hygiene
+//!
+//! `tb tidy` walks through suggested cleanups — stored secrets, heavily
+//! duplicated runs, noise commands worth ignoring, stale workflows —
+//! applying each fix only after confirmation.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sqlx::Row;
+use termbrain_core::privacy::redact_secrets;
+
+use crate::config::Config;
+
+use super::create_storage;
+
+/// Duplicate groups at or above this size are flagged.
+const DUPLICATE_THRESHOLD: i64 = 25;
+/// How many copies of a duplicated command survive cleanup.
+const DUPLICATES_KEPT: i64 = 5;
+/// Noise candidates used at least this often are suggested for ignoring.
+const NOISE_THRESHOLD: i64 = 50;
+/// Commands that rarely carry signal on their own.
+const NOISE_CANDIDATES: &[&str] = &["ls", "cd", "pwd", "clear", "exit", "ll", "history"];
+/// Workflows unused for this long are considered stale.
+const STALE_WORKFLOW_DAYS: i64 = 30;
+
+/// Runs the cleanup assistant. With `yes`, applies every fix without
+/// prompting.
+pub async fn run_tidy(yes: bool) -> Result<()> {
+    let storage = create_storage().await?;
+    let pool = storage.pool();
+    let mut applied: Vec<String> = Vec::new();
+
+    println!("🧹 Checking history for cleanups...\n");
+
+    // 1. Stored secrets
+    let rows = sqlx::query("SELECT id, raw FROM commands")
+        .fetch_all(pool)
+        .await?;
+    let with_secrets: Vec<(String, String)> = rows
+        .iter()
+        .filter_map(|row| {
+            let id: String = row.get("id");
+            let raw: String = row.get("raw");
+            let redacted = redact_secrets(&raw);
+            (redacted != raw).then_some((id, redacted))
+        })
+        .collect();
+
+    if !with_secrets.is_empty() {
+        println!("🔑 {} stored commands look like they contain secrets", with_secrets.len());
+        if confirm("   Redact them in place?", yes)? {
+            for (id, redacted) in &with_secrets {
+                sqlx::query("UPDATE commands SET raw = ?1, arguments = ?2 WHERE id = ?3")
+                    .bind(redacted)
+                    .bind(serde_json::to_string(
+                        &redacted.split_whitespace().skip(1).collect::<Vec<_>>(),
+                    )?)
+                    .bind(id)
+                    .execute(pool)
+                    .await?;
+            }
+            applied.push(format!("redacted {} commands", with_secrets.len()));
+        }
+    }
+
+    // 2. Heavily duplicated runs
+    let groups = sqlx::query(
+        "SELECT raw, COUNT(*) as cnt FROM commands GROUP BY raw HAVING cnt >= ? ORDER BY cnt DESC",
+    )
+    .bind(DUPLICATE_THRESHOLD)
+    .fetch_all(pool)
+    .await?;
+
+    if !groups.is_empty() {
+        let total: i64 = groups.iter().map(|row| row.get::<i64, _>("cnt")).sum();
+        println!(
+            "\n📚 {} commands are each recorded {}+ times ({} rows total)",
+            groups.len(),
+            DUPLICATE_THRESHOLD,
+            total
+        );
+        for row in groups.iter().take(5) {
+            println!("   {:>6}× {}", row.get::<i64, _>("cnt"), row.get::<String, _>("raw"));
+        }
+        if confirm(
+            &format!("   Keep the {} most recent of each and delete the rest?", DUPLICATES_KEPT),
+            yes,
+        )? {
+            let mut deleted = 0u64;
+            for row in &groups {
+                let raw: String = row.get("raw");
+                let result = sqlx::query(
+                    "DELETE FROM commands WHERE id IN (
+                        SELECT id FROM (
+                            SELECT id, ROW_NUMBER() OVER (ORDER BY timestamp DESC) AS rn
+                            FROM commands WHERE raw = ?1
+                        ) WHERE rn > ?2
+                    )",
+                )
+                .bind(&raw)
+                .bind(DUPLICATES_KEPT)
+                .execute(pool)
+                .await?;
+                deleted += result.rows_affected();
+            }
+            applied.push(format!("deleted {} duplicate rows", deleted));
+        }
+    }
+
+    // 3. Noise commands worth ignoring
+    let mut config = Config::load()?;
+    let mut noisy: Vec<(String, i64)> = Vec::new();
+    for candidate in NOISE_CANDIDATES {
+        if config.ignored_commands.iter().any(|c| c == candidate) {
+            continue;
+        }
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM commands WHERE parsed_command = ?")
+                .bind(candidate)
+                .fetch_one(pool)
+                .await?;
+        if count >= NOISE_THRESHOLD {
+            noisy.push((candidate.to_string(), count));
+        }
+    }
+
+    if !noisy.is_empty() {
+        println!("\n🔇 Noise commands recorded often:");
+        for (cmd, count) in &noisy {
+            println!("   {:>6}× {}", count, cmd);
+        }
+        if confirm("   Stop recording these (add to ignore list)?", yes)? {
+            let names: Vec<String> = noisy.iter().map(|(cmd, _)| cmd.clone()).collect();
+            config.ignored_commands.extend(names);
+            config.save()?;
+            applied.push(format!("ignored {} noise commands", noisy.len()));
+        }
+    }
+
+    // 4. Stale workflows
+    let cutoff = (Utc::now() - Duration::days(STALE_WORKFLOW_DAYS)).to_rfc3339();
+    let stale = sqlx::query(
+        "SELECT name FROM workflows WHERE usage_count = 0 AND created_at < ?",
+    )
+    .bind(&cutoff)
+    .fetch_all(pool)
+    .await?;
+
+    if !stale.is_empty() {
+        println!("\n🗄️  Workflows never run since creation ({}+ days old):", STALE_WORKFLOW_DAYS);
+        for row in &stale {
+            println!("   {}", row.get::<String, _>("name"));
+        }
+        if confirm("   Delete them?", yes)? {
+            sqlx::query("DELETE FROM workflows WHERE usage_count = 0 AND created_at < ?")
+                .bind(&cutoff)
+                .execute(pool)
+                .await?;
+            applied.push(format!("deleted {} stale workflows", stale.len()));
+        }
+    }
+
+    println!();
+    if applied.is_empty() {
+        println!("✨ Nothing to tidy — history is clean");
+    } else {
+        println!("✅ Tidy complete: {}", applied.join(", "));
+    }
+
+    Ok(())
+}
+
+/// [y/N] prompt, auto-accepted when `yes` is set.
+fn confirm(message: &str, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    print!("{} [y/N]: ", message);
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_lowercase().starts_with('y'))
+}
@@ -0,0 +1,111 @@
+//! Measured command runs
+//!
+//! `tb time -- <command>` runs the command and records it with measured
+//! peak memory: when GNU time is available (`/usr/bin/time -v`), its
+//! report is parsed for the maximum resident set size and stored in the
+//! `max_rss_kb` extra. Cost estimation in `tb stats` then reports real
+//! memory numbers instead of durations alone.
+
+use anyhow::Result;
+use termbrain_core::cost::MAX_RSS_EXTRA;
+
+use super::record_command;
+
+/// Runs `command` (through the shell, so pipes work), records it, and
+/// prints a one-line cost summary. Exits with the command's own status.
+pub async fn time_command(command: String) -> Result<()> {
+    let time_report = tempfile_path();
+    let gnu_time = has_gnu_time();
+
+    let started = std::time::Instant::now();
+    let status = if gnu_time {
+        std::process::Command::new("/usr/bin/time")
+            .args(["-v", "-o", &time_report, "sh", "-c", &command])
+            .status()?
+    } else {
+        std::process::Command::new("sh").args(["-c", &command]).status()?
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let exit_code = status.code().unwrap_or(-1);
+
+    let max_rss_kb = std::fs::read_to_string(&time_report)
+        .ok()
+        .as_deref()
+        .and_then(parse_max_rss_kb);
+    let _ = std::fs::remove_file(&time_report);
+
+    let mut extras = Vec::new();
+    if let Some(rss) = max_rss_kb {
+        extras.push(format!("{}={}", MAX_RSS_EXTRA, rss));
+    }
+    record_command(
+        command,
+        exit_code,
+        Some(duration_ms),
+        None,
+        "shell-hook".to_string(),
+        extras,
+        None,
+    )
+    .await?;
+
+    match max_rss_kb {
+        Some(rss) => println!(
+            "⏱️  {:.1}s, peak {:.0} MB, exit {}",
+            duration_ms as f64 / 1000.0,
+            rss as f64 / 1024.0,
+            exit_code,
+        ),
+        None => println!(
+            "⏱️  {:.1}s, exit {} (install GNU time for memory measurement)",
+            duration_ms as f64 / 1000.0,
+            exit_code,
+        ),
+    }
+
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// True when /usr/bin/time is GNU time (supports -v). The BSD/macOS
+/// binary rejects -v, so probe once with a trivial command.
+fn has_gnu_time() -> bool {
+    std::process::Command::new("/usr/bin/time")
+        .args(["-v", "true"])
+        .output()
+        .map(|out| out.status.success())
+        .unwrap_or(false)
+}
+
+/// Extracts "Maximum resident set size (kbytes): N" from a GNU time -v
+/// report.
+fn parse_max_rss_kb(report: &str) -> Option<u64> {
+    report
+        .lines()
+        .find(|line| line.trim_start().starts_with("Maximum resident set size"))
+        .and_then(|line| line.rsplit(':').next())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn tempfile_path() -> String {
+    std::env::temp_dir()
+        .join(format!("termbrain-time-{}.txt", std::process::id()))
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_max_rss_from_gnu_time_report() {
+        let report = "\tCommand being timed: \"cargo build\"\n\
+                      \tMaximum resident set size (kbytes): 1048576\n\
+                      \tExit status: 0\n";
+        assert_eq!(parse_max_rss_kb(report), Some(1_048_576));
+        assert_eq!(parse_max_rss_kb("no report here"), None);
+    }
+}
@@ -0,0 +1,106 @@
+//! Tool inventory
+//!
+//! `tb tools` lists every CLI tool ever invoked with first/last use,
+//! frequency, failure rate, and the most recently snapshotted version
+//! (from `tb versions record`) — the raw material for a new machine's
+//! dotfiles bootstrap script.
+
+use anyhow::Result;
+use sqlx::Row;
+
+use crate::OutputFormat;
+
+use super::create_storage;
+
+/// Shows the tool inventory, most used first. `min_uses` drops the
+/// long tail of one-off invocations and typos.
+pub async fn tool_inventory(min_uses: usize, format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let rows = sqlx::query(
+        "SELECT parsed_command AS tool,
+                COUNT(*) AS total,
+                SUM(CASE WHEN exit_code != 0 THEN 1 ELSE 0 END) AS failures,
+                MIN(timestamp) AS first_use,
+                MAX(timestamp) AS last_use,
+                (SELECT v.version FROM tool_versions v
+                 WHERE v.tool = parsed_command
+                 ORDER BY v.recorded_at DESC LIMIT 1) AS version
+         FROM commands
+         WHERE parsed_command != ''
+         GROUP BY parsed_command
+         HAVING COUNT(*) >= ?
+         ORDER BY total DESC",
+    )
+    .bind(min_uses as i64)
+    .fetch_all(storage.pool())
+    .await?;
+
+    if rows.is_empty() {
+        println!("No tools recorded yet");
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let entries: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    let total: i64 = row.get("total");
+                    let failures: i64 = row.get("failures");
+                    serde_json::json!({
+                        "tool": row.get::<String, _>("tool"),
+                        "uses": total,
+                        "failure_rate": failures as f64 / total as f64,
+                        "first_use": row.get::<String, _>("first_use"),
+                        "last_use": row.get::<String, _>("last_use"),
+                        "version": row.get::<Option<String>, _>("version"),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        OutputFormat::Csv => {
+            println!("tool,uses,failure_rate,first_use,last_use,version");
+            for row in &rows {
+                let total: i64 = row.get("total");
+                let failures: i64 = row.get("failures");
+                println!(
+                    "{},{},{:.3},{},{},{}",
+                    row.get::<String, _>("tool"),
+                    total,
+                    failures as f64 / total as f64,
+                    row.get::<String, _>("first_use"),
+                    row.get::<String, _>("last_use"),
+                    row.get::<Option<String>, _>("version").unwrap_or_default(),
+                );
+            }
+        }
+        _ => {
+            println!("🧰 Tool inventory ({} tools):", rows.len());
+            for row in &rows {
+                let total: i64 = row.get("total");
+                let failures: i64 = row.get("failures");
+                let first: String = row.get("first_use");
+                let last: String = row.get("last_use");
+                let version = row
+                    .get::<Option<String>, _>("version")
+                    .map(|v| format!(", {}", v))
+                    .unwrap_or_default();
+                println!(
+                    "   {:<20} {:>6} uses, {:>4.0}% failed, {} → {}{}",
+                    row.get::<String, _>("tool"),
+                    total,
+                    failures as f64 / total as f64 * 100.0,
+                    &first[..10.min(first.len())],
+                    &last[..10.min(last.len())],
+                    version,
+                );
+            }
+            println!();
+            println!("💡 Versions come from 'tb versions record' snapshots; --format csv feeds a bootstrap script");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Vault lock/unlock
+//!
+//! Manages the at-rest encryption key. `tb vault unlock` derives the
+//! key from a passphrase (first unlock sets the vault up) and caches it
+//! in `~/.termbrain/vault.key` so every subsequent `tb` invocation can
+//! encrypt new records and decrypt reads; `tb vault lock` deletes the
+//! cache, leaving encrypted rows unreadable until the next unlock.
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use sqlx::Row;
+use std::path::PathBuf;
+use termbrain_storage::vault::{VaultCipher, VaultKey, KDF_ITERATIONS};
+use uuid::Uuid;
+
+use super::create_storage;
+
+/// Where the unlocked key lives between invocations. Created with
+/// owner-only permissions; deleting it is what "locking" means.
+pub(super) fn vault_key_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".termbrain")
+        .join("vault.key")
+}
+
+/// Loads the cached vault cipher, if the vault is unlocked. Best-effort:
+/// a malformed cache is treated as locked.
+pub(super) fn load_cipher() -> Option<VaultCipher> {
+    let encoded = std::fs::read_to_string(vault_key_path()).ok()?;
+    let bytes = BASE64.decode(encoded.trim()).ok()?;
+    let key: [u8; 32] = bytes.try_into().ok()?;
+    Some(VaultCipher::new(&VaultKey::from_bytes(key)))
+}
+
+/// Reads the vault passphrase from TERMBRAIN_VAULT_PASSPHRASE or an
+/// interactive prompt (no echo).
+fn read_passphrase(prompt: &str) -> Result<String> {
+    if let Ok(passphrase) = std::env::var("TERMBRAIN_VAULT_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+    Ok(rpassword::prompt_password(prompt)?)
+}
+
+/// Unlocks the vault, setting it up on first use: derives a key from
+/// the passphrase, verifies it against (or creates) the stored
+/// verifier, and caches the key for later invocations.
+pub async fn vault_unlock() -> Result<()> {
+    let storage = create_storage().await?;
+
+    let meta = sqlx::query("SELECT salt, verifier FROM vault_meta WHERE id = 1")
+        .fetch_optional(storage.pool())
+        .await?;
+
+    let key = match meta {
+        Some(row) => {
+            let salt: Vec<u8> = row.get("salt");
+            let verifier: String = row.get("verifier");
+
+            let passphrase = read_passphrase("Vault passphrase: ")?;
+            let key = VaultKey::derive(&passphrase, &salt);
+            if !VaultCipher::new(&key).check_verifier(&verifier) {
+                anyhow::bail!("Wrong passphrase");
+            }
+            key
+        }
+        None => {
+            println!("🔐 No vault yet — setting one up");
+            let passphrase = read_passphrase("New vault passphrase: ")?;
+            if passphrase.len() < 8 {
+                anyhow::bail!("Passphrase must be at least 8 characters");
+            }
+            if std::env::var("TERMBRAIN_VAULT_PASSPHRASE").is_err()
+                && read_passphrase("Confirm passphrase: ")? != passphrase
+            {
+                anyhow::bail!("Passphrases do not match");
+            }
+
+            // Fresh random salt per database
+            let salt = Uuid::new_v4().into_bytes().to_vec();
+            let key = VaultKey::derive(&passphrase, &salt);
+
+            sqlx::query(
+                "INSERT INTO vault_meta (id, salt, verifier, iterations, created_at) VALUES (1, ?, ?, ?, ?)",
+            )
+            .bind(&salt)
+            .bind(VaultCipher::new(&key).make_verifier()?)
+            .bind(KDF_ITERATIONS as i64)
+            .bind(Utc::now().to_rfc3339())
+            .execute(storage.pool())
+            .await?;
+            key
+        }
+    };
+
+    let key_path = vault_key_path();
+    if let Some(parent) = key_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&key_path, BASE64.encode(key.as_bytes()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    println!("🔓 Vault unlocked — new commands are encrypted at rest");
+    println!("   Run 'tb vault lock' when you're done");
+    Ok(())
+}
+
+/// Locks the vault by removing the cached key. Encrypted rows show as
+/// "[vault locked]" until the next unlock.
+pub async fn vault_lock() -> Result<()> {
+    let key_path = vault_key_path();
+    if key_path.exists() {
+        std::fs::remove_file(&key_path)?;
+        println!("🔒 Vault locked");
+    } else {
+        println!("Vault is already locked");
+    }
+    Ok(())
+}
+
+/// Shows whether a vault exists and whether it is currently unlocked.
+pub async fn vault_status() -> Result<()> {
+    let storage = create_storage().await?;
+    let configured = sqlx::query("SELECT 1 FROM vault_meta WHERE id = 1")
+        .fetch_optional(storage.pool())
+        .await?
+        .is_some();
+
+    if !configured {
+        println!("No vault configured — run 'tb vault unlock' to set one up");
+    } else if vault_key_path().exists() {
+        println!("🔓 Vault unlocked");
+    } else {
+        println!("🔒 Vault locked");
+    }
+    Ok(())
+}
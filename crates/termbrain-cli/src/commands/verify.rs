@@ -0,0 +1,110 @@
+//! Integrity chain verification
+//!
+//! `tb verify` recomputes the hash chain appended by recording (when
+//! `integrity_chain` is enabled in config) and reports any link whose
+//! stored record no longer matches — a rewritten command, a deleted
+//! row, or a back-dated timestamp. Exits non-zero on any finding so
+//! compliance scripts can gate on it.
+
+use anyhow::Result;
+use sqlx::Row;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::integrity::{chain_hash, chain_payload};
+use uuid::Uuid;
+
+use crate::config::Config;
+
+use super::{create_repo, create_storage, vault};
+
+/// Appends one link for a just-saved command. Called from recording
+/// when the integrity chain is enabled.
+pub(super) async fn append_chain(
+    pool: &sqlx::SqlitePool,
+    command: &termbrain_core::domain::entities::Command,
+) -> Result<()> {
+    let prev: Option<String> =
+        sqlx::query_scalar("SELECT hash FROM integrity_chain ORDER BY seq DESC LIMIT 1")
+            .fetch_optional(pool)
+            .await?;
+    let hash = chain_hash(prev.as_deref().unwrap_or(""), &chain_payload(command));
+
+    sqlx::query("INSERT INTO integrity_chain (command_id, hash, created_at) VALUES (?1, ?2, ?3)")
+        .bind(command.id.to_string())
+        .bind(&hash)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Walks the integrity chain in order and recomputes every hash.
+pub async fn verify_history() -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    // Hashes cover plaintext; with the vault locked every read comes
+    // back as a placeholder and nothing would match
+    let vault_exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vault_meta")
+        .fetch_one(storage.pool())
+        .await?;
+    if vault_exists > 0 && vault::load_cipher().is_none() {
+        anyhow::bail!("Vault is locked — run 'tb vault unlock' before verifying");
+    }
+
+    let links = sqlx::query("SELECT seq, command_id, hash FROM integrity_chain ORDER BY seq")
+        .fetch_all(storage.pool())
+        .await?;
+
+    if links.is_empty() {
+        let config = Config::load()?;
+        if config.integrity_chain {
+            println!("Integrity chain is empty — nothing recorded since it was enabled");
+        } else {
+            println!(
+                "Integrity chain is disabled — set integrity_chain in {} to start one",
+                Config::config_file().display()
+            );
+        }
+        return Ok(());
+    }
+
+    let mut prev = String::new();
+    let mut missing = 0;
+    let mut mismatched = 0;
+    for link in &links {
+        let seq: i64 = link.get("seq");
+        let command_id: String = link.get("command_id");
+        let hash: String = link.get("hash");
+
+        match repo.find_by_id(&Uuid::parse_str(&command_id)?).await? {
+            None => {
+                missing += 1;
+                println!("❌ Link {}: command {} has been deleted", seq, command_id);
+            }
+            Some(command) => {
+                if chain_hash(&prev, &chain_payload(&command)) != hash {
+                    mismatched += 1;
+                    println!(
+                        "❌ Link {}: command {} no longer matches its recorded hash",
+                        seq, command_id
+                    );
+                }
+            }
+        }
+        // Continue from the stored hash so one bad record is reported
+        // once instead of cascading through every later link
+        prev = hash;
+    }
+
+    if missing == 0 && mismatched == 0 {
+        println!("✅ Verified {} records — integrity chain intact", links.len());
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "Integrity check failed: {} rewritten, {} deleted (of {} chained records)",
+            mismatched,
+            missing,
+            links.len()
+        )
+    }
+}
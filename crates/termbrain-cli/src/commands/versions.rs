@@ -0,0 +1,157 @@
+//! Per-tool version tracking
+//!
+//! `tb versions record` snapshots the versions of configured tools on
+//! this host; `tb versions timeline` shows when upgrades happened and
+//! whether failure rates shifted afterwards.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::Row;
+use termbrain_core::domain::repositories::CommandRepository;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+use super::create_storage;
+
+/// Window on each side of a version change when comparing failure rates.
+const CORRELATION_WINDOW_DAYS: i64 = 7;
+
+/// Snapshots current versions of all tracked tools.
+pub async fn record_tool_versions() -> Result<()> {
+    let config = Config::load()?;
+    let storage = create_storage().await?;
+
+    let hostname = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "localhost".to_string());
+
+    let mut recorded = 0;
+    for tool in &config.tracked_tools {
+        let Some(version) = probe_version(tool) else {
+            continue;
+        };
+
+        sqlx::query(
+            "INSERT INTO tool_versions (id, tool, version, hostname, recorded_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(tool)
+        .bind(&version)
+        .bind(&hostname)
+        .bind(Utc::now().to_rfc3339())
+        .execute(storage.pool())
+        .await?;
+
+        println!("📌 {} {}", tool, version);
+        recorded += 1;
+    }
+
+    println!("\n✅ Recorded {} tool versions on {}", recorded, hostname);
+    Ok(())
+}
+
+/// Shows the version-change timeline, with failure-rate deltas around
+/// each change.
+pub async fn show_versions_timeline() -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = super::create_repo(&storage);
+
+    let rows = sqlx::query(
+        "SELECT tool, version, hostname, recorded_at FROM tool_versions ORDER BY tool, recorded_at",
+    )
+    .fetch_all(storage.pool())
+    .await?;
+
+    if rows.is_empty() {
+        println!("No tool versions recorded yet — run: tb versions record");
+        return Ok(());
+    }
+
+    println!("🧰 Tool version timeline:");
+
+    let mut previous: Option<(String, String)> = None; // (tool, version)
+    for row in rows {
+        let tool: String = row.get("tool");
+        let version: String = row.get("version");
+        let hostname: String = row.get("hostname");
+        let recorded_at: String = row.get("recorded_at");
+        let recorded_at = DateTime::parse_from_rfc3339(&recorded_at)?.with_timezone(&Utc);
+
+        let changed = match &previous {
+            Some((prev_tool, prev_version)) => *prev_tool == tool && *prev_version != version,
+            None => false,
+        };
+
+        if changed {
+            println!(
+                "\n   ⬆️  {} upgraded to {} on {} ({})",
+                tool,
+                version,
+                hostname,
+                recorded_at.format("%Y-%m-%d")
+            );
+
+            let commands = repo
+                .find_by_time_range(
+                    recorded_at - Duration::days(CORRELATION_WINDOW_DAYS),
+                    recorded_at + Duration::days(CORRELATION_WINDOW_DAYS),
+                )
+                .await?;
+
+            let (before, after): (Vec<_>, Vec<_>) = commands
+                .iter()
+                .filter(|cmd| cmd.parsed_command == tool)
+                .partition(|cmd| cmd.timestamp < recorded_at);
+
+            if let (Some(before_rate), Some(after_rate)) =
+                (failure_rate(&before), failure_rate(&after))
+            {
+                let trend = if after_rate > before_rate + 5.0 {
+                    "⚠️  failure rate increased"
+                } else if before_rate > after_rate + 5.0 {
+                    "✅ failure rate decreased"
+                } else {
+                    "steady"
+                };
+                println!(
+                    "      {} failures {:.1}% → {:.1}% ({}d window): {}",
+                    tool, before_rate, after_rate, CORRELATION_WINDOW_DAYS, trend
+                );
+            }
+        } else if previous.as_ref().map(|(t, _)| t != &tool).unwrap_or(true) {
+            println!(
+                "\n   {} {} ({}, first recorded {})",
+                tool,
+                version,
+                hostname,
+                recorded_at.format("%Y-%m-%d")
+            );
+        }
+
+        previous = Some((tool, version));
+    }
+
+    Ok(())
+}
+
+/// Failure percentage for a slice of command references.
+fn failure_rate(commands: &[&termbrain_core::domain::entities::Command]) -> Option<f64> {
+    if commands.is_empty() {
+        return None;
+    }
+    let failures = commands.iter().filter(|cmd| cmd.exit_code != 0).count();
+    Some(100.0 * failures as f64 / commands.len() as f64)
+}
+
+/// Runs `<tool> --version` and extracts the first line.
+pub(crate) fn probe_version(tool: &str) -> Option<String> {
+    let output = std::process::Command::new(tool).arg("--version").output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(|line| line.trim().to_string())
+}
@@ -0,0 +1,134 @@
+//! Live activity tail
+//!
+//! `tb watch` streams commands as they are recorded, polling the
+//! database once a second. `--ai` narrows the tail to wrapped agent
+//! activity (source "wrap"), and `--risk` colors every line by the
+//! risk classifier so a destructive step stands out immediately.
+//!
+//! While watching, single letters (followed by Enter) control the
+//! wrapped agent process: `p` pauses it with SIGSTOP, `r` resumes,
+//! `k` kills, `q` quits the tail. The agent's pid is taken from the
+//! `agent_pid` extra that wrappers attach to the commands they record.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashSet;
+use termbrain_core::domain::entities::Command;
+use termbrain_core::domain::repositories::CommandRepository;
+use termbrain_core::risk::{classify_risk, RiskLevel};
+use tokio::io::AsyncBufReadExt;
+use uuid::Uuid;
+
+use super::{create_repo, create_storage};
+
+/// Tails newly recorded commands until `q` or EOF.
+pub async fn watch_commands(ai_only: bool, risk: bool) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = create_repo(&storage);
+
+    println!(
+        "👀 Watching {} — p=pause r=resume k=kill q=quit (then Enter)",
+        if ai_only { "wrapped agent activity" } else { "new commands" }
+    );
+
+    let started = Utc::now();
+    let mut seen: HashSet<Uuid> = HashSet::new();
+    let mut agent_pid: Option<i64> = None;
+
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(1));
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                // A small overlap behind "now" catches records committed
+                // slightly after their timestamp; `seen` drops repeats
+                let window_start = (Utc::now() - chrono::Duration::seconds(10)).max(started);
+                let mut fresh = repo.find_by_time_range(window_start, Utc::now()).await?;
+                fresh.sort_by_key(|cmd| cmd.timestamp);
+
+                for cmd in fresh {
+                    if !seen.insert(cmd.id) {
+                        continue;
+                    }
+                    if ai_only && cmd.source != "wrap" {
+                        continue;
+                    }
+                    if let Some(pid) = extract_pid(&cmd) {
+                        agent_pid = Some(pid);
+                    }
+                    print_command(&cmd, risk);
+                }
+            }
+            line = lines.next_line() => {
+                match line?.as_deref().map(str::trim) {
+                    None | Some("q") => break,
+                    Some("p") => signal_agent(agent_pid, "-STOP", "paused")?,
+                    Some("r") => signal_agent(agent_pid, "-CONT", "resumed")?,
+                    Some("k") => signal_agent(agent_pid, "-TERM", "killed")?,
+                    Some("") => {}
+                    Some(other) => println!("Unknown key '{}' — p, r, k, or q", other),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The wrapped agent's pid, when the wrapper recorded one.
+fn extract_pid(command: &Command) -> Option<i64> {
+    match command.extras.get("agent_pid")? {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn print_command(command: &Command, risk: bool) {
+    let time = command.timestamp.format("%H:%M:%S");
+    let exit = if command.exit_code == 0 {
+        String::new()
+    } else {
+        format!(" (exit {})", command.exit_code)
+    };
+
+    if !risk {
+        println!("[{}] {}{}", time, command.raw, exit);
+        return;
+    }
+
+    // Red for high, yellow for medium, plain for low
+    let (color, label) = match classify_risk(&command.raw) {
+        RiskLevel::High => ("\x1b[31m", " [HIGH]"),
+        RiskLevel::Medium => ("\x1b[33m", " [medium]"),
+        RiskLevel::Low => ("", ""),
+    };
+    let reset = if color.is_empty() { "" } else { "\x1b[0m" };
+    println!("[{}] {}{}{}{}{}", time, color, command.raw, label, exit, reset);
+}
+
+/// Sends a signal to the wrapped agent via kill(1). Unix only — Windows
+/// has no job-control signals to forward.
+#[cfg(target_family = "unix")]
+fn signal_agent(pid: Option<i64>, signal: &str, verb: &str) -> Result<()> {
+    let Some(pid) = pid else {
+        println!("No wrapped agent seen yet (commands carry no agent_pid extra)");
+        return Ok(());
+    };
+    let status = std::process::Command::new("kill")
+        .args([signal, &pid.to_string()])
+        .status()?;
+    if status.success() {
+        println!("🛑 Agent process {} {}", pid, verb);
+    } else {
+        println!("Could not signal process {} — already gone?", pid);
+    }
+    Ok(())
+}
+
+#[cfg(target_family = "windows")]
+fn signal_agent(_pid: Option<i64>, _signal: &str, _verb: &str) -> Result<()> {
+    println!("Pausing and killing agents is not supported on Windows");
+    Ok(())
+}
@@ -0,0 +1,524 @@
+//! Saved workflows: named multi-step command sequences
+//!
+//! Steps support `{{variable}}` placeholders (filled from `--var`),
+//! per-step environment variables, conditionals on the previous exit
+//! code, confirmation prompts, and timeouts. Execution happens through
+//! the user's shell, one step at a time.
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::Row;
+use std::collections::HashMap;
+use termbrain_core::domain::entities::{Workflow, WorkflowRunStep, WorkflowStep};
+use termbrain_core::domain::repositories::{WorkflowRepository, WorkflowRunRepository};
+use termbrain_core::workflow_engine::{draft_from_sequence, placeholders, should_run, substitute_vars};
+use termbrain_storage::sqlite::{SqliteWorkflowRepository, SqliteWorkflowRunRepository};
+use uuid::Uuid;
+
+use super::create_storage;
+use crate::{OutputFormat, WorkflowAction};
+
+pub async fn handle_workflow(action: WorkflowAction, format: OutputFormat) -> Result<()> {
+    match action {
+        WorkflowAction::List => list_workflows(format).await,
+        WorkflowAction::Create { name } => create_workflow(name).await,
+        WorkflowAction::Run { name, var, yes, dry_run, step } => {
+            run_workflow(name, var, yes, dry_run, step).await
+        }
+        WorkflowAction::Delete { name } => delete_workflow(name).await,
+        WorkflowAction::History { name, runs } => workflow_history(name, runs).await,
+        WorkflowAction::Adopt { pattern_id, yes } => adopt_pattern(pattern_id, yes).await,
+    }
+}
+
+async fn list_workflows(format: OutputFormat) -> Result<()> {
+    let storage = create_storage().await?;
+    let rows = sqlx::query(
+        "SELECT name, description, steps, usage_count FROM workflows ORDER BY usage_count DESC, name",
+    )
+    .fetch_all(storage.pool())
+    .await?;
+    let stats = SqliteWorkflowRunRepository::new(storage.pool().clone())
+        .stats()
+        .await?;
+
+    if let OutputFormat::Json = format {
+        let workflows: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|row| {
+                let name: String = row.get("name");
+                let steps: Vec<WorkflowStep> =
+                    serde_json::from_str(&row.get::<String, _>("steps")).unwrap_or_default();
+                serde_json::json!({
+                    "name": name,
+                    "description": row.get::<String, _>("description"),
+                    "steps": steps,
+                    "usage_count": row.get::<i64, _>("usage_count"),
+                    "runs": stats.get(&name).map(|s| s.runs).unwrap_or(0),
+                    "success_rate": stats.get(&name).and_then(|s| s.success_rate()),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&workflows)?);
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("No workflows yet — create one with 'tb workflow create <name>'");
+        return Ok(());
+    }
+
+    println!("🔄 Workflows:");
+    for row in rows {
+        let name: String = row.get("name");
+        let steps: Vec<WorkflowStep> =
+            serde_json::from_str(&row.get::<String, _>("steps")).unwrap_or_default();
+        let trend = match stats.get(&name).and_then(|s| s.success_rate().map(|r| (s.runs, r))) {
+            Some((runs, rate)) => format!(", {:.0}% success over {} runs", rate * 100.0, runs),
+            None => String::new(),
+        };
+        println!(
+            "\n  {} ({} steps, used {} times{})",
+            name,
+            steps.len(),
+            row.get::<i64, _>("usage_count"),
+            trend
+        );
+        let description: String = row.get("description");
+        if !description.is_empty() {
+            println!("    {}", description);
+        }
+        for step in &steps {
+            println!("    {}. {}", step.order, step.command);
+        }
+    }
+    Ok(())
+}
+
+/// Shows the most recent runs of a workflow, step by step.
+async fn workflow_history(name: String, runs: usize) -> Result<()> {
+    let storage = create_storage().await?;
+    let repo = SqliteWorkflowRunRepository::new(storage.pool().clone());
+    let steps = repo.history(&name, runs).await?;
+    if steps.is_empty() {
+        println!("No recorded runs of '{}'", name);
+        return Ok(());
+    }
+
+    let mut current_run = None;
+    for step in &steps {
+        if current_run != Some(step.run_id) {
+            current_run = Some(step.run_id);
+            let by = step.user.as_deref().unwrap_or("unknown");
+            println!("\n▶️  {} — {}", step.started_at.format("%Y-%m-%d %H:%M:%S"), by);
+        }
+        let outcome = match (step.status.as_str(), step.exit_code) {
+            ("ok", _) => "✅".to_string(),
+            ("skipped", _) => "⏭️  skipped".to_string(),
+            ("timeout", _) => "⏱️  timeout".to_string(),
+            (_, Some(code)) => format!("❌ exit {}", code),
+            (_, None) => "❌".to_string(),
+        };
+        println!(
+            "   {}. {} {} ({}ms)",
+            step.step_order,
+            step.command,
+            outcome,
+            step.duration_ms.unwrap_or(0)
+        );
+    }
+    Ok(())
+}
+
+/// Creates a workflow interactively: one command per line, blank line to
+/// finish. `{{placeholders}}` in commands become run-time variables.
+async fn create_workflow(name: String) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let existing = sqlx::query("SELECT name FROM workflows WHERE name = ?")
+        .bind(&name)
+        .fetch_optional(storage.pool())
+        .await?;
+    if existing.is_some() {
+        anyhow::bail!("Workflow '{}' already exists", name);
+    }
+
+    println!("Enter steps for '{}', one command per line (blank line to finish):", name);
+    let mut steps: Vec<WorkflowStep> = Vec::new();
+    loop {
+        print!("  {}> ", steps.len() + 1);
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            break;
+        }
+        let command = line.trim();
+        if command.is_empty() {
+            break;
+        }
+        steps.push(WorkflowStep {
+            order: steps.len() as u32 + 1,
+            command: command.to_string(),
+            description: None,
+            expected_outcome: None,
+            env: HashMap::new(),
+            when: Default::default(),
+            confirm: false,
+            timeout_secs: None,
+        });
+    }
+    if steps.is_empty() {
+        anyhow::bail!("No steps entered — workflow not created");
+    }
+
+    let now = Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO workflows (id, name, description, steps, created_at, updated_at, usage_count)
+         VALUES (?1, ?2, '', ?3, ?4, ?4, 0)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&name)
+    .bind(serde_json::to_string(&steps)?)
+    .bind(&now)
+    .execute(storage.pool())
+    .await?;
+
+    println!("✨ Created workflow '{}' with {} steps", name, steps.len());
+    let vars: Vec<String> = steps.iter().flat_map(|s| placeholders(&s.command)).collect();
+    if !vars.is_empty() {
+        println!("   Run with: tb workflow run {} --var {}=<value>", name, vars[0]);
+    }
+    Ok(())
+}
+
+async fn run_workflow(
+    name: String,
+    var: Vec<String>,
+    yes: bool,
+    dry_run: bool,
+    step_mode: bool,
+) -> Result<()> {
+    let storage = create_storage().await?;
+
+    let row = sqlx::query("SELECT steps FROM workflows WHERE name = ?")
+        .bind(&name)
+        .fetch_optional(storage.pool())
+        .await?;
+    let Some(row) = row else {
+        anyhow::bail!("No workflow named '{}' — see 'tb workflow list'", name);
+    };
+    let steps: Vec<WorkflowStep> = serde_json::from_str(&row.get::<String, _>("steps"))?;
+    if steps.is_empty() {
+        anyhow::bail!("Workflow '{}' has no steps", name);
+    }
+
+    let vars = parse_vars(&var)?;
+    // Fail on missing variables before running anything, not mid-workflow
+    for step in &steps {
+        for needed in placeholders(&step.command) {
+            if !vars.contains_key(&needed) {
+                anyhow::bail!(
+                    "Workflow '{}' needs variable '{}' — pass --var {}=<value>",
+                    name,
+                    needed,
+                    needed
+                );
+            }
+        }
+    }
+
+    if dry_run {
+        println!("🔍 Dry run of '{}' — nothing will execute:", name);
+        for (index, step) in steps.iter().enumerate() {
+            let command = substitute_vars(&step.command, &vars)?;
+            let mut notes = Vec::new();
+            if step.when != Default::default() {
+                notes.push(format!("when: {:?}", step.when));
+            }
+            if step.confirm {
+                notes.push("asks confirmation".to_string());
+            }
+            if let Some(secs) = step.timeout_secs {
+                notes.push(format!("timeout {}s", secs));
+            }
+            match notes.is_empty() {
+                true => println!("   [{}/{}] {}", index + 1, steps.len(), command),
+                false => println!("   [{}/{}] {} ({})", index + 1, steps.len(), command, notes.join(", ")),
+            }
+        }
+        return Ok(());
+    }
+
+    println!("▶️  Running workflow '{}' ({} steps)", name, steps.len());
+    let runs_repo = SqliteWorkflowRunRepository::new(storage.pool().clone());
+    let run_id = Uuid::new_v4();
+    let mut all_succeeded = true;
+    for (index, step) in steps.iter().enumerate() {
+        if !should_run(step.when, all_succeeded) {
+            println!("   [{}/{}] skipped: {}", index + 1, steps.len(), step.command);
+            runs_repo
+                .record_step(&step_record(run_id, &name, step.order, &step.command, "skipped", None, None))
+                .await?;
+            continue;
+        }
+
+        let command = substitute_vars(&step.command, &vars)?;
+        if step_mode {
+            match step_prompt(&command, index + 1, steps.len())? {
+                StepChoice::Run => {}
+                StepChoice::Skip => {
+                    runs_repo
+                        .record_step(&step_record(run_id, &name, step.order, &command, "skipped", None, None))
+                        .await?;
+                    continue;
+                }
+                StepChoice::Abort => {
+                    anyhow::bail!("Workflow '{}' aborted at step {}", name, index + 1);
+                }
+            }
+        } else if step.confirm && !confirm(&format!("   Run '{}'?", command), yes)? {
+            anyhow::bail!("Workflow '{}' aborted at step {}", name, index + 1);
+        }
+
+        println!("   [{}/{}] {}", index + 1, steps.len(), command);
+        let started = std::time::Instant::now();
+        let exit_code = run_step(&command, &step.env, &vars, step.timeout_secs).await?;
+        let duration_ms = started.elapsed().as_millis() as i64;
+        let status = match exit_code {
+            Some(0) => "ok",
+            Some(_) => "failed",
+            None => "timeout",
+        };
+        runs_repo
+            .record_step(&step_record(run_id, &name, step.order, &command, status, exit_code, Some(duration_ms)))
+            .await?;
+        if exit_code != Some(0) {
+            all_succeeded = false;
+        }
+    }
+
+    if all_succeeded {
+        sqlx::query("UPDATE workflows SET usage_count = usage_count + 1, updated_at = ?1 WHERE name = ?2")
+            .bind(Utc::now().to_rfc3339())
+            .bind(&name)
+            .execute(storage.pool())
+            .await?;
+        println!("✅ Workflow '{}' completed", name);
+        Ok(())
+    } else {
+        anyhow::bail!("Workflow '{}' failed — see output above", name);
+    }
+}
+
+/// Promotes a detected pattern (by id or unique id prefix) into a
+/// workflow after confirmation.
+async fn adopt_pattern(pattern_id: String, yes: bool) -> Result<()> {
+    let storage = create_storage().await?;
+    let rows = sqlx::query("SELECT id, name FROM patterns WHERE id LIKE ?1 || '%'")
+        .bind(&pattern_id)
+        .fetch_all(storage.pool())
+        .await?;
+    let row = match rows.len() {
+        0 => anyhow::bail!("No pattern with id '{}' — run 'tb patterns' first", pattern_id),
+        1 => &rows[0],
+        n => anyhow::bail!("'{}' matches {} patterns — use more of the id", pattern_id, n),
+    };
+
+    let sequence: Vec<String> = row
+        .get::<String, _>("name")
+        .split(" → ")
+        .map(String::from)
+        .collect();
+    save_draft_workflow(storage.pool(), &sequence, yes).await?;
+    Ok(())
+}
+
+/// Offers each detected sequence as a draft workflow, saving the ones
+/// the user confirms. Used by `tb patterns --suggest-workflows`.
+pub(super) async fn suggest_workflows_from_patterns(
+    pool: &sqlx::SqlitePool,
+    patterns: &[(String, f32, usize)],
+) -> Result<()> {
+    if patterns.is_empty() {
+        println!("No detected patterns to promote — record more history first");
+        return Ok(());
+    }
+    let mut adopted = 0;
+    for (pattern, _, freq) in patterns {
+        let sequence: Vec<String> = pattern.split(" → ").map(String::from).collect();
+        println!("\nSeen {} times: {}", freq, pattern);
+        if save_draft_workflow(pool, &sequence, false).await? {
+            adopted += 1;
+        }
+    }
+    if adopted == 0 {
+        println!("\nNo workflows created");
+    }
+    Ok(())
+}
+
+/// Shows a draft built from a command sequence and saves it through the
+/// workflow repository if confirmed. Returns whether it was saved.
+async fn save_draft_workflow(
+    pool: &sqlx::SqlitePool,
+    sequence: &[String],
+    yes: bool,
+) -> Result<bool> {
+    let Some((name, steps)) = draft_from_sequence(sequence) else {
+        println!("   Not workflow material — fewer than two distinct steps");
+        return Ok(false);
+    };
+
+    let repo = SqliteWorkflowRepository::new(pool.clone());
+    if repo.find_by_name(&name).await?.is_some() {
+        println!("   Workflow '{}' already exists — skipping", name);
+        return Ok(false);
+    }
+
+    println!("   Draft workflow '{}':", name);
+    for step in &steps {
+        println!("     {}. {}", step.order, step.command);
+    }
+    if !confirm("   Create it?", yes)? {
+        return Ok(false);
+    }
+
+    let now = Utc::now();
+    repo.save(&Workflow {
+        id: Uuid::new_v4(),
+        name: name.clone(),
+        description: "Adopted from a detected pattern".to_string(),
+        steps,
+        created_at: now,
+        updated_at: now,
+        usage_count: 0,
+    })
+    .await?;
+    println!("✨ Created workflow '{}' — run it with 'tb workflow run {}'", name, name);
+    Ok(true)
+}
+
+/// Runs one step through the shell, returning its exit code, or `None`
+/// when the timeout killed it. `env` values may themselves contain
+/// `{{placeholders}}`.
+async fn run_step(
+    command: &str,
+    env: &HashMap<String, String>,
+    vars: &HashMap<String, String>,
+    timeout_secs: Option<u64>,
+) -> Result<Option<i32>> {
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.arg("-c").arg(command);
+    for (key, value) in env {
+        cmd.env(key, substitute_vars(value, vars)?);
+    }
+
+    let mut child = cmd.spawn()?;
+    let status = match timeout_secs {
+        Some(secs) => {
+            let limit = std::time::Duration::from_secs(secs);
+            match tokio::time::timeout(limit, child.wait()).await {
+                Ok(status) => status?,
+                Err(_) => {
+                    child.kill().await?;
+                    println!("   ⏱️  step killed after {}s timeout", secs);
+                    return Ok(None);
+                }
+            }
+        }
+        None => child.wait().await?,
+    };
+
+    if !status.success() {
+        println!("   ❌ step exited with {}", status.code().unwrap_or(-1));
+    }
+    Ok(Some(status.code().unwrap_or(-1)))
+}
+
+/// Builds one step's outcome record, stamped with the triggering user
+/// and shell session.
+fn step_record(
+    run_id: Uuid,
+    workflow: &str,
+    order: u32,
+    command: &str,
+    status: &str,
+    exit_code: Option<i32>,
+    duration_ms: Option<i64>,
+) -> WorkflowRunStep {
+    WorkflowRunStep {
+        id: Uuid::new_v4(),
+        run_id,
+        workflow_name: workflow.to_string(),
+        step_order: order,
+        command: command.to_string(),
+        status: status.to_string(),
+        exit_code,
+        duration_ms,
+        user: std::env::var("USER").ok(),
+        session_id: std::env::var("TERMBRAIN_SESSION_ID").ok(),
+        started_at: Utc::now(),
+    }
+}
+
+enum StepChoice {
+    Run,
+    Skip,
+    Abort,
+}
+
+/// Interactive pause before a step in `--step` mode.
+fn step_prompt(command: &str, index: usize, total: usize) -> Result<StepChoice> {
+    loop {
+        print!("   [{}/{}] {} — [r]un / [s]kip / [a]bort: ", index, total, command);
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input)? == 0 {
+            return Ok(StepChoice::Abort);
+        }
+        match input.trim().to_lowercase().as_str() {
+            "" | "r" | "run" => return Ok(StepChoice::Run),
+            "s" | "skip" => return Ok(StepChoice::Skip),
+            "a" | "abort" | "q" => return Ok(StepChoice::Abort),
+            _ => println!("   Please answer r, s, or a"),
+        }
+    }
+}
+
+async fn delete_workflow(name: String) -> Result<()> {
+    let storage = create_storage().await?;
+    let result = sqlx::query("DELETE FROM workflows WHERE name = ?")
+        .bind(&name)
+        .execute(storage.pool())
+        .await?;
+    if result.rows_affected() == 0 {
+        println!("No workflow named '{}'", name);
+    } else {
+        println!("🗑️  Deleted workflow '{}'", name);
+    }
+    Ok(())
+}
+
+/// Parses `--var KEY=VALUE` pairs.
+fn parse_vars(pairs: &[String]) -> Result<HashMap<String, String>> {
+    let mut vars = HashMap::new();
+    for pair in pairs {
+        let Some((key, value)) = pair.split_once('=') else {
+            anyhow::bail!("Invalid --var '{}': expected KEY=VALUE", pair);
+        };
+        vars.insert(key.to_string(), value.to_string());
+    }
+    Ok(vars)
+}
+
+/// [y/N] prompt, auto-accepted when `yes` is set.
+fn confirm(message: &str, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    print!("{} [y/N]: ", message);
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_lowercase().starts_with('y'))
+}
@@ -5,34 +5,339 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub database_path: PathBuf,
     pub shell_integration: bool,
     pub auto_record: bool,
     pub semantic_search: bool,
     pub max_history_size: usize,
+    /// External command used for AI features (e.g. "claude -p").
+    /// Read from TERMBRAIN_AI_PROVIDER; AI commands are disabled when unset.
+    pub ai_provider: Option<String>,
+    /// External command producing embeddings (text on stdin, JSON float
+    /// array on stdout). Read from TERMBRAIN_EMBEDDING_PROVIDER; vector
+    /// search falls back to lexical ranking when unset.
+    pub embedding_provider: Option<String>,
+    /// How stored embeddings are ranked: "flat" (cosine in Rust) or
+    /// "sqlite-vec" (distance computed inside SQLite; use for large
+    /// histories).
+    #[serde(default = "default_vector_index")]
+    pub vector_index: String,
+    /// Offline mode: no network call may be made (AI providers, webhook
+    /// delivery). Also set by --offline or TERMBRAIN_OFFLINE=1.
+    #[serde(default)]
+    pub offline: bool,
+    /// Shadow mode: only recording and search are active; AI, prediction
+    /// and suggestion features refuse to run. Also set by
+    /// TERMBRAIN_SHADOW_MODE=1. For environments where sending history
+    /// to a model is prohibited.
+    #[serde(default)]
+    pub shadow_mode: bool,
+    /// User-defined metrics evaluated over history and shown in stats.
+    #[serde(default)]
+    pub metrics: Vec<MetricDefinition>,
+    /// Alert rules evaluated against custom metrics.
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    /// Tools whose versions are snapshotted by `tb versions record`.
+    #[serde(default = "default_tracked_tools")]
+    pub tracked_tools: Vec<String>,
+    /// Commands (by leading word) never recorded into history.
+    #[serde(default)]
+    pub ignored_commands: Vec<String>,
+    /// Regexes matched against the full command line; matching commands
+    /// are never recorded. Managed via `tb config ignore`.
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// Directories whose subtrees are never recorded (e.g. "~/secrets");
+    /// a leading `~` expands to the home directory.
+    #[serde(default)]
+    pub ignore_directories: Vec<String>,
+    /// Shared location for multi-machine sync: a directory (possibly a
+    /// WebDAV mount), ssh://host/path, or s3://bucket/prefix. Sync is
+    /// disabled when unset.
+    #[serde(default)]
+    pub sync_remote: Option<String>,
+    /// Granular retention policies evaluated by `tb prune`, e.g. delete
+    /// failed commands after 30 days but keep git commands forever.
+    #[serde(default)]
+    pub retention_policies: Vec<termbrain_core::retention::RetentionPolicy>,
+    /// Per-class retention windows (raw commands, AI transcripts and
+    /// summaries, patterns, notes), each in days; unset classes are
+    /// kept forever.
+    #[serde(default)]
+    pub class_retention: termbrain_core::retention::ClassRetention,
+    /// Extra redaction rules applied before persistence, on top of the
+    /// built-in ones (AWS keys, JWTs, URL passwords, --password flags).
+    #[serde(default)]
+    pub redaction_rules: Vec<RedactionRuleConfig>,
+    /// Scheduled automatic backups; disabled when unset. Backups run
+    /// opportunistically from `tb backup run` (wire it into cron or a
+    /// shell hook) and skip themselves until the interval has elapsed.
+    #[serde(default)]
+    pub backup: Option<BackupConfig>,
+    /// Pre-flight working-tree snapshots before risky git/rm commands,
+    /// taken by `tb snapshot` from the shell preexec hook and surfaced
+    /// by `tb undo-hint`. Off by default.
+    #[serde(default)]
+    pub undo_snapshots: bool,
+    /// Tamper-evident integrity chain: every recorded command appends a
+    /// hash link covering the previous link plus the record, checked by
+    /// `tb verify`. Off by default; enabling it only chains records made
+    /// from then on.
+    #[serde(default)]
+    pub integrity_chain: bool,
+    /// Collapse bursts of near-identical commands (a shell loop running
+    /// `convert` over thousands of files) into one summarized record
+    /// with a count, aggregate duration, and failure count. Off by
+    /// default.
+    #[serde(default)]
+    pub burst_summarize: bool,
+    /// Keep the individual rows of a summarized burst too, instead of
+    /// recording only the summary.
+    #[serde(default)]
+    pub burst_keep_raw: bool,
+    /// Fast recording path: without a daemon, `tb record` appends to a
+    /// write-ahead journal and returns instead of opening SQLite. The
+    /// journal drains on `tb flush` or the next database open.
+    #[serde(default)]
+    pub fast_record: bool,
+    /// Warn at record time when a command lands 2× past its own median
+    /// duration. Off by default: the shell hooks discard output, so
+    /// this mostly matters for manual and wrapped recording.
+    #[serde(default)]
+    pub perf_warnings: bool,
+    /// Branch patterns (`feature/*` style) that auto-create an
+    /// intention on checkout.
+    #[serde(default = "default_branch_intention_patterns")]
+    pub branch_intention_patterns: Vec<String>,
+    /// Template for branch-created intentions; `{branch}` and `{slug}`
+    /// are substituted.
+    #[serde(default = "default_branch_intention_template")]
+    pub branch_intention_template: String,
+}
+
+fn default_vector_index() -> String {
+    "flat".to_string()
+}
+
+fn default_branch_intention_patterns() -> Vec<String> {
+    vec!["feature/*".to_string(), "fix/*".to_string()]
+}
+
+fn default_branch_intention_template() -> String {
+    "Working on {slug}".to_string()
+}
+
+/// Expands a leading `~` in a configured directory to the home directory.
+pub(crate) fn expand_tilde(path: &str) -> PathBuf {
+    match path.strip_prefix('~') {
+        Some(rest) => dirs::home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches('/')),
+        None => PathBuf::from(path),
+    }
+}
+
+fn offline_from_env() -> bool {
+    matches!(
+        std::env::var("TERMBRAIN_OFFLINE").as_deref(),
+        Ok("1") | Ok("true")
+    )
+}
+
+fn shadow_mode_from_env() -> bool {
+    matches!(
+        std::env::var("TERMBRAIN_SHADOW_MODE").as_deref(),
+        Ok("1") | Ok("true")
+    )
+}
+
+fn default_tracked_tools() -> Vec<String> {
+    ["git", "node", "python3", "cargo", "kubectl", "docker"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// A custom metric: either a read-only SQL expression returning a single
+/// value, or a filter substring counted against recorded commands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDefinition {
+    pub name: String,
+    /// Read-only SELECT returning one row/one column, e.g.
+    /// "SELECT COUNT(*) FROM commands WHERE raw LIKE 'kubectl apply%'"
+    #[serde(default)]
+    pub sql: Option<String>,
+    /// Substring matched against raw commands and counted.
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+/// A user-defined redaction rule: text matching `pattern` is replaced
+/// with `placeholder` before a command is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRuleConfig {
+    pub name: String,
+    /// Regex matched against the raw command line.
+    pub pattern: String,
+    /// Replacement text; capture groups like `$pre` may be referenced.
+    #[serde(default = "default_redaction_placeholder")]
+    pub placeholder: String,
+}
+
+fn default_redaction_placeholder() -> String {
+    "<redacted>".to_string()
+}
+
+/// Where and how often history is backed up. The target accepts a local
+/// directory, s3://bucket/prefix, or rclone:<remote>:<path>.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    pub target: String,
+    /// Hours between backups.
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u64,
+    /// How many backup generations to keep at the target.
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+}
+
+fn default_backup_interval_hours() -> u64 {
+    24
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+/// An alert on a custom metric, e.g. `error_count > 20`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    /// Name of the metric (from `metrics`) this rule watches.
+    pub metric: String,
+    /// Comparison operator: ">", ">=", "<", "<=", "==".
+    pub op: String,
+    pub threshold: f64,
+    /// Optional webhook URL to POST the alert to (via curl).
+    #[serde(default)]
+    pub webhook: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            database_path: dirs::home_dir()
-                .unwrap_or_default()
-                .join(".termbrain")
-                .join("termbrain.db"),
+            database_path: crate::platform::data_dir().join("termbrain.db"),
             shell_integration: true,
             auto_record: true,
             semantic_search: false,
             max_history_size: 10000,
+            ai_provider: std::env::var("TERMBRAIN_AI_PROVIDER").ok(),
+            embedding_provider: std::env::var("TERMBRAIN_EMBEDDING_PROVIDER").ok(),
+            vector_index: default_vector_index(),
+            offline: offline_from_env(),
+            shadow_mode: shadow_mode_from_env(),
+            metrics: Vec::new(),
+            alerts: Vec::new(),
+            tracked_tools: default_tracked_tools(),
+            ignored_commands: Vec::new(),
+            ignore_patterns: Vec::new(),
+            ignore_directories: Vec::new(),
+            sync_remote: std::env::var("TERMBRAIN_SYNC_REMOTE").ok(),
+            retention_policies: Vec::new(),
+            class_retention: Default::default(),
+            redaction_rules: Vec::new(),
+            backup: None,
+            undo_snapshots: false,
+            integrity_chain: false,
+            burst_summarize: false,
+            burst_keep_raw: false,
+            fast_record: false,
+            perf_warnings: false,
+            branch_intention_patterns: default_branch_intention_patterns(),
+            branch_intention_template: default_branch_intention_template(),
         }
     }
 }
 
 impl Config {
+    /// Path of the optional user config file.
+    pub fn config_file() -> PathBuf {
+        crate::platform::data_dir().join("config.json")
+    }
+
     pub fn load() -> Result<Self> {
-        // For now, return default config
-        // In a full implementation, this would read from config file
-        Ok(Self::default())
+        let path = Self::config_file();
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let mut config: Config = serde_json::from_str(&content)
+                .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))?;
+            // The kill-switch env vars win even when the file says off
+            config.offline |= offline_from_env();
+            config.shadow_mode |= shadow_mode_from_env();
+            Ok(config)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// True when no network call may be made. Always true in builds
+    /// compiled without the `network` feature.
+    pub fn offline(&self) -> bool {
+        cfg!(not(feature = "network")) || self.offline
     }
-    
-}
\ No newline at end of file
+
+    /// True when the ignore rules say a command must not be recorded:
+    /// its leading word is in `ignored_commands`, the full line matches
+    /// one of `ignore_patterns`, or it ran inside an `ignore_directories`
+    /// subtree. Invalid patterns are warned about and skipped.
+    pub fn should_ignore(&self, command: &str, directory: &str) -> bool {
+        let leading = command.split_whitespace().next().unwrap_or("");
+        if self.ignored_commands.iter().any(|c| c == leading) {
+            return true;
+        }
+        for pattern in &self.ignore_patterns {
+            match regex::Regex::new(pattern) {
+                Ok(re) if re.is_match(command) => return true,
+                Ok(_) => {}
+                Err(e) => eprintln!("Warning: invalid ignore pattern '{}': {}", pattern, e),
+            }
+        }
+        let directory = std::path::Path::new(directory);
+        self.ignore_directories
+            .iter()
+            .any(|ignored| directory.starts_with(expand_tilde(ignored)))
+    }
+
+    /// Persists the config back to the user config file.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::config_file();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_ignore_matches_all_rule_kinds() {
+        let mut config = Config::default();
+        config.ignored_commands.push("ls".to_string());
+        config.ignore_patterns.push("^aws ".to_string());
+        config.ignore_directories.push("/tmp/secrets".to_string());
+
+        assert!(config.should_ignore("ls -la", "/home/me"));
+        assert!(config.should_ignore("aws s3 ls", "/home/me"));
+        assert!(config.should_ignore("cat notes", "/tmp/secrets/deep"));
+        assert!(!config.should_ignore("cargo build", "/home/me"));
+        // Sibling directory sharing a prefix is not inside the subtree
+        assert!(!config.should_ignore("cat notes", "/tmp/secrets-public"));
+    }
+}
@@ -0,0 +1,80 @@
+//! Embedding provider implementation for the CLI
+//!
+//! Like AI completions, embeddings come from an external command the
+//! user configures (text on stdin, JSON float array on stdout), so no
+//! model or HTTP client is bundled.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::process::Stdio;
+use termbrain_core::ai::EmbeddingProvider;
+
+use crate::config::Config;
+
+/// Runs a user-configured embedding command per text.
+pub struct SubprocessEmbedder {
+    command: String,
+}
+
+impl SubprocessEmbedder {
+    /// Builds the configured embedder, or `None` when embeddings are
+    /// unconfigured, shadow mode is active, or the installation is
+    /// offline (embedding commands may call remote APIs).
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.shadow_mode || config.offline() {
+            return None;
+        }
+        config.embedding_provider.as_ref().map(|command| Self {
+            command: command.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for SubprocessEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let command = self.command.clone();
+        let text = text.to_string();
+
+        let output = tokio::task::spawn_blocking(move || -> Result<std::process::Output> {
+            let mut parts = command.split_whitespace();
+            let program = parts
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Empty embedding provider command"))?;
+
+            let mut child = std::process::Command::new(program)
+                .args(parts)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+
+            use std::io::Write;
+            if let Some(stdin) = child.stdin.as_mut() {
+                stdin.write_all(text.as_bytes())?;
+            }
+
+            Ok(child.wait_with_output()?)
+        })
+        .await??;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!(
+                "Embedding provider '{}' failed ({}): {}",
+                self.command,
+                output.status,
+                stderr.trim()
+            ));
+        }
+
+        let vector: Vec<f32> = serde_json::from_slice(&output.stdout).map_err(|e| {
+            anyhow::anyhow!(
+                "Embedding provider '{}' did not return a JSON float array: {}",
+                self.command,
+                e
+            )
+        })?;
+        Ok(vector)
+    }
+}
@@ -1,8 +1,14 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
+#[cfg(feature = "ai")]
+mod ai;
 mod commands;
+#[cfg(feature = "embeddings")]
+mod embedder;
 mod config;
+mod platform;
 
 use commands::*;
 
@@ -24,6 +30,18 @@ struct Cli {
     /// Output format
     #[arg(long, value_enum, default_value = "table", global = true)]
     format: OutputFormat,
+
+    /// Query a specific user's history on a shared backend
+    #[arg(long, global = true)]
+    user: Option<String>,
+
+    /// Query across all users on a shared backend
+    #[arg(long, global = true, conflicts_with = "user")]
+    team: bool,
+
+    /// Forbid all network calls for this invocation
+    #[arg(long, global = true)]
+    offline: bool,
     
     #[command(subcommand)]
     command: Option<Commands>,
@@ -49,14 +67,35 @@ enum Commands {
         /// Working directory
         #[arg(short, long)]
         directory: Option<String>,
+
+        /// Provenance of the record (shell-hook, wrap, ingest:ci, api)
+        #[arg(long, default_value = "shell-hook")]
+        source: String,
+
+        /// Structured extras as KEY=VALUE (repeatable; values may be JSON)
+        #[arg(long = "extra", value_name = "KEY=VALUE")]
+        extra: Vec<String>,
     },
-    
+
+    /// Run a command and record it with measured peak memory
+    Time {
+        /// The command to run (through the shell, so pipes work)
+        #[arg(required = true)]
+        command: Vec<String>,
+    },
+
     /// Search command history
     #[command(alias = "s")]
     Search {
         /// Search query
-        query: String,
-        
+        #[arg(required_unless_present = "interactive")]
+        query: Option<String>,
+
+        /// Interactive picker for the Ctrl-R shell widget; prints the
+        /// selected command to stdout
+        #[arg(short, long)]
+        interactive: bool,
+
         /// Limit number of results
         #[arg(short, long, default_value = "10")]
         limit: usize,
@@ -64,16 +103,112 @@ enum Commands {
         /// Search in specific directory
         #[arg(short, long)]
         directory: Option<String>,
-        
+
+        /// Only commands recorded in one repository (its root path, or
+        /// just the directory name)
+        #[arg(long)]
+        repo: Option<String>,
+
         /// Search from specific date
         #[arg(long)]
         since: Option<String>,
         
+        /// Force keyword search (skip automatic routing)
+        #[arg(long, conflicts_with_all = ["semantic", "hybrid", "fts"])]
+        keyword: bool,
+
+        /// Full-text search with FTS5 syntax (phrases, prefix *), ranked by bm25
+        #[arg(long, conflicts_with_all = ["semantic", "hybrid"])]
+        fts: bool,
+
         /// Use semantic search
         #[arg(long)]
         semantic: bool,
+
+        /// Use hybrid search (merges keyword and semantic rankings)
+        #[arg(long, conflicts_with = "semantic")]
+        hybrid: bool,
+
+        /// Search SQL text extracted from database CLI invocations
+        #[arg(long, conflicts_with_all = ["keyword", "fts", "semantic", "hybrid"])]
+        sql: bool,
+
+        /// Keyword ranking weight for hybrid search (0.0-1.0)
+        #[arg(long, default_value = "0.5", requires = "hybrid")]
+        keyword_weight: f32,
+
+        /// Semantic ranking weight for hybrid search (0.0-1.0)
+        #[arg(long, default_value = "0.5", requires = "hybrid")]
+        semantic_weight: f32,
     },
     
+    /// Ask a natural language question about your history
+    #[cfg(feature = "ai")]
+    Ask {
+        /// The question to answer
+        #[arg(required = true)]
+        question: Vec<String>,
+    },
+
+    /// Generate a command from a goal, grounded in your history
+    #[cfg(feature = "ai")]
+    Synthesize {
+        /// Natural language description of what to do
+        #[arg(required = true)]
+        goal: Vec<String>,
+
+        /// Offer to execute the candidate after confirmation
+        #[arg(long)]
+        execute: bool,
+    },
+
+    /// Diagnose a failed command (heuristics + optional AI)
+    Diagnose {
+        /// Command id to diagnose
+        id: Option<String>,
+
+        /// Diagnose the most recent failure instead
+        #[arg(long, conflicts_with = "id")]
+        last_failure: bool,
+    },
+
+    /// Bulk edit matching commands' extras and tags
+    Edit {
+        /// Filter: dir:PATH, cmd:NAME, source:NAME, or a raw substring
+        #[arg(long = "where", value_name = "FILTER")]
+        filter: String,
+
+        /// Set an extras key, as KEY=VALUE (repeatable; values may be JSON)
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set: Vec<String>,
+
+        /// Append a tag to the extras "tags" array (repeatable)
+        #[arg(long = "add-tag", value_name = "TAG")]
+        add_tag: Vec<String>,
+
+        /// Preview the matches and changes without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Correct one record's semantic type, project, or agent attribution
+    Reclassify {
+        /// Command id (full UUID or unique prefix)
+        id: String,
+
+        /// Corrected semantic type (free-form, e.g. testing)
+        #[arg(long = "type")]
+        semantic_type: Option<String>,
+
+        /// Corrected project identity
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Corrected AI agent attribution
+        #[arg(long)]
+        agent: Option<String>,
+    },
+
     /// Show recent command history
     #[command(alias = "h")]
     History {
@@ -88,8 +223,252 @@ enum Commands {
         /// Filter by directory
         #[arg(short, long)]
         directory: Option<String>,
+
+        /// Filter by the git branch commands were recorded on
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Filter by project name (see tb projects list)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Filter by embedding editor ("vscode", "nvim", or "none")
+        #[arg(long)]
+        editor: Option<String>,
+
+        /// Filter by provenance ("shell-hook", "import", "import:zsh", ...)
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Filter by a structured extra, as KEY=VALUE
+        #[arg(long, value_name = "KEY=VALUE")]
+        extra: Option<String>,
     },
     
+    /// List activity grouped by project (normalized git remote)
+    Projects {
+        #[command(subcommand)]
+        action: Option<ProjectsAction>,
+    },
+
+    /// A/B-compare your own workflow changes from recorded usage
+    Experiment {
+        #[command(subcommand)]
+        action: ExperimentAction,
+    },
+
+    /// Precompute or print the daily shell-startup insight
+    Digest {
+        #[command(subcommand)]
+        action: DigestAction,
+    },
+
+    /// Answer a few questions to seed tools, metrics, and workflows
+    Teach,
+
+    /// Apply configured retention policies to recorded history
+    Prune {
+        /// Report what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Rewrite recorded directories after a project move or rename
+    RemapDir {
+        /// The directory history was recorded under
+        #[arg(required_unless_present = "detect")]
+        old: Option<String>,
+
+        /// Where the project lives now
+        #[arg(required_unless_present = "detect")]
+        new: Option<String>,
+
+        /// Suggest old paths for the repo at NEW (or the current
+        /// directory) by matching its git remote against history
+        #[arg(long)]
+        detect: bool,
+    },
+
+    /// Show everything recorded about one command
+    Explain {
+        /// Command id (a unique prefix is enough) or 1-based history
+        /// index (1 = most recent)
+        reference: String,
+    },
+
+    /// Check the tamper-evidence hash chain over recorded history
+    Verify,
+
+    /// Run crash recovery (journal, spool, sessions) and report health
+    Doctor,
+
+    /// Drive a real shell on a PTY against a throwaway HOME and verify
+    /// the hooks record exit codes and durations (CI regression check)
+    #[command(hide = true)]
+    Selftest {
+        /// Shell to exercise: bash or zsh
+        #[arg(long, default_value = "zsh")]
+        shell: String,
+    },
+
+    /// Check a command against the dangerous-command policy before it
+    /// runs (called by the shell hooks when TERMBRAIN_GUARD=1; exits
+    /// non-zero when the command should not run)
+    Guard {
+        /// The command about to run
+        #[arg(required = true)]
+        command: Vec<String>,
+    },
+
+    /// Print one history-based completion for the current buffer
+    /// (called by the shell autosuggest scripts; prints nothing when
+    /// there is no confident suggestion)
+    #[command(name = "suggest-inline", hide = true)]
+    SuggestInline {
+        /// The buffer typed so far; empty predicts the next command
+        #[arg(trailing_var_arg = true)]
+        buffer: Vec<String>,
+    },
+
+    /// Capture a working-tree snapshot before a risky command (called
+    /// by the shell preexec hook; a no-op unless undo_snapshots is set)
+    Snapshot {
+        /// The command about to run
+        #[arg(required = true)]
+        command: Vec<String>,
+    },
+
+    /// Point at the stash/reflog entry that undoes a recent risky command
+    UndoHint,
+
+    /// Run the ingestion daemon: records queue over a Unix socket
+    /// instead of opening the database from every shell hook
+    Daemon,
+
+    /// Drain the fast-path record journal into the database
+    Flush,
+
+    /// Live tail of commands as they are recorded
+    Watch {
+        /// Only wrapped AI agent activity (source "wrap")
+        #[arg(long)]
+        ai: bool,
+
+        /// Annotate and color each line by risk level
+        #[arg(long)]
+        risk: bool,
+    },
+
+    /// Inventory of every tool invoked: usage, failure rate, version
+    Tools {
+        /// Hide tools used fewer than this many times
+        #[arg(long, default_value = "2")]
+        min_uses: usize,
+    },
+
+    /// Show a tool's help enriched with your own usage history
+    Man {
+        /// The tool to look up
+        tool: String,
+    },
+
+    /// Generate a new-machine setup script from the tool inventory
+    Bootstrap {
+        /// Package manager to target: brew or apt
+        #[arg(long)]
+        target: String,
+
+        /// Only include tools used at least this many times
+        #[arg(long, default_value = "5")]
+        min_uses: usize,
+
+        /// Write the script here instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Report every feature capable of network access or data sharing
+    Privacy {
+        #[command(subcommand)]
+        action: PrivacyAction,
+    },
+
+    /// Inspect the versioned event schema used by webhooks and the API
+    Events {
+        #[command(subcommand)]
+        action: EventsAction,
+    },
+
+    /// Database maintenance: vacuum, integrity check, backup, restore
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+
+    /// Manage configuration (recording ignore rules)
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// List, label, and retire the machines seen in history
+    Devices {
+        #[command(subcommand)]
+        action: Option<DevicesAction>,
+    },
+
+    /// List and replay shell sessions (one per terminal tab)
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsAction,
+    },
+
+    /// Encrypt sensitive command fields at rest
+    Vault {
+        #[command(subcommand)]
+        action: VaultAction,
+    },
+
+    /// Record to an isolated store while pairing or sharing the machine
+    GuestSession {
+        #[command(subcommand)]
+        action: GuestSessionAction,
+    },
+
+    /// Import existing bash/zsh/fish history files
+    Import {
+        /// Import one specific history file instead of the defaults
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Format of --file: "bash", "zsh", or "fish"
+        #[arg(long, requires = "file", conflicts_with_all = ["atuin", "histdb"])]
+        shell: Option<String>,
+
+        /// Import from an Atuin database (--file overrides the default
+        /// ~/.local/share/atuin/history.db)
+        #[arg(long, conflicts_with = "histdb")]
+        atuin: bool,
+
+        /// Import from a zsh-histdb database (--file overrides the
+        /// default ~/.histdb/zsh-history.db)
+        #[arg(long)]
+        histdb: bool,
+
+        /// Termbrain export format of --file: "sqlite" or "parquet"
+        // "--format" (long and id) belongs to the global output flag,
+        // which propagates into every subcommand; reusing either makes
+        // clap panic at startup
+        #[arg(long = "as", id = "import_format", value_name = "FORMAT", requires = "file", conflicts_with_all = ["shell", "atuin", "histdb"])]
+        format: Option<String>,
+    },
+
+    /// Run a read-only SQL query against the database
+    Sql {
+        /// The SELECT/WITH query to execute
+        query: String,
+    },
+
     /// Show usage statistics
     #[command(alias = "stats")]
     Statistics {
@@ -97,11 +476,86 @@ enum Commands {
         #[arg(short, long, default_value = "week")]
         period: String,
         
+        /// Render a weekly activity heatmap with daily trend sparklines
+        #[arg(long)]
+        heatmap: bool,
+
         /// Show top N commands
         #[arg(short, long, default_value = "10")]
         top: usize,
     },
-    
+
+    /// Predict the next command from observed transition probabilities
+    Predict {
+        /// Show the evidence behind each candidate
+        #[arg(long)]
+        explain: bool,
+
+        /// Candidates to show
+        #[arg(short, long, default_value = "5")]
+        top: usize,
+
+        /// Recount the transition table from recorded history
+        #[arg(long)]
+        rebuild: bool,
+
+        /// Measure top-1/top-3 hit rate on held-out history
+        #[arg(long)]
+        accuracy: bool,
+    },
+
+    /// Duration regressions: recurring commands that have gotten slower
+    Perf {
+        /// Look back this many days
+        #[arg(long, default_value = "90")]
+        days: u32,
+    },
+
+    /// Failure analytics: frequent failures, exit-code distribution,
+    /// time to recovery, and flaky commands
+    Errors {
+        /// Look back this many days
+        #[arg(long, default_value = "30")]
+        days: u32,
+
+        /// Commands to show per section
+        #[arg(short, long, default_value = "10")]
+        top: usize,
+    },
+
+    /// Show suggestions derived from your history
+    #[cfg(feature = "ai")]
+    Suggest {
+        /// Show the historical commands behind each suggestion
+        #[arg(long)]
+        explain: bool,
+
+        /// Include suggestions outside your current working set
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Show all terminal activity linked to an issue key
+    Issue {
+        /// Issue key, e.g. ABC-123 or "#456"
+        key: String,
+    },
+
+    /// Show the terminal work behind a PR number, branch, or commit
+    Activity {
+        /// PR number, branch name, or commit sha
+        reference: String,
+    },
+
+    /// Set an intention for your current work (omit text to list)
+    Intend {
+        /// What you're working on
+        text: Vec<String>,
+    },
+
+    /// List heavily-used tools you haven't touched in months
+    Refresh,
+
     /// Detect and show usage patterns
     #[command(alias = "p")]
     Patterns {
@@ -112,8 +566,66 @@ enum Commands {
         /// Show only specific pattern type
         #[arg(short, long)]
         pattern_type: Option<String>,
+
+        /// Offer each detected sequence as a draft workflow
+        #[arg(long)]
+        suggest_workflows: bool,
     },
     
+    /// Walk through suggested history cleanups interactively
+    Tidy {
+        /// Apply every fix without prompting
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Show environment change events (installs, dotfile edits)
+    Changes {
+        /// Number of events to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Manage persisted command embeddings
+    #[cfg(feature = "embeddings")]
+    Embeddings {
+        #[command(subcommand)]
+        action: EmbeddingsAction,
+    },
+
+    /// Serve a local token-guarded HTTP API for integrations
+    #[cfg(feature = "server")]
+    Serve {
+        /// Port to listen on (loopback only)
+        #[arg(long, default_value = "8484")]
+        port: u16,
+    },
+
+    /// Sync history with other machines via a shared location
+    #[cfg(feature = "sync")]
+    Sync {
+        #[command(subcommand)]
+        action: SyncAction,
+    },
+
+    /// Run and inspect scheduled backups
+    Backup {
+        #[command(subcommand)]
+        action: BackupAction,
+    },
+
+    /// Track versions of key tools per host
+    Versions {
+        #[command(subcommand)]
+        action: VersionsAction,
+    },
+
+    /// Evaluate configured alert rules
+    Alerts {
+        #[command(subcommand)]
+        action: AlertsAction,
+    },
+
     /// Manage workflows
     #[command(alias = "w")]
     Workflow {
@@ -128,7 +640,10 @@ enum Commands {
         output: String,
         
         /// Export format
-        #[arg(short, long, value_enum, default_value = "json")]
+        // "--format" (long and id) belongs to the global output flag,
+        // which propagates into every subcommand; reusing either makes
+        // clap panic at startup
+        #[arg(short, long = "as", id = "export_format", value_name = "FORMAT", value_enum, default_value = "json")]
         format: ExportFormat,
         
         /// Date range
@@ -136,12 +651,105 @@ enum Commands {
         since: Option<String>,
         #[arg(long)]
         until: Option<String>,
+
+        /// Export only k-anonymous aggregate statistics (team sharing mode)
+        #[arg(long)]
+        aggregate: bool,
+
+        /// Mirror history into a DuckDB file for heavy analytics
+        #[arg(long, conflicts_with = "aggregate")]
+        duckdb: bool,
+
+        /// Minimum distinct users per aggregate entry (k-anonymity threshold)
+        #[arg(long, default_value = "5", requires = "aggregate")]
+        k_threshold: usize,
+
+        /// Compress output as it is written
+        #[arg(long, value_enum)]
+        compress: Option<ExportCompression>,
+
+        /// Split into numbered parts of this many records each
+        #[arg(long)]
+        chunk_size: Option<usize>,
+
+        /// Only commands run in this directory or below it
+        #[arg(long, conflicts_with_all = ["aggregate", "duckdb"])]
+        directory: Option<String>,
+
+        /// Only commands in this semantic category (version-control,
+        /// containers, build, packages, network, files, editors,
+        /// databases, other)
+        #[arg(long = "type", conflicts_with_all = ["aggregate", "duckdb"])]
+        semantic_type: Option<String>,
+
+        /// Only wrapped agent activity recorded with this agent name
+        #[arg(long, conflicts_with_all = ["aggregate", "duckdb"])]
+        ai_agent: Option<String>,
+
+        /// Only commands that exited with this code
+        #[arg(long, conflicts_with_all = ["aggregate", "duckdb"])]
+        exit_code: Option<i32>,
+
+        /// Redact likely secrets from command lines before writing
+        #[arg(long, conflicts_with_all = ["aggregate", "duckdb"])]
+        redact_sensitive: bool,
     },
     
+    /// Distill an incident timeline into a reusable runbook
+    Runbook {
+        /// Start of the timeline (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+
+        /// End of the timeline (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        to: String,
+
+        /// Output markdown file
+        #[arg(short, long)]
+        output: String,
+
+        /// Also register the steps as a workflow with this name
+        #[arg(long)]
+        register: Option<String>,
+    },
+
+    /// Assemble recent commands into a bug-report template
+    Repro {
+        /// Number of recent commands to include
+        #[arg(long, default_value = "15")]
+        last: usize,
+
+        /// Output file path
+        #[arg(short, long, default_value = "repro.md")]
+        output: String,
+    },
+
+    /// Produce an analytical dataset snapshot (CSV/Parquet)
+    Dataset {
+        /// Output file path (.csv or .parquet)
+        #[arg(short, long)]
+        output: String,
+
+        /// Time range, e.g. 1y, 6m, 30d, 12h
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Drop raw commands and hash users/hosts/directories
+        #[arg(long)]
+        anonymize: bool,
+    },
+
+    /// Build opt-in anonymized bundles for improving the heuristics
+    Contribute {
+        #[command(subcommand)]
+        action: ContributeAction,
+    },
+
     /// Setup shell integration
     #[command(alias = "setup")]
     Install {
-        /// Shell type (bash, zsh, fish)
+        /// Shell type (bash, zsh, fish, powershell, nu)
         #[arg(short, long)]
         shell: Option<String>,
         
@@ -162,6 +770,7 @@ enum Commands {
     },
     
     /// Start interactive session
+    #[cfg(feature = "tui")]
     #[command(alias = "i")]
     Interactive,
     
@@ -169,6 +778,256 @@ enum Commands {
     Status,
 }
 
+#[derive(Subcommand)]
+enum ExperimentAction {
+    /// Start a named experiment window
+    Start { name: String },
+    /// End the running experiment
+    Stop,
+    /// Compare usage before vs during an experiment
+    Report {
+        /// Experiment name (defaults to the most recent)
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DigestAction {
+    /// Compute today's insight and cache it for shell startup
+    Generate,
+    /// Print the cached insight if it is from today (silent otherwise)
+    Show,
+}
+
+#[derive(Subcommand)]
+enum ProjectsAction {
+    /// Per-project counts, success rates, and top commands, grouped by
+    /// marker-detected roots (Cargo.toml, package.json, .git)
+    List,
+    /// One project's identity: roots, remote, tags, activity
+    Show {
+        /// Project name, tag, or remote URL
+        reference: String,
+    },
+    /// Rename a project (all roots sharing the identity)
+    Rename {
+        /// Project name, tag, or remote URL
+        reference: String,
+        /// The new name
+        new_name: String,
+    },
+    /// Add or remove a free-form tag
+    Tag {
+        /// Project name, tag, or remote URL
+        reference: String,
+        /// The tag to add (or remove)
+        tag: String,
+        /// Remove the tag instead of adding it
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Set the remote URL, linking this root with other clones
+    SetRemote {
+        /// Project name, tag, or remote URL
+        reference: String,
+        /// The remote identity to share
+        remote: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum VaultAction {
+    /// Unlock at-rest encryption (first unlock sets the vault up)
+    Unlock,
+    /// Lock the vault; encrypted history is unreadable until unlocked
+    Lock,
+    /// Show whether the vault is configured and unlocked
+    Status,
+}
+
+#[derive(Subcommand)]
+enum PrivacyAction {
+    /// Enumerate network-capable features with state and config source
+    Report,
+}
+
+#[derive(Subcommand)]
+enum ContributeAction {
+    /// Assemble an anonymized template bundle for manual review/submission
+    Build {
+        /// Output file (default termbrain-contribution.json)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Drop templates seen fewer than this many times (default 3)
+        #[arg(long)]
+        min_uses: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum EventsAction {
+    /// Print the JSON Schema documents for emitted events
+    Schema {
+        /// One event type (e.g. command.recorded); all when omitted
+        #[arg(long)]
+        event: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbAction {
+    /// Rebuild the database file and reclaim free pages
+    Vacuum,
+    /// Verify page integrity and detect orphaned references
+    Check,
+    /// Write a consistent copy of the live database (online backup)
+    Backup {
+        /// Where to write the copy
+        path: String,
+    },
+    /// Replace the database with a backup (the current one is kept aside)
+    Restore {
+        /// The backup file to restore from
+        path: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Manage recording ignore rules (commands, regexes, directories)
+    Ignore {
+        #[command(subcommand)]
+        action: IgnoreAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum IgnoreAction {
+    /// Add an ignore rule (one of --command, --pattern, --directory)
+    Add {
+        /// Command word to ignore (matched against the leading word)
+        #[arg(long)]
+        command: Option<String>,
+        /// Regex matched against the full command line
+        #[arg(long)]
+        pattern: Option<String>,
+        /// Directory whose subtree is never recorded (e.g. ~/secrets)
+        #[arg(long)]
+        directory: Option<String>,
+    },
+    /// Remove an ignore rule (exactly as it was added)
+    Remove {
+        /// Command word to stop ignoring
+        #[arg(long)]
+        command: Option<String>,
+        /// Regex to stop ignoring
+        #[arg(long)]
+        pattern: Option<String>,
+        /// Directory to stop ignoring
+        #[arg(long)]
+        directory: Option<String>,
+    },
+    /// List all configured ignore rules
+    List,
+}
+
+#[derive(Subcommand)]
+enum DevicesAction {
+    /// List devices with activity and command counts
+    List {
+        /// Include retired devices
+        #[arg(long)]
+        all: bool,
+    },
+    /// Attach a friendly label to a hostname
+    Rename {
+        /// The hostname as recorded in history
+        hostname: String,
+        /// The label to show instead
+        label: String,
+    },
+    /// Hide a device from the default listing (history is kept)
+    Retire {
+        /// The hostname to retire
+        hostname: String,
+        /// Bring a retired device back instead
+        #[arg(long)]
+        undo: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionsAction {
+    /// Open the current tab's session (called by the shell hooks)
+    Start,
+    /// Close the current tab's session (called by the shell exit hook)
+    End,
+    /// List recent sessions with duration and command counts
+    List {
+        /// Number of sessions to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+    /// Replay one session's commands and directories in order
+    Show {
+        /// Session id (a unique prefix is enough)
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum GuestSessionAction {
+    /// Start recording to the isolated guest store
+    Start,
+    /// Fold the guest session's commands into the main history
+    Merge,
+    /// Drop the guest session's commands
+    Discard,
+}
+
+#[cfg(feature = "embeddings")]
+#[derive(Subcommand)]
+enum EmbeddingsAction {
+    /// Backfill embeddings for commands that don't have one
+    Rebuild,
+}
+
+#[cfg(feature = "sync")]
+#[derive(Subcommand)]
+enum SyncAction {
+    /// Export this device's history to the sync remote
+    Push,
+    /// Merge other devices' histories from the sync remote
+    Pull,
+}
+
+#[derive(Subcommand)]
+enum VersionsAction {
+    /// Snapshot the versions of tracked tools now
+    Record,
+    /// Show version changes and correlated failure-rate shifts
+    Timeline,
+}
+
+#[derive(Subcommand)]
+enum BackupAction {
+    /// Back up now if one is due (cheap no-op otherwise)
+    Run {
+        /// Back up even if the interval hasn't elapsed
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show the backup configuration and last run
+    Status,
+}
+
+#[derive(Subcommand)]
+enum AlertsAction {
+    /// Evaluate all alert rules once and deliver any that fire
+    Check,
+}
+
 #[derive(Subcommand)]
 enum WorkflowAction {
     /// List all workflows
@@ -176,9 +1035,38 @@ enum WorkflowAction {
     /// Create a new workflow
     Create { name: String },
     /// Run a workflow
-    Run { name: String },
+    Run {
+        name: String,
+        /// Fill a {{placeholder}} in the workflow's steps
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        var: Vec<String>,
+        /// Answer yes to every confirmation prompt
+        #[arg(long)]
+        yes: bool,
+        /// Print resolved commands without executing anything
+        #[arg(long, conflicts_with = "step")]
+        dry_run: bool,
+        /// Pause before each step: run, skip, or abort interactively
+        #[arg(long)]
+        step: bool,
+    },
     /// Delete a workflow
     Delete { name: String },
+    /// Promote a detected pattern into a workflow
+    Adopt {
+        /// Pattern id (or unique prefix) from `tb patterns`
+        pattern_id: String,
+        /// Create without asking
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Show recent runs of a workflow, step by step
+    History {
+        name: String,
+        /// How many runs to show
+        #[arg(long, default_value = "10")]
+        runs: usize,
+    },
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -194,6 +1082,17 @@ enum ExportFormat {
     Json,
     Csv,
     Markdown,
+    /// Portable full-fidelity .db snapshot (SQLite online backup)
+    Sqlite,
+    /// Columnar file for DuckDB/pandas (needs the duckdb CLI)
+    Parquet,
+}
+
+/// Streaming compression applied to export output.
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq, Eq)]
+enum ExportCompression {
+    Gzip,
+    Zstd,
 }
 
 #[tokio::main]
@@ -213,36 +1112,409 @@ async fn main() -> Result<()> {
             .init();
     }
     
+    // --offline flows through the same kill-switch as the env var, so
+    // every Config::load() in this process sees it
+    if cli.offline {
+        std::env::set_var("TERMBRAIN_OFFLINE", "1");
+    }
+
+    // Resolve the user scope once; repositories enforce it from here on
+    let scope = if cli.team {
+        termbrain_core::domain::repositories::UserScope::Team
+    } else if let Some(user) = &cli.user {
+        termbrain_core::domain::repositories::UserScope::User(user.clone())
+    } else {
+        termbrain_core::domain::repositories::UserScope::current_user()
+    };
+    set_user_scope(scope);
+
     // Handle commands
     match cli.command {
-        Some(Commands::Record { command, exit_code, duration, directory }) => {
-            record_command(command.join(" "), exit_code, duration, directory).await?;
+        Some(Commands::Record { command, exit_code, duration, directory, source, extra }) => {
+            record_command(command.join(" "), exit_code, duration, directory, source, extra, None).await?;
         }
-        
-        Some(Commands::Search { query, limit, directory, since, semantic }) => {
-            search_commands(query, limit, directory, since, semantic, cli.format).await?;
+
+        Some(Commands::Time { command }) => {
+            time_command(command.join(" ")).await?;
         }
-        
-        Some(Commands::History { limit, success_only, directory }) => {
-            show_history(limit, success_only, directory, cli.format).await?;
+
+        Some(Commands::Search { query, interactive, limit, directory, repo, since, keyword, fts, semantic, hybrid, sql, keyword_weight, semantic_weight }) => {
+            if interactive {
+                interactive_search(query.unwrap_or_default(), limit).await?;
+                return Ok(());
+            }
+            let query = query.unwrap_or_default();
+            let mode = if sql {
+                Some(SearchMode::Sql)
+            } else if hybrid {
+                Some(SearchMode::Hybrid { keyword_weight, semantic_weight })
+            } else if semantic {
+                Some(SearchMode::Semantic)
+            } else if fts {
+                Some(SearchMode::FullText)
+            } else if keyword {
+                Some(SearchMode::Keyword)
+            } else {
+                // No explicit flag: route by query intent
+                None
+            };
+            search_commands(query, limit, directory, repo, since, mode, cli.format).await?;
         }
         
-        Some(Commands::Statistics { period, top }) => {
-            show_statistics(period, top, cli.format).await?;
+        #[cfg(feature = "ai")]
+        Some(Commands::Ask { question }) => {
+            ask_question(question.join(" "), cli.format).await?;
+        }
+
+        #[cfg(feature = "ai")]
+        Some(Commands::Synthesize { goal, execute }) => {
+            synthesize_command(goal.join(" "), execute).await?;
+        }
+
+        Some(Commands::Diagnose { id, last_failure }) => {
+            diagnose_command(id, last_failure, cli.format).await?;
+        }
+
+        Some(Commands::Edit { filter, set, add_tag, dry_run }) => {
+            bulk_edit(filter, set, add_tag, dry_run).await?;
+        }
+
+        Some(Commands::Reclassify { id, semantic_type, project, agent }) => {
+            reclassify_record(id, semantic_type, project, agent).await?;
+        }
+
+        Some(Commands::History { limit, success_only, directory, branch, project, editor, source, extra }) => {
+            let filters = HistoryFilters { success_only, directory, branch, project, editor, source, extra };
+            show_history(limit, filters, cli.format).await?;
         }
         
-        Some(Commands::Patterns { confidence, pattern_type }) => {
-            show_patterns(confidence, pattern_type, cli.format).await?;
+        Some(Commands::Projects { action }) => {
+            match action {
+                // Default view: remote-identity rollup
+                None => show_projects(cli.format).await?,
+                Some(ProjectsAction::List) => project_list(cli.format).await?,
+                Some(ProjectsAction::Show { reference }) => project_show(reference).await?,
+                Some(ProjectsAction::Rename {
+                    reference,
+                    new_name,
+                }) => project_rename(reference, new_name).await?,
+                Some(ProjectsAction::Tag {
+                    reference,
+                    tag,
+                    remove,
+                }) => project_tag(reference, tag, remove).await?,
+                Some(ProjectsAction::SetRemote { reference, remote }) => {
+                    project_set_remote(reference, remote).await?
+                }
+            }
+        }
+
+        Some(Commands::Experiment { action }) => {
+            match action {
+                ExperimentAction::Start { name } => experiment_start(name).await?,
+                ExperimentAction::Stop => experiment_stop().await?,
+                ExperimentAction::Report { name } => experiment_report(name).await?,
+            }
+        }
+
+        Some(Commands::Digest { action }) => {
+            match action {
+                DigestAction::Generate => generate_digest().await?,
+                DigestAction::Show => show_digest().await?,
+            }
+        }
+
+        Some(Commands::Teach) => {
+            run_teach().await?;
+        }
+
+        Some(Commands::Prune { dry_run }) => {
+            run_prune(dry_run).await?;
+        }
+
+        Some(Commands::RemapDir { old, new, detect }) => {
+            if detect {
+                detect_remaps(new).await?;
+            } else {
+                remap_dir(old.unwrap(), new.unwrap()).await?;
+            }
+        }
+
+        Some(Commands::Explain { reference }) => {
+            explain_command(reference).await?;
+        }
+
+        Some(Commands::Verify) => {
+            verify_history().await?;
+        }
+
+        Some(Commands::Doctor) => {
+            run_doctor().await?;
+        }
+
+        Some(Commands::Selftest { shell }) => {
+            run_selftest(shell).await?;
+        }
+
+        Some(Commands::Guard { command }) => {
+            run_guard(command).await?;
+        }
+
+        Some(Commands::SuggestInline { buffer }) => {
+            suggest_inline(buffer).await?;
+        }
+
+        Some(Commands::Snapshot { command }) => {
+            snapshot_before(command.join(" ")).await?;
+        }
+
+        Some(Commands::UndoHint) => {
+            undo_hint().await?;
+        }
+
+        Some(Commands::Watch { ai, risk }) => {
+            watch_commands(ai, risk).await?;
+        }
+
+        Some(Commands::Daemon) => {
+            run_daemon().await?;
+        }
+
+        Some(Commands::Flush) => {
+            flush_journal().await?;
+        }
+
+        Some(Commands::Tools { min_uses }) => {
+            tool_inventory(min_uses, cli.format).await?;
+        }
+
+        Some(Commands::Man { tool }) => {
+            man_page(tool).await?;
+        }
+
+        Some(Commands::Bootstrap {
+            target,
+            min_uses,
+            output,
+        }) => {
+            generate_bootstrap(target, min_uses, output).await?;
+        }
+
+        Some(Commands::Privacy { action }) => match action {
+            PrivacyAction::Report => privacy_report(cli.format)?,
+        },
+
+        Some(Commands::Events { action }) => match action {
+            EventsAction::Schema { event } => events_schema(event)?,
+        },
+
+        Some(Commands::Db { action }) => match action {
+            DbAction::Vacuum => db_vacuum().await?,
+            DbAction::Check => db_check().await?,
+            DbAction::Backup { path } => db_backup(path).await?,
+            DbAction::Restore { path } => db_restore(path).await?,
+        },
+
+        Some(Commands::Config { action }) => match action {
+            ConfigAction::Ignore { action } => match action {
+                IgnoreAction::Add {
+                    command,
+                    pattern,
+                    directory,
+                } => ignore_add(command, pattern, directory)?,
+                IgnoreAction::Remove {
+                    command,
+                    pattern,
+                    directory,
+                } => ignore_remove(command, pattern, directory)?,
+                IgnoreAction::List => ignore_list()?,
+            },
+        },
+
+        Some(Commands::Devices { action }) => {
+            match action.unwrap_or(DevicesAction::List { all: false }) {
+                DevicesAction::List { all } => device_list(all, cli.format).await?,
+                DevicesAction::Rename { hostname, label } => device_rename(hostname, label).await?,
+                DevicesAction::Retire { hostname, undo } => device_retire(hostname, undo).await?,
+            }
+        }
+
+        Some(Commands::Sessions { action }) => {
+            match action {
+                SessionsAction::Start => session_start().await?,
+                SessionsAction::End => session_end().await?,
+                SessionsAction::List { limit } => session_list(limit, cli.format).await?,
+                SessionsAction::Show { id } => session_show(id).await?,
+            }
+        }
+
+        Some(Commands::Vault { action }) => {
+            match action {
+                VaultAction::Unlock => vault_unlock().await?,
+                VaultAction::Lock => vault_lock().await?,
+                VaultAction::Status => vault_status().await?,
+            }
+        }
+
+        Some(Commands::GuestSession { action }) => {
+            match action {
+                GuestSessionAction::Start => guest_session_start().await?,
+                GuestSessionAction::Merge => guest_session_merge().await?,
+                GuestSessionAction::Discard => guest_session_discard().await?,
+            }
+        }
+
+        Some(Commands::Import { file, shell, atuin, histdb, format }) => {
+            import_history(file, shell, atuin, histdb, format).await?;
+        }
+
+        Some(Commands::Sql { query }) => {
+            run_sql(query, cli.format).await?;
+        }
+
+        Some(Commands::Statistics { period, heatmap, top }) => {
+            if heatmap {
+                show_heatmap(period, cli.format).await?;
+            } else {
+                show_statistics(period, top, cli.format).await?;
+            }
+        }
+
+        Some(Commands::Predict {
+            explain,
+            top,
+            rebuild,
+            accuracy,
+        }) => {
+            show_predictions(explain, top, rebuild, accuracy, cli.format).await?;
+        }
+
+        Some(Commands::Perf { days }) => {
+            show_perf(days, cli.format).await?;
+        }
+
+        Some(Commands::Errors { days, top }) => {
+            show_errors(days, top, cli.format).await?;
+        }
+
+        #[cfg(feature = "ai")]
+        Some(Commands::Suggest { explain, all }) => {
+            show_suggestions(explain, all, cli.format).await?;
+        }
+
+        Some(Commands::Issue { key }) => {
+            show_issue(key, cli.format).await?;
+        }
+
+        Some(Commands::Activity { reference }) => {
+            show_activity(reference, cli.format).await?;
+        }
+
+        Some(Commands::Intend { text }) => {
+            intend(text.join(" ")).await?;
+        }
+
+        Some(Commands::Refresh) => {
+            show_refreshers(cli.format).await?;
+        }
+
+        Some(Commands::Patterns { confidence, pattern_type, suggest_workflows }) => {
+            show_patterns(confidence, pattern_type, suggest_workflows, cli.format).await?;
         }
         
+        Some(Commands::Tidy { yes }) => {
+            run_tidy(yes).await?;
+        }
+
+        Some(Commands::Changes { limit }) => {
+            show_env_changes(limit, cli.format).await?;
+        }
+
+        #[cfg(feature = "embeddings")]
+        Some(Commands::Embeddings { action }) => {
+            match action {
+                EmbeddingsAction::Rebuild => rebuild_embeddings().await?,
+            }
+        }
+
+        #[cfg(feature = "server")]
+        Some(Commands::Serve { port }) => {
+            run_serve(port).await?;
+        }
+
+        #[cfg(feature = "sync")]
+        Some(Commands::Sync { action }) => {
+            match action {
+                SyncAction::Push => sync_push().await?,
+                SyncAction::Pull => sync_pull().await?,
+            }
+        }
+
+        Some(Commands::Versions { action }) => {
+            match action {
+                VersionsAction::Record => record_tool_versions().await?,
+                VersionsAction::Timeline => show_versions_timeline().await?,
+            }
+        }
+
+        Some(Commands::Alerts { action }) => {
+            match action {
+                AlertsAction::Check => check_alerts().await?,
+            }
+        }
+
         Some(Commands::Workflow { action }) => {
             handle_workflow(action, cli.format).await?;
         }
+
+        Some(Commands::Backup { action }) => {
+            match action {
+                BackupAction::Run { force } => backup_run(force).await?,
+                BackupAction::Status => backup_status().await?,
+            }
+        }
         
-        Some(Commands::Export { output, format, since, until }) => {
-            export_data(output, format, since, until).await?;
+        Some(Commands::Export { output, format, since, until, aggregate, duckdb, k_threshold, compress, chunk_size, directory, semantic_type, ai_agent, exit_code, redact_sensitive }) => {
+            if aggregate {
+                export_aggregates(output, k_threshold).await?;
+            } else if duckdb {
+                export_duckdb(output).await?;
+            } else {
+                let options = ExportOptions {
+                    since,
+                    until,
+                    compress,
+                    chunk_size,
+                    filter: termbrain_core::domain::CommandFilter {
+                        directory,
+                        semantic_type,
+                        ai_agent,
+                        exit_code,
+                    },
+                    redact: redact_sensitive,
+                };
+                export_data(output, format, options).await?;
+            }
         }
         
+        Some(Commands::Runbook { from, to, output, register }) => {
+            generate_runbook(from, to, output, register).await?;
+        }
+
+        Some(Commands::Repro { last, output }) => {
+            export_repro_bundle(last, output).await?;
+        }
+
+        Some(Commands::Dataset { output, since, anonymize }) => {
+            export_dataset(since, output, anonymize).await?;
+        }
+
+        Some(Commands::Contribute { action }) => match action {
+            ContributeAction::Build { output, min_uses } => {
+                contribute_build(output, min_uses).await?;
+            }
+        },
+
         Some(Commands::Install { shell, yes }) => {
             install_shell_integration(shell, yes).await?;
         }
@@ -251,6 +1523,7 @@ async fn main() -> Result<()> {
             uninstall_termbrain(purge, yes).await?;
         }
         
+        #[cfg(feature = "tui")]
         Some(Commands::Interactive) => {
             start_interactive_session().await?;
         }
@@ -265,4 +1538,37 @@ async fn main() -> Result<()> {
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition_is_consistent() {
+        // Catches arg-id collisions (a subcommand arg reusing the id of
+        // a global like --format panics at startup, not at compile time)
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_import_and_export_parse_alongside_global_format() {
+        let cli = Cli::try_parse_from(["tb", "import", "--file", "h.db", "--as", "sqlite"])
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Import { format: Some(ref f), .. }) if f == "sqlite"
+        ));
+
+        let cli = Cli::try_parse_from(["tb", "export", "-o", "out.csv", "--as", "csv"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Export { format: ExportFormat::Csv, .. })
+        ));
+
+        // The global --format still reaches these subcommands
+        let cli = Cli::try_parse_from(["tb", "export", "-o", "o.json", "--format", "json"])
+            .unwrap();
+        assert!(matches!(cli.format, OutputFormat::Json));
+    }
+}
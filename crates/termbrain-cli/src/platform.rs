@@ -0,0 +1,101 @@
+//! Platform abstraction
+//!
+//! Everything that differs between Unix and Windows lives here: where
+//! the data directory sits, how the user's shell is detected, and which
+//! profile file each shell loads. Command code should go through these
+//! instead of reading $SHELL or hardcoding ~/.bashrc.
+
+use std::path::PathBuf;
+
+/// The termbrain home directory holding the database, config file, and
+/// installed integration scripts: `~/.termbrain` on Unix, `%APPDATA%\termbrain`
+/// on Windows.
+pub fn data_dir() -> PathBuf {
+    #[cfg(target_family = "windows")]
+    {
+        dirs::config_dir().unwrap_or_default().join("termbrain")
+    }
+    #[cfg(target_family = "unix")]
+    {
+        dirs::home_dir().unwrap_or_default().join(".termbrain")
+    }
+}
+
+/// Name of the shell this process runs under ("zsh", "powershell", ...).
+pub fn detect_shell() -> String {
+    #[cfg(target_family = "unix")]
+    {
+        std::env::var("SHELL")
+            .unwrap_or_else(|_| "/bin/bash".to_string())
+            .split('/')
+            .next_back()
+            .unwrap_or("bash")
+            .to_string()
+    }
+    #[cfg(target_family = "windows")]
+    {
+        // Every PowerShell session exports PSModulePath; plain cmd.exe
+        // does not
+        if std::env::var("PSModulePath").is_ok() {
+            "powershell".to_string()
+        } else {
+            "cmd".to_string()
+        }
+    }
+}
+
+/// The shell binary and flag used to run a command line, for features
+/// that execute generated or replayed commands. Only AI-gated commands
+/// execute such commands today, hence the feature gate.
+#[cfg(feature = "ai")]
+pub fn shell_invocation() -> (String, &'static str) {
+    #[cfg(target_family = "unix")]
+    {
+        (
+            std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string()),
+            "-c",
+        )
+    }
+    #[cfg(target_family = "windows")]
+    {
+        ("powershell".to_string(), "-Command")
+    }
+}
+
+/// The startup file a shell reads, where the integration gets sourced.
+/// None for shells without a known profile location.
+pub fn shell_profile(shell: &str) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    match shell {
+        "bash" => Some(home.join(".bashrc")),
+        "zsh" => Some(home.join(".zshrc")),
+        "fish" => Some(home.join(".config/fish/config.fish")),
+        "powershell" => {
+            if cfg!(target_family = "windows") {
+                Some(home.join("Documents/PowerShell/Microsoft.PowerShell_profile.ps1"))
+            } else {
+                Some(home.join(".config/powershell/Microsoft.PowerShell_profile.ps1"))
+            }
+        }
+        "nu" => Some(home.join(".config/nushell/config.nu")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_dir_is_not_empty() {
+        assert!(data_dir().to_string_lossy().contains("termbrain"));
+    }
+
+    #[test]
+    fn test_known_shells_have_profiles() {
+        for shell in ["bash", "zsh", "fish", "powershell", "nu"] {
+            assert!(shell_profile(shell).is_some(), "no profile for {}", shell);
+        }
+        assert!(shell_profile("csh").is_none());
+    }
+}
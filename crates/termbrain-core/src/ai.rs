@@ -0,0 +1,101 @@
+//! AI provider abstraction
+//!
+//! TermBrain talks to AI models through external provider commands
+//! (e.g. `claude`, `tb-gemini`) rather than bundling HTTP clients, so
+//! users keep full control over credentials and which model is used.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+/// A provider that can answer a prompt with a text completion.
+#[async_trait]
+pub trait AiProvider: Send + Sync {
+    /// Human-readable provider name for display in output.
+    fn name(&self) -> &str;
+
+    /// Sends the prompt and returns the model's text response.
+    async fn complete(&self, prompt: &str) -> Result<String>;
+}
+
+/// A provider that turns text into an embedding vector.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Embeds one piece of text.
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
+
+/// A structured query the model is allowed to issue mid-conversation,
+/// instead of relying only on the context stuffed into the prompt.
+pub struct ToolSpec {
+    pub name: &'static str,
+    /// What the tool does and the JSON arguments it takes.
+    pub description: &'static str,
+}
+
+/// A tool invocation parsed out of a model response.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ToolCall {
+    pub tool: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+/// Renders the tool-calling protocol preamble appended to prompts.
+///
+/// Since providers are plain text-in/text-out commands, tool calling is
+/// a convention: the model replies with a single JSON object naming the
+/// tool, we execute it and continue the conversation with the result.
+pub fn render_tool_instructions(tools: &[ToolSpec]) -> String {
+    let mut out = String::from(
+        "\nYou may look up more history before answering. To do so, reply \
+         with ONLY a JSON object of the form \
+         {\"tool\": \"<name>\", \"arguments\": {...}} and nothing else. \
+         You will receive the result and can then answer or query again.\n\
+         Available tools:\n",
+    );
+    for tool in tools {
+        out.push_str(&format!("- {}: {}\n", tool.name, tool.description));
+    }
+    out
+}
+
+/// Extracts a tool call from a model response, if the response is one.
+/// Tolerates surrounding markdown code fences.
+pub fn parse_tool_call(response: &str) -> Option<ToolCall> {
+    let mut text = response.trim();
+    if let Some(rest) = text.strip_prefix("```") {
+        let rest = rest.strip_prefix("json").unwrap_or(rest);
+        text = rest.trim_end_matches('`').trim();
+    }
+    if !text.starts_with('{') {
+        return None;
+    }
+    serde_json::from_str(text).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_plain_tool_call() {
+        let call = parse_tool_call(r#"{"tool": "search", "arguments": {"query": "deploy"}}"#)
+            .unwrap();
+        assert_eq!(call.tool, "search");
+        assert_eq!(call.arguments["query"], "deploy");
+    }
+
+    #[test]
+    fn test_parses_fenced_tool_call() {
+        let response = "```json\n{\"tool\": \"get_session\", \"arguments\": {\"session_id\": \"abc\"}}\n```";
+        let call = parse_tool_call(response).unwrap();
+        assert_eq!(call.tool, "get_session");
+    }
+
+    #[test]
+    fn test_prose_is_not_a_tool_call() {
+        assert!(parse_tool_call("You ran `git push` three times yesterday.").is_none());
+        assert!(parse_tool_call("").is_none());
+    }
+}
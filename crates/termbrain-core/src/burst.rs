@@ -0,0 +1,64 @@
+//! Bulk-loop burst detection
+//!
+//! A shell loop (`for f in *; do convert ...; done`) can issue
+//! thousands of near-identical commands in seconds. Once a burst of the
+//! same command crosses the threshold inside the window, recording
+//! switches to a single summarized row — a command template plus count,
+//! aggregate duration, and failure count in extras — instead of
+//! thousands of rows.
+
+/// Identical-command records within the window before a burst is
+/// declared and summarization kicks in.
+pub const BURST_THRESHOLD: usize = 10;
+
+/// How far back identical commands count toward a burst, in seconds.
+pub const BURST_WINDOW_SECS: i64 = 60;
+
+/// Extras keys on a burst summary record.
+pub const BURST_COUNT_EXTRA: &str = "burst_count";
+pub const BURST_FAILURES_EXTRA: &str = "burst_failures";
+pub const BURST_DURATION_EXTRA: &str = "burst_duration_ms";
+
+/// Merges two raw command lines into a template: tokens they share stay
+/// verbatim, runs of differing tokens become a single `…`. Applied to
+/// two iterations of a loop this yields the invariant part of the
+/// command — `convert … -resize 50% …`.
+pub fn merge_template(a: &str, b: &str) -> String {
+    let a_tokens: Vec<&str> = a.split_whitespace().collect();
+    let b_tokens: Vec<&str> = b.split_whitespace().collect();
+
+    let mut template: Vec<&str> = Vec::new();
+    for (a_token, b_token) in a_tokens.iter().zip(&b_tokens) {
+        if a_token == b_token {
+            template.push(a_token);
+        } else if template.last() != Some(&"…") {
+            template.push("…");
+        }
+    }
+    if a_tokens.len() != b_tokens.len() && template.last() != Some(&"…") {
+        template.push("…");
+    }
+    template.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_template_keeps_shared_tokens() {
+        assert_eq!(
+            merge_template(
+                "convert img001.png -resize 50% out/img001.png",
+                "convert img002.png -resize 50% out/img002.png",
+            ),
+            "convert … -resize 50% …",
+        );
+    }
+
+    #[test]
+    fn test_merge_template_handles_length_differences() {
+        assert_eq!(merge_template("rm -f a b", "rm -f a"), "rm -f a …");
+        assert_eq!(merge_template("ls", "ls"), "ls");
+    }
+}
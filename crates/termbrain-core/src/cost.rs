@@ -0,0 +1,155 @@
+//! Resource cost estimation for recorded commands
+//!
+//! Duration gives a cheap CPU-seconds estimate for every record (it is
+//! wall-clock, so an upper bound for single-threaded work and a lower
+//! bound for parallel builds). Records made through `tb time` also
+//! carry a measured peak RSS in the `max_rss_kb` extra. Aggregated per
+//! command, the heaviest recurring entries surface in statistics along
+//! with hints for reducing the waste.
+
+use std::collections::HashMap;
+
+use crate::domain::entities::Command;
+
+/// Extras key under which `tb time` stores measured peak RSS in kB.
+pub const MAX_RSS_EXTRA: &str = "max_rss_kb";
+
+/// Aggregated cost of one recurring command.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CommandCost {
+    pub parsed_command: String,
+    pub runs: usize,
+    /// Summed wall-clock seconds across all runs.
+    pub total_cpu_seconds: f64,
+    /// Largest measured peak RSS in kB, when any run recorded one.
+    pub max_rss_kb: Option<u64>,
+}
+
+/// Groups commands by parsed command and sums their estimated cost,
+/// heaviest first. Commands with fewer than `min_runs` records are
+/// dropped: one-off long runs are not recurring waste.
+pub fn aggregate_costs(commands: &[Command], min_runs: usize) -> Vec<CommandCost> {
+    let mut grouped: HashMap<&str, CommandCost> = HashMap::new();
+    for cmd in commands {
+        let entry = grouped
+            .entry(cmd.parsed_command.as_str())
+            .or_insert_with(|| CommandCost {
+                parsed_command: cmd.parsed_command.clone(),
+                runs: 0,
+                total_cpu_seconds: 0.0,
+                max_rss_kb: None,
+            });
+        entry.runs += 1;
+        entry.total_cpu_seconds += cmd.duration_ms as f64 / 1000.0;
+        if let Some(rss) = max_rss_kb(cmd) {
+            entry.max_rss_kb = Some(entry.max_rss_kb.map_or(rss, |prev| prev.max(rss)));
+        }
+    }
+
+    let mut costs: Vec<CommandCost> = grouped
+        .into_values()
+        .filter(|cost| cost.runs >= min_runs)
+        .collect();
+    costs.sort_by(|a, b| b.total_cpu_seconds.total_cmp(&a.total_cpu_seconds));
+    costs
+}
+
+/// Reads the measured peak RSS from a record's extras, tolerating both
+/// numeric and string-encoded values.
+pub fn max_rss_kb(command: &Command) -> Option<u64> {
+    match command.extras.get(MAX_RSS_EXTRA)? {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Known ways to make a heavy recurring command cheaper. Keyed by the
+/// parsed command's leading words; first match wins.
+const WASTE_HINTS: &[(&str, &str)] = &[
+    ("cargo build", "a shared compilation cache (sccache) reuses artifacts across clean builds"),
+    ("cargo test", "'cargo test <name>' or 'cargo nextest' avoids re-running the whole suite"),
+    ("npm install", "'npm ci' with a cached ~/.npm is faster and reproducible"),
+    ("yarn install", "'yarn install --frozen-lockfile' with a warm cache skips resolution"),
+    ("pip install", "a persistent pip cache dir avoids re-downloading wheels"),
+    ("docker build", "BuildKit layer caching ('DOCKER_BUILDKIT=1') reuses unchanged layers"),
+    ("docker-compose up", "'--no-recreate' skips rebuilding containers that didn't change"),
+    ("pytest", "'pytest --lf' re-runs only the last failures"),
+    ("make", "'make -jN' parallelizes independent targets"),
+    ("mvn", "'mvn -o' works offline once dependencies are cached"),
+    ("gradle", "the Gradle build cache ('org.gradle.caching=true') reuses task outputs"),
+    ("webpack", "persistent caching ('cache.type: filesystem') speeds up rebuilds"),
+    ("tsc", "'tsc --incremental' reuses the previous typecheck"),
+];
+
+/// Returns a flag or caching suggestion for a heavy command, if one is
+/// known.
+pub fn waste_hint(parsed_command: &str) -> Option<&'static str> {
+    WASTE_HINTS.iter().find_map(|(prefix, hint)| {
+        (parsed_command == *prefix || parsed_command.starts_with(&format!("{} ", prefix)))
+            .then_some(*hint)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn command(parsed: &str, duration_ms: u64, rss: Option<u64>) -> Command {
+        let mut extras = HashMap::new();
+        if let Some(rss) = rss {
+            extras.insert(MAX_RSS_EXTRA.to_string(), serde_json::json!(rss));
+        }
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: parsed.to_string(),
+            parsed_command: parsed.to_string(),
+            arguments: vec![],
+            working_directory: "/tmp".to_string(),
+            exit_code: 0,
+            duration_ms,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras,
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "zsh".to_string(),
+                user: "test".to_string(),
+                hostname: "host".to_string(),
+                terminal: "term".to_string(),
+                environment: HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_aggregate_drops_one_offs_and_sorts_heaviest_first() {
+        let commands = vec![
+            command("cargo build", 60_000, Some(900_000)),
+            command("cargo build", 45_000, Some(1_200_000)),
+            command("ls", 10, None),
+            command("ls", 15, None),
+            command("terraform apply", 300_000, None), // one-off, dropped
+        ];
+
+        let costs = aggregate_costs(&commands, 2);
+        assert_eq!(costs.len(), 2);
+        assert_eq!(costs[0].parsed_command, "cargo build");
+        assert_eq!(costs[0].runs, 2);
+        assert!((costs[0].total_cpu_seconds - 105.0).abs() < f64::EPSILON);
+        assert_eq!(costs[0].max_rss_kb, Some(1_200_000));
+        assert_eq!(costs[1].max_rss_kb, None);
+    }
+
+    #[test]
+    fn test_waste_hint_matches_on_word_boundary() {
+        assert!(waste_hint("cargo build").is_some());
+        assert!(waste_hint("pytest").is_some());
+        // "cargo builder" is a different command, not a flag variant
+        assert!(waste_hint("cargo builder").is_none());
+        assert!(waste_hint("vim").is_none());
+    }
+}
@@ -0,0 +1,137 @@
+//! Knowledge decay tracking
+//!
+//! Identifies tools the user once used heavily but hasn't touched in
+//! months. `tb refresh` surfaces them with the user's own best past
+//! invocations as a memory jog, and suggestions warn before
+//! recommending a rusty tool.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+use crate::domain::entities::Command;
+
+/// Minimum historical uses before a tool counts as "known".
+pub const HEAVY_USE_THRESHOLD: usize = 20;
+
+/// Days without use after which a known tool is considered rusty.
+pub const RUSTY_AFTER_DAYS: i64 = 60;
+
+/// How many past invocations are kept as refresher examples.
+const EXAMPLE_LIMIT: usize = 3;
+
+/// A tool the user knew well but hasn't used recently.
+#[derive(Debug, Clone)]
+pub struct RustyTool {
+    pub tool: String,
+    /// Total recorded uses.
+    pub uses: usize,
+    pub last_used: DateTime<Utc>,
+    /// The user's own most common successful invocations.
+    pub examples: Vec<String>,
+}
+
+/// Finds heavily-used tools that have gone untouched for
+/// [`RUSTY_AFTER_DAYS`], sorted by how long they've been idle.
+pub fn rusty_tools(commands: &[Command], now: DateTime<Utc>) -> Vec<RustyTool> {
+    let cutoff = now - Duration::days(RUSTY_AFTER_DAYS);
+
+    let mut per_tool: HashMap<&str, Vec<&Command>> = HashMap::new();
+    for cmd in commands {
+        if cmd.parsed_command.is_empty() {
+            continue;
+        }
+        per_tool.entry(cmd.parsed_command.as_str()).or_default().push(cmd);
+    }
+
+    let mut rusty: Vec<RustyTool> = per_tool
+        .into_iter()
+        .filter_map(|(tool, uses)| {
+            let last_used = uses.iter().map(|cmd| cmd.timestamp).max()?;
+            if uses.len() < HEAVY_USE_THRESHOLD || last_used >= cutoff {
+                return None;
+            }
+
+            // Most common successful invocations as refresher examples
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for cmd in uses.iter().filter(|cmd| cmd.exit_code == 0) {
+                *counts.entry(cmd.raw.as_str()).or_default() += 1;
+            }
+            let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+            ranked.sort_by_key(|(raw, count)| (std::cmp::Reverse(*count), raw.to_string()));
+
+            Some(RustyTool {
+                tool: tool.to_string(),
+                uses: uses.len(),
+                last_used,
+                examples: ranked
+                    .into_iter()
+                    .take(EXAMPLE_LIMIT)
+                    .map(|(raw, _)| raw.to_string())
+                    .collect(),
+            })
+        })
+        .collect();
+
+    rusty.sort_by_key(|tool| tool.last_used);
+    rusty
+}
+
+/// Whether `tool` appears in the rusty list — used by suggestion
+/// display to attach a warning.
+pub fn is_rusty(tool: &str, rusty: &[RustyTool]) -> bool {
+    rusty.iter().any(|r| r.tool == tool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+
+    fn command_at(raw: &str, days_ago: i64) -> Command {
+        let mut parts = raw.split_whitespace();
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: parts.next().unwrap().to_string(),
+            arguments: parts.map(String::from).collect(),
+            working_directory: "/home/test".to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: Utc::now() - Duration::days(days_ago),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_detects_heavily_used_idle_tools() {
+        let mut commands: Vec<Command> = (0..25)
+            .map(|i| command_at("terraform plan", 90 + i))
+            .collect();
+        commands.push(command_at("git status", 1));
+
+        let rusty = rusty_tools(&commands, Utc::now());
+        assert_eq!(rusty.len(), 1);
+        assert_eq!(rusty[0].tool, "terraform");
+        assert_eq!(rusty[0].examples[0], "terraform plan");
+        assert!(is_rusty("terraform", &rusty));
+        assert!(!is_rusty("git", &rusty));
+    }
+
+    #[test]
+    fn test_recent_or_light_use_is_not_rusty() {
+        let mut commands: Vec<Command> = (0..25).map(|_| command_at("git status", 2)).collect();
+        commands.extend((0..5).map(|i| command_at("ansible deploy", 200 + i)));
+
+        assert!(rusty_tools(&commands, Utc::now()).is_empty());
+    }
+}
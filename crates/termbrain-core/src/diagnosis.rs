@@ -0,0 +1,223 @@
+//! Failure diagnosis heuristics
+//!
+//! The local layers of `tb diagnose`: shell exit-code semantics and a
+//! small knowledge base of recoveries for well-known failure shapes.
+//! AI analysis, when configured, is layered on top by the CLI.
+
+use crate::domain::entities::Command;
+
+/// What a conventional shell exit code means, when it carries meaning
+/// beyond "the program failed".
+pub fn exit_code_meaning(code: i32) -> Option<&'static str> {
+    match code {
+        2 => Some("misuse of shell builtin or invalid arguments"),
+        126 => Some("command found but not executable (check permissions)"),
+        127 => Some("command not found (typo, or tool not installed / not on PATH)"),
+        130 => Some("interrupted by Ctrl-C (SIGINT)"),
+        137 => Some("killed (SIGKILL) — often the OOM killer"),
+        139 => Some("segmentation fault (SIGSEGV)"),
+        143 => Some("terminated (SIGTERM)"),
+        255 => Some("exit status out of range — for ssh, a connection or authentication failure"),
+        _ => None,
+    }
+}
+
+/// Known-recovery knowledge base: concrete next steps for failure
+/// shapes we have seen often enough to hardcode.
+pub fn known_recovery(command: &Command) -> Option<&'static str> {
+    let tool = command.parsed_command.as_str();
+    let first_arg = command.arguments.first().map(String::as_str).unwrap_or("");
+
+    match (tool, first_arg, command.exit_code) {
+        ("git", "push", 1) => Some(
+            "The remote likely has commits you don't: git pull --rebase, resolve any conflicts, then push again",
+        ),
+        ("git", _, 128) => Some(
+            "Repository-level git error: check the remote URL, your authentication, or whether you're inside a repository",
+        ),
+        ("cargo", "build", _) | ("cargo", "test", _) | ("cargo", "check", _) => Some(
+            "Compilation failed: fix the first reported error first; later errors are often cascades",
+        ),
+        ("npm", "install", _) | ("npm", "ci", _) => Some(
+            "Dependency install failed: try deleting node_modules and the lockfile, then reinstall; check the required node version",
+        ),
+        ("docker", _, 125) => Some(
+            "The docker daemon refused the command: check that the daemon is running and the flags are valid",
+        ),
+        ("kubectl", _, 1) => Some(
+            "Check you're pointed at the right cluster: kubectl config current-context",
+        ),
+        ("ssh", _, 255) => Some(
+            "Connection failed before running anything: verify host, network, and keys (ssh -v shows the handshake)",
+        ),
+        _ => None,
+    }
+}
+
+/// Seconds from each failure to the next success of the same command —
+/// the single-failure recovery window `tb explain` shows, aggregated.
+/// Commands must be oldest first; failures never followed by a success
+/// (still broken) produce no entry.
+pub fn recovery_times(commands: &[Command]) -> Vec<(String, i64)> {
+    use std::collections::HashMap;
+
+    // Walk newest to oldest keeping, per command, the nearest later
+    // success — each failure then pairs with it in one pass
+    let mut next_success: HashMap<&str, chrono::DateTime<chrono::Utc>> = HashMap::new();
+    let mut times = Vec::new();
+    for command in commands.iter().rev() {
+        if command.exit_code == 0 {
+            next_success.insert(&command.parsed_command, command.timestamp);
+        } else if let Some(recovered_at) = next_success.get(command.parsed_command.as_str()) {
+            times.push((
+                command.parsed_command.clone(),
+                (*recovered_at - command.timestamp).num_seconds(),
+            ));
+        }
+    }
+    times.reverse();
+    times
+}
+
+/// A command whose runs alternate between success and failure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlakyCommand {
+    pub parsed_command: String,
+    pub runs: usize,
+    pub failures: usize,
+    /// Adjacent runs whose outcome flipped (pass→fail or fail→pass).
+    pub flips: usize,
+}
+
+/// Commands whose outcome keeps flipping across at least `min_runs`
+/// runs — flaky tests, racy services, unreliable networks. A command
+/// qualifies when more than a third of its adjacent run pairs flip.
+/// Commands must be oldest first; the flakiest come back first.
+pub fn flaky_commands(commands: &[Command], min_runs: usize) -> Vec<FlakyCommand> {
+    use std::collections::HashMap;
+
+    let mut by_command: HashMap<&str, (usize, usize, usize, Option<bool>)> = HashMap::new();
+    for command in commands {
+        let ok = command.exit_code == 0;
+        let entry = by_command
+            .entry(command.parsed_command.as_str())
+            .or_insert((0, 0, 0, None));
+        entry.0 += 1;
+        if !ok {
+            entry.1 += 1;
+        }
+        if entry.3.is_some_and(|previous| previous != ok) {
+            entry.2 += 1;
+        }
+        entry.3 = Some(ok);
+    }
+
+    let mut flaky: Vec<FlakyCommand> = by_command
+        .into_iter()
+        .filter(|(_, (runs, failures, flips, _))| {
+            *runs >= min_runs.max(2)
+                && *failures > 0
+                && *failures < *runs
+                && *flips * 3 > runs - 1
+        })
+        .map(|(parsed_command, (runs, failures, flips, _))| FlakyCommand {
+            parsed_command: parsed_command.to_string(),
+            runs,
+            failures,
+            flips,
+        })
+        .collect();
+    flaky.sort_by_key(|f| std::cmp::Reverse(f.flips));
+    flaky
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn failed(raw: &str, exit_code: i32) -> Command {
+        let mut parts = raw.split_whitespace();
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: parts.next().unwrap().to_string(),
+            arguments: parts.map(String::from).collect(),
+            working_directory: "/home/test".to_string(),
+            exit_code,
+            duration_ms: 0,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_exit_code_semantics() {
+        assert!(exit_code_meaning(127).unwrap().contains("not found"));
+        assert!(exit_code_meaning(130).unwrap().contains("SIGINT"));
+        assert_eq!(exit_code_meaning(1), None);
+    }
+
+    /// A run of `raw` with the given outcome, `minute` minutes into
+    /// the test timeline.
+    fn run_at(raw: &str, exit_code: i32, minute: i64) -> Command {
+        let mut command = failed(raw, exit_code);
+        command.timestamp = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + chrono::Duration::minutes(minute);
+        command
+    }
+
+    #[test]
+    fn test_recovery_times_pair_each_failure_with_the_next_success() {
+        let history = vec![
+            run_at("cargo test", 1, 0),
+            run_at("git status", 0, 1),
+            run_at("cargo test", 1, 2),
+            run_at("cargo test", 0, 5),
+            run_at("make deploy", 1, 6), // never recovers
+        ];
+        assert_eq!(
+            recovery_times(&history),
+            vec![
+                ("cargo".to_string(), 300),
+                ("cargo".to_string(), 180),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flaky_commands_require_alternation() {
+        let mut history = Vec::new();
+        for minute in 0..6 {
+            // cargo alternates every run; ls always succeeds
+            history.push(run_at("cargo test", (minute % 2) as i32, minute));
+            history.push(run_at("ls -la", 0, minute));
+        }
+        let flaky = flaky_commands(&history, 4);
+        assert_eq!(flaky.len(), 1);
+        assert_eq!(flaky[0].parsed_command, "cargo");
+        assert_eq!(flaky[0].flips, 5);
+    }
+
+    #[test]
+    fn test_known_recoveries() {
+        assert!(known_recovery(&failed("git push origin main", 1))
+            .unwrap()
+            .contains("pull --rebase"));
+        assert!(known_recovery(&failed("ssh deploy@prod", 255)).is_some());
+        assert_eq!(known_recovery(&failed("ls -la", 2)), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Daily suggestion digest
+//!
+//! Picks one insight from the last day of history for the shell-startup
+//! digest. Generation happens ahead of time (`tb digest generate`, via
+//! cron or a scheduler); the shell snippet only reads the cached file,
+//! so startup stays fast.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+use crate::domain::entities::Command;
+
+/// Command pairs where running the first repeatedly without the second
+/// usually means a step is being forgotten or deserves a workflow.
+const FOLLOW_UP_PAIRS: &[(&str, &str)] = &[
+    ("terraform plan", "terraform apply"),
+    ("docker build", "docker push"),
+    ("cargo build", "cargo test"),
+    ("git diff", "git commit"),
+];
+
+/// Repetitions of the first command before the pair is worth surfacing.
+const REPEAT_THRESHOLD: usize = 5;
+/// Failures of one tool before it is worth surfacing.
+const FAILURE_THRESHOLD: usize = 5;
+
+/// Picks the single most useful insight from the last 24 hours of
+/// history, or `None` when there is nothing worth saying — a quiet
+/// digest beats a noisy one.
+pub fn daily_insight(commands: &[Command], now: DateTime<Utc>) -> Option<String> {
+    let cutoff = now - Duration::days(1);
+    let recent: Vec<&Command> = commands.iter().filter(|c| c.timestamp >= cutoff).collect();
+    if recent.is_empty() {
+        return None;
+    }
+
+    // 1. Repeated first halves of known pairs with no follow-up
+    for (first, second) in FOLLOW_UP_PAIRS {
+        let runs = recent.iter().filter(|c| c.raw.starts_with(first)).count();
+        let followed = recent.iter().any(|c| c.raw.starts_with(second));
+        if runs >= REPEAT_THRESHOLD && !followed {
+            return Some(format!(
+                "You ran `{}` {} times in the last day without `{}` — want a workflow? (tb workflow create)",
+                first, runs, second
+            ));
+        }
+    }
+
+    // 2. A tool failing over and over
+    let mut failures: HashMap<&str, usize> = HashMap::new();
+    for cmd in recent.iter().filter(|c| c.exit_code != 0) {
+        *failures.entry(cmd.parsed_command.as_str()).or_default() += 1;
+    }
+    if let Some((tool, count)) = failures
+        .into_iter()
+        .filter(|(tool, _)| !tool.is_empty())
+        .max_by_key(|(_, count)| *count)
+    {
+        if count >= FAILURE_THRESHOLD {
+            return Some(format!(
+                "`{}` failed {} times in the last day — `tb diagnose --last-failure` might save the next retry",
+                tool, count
+            ));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+
+    fn command(raw: &str, exit_code: i32, hours_ago: i64) -> Command {
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: raw.split_whitespace().next().unwrap().to_string(),
+            arguments: Vec::new(),
+            working_directory: "/work".to_string(),
+            exit_code,
+            duration_ms: 0,
+            timestamp: Utc::now() - Duration::hours(hours_ago),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_repeated_plan_without_apply_is_surfaced() {
+        let mut commands: Vec<Command> =
+            (0..6).map(|i| command("terraform plan", 0, i)).collect();
+        commands.push(command("ls", 0, 1));
+
+        let insight = daily_insight(&commands, Utc::now()).unwrap();
+        assert!(insight.contains("terraform plan"));
+        assert!(insight.contains("6 times"));
+
+        // With an apply in the window there is nothing to say
+        commands.push(command("terraform apply", 0, 1));
+        assert_eq!(daily_insight(&commands, Utc::now()), None);
+    }
+
+    #[test]
+    fn test_repeated_failures_are_surfaced_but_old_history_is_not() {
+        let failing: Vec<Command> = (0..5).map(|i| command("make", 2, i)).collect();
+        let insight = daily_insight(&failing, Utc::now()).unwrap();
+        assert!(insight.contains("`make` failed 5 times"));
+
+        let stale: Vec<Command> = (0..5).map(|_| command("make", 2, 48)).collect();
+        assert_eq!(daily_insight(&stale, Utc::now()), None);
+    }
+}
@@ -15,9 +15,29 @@ pub struct Command {
     pub duration_ms: u64,
     pub timestamp: DateTime<Utc>,
     pub session_id: String,
+    /// Provenance of the record: how it entered the database
+    /// ("shell-hook", "wrap", "import:zsh", "ingest:ci", "api",
+    /// "synthetic"). Lets analytics separate organically typed commands
+    /// from imported or machine-generated ones.
+    #[serde(default = "default_source")]
+    pub source: String,
+    /// Arbitrary structured data stashed by plugins and integrations
+    /// (e.g. k8s context, PR number). Keys are free-form; values are
+    /// any JSON.
+    #[serde(default)]
+    pub extras: HashMap<String, serde_json::Value>,
+    /// Hybrid logical clock stamp (see `crate::hlc`) giving merged
+    /// multi-device histories a stable total order. Absent on records
+    /// from before the column existed.
+    #[serde(default)]
+    pub logical_clock: Option<String>,
     pub metadata: CommandMetadata,
 }
 
+pub(crate) fn default_source() -> String {
+    "shell-hook".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CommandMetadata {
     pub shell: String,
@@ -30,10 +50,42 @@ pub struct CommandMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Session {
     pub id: String,
+    /// The session this one was started from (a zsh inside bash, a
+    /// tmux pane). None for top-level terminal tabs.
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
     pub shell: String,
     pub terminal: String,
+    /// Arbitrary structured data stashed by plugins and integrations.
+    #[serde(default)]
+    pub extras: HashMap<String, serde_json::Value>,
+}
+
+/// A detected project: the directory subtree rooted where a build or
+/// vcs marker file (Cargo.toml, package.json, .git) sits. Distinct from
+/// the remote-based project identity in `extras`: a Project exists for
+/// local-only trees too and is keyed by its root path.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Project {
+    pub id: uuid::Uuid,
+    /// Root directory basename; what `--project` filters match.
+    pub name: String,
+    /// Absolute path of the project root.
+    pub root: String,
+    /// The marker file that identified the root.
+    pub marker: String,
+    /// Normalized git remote, when the root is a clone of something.
+    /// Rows sharing a remote are one logical project across paths and
+    /// machines.
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// Free-form labels set via `tb projects tag`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -55,6 +107,33 @@ pub enum PatternType {
     ErrorRecovery(String, String),
 }
 
+/// A recommendation surfaced to the user, together with the historical
+/// evidence that produced it so every suggestion can be audited.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    /// The suggested command, workflow or alias definition.
+    pub value: String,
+    pub description: String,
+    pub confidence: f32,
+    pub provenance: Vec<ProvenanceRecord>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SuggestionKind {
+    PredictedCommand,
+    Workflow,
+    Alias,
+}
+
+/// A single piece of historical evidence behind a suggestion.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProvenanceRecord {
+    pub command_id: uuid::Uuid,
+    pub raw: String,
+    pub timestamp: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Workflow {
     pub id: uuid::Uuid,
@@ -68,10 +147,58 @@ pub struct Workflow {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct WorkflowStep {
+    #[serde(default)]
     pub order: u32,
     pub command: String,
+    #[serde(default)]
     pub description: Option<String>,
+    #[serde(default)]
     pub expected_outcome: Option<String>,
+    /// Extra environment variables exported to this step only.
+    #[serde(default)]
+    pub env: std::collections::HashMap<String, String>,
+    /// Whether the step runs given the previous step's outcome.
+    #[serde(default)]
+    pub when: StepCondition,
+    /// Ask before running (for destructive steps like `terraform apply`).
+    #[serde(default)]
+    pub confirm: bool,
+    /// Kill the step if it runs longer than this.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// One step's outcome within a recorded workflow run. Steps of the same
+/// invocation share a `run_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowRunStep {
+    pub id: uuid::Uuid,
+    pub run_id: uuid::Uuid,
+    pub workflow_name: String,
+    pub step_order: u32,
+    /// The command after variable substitution.
+    pub command: String,
+    /// "ok", "failed", "skipped", or "timeout".
+    pub status: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<i64>,
+    /// Who/where triggered the run.
+    pub user: Option<String>,
+    pub session_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// When a workflow step runs, relative to the previous step's exit code.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StepCondition {
+    /// Run only if everything so far succeeded (the default).
+    #[default]
+    OnSuccess,
+    /// Run only if the previous step failed — cleanup/rollback steps.
+    OnFailure,
+    /// Run regardless.
+    Always,
 }
 
 #[cfg(test)]
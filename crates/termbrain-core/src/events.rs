@@ -0,0 +1,150 @@
+//! Versioned event schema
+//!
+//! Every way termbrain pushes data outward — alert webhooks, export
+//! mirrors, the HTTP API — wraps it in the same envelope, so consumers
+//! integrate once against a stable, versioned contract instead of
+//! reverse-engineering each surface. `tb events schema` prints the JSON
+//! Schema documents.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Bumped only on breaking envelope or payload changes; additive fields
+/// don't count.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The event types termbrain emits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventKind {
+    CommandRecorded,
+    SessionStarted,
+    WorkflowCompleted,
+    AnomalyDetected,
+}
+
+impl EventKind {
+    /// The wire name ("command.recorded").
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::CommandRecorded => "command.recorded",
+            EventKind::SessionStarted => "session.started",
+            EventKind::WorkflowCompleted => "workflow.completed",
+            EventKind::AnomalyDetected => "anomaly.detected",
+        }
+    }
+
+    pub fn all() -> &'static [EventKind] {
+        &[
+            EventKind::CommandRecorded,
+            EventKind::SessionStarted,
+            EventKind::WorkflowCompleted,
+            EventKind::AnomalyDetected,
+        ]
+    }
+}
+
+/// The envelope every emitted event is wrapped in.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Event {
+    pub schema_version: u32,
+    /// The event type's wire name.
+    pub event: String,
+    pub occurred_at: DateTime<Utc>,
+    /// Event-specific payload, per the type's schema.
+    pub payload: serde_json::Value,
+}
+
+impl Event {
+    pub fn new(kind: EventKind, payload: serde_json::Value) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            event: kind.name().to_string(),
+            occurred_at: Utc::now(),
+            payload,
+        }
+    }
+}
+
+/// The JSON Schema document for one event type.
+pub fn schema_for(kind: EventKind) -> serde_json::Value {
+    let payload = match kind {
+        EventKind::CommandRecorded => serde_json::json!({
+            "type": "object",
+            "required": ["id", "raw", "exit_code", "timestamp"],
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "raw": { "type": "string", "description": "The command line, post-redaction" },
+                "parsed_command": { "type": "string" },
+                "exit_code": { "type": "integer" },
+                "duration_ms": { "type": "integer" },
+                "working_directory": { "type": "string" },
+                "session_id": { "type": "string" },
+                "hostname": { "type": "string" },
+                "timestamp": { "type": "string", "format": "date-time" },
+                "extras": { "type": "object" }
+            }
+        }),
+        EventKind::SessionStarted => serde_json::json!({
+            "type": "object",
+            "required": ["id", "shell", "start_time"],
+            "properties": {
+                "id": { "type": "string" },
+                "parent_id": { "type": ["string", "null"] },
+                "shell": { "type": "string" },
+                "terminal": { "type": "string" },
+                "start_time": { "type": "string", "format": "date-time" }
+            }
+        }),
+        EventKind::WorkflowCompleted => serde_json::json!({
+            "type": "object",
+            "required": ["workflow", "success"],
+            "properties": {
+                "workflow": { "type": "string" },
+                "run_id": { "type": "string" },
+                "success": { "type": "boolean" },
+                "steps_completed": { "type": "integer" },
+                "duration_ms": { "type": "integer" }
+            }
+        }),
+        EventKind::AnomalyDetected => serde_json::json!({
+            "type": "object",
+            "required": ["alert", "message"],
+            "properties": {
+                "alert": { "type": "string", "description": "The alert rule's name" },
+                "metric": { "type": "string" },
+                "value": { "type": "number" },
+                "threshold": { "type": "number" },
+                "message": { "type": "string" }
+            }
+        }),
+    };
+
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("https://termbrain.dev/events/v{}/{}.json", SCHEMA_VERSION, kind.name()),
+        "title": kind.name(),
+        "type": "object",
+        "required": ["schema_version", "event", "occurred_at", "payload"],
+        "properties": {
+            "schema_version": { "const": SCHEMA_VERSION },
+            "event": { "const": kind.name() },
+            "occurred_at": { "type": "string", "format": "date-time" },
+            "payload": payload
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_matches_schema_constants() {
+        let event = Event::new(EventKind::AnomalyDetected, serde_json::json!({"alert": "x"}));
+        assert_eq!(event.schema_version, SCHEMA_VERSION);
+        assert_eq!(event.event, "anomaly.detected");
+
+        let schema = schema_for(EventKind::AnomalyDetected);
+        assert_eq!(schema["properties"]["event"]["const"], event.event);
+    }
+}
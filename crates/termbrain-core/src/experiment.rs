@@ -0,0 +1,133 @@
+//! Workflow experiments
+//!
+//! Compares your own usage before and during a marked experiment
+//! ("switch to pnpm") so the verdict comes from real recorded data —
+//! failure rates, durations, and volume — rather than impressions.
+
+use crate::domain::entities::Command;
+
+/// Change below which two rates are called even, to avoid declaring
+/// winners from noise.
+const SIGNIFICANCE: f64 = 0.05;
+
+/// Aggregate usage over one comparison window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageStats {
+    pub commands: usize,
+    /// Fraction of commands that exited non-zero.
+    pub failure_rate: f64,
+    /// Mean duration over commands that reported one.
+    pub avg_duration_ms: f64,
+}
+
+/// Summarizes a window of commands.
+pub fn usage_stats(commands: &[Command]) -> UsageStats {
+    let failures = commands.iter().filter(|c| c.exit_code != 0).count();
+    let timed: Vec<u64> = commands
+        .iter()
+        .map(|c| c.duration_ms)
+        .filter(|d| *d > 0)
+        .collect();
+
+    UsageStats {
+        commands: commands.len(),
+        failure_rate: if commands.is_empty() {
+            0.0
+        } else {
+            failures as f64 / commands.len() as f64
+        },
+        avg_duration_ms: if timed.is_empty() {
+            0.0
+        } else {
+            timed.iter().sum::<u64>() as f64 / timed.len() as f64
+        },
+    }
+}
+
+/// Renders a verdict comparing the experiment window against the
+/// baseline. Differences under [`SIGNIFICANCE`] (relative, for
+/// durations; absolute, for failure rates) are reported as even.
+pub fn verdict(before: &UsageStats, during: &UsageStats) -> String {
+    let mut findings: Vec<String> = Vec::new();
+
+    let failure_delta = during.failure_rate - before.failure_rate;
+    if failure_delta.abs() >= SIGNIFICANCE {
+        findings.push(format!(
+            "failure rate {} from {:.0}% to {:.0}%",
+            if failure_delta < 0.0 { "improved" } else { "worsened" },
+            before.failure_rate * 100.0,
+            during.failure_rate * 100.0
+        ));
+    }
+
+    if before.avg_duration_ms > 0.0 && during.avg_duration_ms > 0.0 {
+        let duration_delta = (during.avg_duration_ms - before.avg_duration_ms) / before.avg_duration_ms;
+        if duration_delta.abs() >= SIGNIFICANCE {
+            findings.push(format!(
+                "average duration {} {:.0}% ({:.0}ms → {:.0}ms)",
+                if duration_delta < 0.0 { "down" } else { "up" },
+                duration_delta.abs() * 100.0,
+                before.avg_duration_ms,
+                during.avg_duration_ms
+            ));
+        }
+    }
+
+    if findings.is_empty() {
+        "No significant difference — keep whichever you prefer".to_string()
+    } else {
+        findings.join("; ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn command(exit_code: i32, duration_ms: u64) -> Command {
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: "npm install".to_string(),
+            parsed_command: "npm".to_string(),
+            arguments: Vec::new(),
+            working_directory: "/work".to_string(),
+            exit_code,
+            duration_ms,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_stats_summarize_failures_and_durations() {
+        let stats = usage_stats(&[command(0, 1000), command(1, 3000), command(0, 0)]);
+        assert_eq!(stats.commands, 3);
+        assert!((stats.failure_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert!((stats.avg_duration_ms - 2000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_verdict_reports_improvements_and_noise() {
+        let before = usage_stats(&[command(1, 4000), command(1, 4000), command(0, 4000), command(0, 4000)]);
+        let during = usage_stats(&[command(0, 2000), command(0, 2000), command(0, 2000), command(0, 2000)]);
+
+        let verdict = verdict(&before, &during);
+        assert!(verdict.contains("failure rate improved"));
+        assert!(verdict.contains("duration down"));
+
+        let same = usage_stats(&[command(0, 1000)]);
+        assert!(super::verdict(&same, &same).contains("No significant difference"));
+    }
+}
@@ -0,0 +1,62 @@
+//! Deep-focus detection from command cadence
+//!
+//! A steady stream of recent commands means the user is mid-flow, and
+//! proactive output (suggestions, notifications, the startup digest)
+//! would be an interruption. The detector is purely cadence-based: no
+//! state is kept, so "focus" lapses on its own the moment the user
+//! pauses — that pause is the natural break where deferred output can
+//! be surfaced.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// How far back to look when measuring cadence.
+pub const FOCUS_WINDOW_MINUTES: i64 = 10;
+
+/// Commands within the window needed to count as deep focus.
+pub const FOCUS_MIN_COMMANDS: usize = 6;
+
+/// A gap this long since the last command counts as a break.
+pub const BREAK_MINUTES: i64 = 3;
+
+/// Whether the cadence of `timestamps` (any order) indicates deep
+/// focus at `now`: at least [`FOCUS_MIN_COMMANDS`] commands within the
+/// last [`FOCUS_WINDOW_MINUTES`], the latest no more than
+/// [`BREAK_MINUTES`] ago.
+pub fn in_deep_focus(timestamps: &[DateTime<Utc>], now: DateTime<Utc>) -> bool {
+    let window_start = now - Duration::minutes(FOCUS_WINDOW_MINUTES);
+    let recent: Vec<_> = timestamps
+        .iter()
+        .filter(|ts| **ts > window_start && **ts <= now)
+        .collect();
+
+    if recent.len() < FOCUS_MIN_COMMANDS {
+        return false;
+    }
+
+    let latest = recent.iter().copied().max().copied().unwrap_or(now);
+    now - latest < Duration::minutes(BREAK_MINUTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steady_cadence_is_focus_and_a_pause_is_a_break() {
+        let now = Utc::now();
+        let steady: Vec<_> = (1..=8).map(|i| now - Duration::minutes(i)).collect();
+        assert!(in_deep_focus(&steady, now));
+
+        // Same burst, but the user stopped typing five minutes ago.
+        let paused: Vec<_> = (5..=13).map(|i| now - Duration::minutes(i)).collect();
+        assert!(!in_deep_focus(&paused, now));
+    }
+
+    #[test]
+    fn sparse_history_is_not_focus() {
+        let now = Utc::now();
+        let sparse = vec![now - Duration::minutes(1), now - Duration::minutes(2)];
+        assert!(!in_deep_focus(&sparse, now));
+        assert!(!in_deep_focus(&[], now));
+    }
+}
@@ -0,0 +1,183 @@
+//! Git context from .git file reads
+//!
+//! Enriches recording with where-in-git a command ran: repository
+//! root, branch, short commit hash, and a dirty heuristic. Everything
+//! comes from reading `.git` files directly — recording is on the shell
+//! hook's hot path and must not shell out to git for every keystroke.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Git state of the directory a command ran in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitContext {
+    /// Top-level directory of the working tree.
+    pub repo_root: String,
+    /// Checked-out branch; `None` when HEAD is detached.
+    pub branch: Option<String>,
+    /// Short (8 char) hash of the current commit, when resolvable.
+    pub commit: Option<String>,
+    /// Heuristic: the index was touched after the current ref last
+    /// moved, which usually means uncommitted work. `None` when the
+    /// files needed to compare are missing.
+    pub dirty: Option<bool>,
+}
+
+/// Reads the git context for `directory`, walking up to find the
+/// enclosing repository. Returns `None` outside any repository.
+pub fn read_git_context(directory: &Path) -> Option<GitContext> {
+    let (root, git_dir) = find_git_dir(directory)?;
+
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+
+    let (branch, commit) = match head.strip_prefix("ref: ") {
+        Some(ref_name) => (
+            ref_name.strip_prefix("refs/heads/").map(str::to_string),
+            resolve_ref(&git_dir, ref_name),
+        ),
+        // Detached HEAD holds the hash directly
+        None => (None, Some(head.to_string())),
+    };
+
+    Some(GitContext {
+        repo_root: root.display().to_string(),
+        branch,
+        commit: commit.map(|hash| hash.chars().take(8).collect()),
+        dirty: dirty_heuristic(&git_dir, head),
+    })
+}
+
+/// Finds the repository root and its git directory, following a
+/// `.git` gitfile (worktrees, submodules) when present.
+fn find_git_dir(directory: &Path) -> Option<(PathBuf, PathBuf)> {
+    for ancestor in directory.ancestors() {
+        let dot_git = ancestor.join(".git");
+        if dot_git.is_dir() {
+            return Some((ancestor.to_path_buf(), dot_git));
+        }
+        if dot_git.is_file() {
+            let content = fs::read_to_string(&dot_git).ok()?;
+            let gitdir = content.trim().strip_prefix("gitdir: ")?.trim();
+            let gitdir = if Path::new(gitdir).is_absolute() {
+                PathBuf::from(gitdir)
+            } else {
+                ancestor.join(gitdir)
+            };
+            return Some((ancestor.to_path_buf(), gitdir));
+        }
+    }
+    None
+}
+
+/// Resolves a ref to its commit hash: the loose ref file first, then a
+/// scan of packed-refs.
+fn resolve_ref(git_dir: &Path, ref_name: &str) -> Option<String> {
+    if let Ok(hash) = fs::read_to_string(git_dir.join(ref_name)) {
+        return Some(hash.trim().to_string());
+    }
+
+    let packed = fs::read_to_string(git_dir.join("packed-refs")).ok()?;
+    packed
+        .lines()
+        .filter(|line| !line.starts_with(['#', '^']))
+        .find_map(|line| {
+            let (hash, name) = line.split_once(' ')?;
+            (name.trim() == ref_name).then(|| hash.to_string())
+        })
+}
+
+/// Dirty heuristic without scanning the working tree: git rewrites the
+/// index on add/status after a commit moves the ref, so an index newer
+/// than the ref usually means uncommitted work sits on top of it.
+fn dirty_heuristic(git_dir: &Path, head: &str) -> Option<bool> {
+    let ref_file = match head.strip_prefix("ref: ") {
+        Some(ref_name) if git_dir.join(ref_name).exists() => git_dir.join(ref_name),
+        // Packed ref or detached HEAD: compare against HEAD itself
+        _ => git_dir.join("HEAD"),
+    };
+    let ref_mtime = fs::metadata(ref_file).and_then(|m| m.modified()).ok()?;
+    let index_mtime = fs::metadata(git_dir.join("index"))
+        .and_then(|m| m.modified())
+        .ok()?;
+    Some(index_mtime > ref_mtime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lays out a minimal fake repository under the system temp dir.
+    struct FakeRepo {
+        root: PathBuf,
+    }
+
+    impl FakeRepo {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!(
+                "termbrain-git-test-{}-{}",
+                name,
+                std::process::id()
+            ));
+            fs::create_dir_all(root.join(".git/refs/heads")).unwrap();
+            Self { root }
+        }
+
+        fn write(&self, relative: &str, content: &str) {
+            let path = self.root.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    impl Drop for FakeRepo {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn test_reads_branch_and_short_hash() {
+        let repo = FakeRepo::new("branch");
+        repo.write(".git/HEAD", "ref: refs/heads/feature/login\n");
+        repo.write(
+            ".git/refs/heads/feature/login",
+            "0123456789abcdef0123456789abcdef01234567\n",
+        );
+
+        let subdir = repo.root.join("src/deep");
+        fs::create_dir_all(&subdir).unwrap();
+        let context = read_git_context(&subdir).unwrap();
+
+        assert_eq!(context.repo_root, repo.root.display().to_string());
+        assert_eq!(context.branch.as_deref(), Some("feature/login"));
+        assert_eq!(context.commit.as_deref(), Some("01234567"));
+    }
+
+    #[test]
+    fn test_detached_head_and_packed_refs() {
+        let repo = FakeRepo::new("detached");
+        repo.write(".git/HEAD", "fedcba9876543210fedcba9876543210fedcba98\n");
+        let context = read_git_context(&repo.root).unwrap();
+        assert_eq!(context.branch, None);
+        assert_eq!(context.commit.as_deref(), Some("fedcba98"));
+
+        repo.write(".git/HEAD", "ref: refs/heads/main\n");
+        repo.write(
+            ".git/packed-refs",
+            "# pack-refs with: peeled fully-peeled sorted\n\
+             aaaabbbbccccddddaaaabbbbccccddddaaaabbbb refs/heads/main\n",
+        );
+        let context = read_git_context(&repo.root).unwrap();
+        assert_eq!(context.branch.as_deref(), Some("main"));
+        assert_eq!(context.commit.as_deref(), Some("aaaabbbb"));
+    }
+
+    #[test]
+    fn test_outside_a_repository_is_none() {
+        let outside = std::env::temp_dir().join(format!("termbrain-no-git-{}", std::process::id()));
+        fs::create_dir_all(&outside).unwrap();
+        assert_eq!(read_git_context(&outside), None);
+        let _ = fs::remove_dir_all(&outside);
+    }
+}
@@ -0,0 +1,115 @@
+//! GitHub CLI and git push activity detection
+//!
+//! Recognizes recorded commands that move work to GitHub — `git push`,
+//! `gh pr create`, `gh pr merge` — so the CLI can attach local git
+//! metadata (commit sha, branch, PR number) and build per-PR activity
+//! reports.
+
+use crate::domain::entities::Command;
+
+/// A GitHub-bound activity extracted from a recorded command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitActivity {
+    /// `git push` — the pushed HEAD commit is worth remembering.
+    Push,
+    /// `gh pr create` — the PR isn't numbered locally yet, so the head
+    /// branch is the reference.
+    PrCreate,
+    /// `gh pr merge [number|url|branch]`.
+    PrMerge { reference: Option<String> },
+}
+
+/// Detects successful push / PR commands.
+pub fn detect_git_activity(command: &Command) -> Option<GitActivity> {
+    if command.exit_code != 0 {
+        return None;
+    }
+    let args = &command.arguments;
+
+    match command.parsed_command.as_str() {
+        "git" if args.first().map(String::as_str) == Some("push") => Some(GitActivity::Push),
+        "gh" if args.first().map(String::as_str) == Some("pr") => {
+            match args.get(1).map(String::as_str) {
+                Some("create") => Some(GitActivity::PrCreate),
+                Some("merge") => Some(GitActivity::PrMerge {
+                    reference: args
+                        .iter()
+                        .skip(2)
+                        .find(|a| !a.starts_with('-'))
+                        .map(|a| normalize_pr_reference(a)),
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Normalizes a `gh pr merge` selector to a bare PR number where
+/// possible: `#123` and `https://github.com/o/r/pull/123` both become
+/// `123`; branch names pass through unchanged.
+pub fn normalize_pr_reference(selector: &str) -> String {
+    if let Some(number) = selector.strip_prefix('#') {
+        return number.to_string();
+    }
+    if let Some((_, tail)) = selector.rsplit_once("/pull/") {
+        return tail.trim_end_matches('/').to_string();
+    }
+    selector.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn cmd(raw: &str) -> Command {
+        let mut parts = raw.split_whitespace();
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: parts.next().unwrap().to_string(),
+            arguments: parts.map(String::from).collect(),
+            working_directory: "/home/test/project".to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_detects_push_and_pr_commands() {
+        assert_eq!(detect_git_activity(&cmd("git push origin main")), Some(GitActivity::Push));
+        assert_eq!(detect_git_activity(&cmd("gh pr create --fill")), Some(GitActivity::PrCreate));
+        assert_eq!(
+            detect_git_activity(&cmd("gh pr merge 123 --squash")),
+            Some(GitActivity::PrMerge {
+                reference: Some("123".to_string())
+            })
+        );
+        assert_eq!(detect_git_activity(&cmd("gh pr view 123")), None);
+        assert_eq!(detect_git_activity(&cmd("git status")), None);
+    }
+
+    #[test]
+    fn test_normalizes_pr_selectors() {
+        assert_eq!(normalize_pr_reference("#456"), "456");
+        assert_eq!(
+            normalize_pr_reference("https://github.com/anivar/termbrain/pull/789"),
+            "789"
+        );
+        assert_eq!(normalize_pr_reference("feature/login"), "feature/login");
+    }
+}
@@ -0,0 +1,140 @@
+//! Dangerous-command policy evaluation
+//!
+//! Backs `tb guard`, the pre-exec check the shell hooks run before a
+//! command executes. A policy is an ordered list of regex rules, each
+//! with a severity — warn prints and lets the command through, confirm
+//! asks first, block refuses — and optional directory scoping so a rule
+//! can apply only inside (or everywhere except) chosen subtrees. When
+//! several rules match, the most severe one wins. This module is pure
+//! matching; loading the policy file, prompting, and the audit log live
+//! in the CLI.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// What happens when a rule matches, from least to most restrictive.
+/// The derived ordering is what lets the most severe match win.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Print the reason and run the command.
+    Warn,
+    /// Ask for confirmation before running.
+    Confirm,
+    /// Refuse to run the command.
+    Block,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Warn => "warn",
+            Self::Confirm => "confirm",
+            Self::Block => "block",
+        }
+    }
+}
+
+/// One user-editable policy rule. `pattern` is a regex matched against
+/// the full command line. An invalid pattern never matches; the CLI
+/// warns about it when loading the policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardRule {
+    pub pattern: String,
+    pub severity: Severity,
+    /// Shown to the user when the rule fires.
+    pub reason: String,
+    /// Directory prefixes the rule is limited to; empty means everywhere.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directories: Vec<String>,
+    /// Directory prefixes where the rule is suppressed (e.g. a scratch
+    /// tree where `rm -rf` is routine). Overrides `directories`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exempt_directories: Vec<String>,
+}
+
+/// The rule that fired for a command, reduced to what the CLI shows
+/// and audits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardMatch {
+    pub severity: Severity,
+    pub reason: String,
+    pub pattern: String,
+}
+
+fn under_any(directory: &str, prefixes: &[String]) -> bool {
+    let directory = std::path::Path::new(directory);
+    prefixes.iter().any(|p| directory.starts_with(p))
+}
+
+/// Evaluates a command against the policy, returning the most severe
+/// matching rule. Directory prefixes must already be expanded (no `~`).
+pub fn evaluate(rules: &[GuardRule], command: &str, directory: &str) -> Option<GuardMatch> {
+    rules
+        .iter()
+        .filter(|rule| rule.directories.is_empty() || under_any(directory, &rule.directories))
+        .filter(|rule| !under_any(directory, &rule.exempt_directories))
+        .filter(|rule| {
+            Regex::new(&rule.pattern)
+                .map(|re| re.is_match(command))
+                .unwrap_or(false)
+        })
+        .max_by_key(|rule| rule.severity)
+        .map(|rule| GuardMatch {
+            severity: rule.severity,
+            reason: rule.reason.clone(),
+            pattern: rule.pattern.clone(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, severity: Severity) -> GuardRule {
+        GuardRule {
+            pattern: pattern.to_string(),
+            severity,
+            reason: format!("matched {}", pattern),
+            directories: Vec::new(),
+            exempt_directories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_most_severe_match_wins() {
+        let rules = vec![
+            rule("^rm ", Severity::Warn),
+            rule("^rm -rf /", Severity::Block),
+        ];
+        let fired = evaluate(&rules, "rm -rf /etc", "/home/dev").unwrap();
+        assert_eq!(fired.severity, Severity::Block);
+
+        let fired = evaluate(&rules, "rm notes.txt", "/home/dev").unwrap();
+        assert_eq!(fired.severity, Severity::Warn);
+        assert!(evaluate(&rules, "ls", "/home/dev").is_none());
+    }
+
+    #[test]
+    fn test_directory_scoping() {
+        let mut scoped = rule("^git push --force", Severity::Confirm);
+        scoped.directories = vec!["/work".to_string()];
+        let rules = vec![scoped];
+
+        assert!(evaluate(&rules, "git push --force", "/work/api").is_some());
+        assert!(evaluate(&rules, "git push --force", "/home/dev/toy").is_none());
+    }
+
+    #[test]
+    fn test_exempt_directories_suppress_the_rule() {
+        let mut exempt = rule("^rm -rf ", Severity::Confirm);
+        exempt.exempt_directories = vec!["/home/dev/scratch".to_string()];
+        let rules = vec![exempt];
+
+        assert!(evaluate(&rules, "rm -rf build", "/home/dev/api").is_some());
+        assert!(evaluate(&rules, "rm -rf build", "/home/dev/scratch/x").is_none());
+
+        // Invalid patterns never match instead of poisoning the policy
+        assert!(evaluate(&[rule("(unclosed", Severity::Block)], "anything", "/").is_none());
+    }
+}
@@ -0,0 +1,56 @@
+//! Integrity hash chain
+//!
+//! Optional tamper-evidence for stored history: each recorded command
+//! appends a link whose hash covers the previous link's hash plus the
+//! record's content, so editing, deleting, or back-dating any past
+//! record breaks every hash after it. `tb verify` walks the chain and
+//! recomputes it; some compliance environments require this before
+//! allowing terminal recording at all.
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::entities::Command;
+
+/// Canonical content string hashed into the chain. Covers what ran,
+/// where, and when; mutable annotations (extras, environment) are
+/// deliberately excluded so after-the-fact enrichment does not break
+/// the chain.
+pub fn chain_payload(command: &Command) -> String {
+    [
+        command.id.to_string(),
+        command.raw.clone(),
+        command.working_directory.clone(),
+        command.exit_code.to_string(),
+        command.duration_ms.to_string(),
+        command.timestamp.to_rfc3339(),
+        command.session_id.clone(),
+        command.source.clone(),
+    ]
+    .join("\n")
+}
+
+/// Hash of one link: SHA-256 over the previous link's hash and this
+/// record's payload. The first link uses the empty string as the
+/// previous hash.
+pub fn chain_hash(prev: &str, payload: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(prev.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(payload.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_hash_depends_on_prev_and_payload() {
+        let first = chain_hash("", "payload");
+        assert_eq!(first.len(), 64);
+        assert_ne!(first, chain_hash("", "other payload"));
+        assert_ne!(first, chain_hash(&first, "payload"));
+        // Deterministic, so verification can recompute it later
+        assert_eq!(first, chain_hash("", "payload"));
+    }
+}
@@ -0,0 +1,146 @@
+//! Branch-driven intention detection
+//!
+//! Watches recorded git commands for branch lifecycle events so an
+//! intention can be opened when a `feature/*`-style branch is checked
+//! out and closed again when the branch is merged or deleted — without
+//! manual `tb intend` calls.
+
+use crate::domain::entities::Command;
+
+/// A branch lifecycle event extracted from a recorded command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BranchEvent {
+    CheckedOut(String),
+    Merged(String),
+    Deleted(String),
+}
+
+/// Detects branch checkouts, merges, and deletions in git invocations.
+pub fn detect_branch_event(command: &Command) -> Option<BranchEvent> {
+    if command.parsed_command != "git" || command.exit_code != 0 {
+        return None;
+    }
+    let args = &command.arguments;
+    let sub = args.first().map(String::as_str)?;
+
+    match sub {
+        "checkout" | "switch" => {
+            // `git checkout -b name`, `git switch -c name`, or a plain
+            // checkout of an existing branch
+            let branch = args
+                .iter()
+                .skip(1)
+                .find(|a| !a.starts_with('-'))?;
+            Some(BranchEvent::CheckedOut(branch.clone()))
+        }
+        "merge" => {
+            let branch = args.iter().skip(1).find(|a| !a.starts_with('-'))?;
+            Some(BranchEvent::Merged(branch.clone()))
+        }
+        "branch" => {
+            if args.iter().any(|a| a == "-d" || a == "-D" || a == "--delete") {
+                let branch = args.iter().skip(1).find(|a| !a.starts_with('-'))?;
+                Some(BranchEvent::Deleted(branch.clone()))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether `branch` matches one of the configured patterns
+/// (`feature/*` style prefix globs, or exact names).
+pub fn matches_branch_pattern(branch: &str, patterns: &[String]) -> bool {
+    patterns.iter().any(|pattern| {
+        match pattern.strip_suffix('*') {
+            Some(prefix) => branch.starts_with(prefix),
+            None => branch == pattern,
+        }
+    })
+}
+
+/// Renders an intention from a template. `{branch}` is the full branch
+/// name; `{slug}` is the last path segment with dashes and underscores
+/// turned into spaces.
+pub fn render_intention(template: &str, branch: &str) -> String {
+    let slug = branch
+        .rsplit('/')
+        .next()
+        .unwrap_or(branch)
+        .replace(['-', '_'], " ");
+    template.replace("{branch}", branch).replace("{slug}", &slug)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn git(raw: &str) -> Command {
+        let mut parts = raw.split_whitespace();
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: parts.next().unwrap().to_string(),
+            arguments: parts.map(String::from).collect(),
+            working_directory: "/home/test/project".to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_detects_branch_lifecycle() {
+        assert_eq!(
+            detect_branch_event(&git("git checkout -b feature/search-ranking")),
+            Some(BranchEvent::CheckedOut("feature/search-ranking".to_string()))
+        );
+        assert_eq!(
+            detect_branch_event(&git("git switch -c fix/login")),
+            Some(BranchEvent::CheckedOut("fix/login".to_string()))
+        );
+        assert_eq!(
+            detect_branch_event(&git("git merge feature/search-ranking")),
+            Some(BranchEvent::Merged("feature/search-ranking".to_string()))
+        );
+        assert_eq!(
+            detect_branch_event(&git("git branch -d fix/login")),
+            Some(BranchEvent::Deleted("fix/login".to_string()))
+        );
+        assert_eq!(detect_branch_event(&git("git status")), None);
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        let patterns = vec!["feature/*".to_string(), "fix/*".to_string()];
+        assert!(matches_branch_pattern("feature/search", &patterns));
+        assert!(matches_branch_pattern("fix/login", &patterns));
+        assert!(!matches_branch_pattern("main", &patterns));
+    }
+
+    #[test]
+    fn test_template_rendering() {
+        assert_eq!(
+            render_intention("Working on {slug}", "feature/search-ranking"),
+            "Working on search ranking"
+        );
+        assert_eq!(
+            render_intention("{branch}", "fix/login"),
+            "fix/login"
+        );
+    }
+}
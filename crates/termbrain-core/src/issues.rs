@@ -0,0 +1,91 @@
+//! Issue reference extraction
+//!
+//! Finds Jira-style keys (ABC-123) and GitHub-style numbers (#456) in
+//! branch names, commit messages, and intention text so terminal
+//! activity can be grouped per issue.
+
+/// Extracts issue keys from free text, in order of first appearance.
+pub fn extract_issue_keys(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut keys: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let at_boundary = i == 0 || !chars[i - 1].is_ascii_alphanumeric();
+
+        // GitHub-style: #456
+        if chars[i] == '#' && at_boundary {
+            let digits: String = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if !digits.is_empty() {
+                push_unique(&mut keys, format!("#{}", digits));
+                i += 1 + digits.len();
+                continue;
+            }
+        }
+
+        // Jira-style: ABC-123 (2+ uppercase letters, dash, digits)
+        if chars[i].is_ascii_uppercase() && at_boundary {
+            let letters: String = chars[i..]
+                .iter()
+                .take_while(|c| c.is_ascii_uppercase())
+                .collect();
+            let rest = i + letters.len();
+            if letters.len() >= 2 && chars.get(rest) == Some(&'-') {
+                let digits: String = chars[rest + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                if !digits.is_empty() {
+                    push_unique(&mut keys, format!("{}-{}", letters, digits));
+                    i = rest + 1 + digits.len();
+                    continue;
+                }
+            }
+            i += letters.len().max(1);
+            continue;
+        }
+
+        i += 1;
+    }
+
+    keys
+}
+
+fn push_unique(keys: &mut Vec<String>, key: String) {
+    if !keys.contains(&key) {
+        keys.push(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_jira_and_github_keys() {
+        assert_eq!(
+            extract_issue_keys("git commit -m 'Fix ABC-123 and close #456'"),
+            vec!["ABC-123", "#456"]
+        );
+    }
+
+    #[test]
+    fn test_extracts_from_branch_names() {
+        assert_eq!(
+            extract_issue_keys("feature/ABC-123-login-fix"),
+            vec!["ABC-123"]
+        );
+    }
+
+    #[test]
+    fn test_ignores_plain_text_and_dedupes() {
+        assert!(extract_issue_keys("ls -la /tmp").is_empty());
+        assert_eq!(
+            extract_issue_keys("ABC-123 then ABC-123 again"),
+            vec!["ABC-123"]
+        );
+    }
+}
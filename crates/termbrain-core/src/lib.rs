@@ -1,6 +1,41 @@
 //! TermBrain Core - Domain logic and entities
 
+pub mod ai;
+pub mod burst;
+pub mod cost;
+pub mod decay;
+pub mod diagnosis;
+pub mod digest;
 pub mod domain;
+pub mod editor;
+pub mod embedding;
+pub mod env_changes;
+pub mod events;
+pub mod experiment;
+pub mod focus;
+pub mod git_context;
+pub mod github;
+pub mod guard;
+pub mod hlc;
+pub mod integrity;
+pub mod intentions;
+pub mod issues;
+pub mod markov;
+pub mod perf;
+pub mod picker;
+pub mod privacy;
+pub mod project;
+pub mod redaction;
+pub mod repl;
+pub mod retention;
+pub mod risk;
+pub mod search;
+pub mod sessionize;
+pub mod shell_history;
+pub mod sql_extract;
 pub mod validation;
+pub mod viz;
+pub mod workflow_engine;
+pub mod working_set;
 
 pub use domain::*;
\ No newline at end of file
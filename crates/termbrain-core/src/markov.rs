@@ -0,0 +1,272 @@
+//! Markov next-command model
+//!
+//! Transition counts over recorded history — what actually followed
+//! what, per directory and globally — give `tb predict` data-driven
+//! probabilities instead of a single-follower heuristic. Persistence
+//! and incremental counting live in the CLI layer; this module defines
+//! the contexts, the backoff-weighted ranking, and an offline accuracy
+//! evaluation over held-out history.
+
+use crate::domain::entities::Command;
+use std::collections::HashMap;
+
+/// Longest context tracked, in previous commands.
+pub const MAX_ORDER: usize = 2;
+
+/// Per-order weight multipliers: a bigram match is much stronger
+/// evidence than a unigram one.
+const ORDER_WEIGHTS: [f64; MAX_ORDER] = [1.0, 4.0];
+
+/// Directory-local evidence outweighs global evidence.
+const SCOPE_WEIGHT: f64 = 2.0;
+
+/// One stored transition count relevant to the current contexts.
+pub struct TransitionObservation {
+    pub context: String,
+    /// True for directory-scoped rows, false for global ones.
+    pub scoped: bool,
+    pub next: String,
+    pub count: u32,
+}
+
+/// Why a prediction scored what it did — one line per contributing
+/// observation, for `tb predict --explain`.
+pub struct Evidence {
+    pub context: String,
+    pub scoped: bool,
+    pub count: u32,
+    /// Total observations sharing this (context, scope) group.
+    pub total: u32,
+    pub weight: f64,
+}
+
+pub struct Prediction {
+    pub next: String,
+    /// Normalized across the returned candidates; sums to 1.
+    pub probability: f64,
+    pub evidence: Vec<Evidence>,
+}
+
+/// The transition contexts a position in history belongs to, shortest
+/// first: the previous command, then the previous two joined with a
+/// space. `previous` is oldest first.
+pub fn contexts(previous: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    if let Some(last) = previous.last() {
+        out.push(last.clone());
+    }
+    if previous.len() >= MAX_ORDER {
+        out.push(format!(
+            "{} {}",
+            previous[previous.len() - 2],
+            previous[previous.len() - 1]
+        ));
+    }
+    out
+}
+
+fn order_of(context: &str) -> usize {
+    context.split(' ').count().min(MAX_ORDER)
+}
+
+/// Ranks candidate next commands. Each observation contributes its
+/// share of its (context, scope) group, weighted by context order and
+/// scope; the summed scores are normalized into probabilities.
+pub fn rank_predictions(observations: &[TransitionObservation]) -> Vec<Prediction> {
+    let mut totals: HashMap<(&str, bool), u32> = HashMap::new();
+    for observation in observations {
+        *totals
+            .entry((observation.context.as_str(), observation.scoped))
+            .or_default() += observation.count;
+    }
+
+    let mut scored: HashMap<&str, (f64, Vec<Evidence>)> = HashMap::new();
+    for observation in observations {
+        let total = totals[&(observation.context.as_str(), observation.scoped)];
+        let share = observation.count as f64 / total as f64;
+        let weight = ORDER_WEIGHTS[order_of(&observation.context) - 1]
+            * if observation.scoped { SCOPE_WEIGHT } else { 1.0 };
+        let entry = scored.entry(observation.next.as_str()).or_default();
+        entry.0 += share * weight;
+        entry.1.push(Evidence {
+            context: observation.context.clone(),
+            scoped: observation.scoped,
+            count: observation.count,
+            total,
+            weight,
+        });
+    }
+
+    let score_sum: f64 = scored.values().map(|(score, _)| score).sum();
+    let mut predictions: Vec<Prediction> = scored
+        .into_iter()
+        .map(|(next, (score, evidence))| Prediction {
+            next: next.to_string(),
+            probability: if score_sum > 0.0 { score / score_sum } else { 0.0 },
+            evidence,
+        })
+        .collect();
+    predictions.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+    predictions
+}
+
+/// Hit rates from replaying held-out history through the model.
+pub struct ModelAccuracy {
+    pub evaluated: usize,
+    pub top1: usize,
+    pub top3: usize,
+}
+
+/// Chronological 80/20 evaluation: trains in-memory counts on the
+/// older split, then checks whether each held-out command was the
+/// model's first (or top-three) guess given its session context.
+/// `history` is oldest first.
+pub fn evaluate(history: &[Command]) -> ModelAccuracy {
+    let split = history.len() * 4 / 5;
+    let mut counts: HashMap<(String, Option<String>), HashMap<String, u32>> = HashMap::new();
+    let mut session_tail: HashMap<&str, Vec<String>> = HashMap::new();
+
+    let mut accuracy = ModelAccuracy {
+        evaluated: 0,
+        top1: 0,
+        top3: 0,
+    };
+
+    for (position, command) in history.iter().enumerate() {
+        let previous = session_tail
+            .entry(command.session_id.as_str())
+            .or_default();
+        let command_contexts = contexts(previous);
+
+        if position >= split && !command_contexts.is_empty() {
+            let mut observations = Vec::new();
+            for context in &command_contexts {
+                for scope in [None, Some(command.working_directory.clone())] {
+                    if let Some(nexts) = counts.get(&(context.clone(), scope.clone())) {
+                        for (next, count) in nexts {
+                            observations.push(TransitionObservation {
+                                context: context.clone(),
+                                scoped: scope.is_some(),
+                                next: next.clone(),
+                                count: *count,
+                            });
+                        }
+                    }
+                }
+            }
+            if !observations.is_empty() {
+                let ranked = rank_predictions(&observations);
+                accuracy.evaluated += 1;
+                if ranked.first().is_some_and(|p| p.next == command.raw) {
+                    accuracy.top1 += 1;
+                }
+                if ranked.iter().take(3).any(|p| p.next == command.raw) {
+                    accuracy.top3 += 1;
+                }
+            }
+        }
+
+        // Train on everything already seen, held-out rows included,
+        // exactly as the incremental on-record counting would
+        for context in &command_contexts {
+            for scope in [None, Some(command.working_directory.clone())] {
+                *counts
+                    .entry((context.clone(), scope))
+                    .or_default()
+                    .entry(command.raw.clone())
+                    .or_default() += 1;
+            }
+        }
+        previous.push(command.parsed_command.clone());
+        if previous.len() > MAX_ORDER {
+            previous.remove(0);
+        }
+    }
+
+    accuracy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn in_session(raw: &str, session: &str) -> Command {
+        let mut parts = raw.split_whitespace();
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: parts.next().unwrap().to_string(),
+            arguments: parts.map(String::from).collect(),
+            working_directory: "/work".to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: Utc::now(),
+            session_id: session.to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_contexts_grow_with_history() {
+        assert!(contexts(&[]).is_empty());
+        assert_eq!(contexts(&["git".to_string()]), vec!["git"]);
+        assert_eq!(
+            contexts(&["cd".to_string(), "git".to_string()]),
+            vec!["git", "cd git"]
+        );
+    }
+
+    #[test]
+    fn test_bigram_evidence_outranks_unigram() {
+        let observations = vec![
+            TransitionObservation {
+                context: "git".to_string(),
+                scoped: false,
+                next: "git push".to_string(),
+                count: 6,
+            },
+            TransitionObservation {
+                context: "cd git".to_string(),
+                scoped: false,
+                next: "git status".to_string(),
+                count: 4,
+            },
+            TransitionObservation {
+                context: "cd git".to_string(),
+                scoped: false,
+                next: "git push".to_string(),
+                count: 2,
+            },
+        ];
+        let ranked = rank_predictions(&observations);
+        // The unigram says push, but the more specific bigram says
+        // status — and bigrams weigh 4×
+        assert_eq!(ranked[0].next, "git status");
+        assert!((ranked.iter().map(|p| p.probability).sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_evaluate_learns_a_deterministic_habit() {
+        let mut history = Vec::new();
+        for session in 0..10 {
+            let id = format!("s{}", session);
+            history.push(in_session("cd /work", &id));
+            history.push(in_session("git status", &id));
+            history.push(in_session("cargo test", &id));
+        }
+        let accuracy = evaluate(&history);
+        assert!(accuracy.evaluated > 0);
+        assert_eq!(accuracy.top1, accuracy.evaluated);
+    }
+}
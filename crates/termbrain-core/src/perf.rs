@@ -0,0 +1,176 @@
+//! Duration regression detection
+//!
+//! Recurring commands drift slower — a build picks up a heavy
+//! dependency, a test suite grows, a disk fills up. This compares each
+//! command's recent runs against its own older baseline and flags the
+//! ones whose median has moved significantly. Pure functions over
+//! chronological history; `tb perf` and the record-time warning sit on
+//! top.
+
+use crate::domain::entities::Command;
+
+/// Timed runs needed on each side of the split before a comparison
+/// means anything.
+pub const MIN_SAMPLES: usize = 5;
+
+/// The recent median must be at least this multiple of the baseline
+/// median to count as a regression.
+pub const REGRESSION_RATIO: f64 = 1.5;
+
+/// Baselines below this are noise — a command that went from 3ms to
+/// 9ms has not meaningfully regressed.
+pub const MIN_BASELINE_MS: u64 = 100;
+
+/// How many of a command's latest runs form the "recent" window.
+pub const RECENT_RUNS: usize = 10;
+
+/// A command whose recent runs are significantly slower than its own
+/// baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DurationRegression {
+    pub parsed_command: String,
+    pub baseline_median_ms: u64,
+    pub recent_median_ms: u64,
+    /// recent median / baseline median.
+    pub ratio: f64,
+    pub baseline_samples: usize,
+    pub recent_samples: usize,
+}
+
+/// Median of a sample set; 0 for an empty one.
+pub fn median_ms(samples: &[u64]) -> u64 {
+    if samples.is_empty() {
+        return 0;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    sorted[sorted.len() / 2]
+}
+
+/// Splits each command's successful timed runs into an older baseline
+/// and the last [`RECENT_RUNS`] runs, and reports the commands whose
+/// recent median crossed [`REGRESSION_RATIO`]. `commands` must be
+/// oldest first; failures are excluded because they abort at
+/// unrepresentative points. The worst ratio comes back first.
+pub fn detect_regressions(commands: &[Command]) -> Vec<DurationRegression> {
+    use std::collections::HashMap;
+
+    let mut durations: HashMap<&str, Vec<u64>> = HashMap::new();
+    for command in commands {
+        if command.exit_code == 0 && command.duration_ms > 0 {
+            durations
+                .entry(command.parsed_command.as_str())
+                .or_default()
+                .push(command.duration_ms);
+        }
+    }
+
+    let mut regressions: Vec<DurationRegression> = durations
+        .into_iter()
+        .filter_map(|(parsed_command, samples)| {
+            let split = samples.len().saturating_sub(RECENT_RUNS);
+            let (baseline, recent) = samples.split_at(split);
+            if baseline.len() < MIN_SAMPLES || recent.len() < MIN_SAMPLES {
+                return None;
+            }
+            let baseline_median_ms = median_ms(baseline);
+            let recent_median_ms = median_ms(recent);
+            if baseline_median_ms < MIN_BASELINE_MS {
+                return None;
+            }
+            let ratio = recent_median_ms as f64 / baseline_median_ms as f64;
+            if ratio < REGRESSION_RATIO {
+                return None;
+            }
+            Some(DurationRegression {
+                parsed_command: parsed_command.to_string(),
+                baseline_median_ms,
+                recent_median_ms,
+                ratio,
+                baseline_samples: baseline.len(),
+                recent_samples: recent.len(),
+            })
+        })
+        .collect();
+    regressions.sort_by(|a, b| b.ratio.total_cmp(&a.ratio));
+    regressions
+}
+
+/// The record-time check: one fresh run at least twice the command's
+/// own median (and past the noise floor) is anomalously slow.
+pub fn is_anomalously_slow(duration_ms: u64, median_ms: u64) -> bool {
+    median_ms >= MIN_BASELINE_MS && duration_ms >= median_ms * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn timed(raw: &str, duration_ms: u64) -> Command {
+        let mut parts = raw.split_whitespace();
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: parts.next().unwrap().to_string(),
+            arguments: parts.map(String::from).collect(),
+            working_directory: "/home/test".to_string(),
+            exit_code: 0,
+            duration_ms,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_detects_a_command_that_got_slower() {
+        let mut history = Vec::new();
+        // Ten baseline builds around 1s, then ten recent ones around 2s
+        for _ in 0..10 {
+            history.push(timed("cargo build", 1_000));
+        }
+        for _ in 0..10 {
+            history.push(timed("cargo build", 2_000));
+        }
+        // A stable command for contrast
+        for _ in 0..20 {
+            history.push(timed("git status", 150));
+        }
+
+        let regressions = detect_regressions(&history);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].parsed_command, "cargo");
+        assert_eq!(regressions[0].baseline_median_ms, 1_000);
+        assert_eq!(regressions[0].recent_median_ms, 2_000);
+    }
+
+    #[test]
+    fn test_fast_commands_stay_below_the_noise_floor() {
+        let mut history = Vec::new();
+        for _ in 0..10 {
+            history.push(timed("ls -la", 3));
+        }
+        for _ in 0..10 {
+            history.push(timed("ls -la", 9));
+        }
+        assert!(detect_regressions(&history).is_empty());
+    }
+
+    #[test]
+    fn test_anomaly_threshold() {
+        assert!(is_anomalously_slow(2_500, 1_000));
+        assert!(!is_anomalously_slow(1_500, 1_000));
+        assert!(!is_anomalously_slow(20, 10));
+    }
+}
@@ -0,0 +1,118 @@
+//! Candidate ranking for the interactive history picker
+//!
+//! Backs the Ctrl-R shell widget: filters history by query terms and
+//! ranks with frequency, recency, and a bonus for commands previously
+//! run in the current directory.
+
+use crate::domain::entities::Command;
+
+/// Extra score for commands previously run in the current directory.
+const SAME_DIRECTORY_BONUS: f64 = 3.0;
+/// Maximum extra score for the most recently run candidate.
+const RECENCY_WEIGHT: f64 = 2.0;
+
+/// Filters `commands` (newest first) to those matching every
+/// whitespace-separated term of `query`, deduplicates by raw text, and
+/// returns up to `limit` candidates ranked best first.
+pub fn rank_candidates(commands: &[Command], query: &str, cwd: &str, limit: usize) -> Vec<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|t| t.to_lowercase())
+        .collect();
+
+    struct Candidate {
+        raw: String,
+        count: usize,
+        newest_index: usize,
+        same_directory: bool,
+    }
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for (index, cmd) in commands.iter().enumerate() {
+        let lowered = cmd.raw.to_lowercase();
+        if !terms.iter().all(|t| lowered.contains(t)) {
+            continue;
+        }
+        match candidates.iter_mut().find(|c| c.raw == cmd.raw) {
+            Some(existing) => {
+                existing.count += 1;
+                existing.same_directory |= cmd.working_directory == cwd;
+            }
+            None => candidates.push(Candidate {
+                raw: cmd.raw.clone(),
+                count: 1,
+                newest_index: index,
+                same_directory: cmd.working_directory == cwd,
+            }),
+        }
+    }
+
+    let total = commands.len().max(1) as f64;
+    let score = |c: &Candidate| {
+        c.count as f64
+            + if c.same_directory { SAME_DIRECTORY_BONUS } else { 0.0 }
+            + (total - c.newest_index as f64) / total * RECENCY_WEIGHT
+    };
+
+    candidates.sort_by(|a, b| {
+        score(b)
+            .partial_cmp(&score(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    candidates.truncate(limit);
+    candidates.into_iter().map(|c| c.raw).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn cmd(raw: &str, dir: &str) -> Command {
+        let mut parts = raw.split_whitespace();
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: parts.next().unwrap().to_string(),
+            arguments: parts.map(String::from).collect(),
+            working_directory: dir.to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_requires_every_term_and_dedupes() {
+        let commands = vec![
+            cmd("git push origin main", "/a"),
+            cmd("git push origin main", "/a"),
+            cmd("git pull", "/a"),
+        ];
+        let ranked = rank_candidates(&commands, "git push", "/b", 10);
+        assert_eq!(ranked, vec!["git push origin main".to_string()]);
+    }
+
+    #[test]
+    fn test_current_directory_outranks_frequency() {
+        let commands = vec![
+            cmd("cargo test", "/elsewhere"),
+            cmd("cargo test", "/elsewhere"),
+            cmd("cargo build", "/here"),
+        ];
+        let ranked = rank_candidates(&commands, "cargo", "/here", 10);
+        assert_eq!(ranked[0], "cargo build");
+    }
+}
@@ -0,0 +1,312 @@
+//! Privacy-preserving aggregation
+//!
+//! Produces team-shareable statistics without exposing any individual's
+//! history: only aggregates that at least `k` distinct users contribute
+//! to are emitted, and the threshold is enforced here in code rather
+//! than left to exporter configuration.
+
+use crate::domain::entities::Command;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Aggregate statistics safe to share outside a single user's machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateReport {
+    /// The k-anonymity threshold the report was built with.
+    pub k: usize,
+    /// Number of distinct users contributing to the report.
+    pub contributing_users: usize,
+    /// Tool usage counts, suppressed below the k threshold.
+    pub top_tools: Vec<ToolUsage>,
+    /// Failure rates per command category, suppressed below k.
+    pub failure_rates: Vec<CategoryFailureRate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolUsage {
+    pub tool: String,
+    pub count: usize,
+    pub users: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryFailureRate {
+    pub category: String,
+    pub total: usize,
+    pub failures: usize,
+    pub users: usize,
+}
+
+/// The category table behind [`categorize`], kept as data so callers
+/// that need the inverse mapping (e.g. pushing a category filter down
+/// into SQL) stay in sync with the classifier. Anything unlisted is
+/// "other".
+pub const CATEGORY_TOOLS: &[(&str, &[&str])] = &[
+    ("version-control", &["git", "gh", "hg", "svn"]),
+    ("containers", &["docker", "podman", "kubectl", "helm"]),
+    ("build", &["cargo", "make", "cmake", "gcc", "go", "javac"]),
+    ("packages", &["npm", "yarn", "pnpm", "pip", "pipx", "gem", "brew", "apt", "dnf"]),
+    ("network", &["ssh", "scp", "curl", "wget", "rsync", "ping"]),
+    ("files", &["ls", "cd", "cp", "mv", "rm", "mkdir", "find", "cat", "grep"]),
+    ("editors", &["vim", "nvim", "nano", "emacs", "code"]),
+    ("databases", &["psql", "mysql", "sqlite3", "redis-cli", "mongo"]),
+];
+
+/// Rough command categorization used for failure-rate aggregation.
+pub fn categorize(parsed_command: &str) -> &'static str {
+    CATEGORY_TOOLS
+        .iter()
+        .find(|(_, tools)| tools.contains(&parsed_command))
+        .map(|(category, _)| *category)
+        .unwrap_or("other")
+}
+
+/// The tools making up a named category, or None for a name the
+/// classifier doesn't know ("other" included — it has no finite tool
+/// list).
+pub fn category_tools(category: &str) -> Option<&'static [&'static str]> {
+    CATEGORY_TOOLS
+        .iter()
+        .find(|(name, _)| *name == category)
+        .map(|(_, tools)| *tools)
+}
+
+/// Flag-value pairs and inline assignments that commonly carry secrets.
+const SECRET_MARKERS: &[&str] = &[
+    "token", "password", "passwd", "secret", "api-key", "api_key", "apikey", "auth",
+    "credential", "access-key", "access_key",
+];
+
+/// Redacts likely secrets from a command line before it leaves the
+/// machine (exports, bug-report bundles). Replaces the value after
+/// `--token`-style flags and `KEY=value` assignments whose key looks
+/// sensitive.
+pub fn redact_secrets(raw: &str) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut redact_next = false;
+
+    for word in raw.split_whitespace() {
+        if redact_next {
+            out.push("<redacted>".to_string());
+            redact_next = false;
+            continue;
+        }
+
+        let lower = word.to_lowercase();
+
+        // KEY=value or --flag=value
+        if let Some(eq) = word.find('=') {
+            let key = &lower[..eq];
+            if SECRET_MARKERS.iter().any(|m| key.contains(m)) {
+                out.push(format!("{}=<redacted>", &word[..eq]));
+                continue;
+            }
+        } else if lower.starts_with('-') && SECRET_MARKERS.iter().any(|m| lower.contains(m)) {
+            // --token abc: redact the following argument
+            out.push(word.to_string());
+            redact_next = true;
+            continue;
+        }
+
+        out.push(word.to_string());
+    }
+
+    out.join(" ")
+}
+
+/// Reduces a command line to its anonymized shape: the program and
+/// flag names survive, every value — paths, numbers, free arguments,
+/// assignment and flag values — becomes a placeholder. Templates carry
+/// no user data, so they are safe to share for heuristic tuning.
+pub fn command_template(raw: &str) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut program_seen = false;
+
+    for word in raw.split_whitespace() {
+        if !program_seen {
+            // Leading KEY=value assignments precede the program
+            if let Some(eq) = word.find('=') {
+                out.push(format!("{}=<value>", &word[..eq]));
+            } else {
+                // Strip any leading path from the program itself
+                out.push(word.rsplit('/').next().unwrap_or(word).to_string());
+                program_seen = true;
+            }
+            continue;
+        }
+
+        if let Some(stripped) = word.strip_prefix('-') {
+            // Flag names stay; attached values are blanked
+            match stripped.find('=') {
+                Some(eq) => out.push(format!("-{}=<value>", &stripped[..eq])),
+                None => out.push(word.to_string()),
+            }
+        } else if word.contains('=') {
+            let key = word.split('=').next().unwrap_or("");
+            out.push(format!("{}=<value>", key));
+        } else if word.contains('/') || word.starts_with('~') {
+            out.push("<path>".to_string());
+        } else if word.chars().all(|c| c.is_ascii_digit()) {
+            out.push("<n>".to_string());
+        } else {
+            out.push("<arg>".to_string());
+        }
+    }
+
+    out.join(" ")
+}
+
+/// Builds a k-anonymous aggregate report. Any tool or category that
+/// fewer than `k` distinct users touched is dropped entirely.
+pub fn k_anonymous_aggregates(commands: &[Command], k: usize) -> AggregateReport {
+    let mut tool_counts: HashMap<&str, (usize, HashSet<&str>)> = HashMap::new();
+    let mut category_stats: HashMap<&'static str, (usize, usize, HashSet<&str>)> = HashMap::new();
+    let mut all_users: HashSet<&str> = HashSet::new();
+
+    for cmd in commands {
+        let user = cmd.metadata.user.as_str();
+        all_users.insert(user);
+
+        let tool_entry = tool_counts.entry(&cmd.parsed_command).or_default();
+        tool_entry.0 += 1;
+        tool_entry.1.insert(user);
+
+        let category = categorize(&cmd.parsed_command);
+        let cat_entry = category_stats.entry(category).or_default();
+        cat_entry.0 += 1;
+        if cmd.exit_code != 0 {
+            cat_entry.1 += 1;
+        }
+        cat_entry.2.insert(user);
+    }
+
+    let mut top_tools: Vec<ToolUsage> = tool_counts
+        .into_iter()
+        .filter(|(_, (_, users))| users.len() >= k)
+        .map(|(tool, (count, users))| ToolUsage {
+            tool: tool.to_string(),
+            count,
+            users: users.len(),
+        })
+        .collect();
+    top_tools.sort_by_key(|usage| std::cmp::Reverse(usage.count));
+
+    let mut failure_rates: Vec<CategoryFailureRate> = category_stats
+        .into_iter()
+        .filter(|(_, (_, _, users))| users.len() >= k)
+        .map(|(category, (total, failures, users))| CategoryFailureRate {
+            category: category.to_string(),
+            total,
+            failures,
+            users: users.len(),
+        })
+        .collect();
+    failure_rates.sort_by_key(|rate| std::cmp::Reverse(rate.total));
+
+    AggregateReport {
+        k,
+        contributing_users: all_users.len(),
+        top_tools,
+        failure_rates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Utc;
+
+    fn command_by(user: &str, raw: &str, exit_code: i32) -> Command {
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: raw.split_whitespace().next().unwrap().to_string(),
+            arguments: Vec::new(),
+            working_directory: "/work".to_string(),
+            exit_code,
+            duration_ms: 0,
+            timestamp: Utc::now(),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: user.to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_tools_below_k_are_suppressed() {
+        let commands = vec![
+            command_by("alice", "git status", 0),
+            command_by("bob", "git push", 1),
+            command_by("alice", "secret-internal-tool deploy", 0),
+        ];
+
+        let report = k_anonymous_aggregates(&commands, 2);
+
+        assert!(report.top_tools.iter().any(|t| t.tool == "git"));
+        assert!(!report.top_tools.iter().any(|t| t.tool == "secret-internal-tool"));
+    }
+
+    #[test]
+    fn test_failure_rates_count_nonzero_exits() {
+        let commands = vec![
+            command_by("alice", "git push", 1),
+            command_by("bob", "git pull", 0),
+        ];
+
+        let report = k_anonymous_aggregates(&commands, 2);
+        let vcs = report
+            .failure_rates
+            .iter()
+            .find(|r| r.category == "version-control")
+            .unwrap();
+
+        assert_eq!(vcs.total, 2);
+        assert_eq!(vcs.failures, 1);
+    }
+
+    #[test]
+    fn test_command_template_drops_every_value() {
+        assert_eq!(
+            command_template("git commit -m 'fix the bug' --author=me@example.com"),
+            "git <arg> -m <arg> <arg> <arg> --author=<value>"
+        );
+        assert_eq!(
+            command_template("/usr/local/bin/rsync -avz ~/secrets host:/backup 8080"),
+            "rsync -avz <path> <path> <n>"
+        );
+        assert_eq!(command_template("AWS_REGION=eu-west-1 deploy"), "AWS_REGION=<value> deploy");
+    }
+
+    #[test]
+    fn test_category_tools_inverts_categorize() {
+        for (category, tools) in CATEGORY_TOOLS {
+            for tool in *tools {
+                assert_eq!(categorize(tool), *category);
+            }
+        }
+        assert_eq!(categorize("some-internal-tool"), "other");
+        assert_eq!(category_tools("other"), None);
+    }
+
+    #[test]
+    fn test_redacts_secret_flags_and_assignments() {
+        assert_eq!(
+            redact_secrets("curl -H x --token abc123 https://api.example.com"),
+            "curl -H x --token <redacted> https://api.example.com"
+        );
+        assert_eq!(
+            redact_secrets("AWS_SECRET_ACCESS_KEY=abc deploy"),
+            "AWS_SECRET_ACCESS_KEY=<redacted> deploy"
+        );
+        assert_eq!(redact_secrets("git push origin main"), "git push origin main");
+    }
+}
@@ -0,0 +1,138 @@
+//! REPL session detection
+//!
+//! Time spent inside psql, python, node and friends is a blind spot:
+//! the shell hook records one opaque command spanning the whole
+//! session. This module recognizes invocations that enter an
+//! interactive REPL — as opposed to run-and-exit uses like `python
+//! script.py` or `psql -c` — and knows where each REPL keeps its
+//! history file, so the statements typed inside can be captured after
+//! the session ends.
+
+/// One supported REPL: how to recognize entering it and where it
+/// leaves a history trail.
+pub struct ReplProfile {
+    /// Display name, also the extras tag and capture source suffix.
+    pub name: &'static str,
+    /// Binaries that start this REPL.
+    commands: &'static [&'static str],
+    /// Flags that make the invocation run-and-exit instead.
+    non_interactive_flags: &'static [&'static str],
+    /// Whether a bare positional argument is a script or inline command
+    /// (python, node) rather than a connection target (psql's database
+    /// name stays interactive).
+    script_positional: bool,
+    /// History file under the home directory, when the REPL writes one.
+    pub history_file: Option<&'static str>,
+}
+
+/// The REPLs recognized out of the box.
+pub const REPLS: &[ReplProfile] = &[
+    ReplProfile {
+        name: "python",
+        commands: &["python", "python3", "ipython"],
+        non_interactive_flags: &["-c", "-m"],
+        script_positional: true,
+        history_file: Some(".python_history"),
+    },
+    ReplProfile {
+        name: "node",
+        commands: &["node"],
+        non_interactive_flags: &["-e", "--eval", "-p", "--print"],
+        script_positional: true,
+        history_file: Some(".node_repl_history"),
+    },
+    ReplProfile {
+        name: "psql",
+        commands: &["psql"],
+        non_interactive_flags: &["-c", "--command", "-f", "--file", "-l", "--list"],
+        script_positional: false,
+        history_file: Some(".psql_history"),
+    },
+    ReplProfile {
+        name: "mysql",
+        commands: &["mysql"],
+        non_interactive_flags: &["-e", "--execute"],
+        script_positional: false,
+        history_file: Some(".mysql_history"),
+    },
+    ReplProfile {
+        name: "sqlite3",
+        commands: &["sqlite3"],
+        non_interactive_flags: &[],
+        script_positional: false,
+        history_file: Some(".sqlite_history"),
+    },
+    ReplProfile {
+        name: "redis-cli",
+        commands: &["redis-cli"],
+        non_interactive_flags: &["--eval"],
+        script_positional: true,
+        history_file: Some(".rediscli_history"),
+    },
+    ReplProfile {
+        name: "irb",
+        commands: &["irb"],
+        non_interactive_flags: &[],
+        script_positional: false,
+        history_file: None,
+    },
+];
+
+/// Returns the REPL this invocation enters interactively, or None for
+/// run-and-exit uses. A positional right after a flag counts as that
+/// flag's value (`redis-cli -h host`), not as a script.
+pub fn detect_repl(parsed_command: &str, arguments: &[String]) -> Option<&'static ReplProfile> {
+    let profile = REPLS
+        .iter()
+        .find(|profile| profile.commands.contains(&parsed_command))?;
+
+    let mut previous_was_flag = false;
+    for arg in arguments {
+        if profile
+            .non_interactive_flags
+            .iter()
+            .any(|flag| arg == flag || arg.starts_with(&format!("{}=", flag)))
+        {
+            return None;
+        }
+        if arg.starts_with('-') {
+            previous_was_flag = !arg.contains('=');
+            continue;
+        }
+        if profile.script_positional && !previous_was_flag {
+            return None;
+        }
+        previous_was_flag = false;
+    }
+    Some(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_interactive_entries_only() {
+        assert_eq!(detect_repl("python3", &[]).unwrap().name, "python");
+        assert!(detect_repl("python3", &args(&["script.py"])).is_none());
+        assert!(detect_repl("python3", &args(&["-c", "print(1)"])).is_none());
+        assert_eq!(detect_repl("psql", &args(&["mydb"])).unwrap().name, "psql");
+        assert!(detect_repl("psql", &args(&["-c", "select 1"])).is_none());
+        assert!(detect_repl("cargo", &args(&["build"])).is_none());
+    }
+
+    #[test]
+    fn flag_values_are_not_scripts() {
+        assert_eq!(
+            detect_repl("redis-cli", &args(&["-h", "cache.internal"]))
+                .unwrap()
+                .name,
+            "redis-cli"
+        );
+        assert!(detect_repl("redis-cli", &args(&["get", "key"])).is_none());
+    }
+}
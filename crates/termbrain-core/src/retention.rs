@@ -0,0 +1,285 @@
+//! Retention policies
+//!
+//! The garbage collector handles size and age globally; retention
+//! policies make it granular. Each policy scopes deletion (or
+//! protection) by directory, command, semantic category, failure, or
+//! sensitivity — e.g. "delete failed commands older than 30 days" next
+//! to "keep git commands forever". `tb prune` evaluates them.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::Command;
+use crate::privacy::categorize;
+use crate::redaction::REDACTIONS_EXTRA_KEY;
+
+/// One retention rule. All specified criteria must match; unspecified
+/// ones are ignored. `keep` inverts the rule into a protection: a
+/// command matched by any keep policy is never pruned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub name: String,
+    /// Protect matching commands instead of deleting them.
+    #[serde(default)]
+    pub keep: bool,
+    /// Working directory, matching the directory itself and anything
+    /// under it.
+    #[serde(default)]
+    pub directory: Option<String>,
+    /// Leading command word (e.g. "git").
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Record provenance (`source` column), with the usual prefix
+    /// semantics ("import" matches "import:zsh"). A leading '!'
+    /// negates: "!wrap" matches everything except wrapped agent
+    /// activity.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Semantic category from [`categorize`] (e.g. "network", "files").
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Only commands that exited non-zero.
+    #[serde(default)]
+    pub failed_only: bool,
+    /// Only commands the redaction pipeline altered.
+    #[serde(default)]
+    pub sensitive_only: bool,
+    /// Only commands older than this many days.
+    #[serde(default)]
+    pub max_age_days: Option<i64>,
+}
+
+impl RetentionPolicy {
+    fn matches(&self, command: &Command, now: DateTime<Utc>) -> bool {
+        if let Some(directory) = &self.directory {
+            let dir = directory.trim_end_matches('/');
+            if command.working_directory != dir
+                && !command.working_directory.starts_with(&format!("{}/", dir))
+            {
+                return false;
+            }
+        }
+        if let Some(name) = &self.command {
+            if command.parsed_command != *name {
+                return false;
+            }
+        }
+        if let Some(source) = &self.source {
+            let (want, negate) = match source.strip_prefix('!') {
+                Some(rest) => (rest, true),
+                None => (source.as_str(), false),
+            };
+            let matched =
+                command.source == want || command.source.starts_with(&format!("{}:", want));
+            if matched == negate {
+                return false;
+            }
+        }
+        if let Some(category) = &self.category {
+            if categorize(&command.parsed_command) != category {
+                return false;
+            }
+        }
+        if self.failed_only && command.exit_code == 0 {
+            return false;
+        }
+        if self.sensitive_only && !command.extras.contains_key(REDACTIONS_EXTRA_KEY) {
+            return false;
+        }
+        if let Some(days) = self.max_age_days {
+            if command.timestamp >= now - Duration::days(days) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Per-class retention windows, each in days. Different data classes
+/// age differently: wrapped agent activity is huge but disposable,
+/// summaries and patterns are small and worth keeping. A class left
+/// unset is kept forever. Enforced by `tb prune` alongside the
+/// row-level policies.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClassRetention {
+    /// Regular shell history (every source except "wrap").
+    #[serde(default)]
+    pub raw_commands_days: Option<i64>,
+    /// Wrapped AI agent activity (source "wrap") — the transcript-like
+    /// bulk of an AI session.
+    #[serde(default)]
+    pub ai_transcripts_days: Option<i64>,
+    /// Stored `tb diagnose` summaries.
+    #[serde(default)]
+    pub ai_summaries_days: Option<i64>,
+    /// Learned patterns, aged by when they were last seen.
+    #[serde(default)]
+    pub patterns_days: Option<i64>,
+    /// Closed intentions; active ones are never pruned.
+    #[serde(default)]
+    pub notes_days: Option<i64>,
+}
+
+impl ClassRetention {
+    pub fn is_empty(&self) -> bool {
+        self.raw_commands_days.is_none()
+            && self.ai_transcripts_days.is_none()
+            && self.ai_summaries_days.is_none()
+            && self.patterns_days.is_none()
+            && self.notes_days.is_none()
+    }
+
+    /// The command-table classes expressed as delete policies, so they
+    /// run through the same engine and keep policies still protect.
+    pub fn command_policies(&self) -> Vec<RetentionPolicy> {
+        let class = |name: &str, source: &str, days: i64| RetentionPolicy {
+            name: name.to_string(),
+            keep: false,
+            directory: None,
+            command: None,
+            source: Some(source.to_string()),
+            category: None,
+            failed_only: false,
+            sensitive_only: false,
+            max_age_days: Some(days),
+        };
+        let mut policies = Vec::new();
+        if let Some(days) = self.raw_commands_days {
+            policies.push(class("class:raw-commands", "!wrap", days));
+        }
+        if let Some(days) = self.ai_transcripts_days {
+            policies.push(class("class:ai-transcripts", "wrap", days));
+        }
+        policies
+    }
+}
+
+/// A command due for removal, with the policy that condemned it.
+pub struct PruneCandidate<'a> {
+    pub command: &'a Command,
+    pub policy: &'a str,
+}
+
+/// Evaluates every policy over `commands`: a command is a candidate
+/// when some delete policy matches it and no keep policy does.
+pub fn prune_candidates<'a>(
+    commands: &'a [Command],
+    policies: &'a [RetentionPolicy],
+    now: DateTime<Utc>,
+) -> Vec<PruneCandidate<'a>> {
+    commands
+        .iter()
+        .filter_map(|command| {
+            if policies.iter().any(|p| p.keep && p.matches(command, now)) {
+                return None;
+            }
+            policies
+                .iter()
+                .find(|p| !p.keep && p.matches(command, now))
+                .map(|p| PruneCandidate {
+                    command,
+                    policy: &p.name,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+
+    fn command(raw: &str, exit_code: i32, age_days: i64) -> Command {
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: raw.split_whitespace().next().unwrap().to_string(),
+            arguments: Vec::new(),
+            working_directory: "/work".to_string(),
+            exit_code,
+            duration_ms: 0,
+            timestamp: Utc::now() - Duration::days(age_days),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    fn policy(name: &str) -> RetentionPolicy {
+        RetentionPolicy {
+            name: name.to_string(),
+            keep: false,
+            directory: None,
+            command: None,
+            source: None,
+            category: None,
+            failed_only: false,
+            sensitive_only: false,
+            max_age_days: None,
+        }
+    }
+
+    #[test]
+    fn test_old_failures_are_pruned_but_fresh_ones_kept() {
+        let commands = vec![
+            command("make build", 2, 60),
+            command("make build", 2, 5),
+            command("make build", 0, 60),
+        ];
+        let policies = vec![RetentionPolicy {
+            failed_only: true,
+            max_age_days: Some(30),
+            ..policy("drop-old-failures")
+        }];
+
+        let candidates = prune_candidates(&commands, &policies, Utc::now());
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].command.id, commands[0].id);
+        assert_eq!(candidates[0].policy, "drop-old-failures");
+    }
+
+    #[test]
+    fn test_class_windows_split_wrapped_from_regular_history() {
+        let mut wrapped = command("claude run", 0, 60);
+        wrapped.source = "wrap".to_string();
+        let commands = vec![wrapped, command("git status", 0, 60)];
+
+        let classes = ClassRetention {
+            ai_transcripts_days: Some(30),
+            ..Default::default()
+        };
+        let policies = classes.command_policies();
+        let candidates = prune_candidates(&commands, &policies, Utc::now());
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].command.source, "wrap");
+        assert_eq!(candidates[0].policy, "class:ai-transcripts");
+    }
+
+    #[test]
+    fn test_keep_policy_overrides_delete() {
+        let commands = vec![command("git push", 1, 90), command("ssh prod", 1, 90)];
+        let policies = vec![
+            RetentionPolicy {
+                command: Some("git".to_string()),
+                keep: true,
+                ..policy("keep-git")
+            },
+            RetentionPolicy {
+                failed_only: true,
+                ..policy("drop-failures")
+            },
+        ];
+
+        let candidates = prune_candidates(&commands, &policies, Utc::now());
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].command.raw, "ssh prod");
+    }
+}
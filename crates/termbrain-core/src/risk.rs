@@ -0,0 +1,171 @@
+//! Command risk classification
+//!
+//! Rates how much damage a command line could do if it runs unreviewed.
+//! Used by `tb watch --risk` to color a live tail of agent activity so
+//! a human can spot a destructive step before — or right as — it
+//! happens. Heuristic by design: unknown commands rate Low, and the
+//! High patterns favor recall over precision.
+
+/// How much damage a command could do if it runs unreviewed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskLevel {
+    /// Read-only or easily reversed.
+    Low,
+    /// Mutates state that can usually be recovered.
+    Medium,
+    /// Destroys data, changes security posture, or affects other hosts.
+    High,
+}
+
+impl std::fmt::Display for RiskLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Medium => write!(f, "medium"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Substrings that mark a command High regardless of anything else.
+const HIGH_PATTERNS: &[&str] = &[
+    "rm -rf",
+    "rm -fr",
+    "mkfs",
+    "dd if=",
+    "chmod 777",
+    "chmod -r 777",
+    ":(){",
+    "push --force",
+    "push -f",
+    "reset --hard",
+    "clean -fd",
+    "drop table",
+    "drop database",
+    "truncate table",
+    "delete from",
+    "shutdown",
+    "reboot",
+    "| sh",
+    "| bash",
+    "|sh",
+    "|bash",
+];
+
+/// Leading words that mark a command at least Medium.
+const MEDIUM_COMMANDS: &[&str] = &[
+    "rm", "rmdir", "mv", "kill", "killall", "pkill", "chmod", "chown", "truncate",
+];
+
+/// Subcommands (second word) that mark specific tools Medium.
+const MEDIUM_SUBCOMMANDS: &[(&str, &str)] = &[
+    ("git", "push"),
+    ("git", "rebase"),
+    ("kubectl", "delete"),
+    ("kubectl", "drain"),
+    ("docker", "rm"),
+    ("docker", "rmi"),
+    ("docker", "prune"),
+    ("terraform", "apply"),
+    ("terraform", "destroy"),
+];
+
+/// Classifies one command line. `sudo` escalates the rating of whatever
+/// it wraps by one level.
+pub fn classify_risk(raw: &str) -> RiskLevel {
+    let lowered = raw.to_lowercase();
+    let trimmed = lowered.trim();
+
+    let (escalated, rest) = match trimmed.strip_prefix("sudo ") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, trimmed),
+    };
+
+    let base = classify_unescalated(rest);
+    match (base, escalated) {
+        (RiskLevel::Low, true) => RiskLevel::Medium,
+        (RiskLevel::Medium, true) => RiskLevel::High,
+        (level, _) => level,
+    }
+}
+
+fn classify_unescalated(command: &str) -> RiskLevel {
+    if HIGH_PATTERNS.iter().any(|p| command.contains(p)) {
+        return RiskLevel::High;
+    }
+
+    let mut words = command.split_whitespace();
+    let first = words.next().unwrap_or("");
+    let second = words.next().unwrap_or("");
+
+    if MEDIUM_COMMANDS.contains(&first) {
+        return RiskLevel::Medium;
+    }
+    if MEDIUM_SUBCOMMANDS
+        .iter()
+        .any(|(tool, sub)| *tool == first && *sub == second)
+    {
+        return RiskLevel::Medium;
+    }
+
+    RiskLevel::Low
+}
+
+/// True for commands worth a pre-flight working-tree snapshot: ones
+/// that rewrite git history or delete files in ways git alone cannot
+/// always undo. Deliberately broader than the High risk patterns — a
+/// plain `git checkout` can still discard local edits.
+pub fn needs_undo_snapshot(raw: &str) -> bool {
+    let lowered = raw.to_lowercase();
+    let command = lowered.trim().strip_prefix("sudo ").unwrap_or(lowered.trim());
+
+    command.starts_with("rm ")
+        || command.starts_with("git rebase")
+        || command.starts_with("git clean")
+        || command.starts_with("git checkout")
+        || (command.starts_with("git reset") && command.contains("--hard"))
+        || command.starts_with("git stash drop")
+        || command.starts_with("git stash pop")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destructive_commands_rate_high() {
+        assert_eq!(classify_risk("rm -rf /var/data"), RiskLevel::High);
+        assert_eq!(classify_risk("git push --force origin main"), RiskLevel::High);
+        assert_eq!(classify_risk("curl https://x.sh | bash"), RiskLevel::High);
+        assert_eq!(classify_risk("psql -c 'DROP TABLE users'"), RiskLevel::High);
+    }
+
+    #[test]
+    fn test_mutating_commands_rate_medium() {
+        assert_eq!(classify_risk("rm build.log"), RiskLevel::Medium);
+        assert_eq!(classify_risk("git push origin main"), RiskLevel::Medium);
+        assert_eq!(classify_risk("kubectl delete pod web-1"), RiskLevel::Medium);
+    }
+
+    #[test]
+    fn test_sudo_escalates_one_level() {
+        assert_eq!(classify_risk("sudo ls /root"), RiskLevel::Medium);
+        assert_eq!(classify_risk("sudo chmod 600 key.pem"), RiskLevel::High);
+    }
+
+    #[test]
+    fn test_snapshot_worthy_commands() {
+        assert!(needs_undo_snapshot("git rebase -i HEAD~3"));
+        assert!(needs_undo_snapshot("git reset --hard origin/main"));
+        assert!(needs_undo_snapshot("rm -r target"));
+        assert!(!needs_undo_snapshot("git reset --soft HEAD~1"));
+        assert!(!needs_undo_snapshot("git status"));
+    }
+
+    #[test]
+    fn test_everyday_commands_rate_low() {
+        assert_eq!(classify_risk("ls -la"), RiskLevel::Low);
+        assert_eq!(classify_risk("git status"), RiskLevel::Low);
+        assert_eq!(classify_risk("cargo test"), RiskLevel::Low);
+    }
+}
@@ -0,0 +1,107 @@
+//! Query-intent classification for search routing
+//!
+//! Inspects a search string and guesses which search strategy will serve
+//! it best: error messages match better semantically, exact flags and
+//! tokens need keyword matching, and natural language questions benefit
+//! from AI-assisted answering.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The inferred intent behind a search query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryIntent {
+    /// Exact token or flag lookup, e.g. `--no-verify` or `rsync`
+    Keyword,
+    /// Pasted error output, e.g. `ENOENT: no such file or directory`
+    ErrorMessage,
+    /// Natural language question, e.g. `how did I deploy last week`
+    NaturalLanguage,
+}
+
+impl fmt::Display for QueryIntent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryIntent::Keyword => write!(f, "keyword"),
+            QueryIntent::ErrorMessage => write!(f, "error-message"),
+            QueryIntent::NaturalLanguage => write!(f, "natural-language"),
+        }
+    }
+}
+
+/// Words that commonly open a natural language question.
+const QUESTION_WORDS: &[&str] = &[
+    "how", "what", "why", "when", "where", "which", "who", "can", "did", "do", "does", "show",
+];
+
+/// Substrings that strongly suggest pasted error output.
+const ERROR_MARKERS: &[&str] = &[
+    "error", "failed", "failure", "exception", "panic", "traceback", "not found", "denied",
+    "cannot", "fatal", "unexpected", "refused", "timeout", "enoent", "eacces",
+];
+
+/// Classifies a search string so the caller can route it to the most
+/// appropriate search strategy.
+pub fn classify_query(query: &str) -> QueryIntent {
+    let trimmed = query.trim();
+    let lowered = trimmed.to_lowercase();
+    let words: Vec<&str> = trimmed.split_whitespace().collect();
+
+    // A single token, or anything that looks like a flag or path, is an
+    // exact lookup: keyword search will find it, semantic search may not.
+    if words.len() <= 1 || words.iter().any(|w| w.starts_with('-') || w.starts_with('/')) {
+        return QueryIntent::Keyword;
+    }
+
+    if ERROR_MARKERS.iter().any(|marker| lowered.contains(marker)) {
+        return QueryIntent::ErrorMessage;
+    }
+
+    let is_question = trimmed.ends_with('?')
+        || words
+            .first()
+            .map(|w| QUESTION_WORDS.contains(&w.to_lowercase().as_str()))
+            .unwrap_or(false);
+
+    if is_question && words.len() >= 3 {
+        return QueryIntent::NaturalLanguage;
+    }
+
+    QueryIntent::Keyword
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_queries_are_keyword() {
+        assert_eq!(classify_query("--no-verify"), QueryIntent::Keyword);
+        assert_eq!(classify_query("git rebase --onto"), QueryIntent::Keyword);
+        assert_eq!(classify_query("rsync"), QueryIntent::Keyword);
+    }
+
+    #[test]
+    fn test_error_output_is_error_message() {
+        assert_eq!(
+            classify_query("ENOENT: no such file or directory"),
+            QueryIntent::ErrorMessage
+        );
+        assert_eq!(
+            classify_query("connection refused by remote host"),
+            QueryIntent::ErrorMessage
+        );
+    }
+
+    #[test]
+    fn test_questions_are_natural_language() {
+        assert_eq!(
+            classify_query("how did I set up the database"),
+            QueryIntent::NaturalLanguage
+        );
+        assert_eq!(
+            classify_query("what was that docker command?"),
+            QueryIntent::NaturalLanguage
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Heuristic sessionization for imported history
+//!
+//! Shell history files carry no session ids, so imported commands would
+//! otherwise collapse into one giant session and ruin pattern, session
+//! and statistics analysis. This module infers session boundaries from
+//! time gaps and working-directory continuity.
+
+use crate::domain::entities::Command;
+use chrono::Duration;
+
+/// Idle gap after which a new session is assumed.
+const SESSION_GAP_MINUTES: i64 = 30;
+
+/// A directory change within a shorter gap still starts a new session:
+/// jumping projects usually means a new terminal or a new task.
+const DIRECTORY_CHANGE_GAP_MINUTES: i64 = 10;
+
+/// Assigns inferred session ids to commands that lack real ones.
+///
+/// Commands must be sorted oldest-first. Each inferred session id is
+/// prefixed with `imported-` so it can never collide with live ids
+/// produced by shell integration.
+pub fn assign_inferred_sessions(commands: &mut [Command]) {
+    let mut session_index: u64 = 0;
+    let mut previous: Option<(chrono::DateTime<chrono::Utc>, String)> = None;
+
+    for cmd in commands.iter_mut() {
+        if let Some((prev_time, prev_dir)) = &previous {
+            let gap = cmd.timestamp - *prev_time;
+            let directory_changed = &cmd.working_directory != prev_dir;
+
+            let new_session = gap > Duration::minutes(SESSION_GAP_MINUTES)
+                || (directory_changed && gap > Duration::minutes(DIRECTORY_CHANGE_GAP_MINUTES));
+
+            if new_session {
+                session_index += 1;
+            }
+        }
+
+        previous = Some((cmd.timestamp, cmd.working_directory.clone()));
+        cmd.session_id = format!(
+            "imported-{}-{}",
+            cmd.timestamp.format("%Y%m%d"),
+            session_index
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::{TimeZone, Utc};
+
+    fn command_at(minute: u32, dir: &str) -> Command {
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: "git status".to_string(),
+            parsed_command: "git".to_string(),
+            arguments: vec!["status".to_string()],
+            working_directory: dir.to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, minute / 60, minute % 60, 0).unwrap(),
+            session_id: String::new(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_time_gap_starts_new_session() {
+        let mut commands = vec![
+            command_at(0, "/work"),
+            command_at(5, "/work"),
+            command_at(60, "/work"), // 55 minute gap
+        ];
+
+        assign_inferred_sessions(&mut commands);
+
+        assert_eq!(commands[0].session_id, commands[1].session_id);
+        assert_ne!(commands[1].session_id, commands[2].session_id);
+    }
+
+    #[test]
+    fn test_directory_change_with_pause_starts_new_session() {
+        let mut commands = vec![
+            command_at(0, "/work/project-a"),
+            command_at(15, "/work/project-b"), // new dir after 15 min
+        ];
+
+        assign_inferred_sessions(&mut commands);
+
+        assert_ne!(commands[0].session_id, commands[1].session_id);
+    }
+
+    #[test]
+    fn test_quick_directory_hop_stays_in_session() {
+        let mut commands = vec![
+            command_at(0, "/work/project-a"),
+            command_at(2, "/work/project-a/src"), // cd within the task
+        ];
+
+        assign_inferred_sessions(&mut commands);
+
+        assert_eq!(commands[0].session_id, commands[1].session_id);
+    }
+}
@@ -0,0 +1,192 @@
+//! Shell history file parsing
+//!
+//! Parsers for the three common history formats so `tb import` can
+//! seed a fresh database from a user's existing bash, zsh, or fish
+//! history. Timestamps are best-effort: bash only records them with
+//! HISTTIMEFORMAT set, zsh only in extended format.
+
+use chrono::{DateTime, TimeZone, Utc};
+
+/// One entry parsed from a shell history file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedCommand {
+    pub raw: String,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+fn entry(raw: &str, timestamp: Option<DateTime<Utc>>) -> Option<ImportedCommand> {
+    let raw = raw.trim();
+    (!raw.is_empty()).then(|| ImportedCommand {
+        raw: raw.to_string(),
+        timestamp,
+    })
+}
+
+fn from_epoch(epoch: &str) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(epoch.parse().ok()?, 0).single()
+}
+
+/// Parses bash history. With HISTTIMEFORMAT set, bash writes a
+/// `#<epoch>` comment line before each command.
+pub fn parse_bash_history(content: &str) -> Vec<ImportedCommand> {
+    let mut entries = Vec::new();
+    let mut pending_timestamp = None;
+
+    for line in content.lines() {
+        if let Some(epoch) = line.strip_prefix('#') {
+            if let Some(timestamp) = from_epoch(epoch.trim()) {
+                pending_timestamp = Some(timestamp);
+                continue;
+            }
+        }
+        if let Some(e) = entry(line, pending_timestamp.take()) {
+            entries.push(e);
+        }
+    }
+
+    entries
+}
+
+/// Parses zsh history, including the extended format
+/// `: <epoch>:<duration>;<command>`.
+pub fn parse_zsh_history(content: &str) -> Vec<ImportedCommand> {
+    content
+        .lines()
+        .filter_map(|line| {
+            if let Some(rest) = line.strip_prefix(": ") {
+                if let Some((meta, command)) = rest.split_once(';') {
+                    let epoch = meta.split(':').next().unwrap_or("");
+                    return entry(command, from_epoch(epoch.trim()));
+                }
+            }
+            entry(line, None)
+        })
+        .collect()
+}
+
+/// Parses fish history (YAML-ish `- cmd:` / `  when:` pairs).
+pub fn parse_fish_history(content: &str) -> Vec<ImportedCommand> {
+    let mut entries: Vec<ImportedCommand> = Vec::new();
+
+    for line in content.lines() {
+        if let Some(command) = line.strip_prefix("- cmd:") {
+            if let Some(e) = entry(command, None) {
+                entries.push(e);
+            }
+        } else if let Some(epoch) = line.trim_start().strip_prefix("when:") {
+            if let Some(last) = entries.last_mut() {
+                last.timestamp = from_epoch(epoch.trim());
+            }
+        }
+    }
+
+    entries
+}
+
+/// Deduplicates by raw command, keeping the most recent timestamp seen
+/// for each, and returns entries in chronological order (undated
+/// entries first, preserving file order).
+pub fn dedupe_imported(entries: Vec<ImportedCommand>) -> Vec<ImportedCommand> {
+    let mut deduped: Vec<ImportedCommand> = Vec::new();
+
+    for e in entries {
+        match deduped.iter_mut().find(|existing| existing.raw == e.raw) {
+            Some(existing) => {
+                if e.timestamp > existing.timestamp {
+                    existing.timestamp = e.timestamp;
+                }
+            }
+            None => deduped.push(e),
+        }
+    }
+
+    deduped.sort_by_key(|e| e.timestamp);
+    deduped
+}
+
+/// Content fingerprint of an imported entry (command text, timestamp,
+/// host), used by the import ledger to make re-runs idempotent. Entries
+/// without a timestamp hash the empty string — a parser's mtime
+/// fallback would shift between runs and defeat the dedup.
+pub fn import_fingerprint(
+    raw: &str,
+    timestamp: Option<&chrono::DateTime<chrono::Utc>>,
+    host: &str,
+) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(raw.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(timestamp.map(|t| t.to_rfc3339()).unwrap_or_default().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(host.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_bash_with_and_without_timestamps() {
+        let entries = parse_bash_history("#1700000000\ngit status\nls -la\n");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].raw, "git status");
+        assert_eq!(entries[0].timestamp, Utc.timestamp_opt(1_700_000_000, 0).single());
+        assert_eq!(entries[1].raw, "ls -la");
+        assert_eq!(entries[1].timestamp, None);
+    }
+
+    #[test]
+    fn test_parses_zsh_extended_format() {
+        let entries = parse_zsh_history(": 1700000000:5;cargo build\nplain command\n");
+        assert_eq!(entries[0].raw, "cargo build");
+        assert_eq!(entries[0].timestamp, Utc.timestamp_opt(1_700_000_000, 0).single());
+        assert_eq!(entries[1].raw, "plain command");
+        assert_eq!(entries[1].timestamp, None);
+    }
+
+    #[test]
+    fn test_parses_fish_history() {
+        let entries = parse_fish_history("- cmd: git push\n  when: 1700000000\n- cmd: ls\n");
+        assert_eq!(entries[0].raw, "git push");
+        assert_eq!(entries[0].timestamp, Utc.timestamp_opt(1_700_000_000, 0).single());
+        assert_eq!(entries[1].raw, "ls");
+    }
+
+    #[test]
+    fn test_dedupes_keeping_latest_timestamp() {
+        let entries = vec![
+            ImportedCommand {
+                raw: "git status".to_string(),
+                timestamp: Utc.timestamp_opt(1_700_000_000, 0).single(),
+            },
+            ImportedCommand {
+                raw: "git status".to_string(),
+                timestamp: Utc.timestamp_opt(1_700_000_100, 0).single(),
+            },
+            ImportedCommand {
+                raw: "ls".to_string(),
+                timestamp: None,
+            },
+        ];
+
+        let deduped = dedupe_imported(entries);
+        assert_eq!(deduped.len(), 2);
+        assert_eq!(deduped[0].raw, "ls");
+        assert_eq!(
+            deduped[1].timestamp,
+            Utc.timestamp_opt(1_700_000_100, 0).single()
+        );
+    }
+
+    #[test]
+    fn test_import_fingerprint_is_stable_and_distinct() {
+        let ts = Utc.timestamp_opt(1_700_000_000, 0).single().unwrap();
+        let a = import_fingerprint("ls -la", Some(&ts), "laptop");
+        assert_eq!(a, import_fingerprint("ls -la", Some(&ts), "laptop"));
+        assert_ne!(a, import_fingerprint("ls -la", Some(&ts), "server"));
+        assert_ne!(a, import_fingerprint("ls -la", None, "laptop"));
+    }
+}
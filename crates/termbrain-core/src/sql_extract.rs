@@ -0,0 +1,151 @@
+//! SQL extraction from database CLI invocations
+//!
+//! `psql -c "ALTER TABLE ..."`, `mysql -e "..."`, `sqlite3 db.sqlite
+//! "..."` and heredoc variants carry SQL inside the command line.
+//! Pulling the statement out and indexing it separately lets search and
+//! analytics treat database work as SQL instead of opaque argument
+//! soup.
+
+/// Which flags carry inline SQL per database CLI, and whether a bare
+/// positional (after the database argument) is a statement.
+const SQL_CLIS: &[(&str, &[&str], usize)] = &[
+    // (tool, sql flags, positional index carrying SQL — usize::MAX for none)
+    ("psql", &["-c", "--command"], usize::MAX),
+    ("mysql", &["-e", "--execute"], usize::MAX),
+    ("sqlite3", &[], 1),
+];
+
+/// Extracts the SQL text from a database CLI invocation, from an inline
+/// flag, a statement positional, or a heredoc body. None when the
+/// command isn't a recognized database CLI or carries no SQL.
+pub fn extract_sql(raw: &str) -> Option<String> {
+    let first_line = raw.lines().next().unwrap_or(raw);
+    let tokens = shell_tokens(first_line);
+    let tool = tokens.first()?.rsplit('/').next()?.to_string();
+    let (_, flags, positional_index) = SQL_CLIS.iter().find(|(name, _, _)| *name == tool)?;
+
+    // Inline flag: the next token, or the =value tail
+    let mut iter = tokens.iter().skip(1);
+    while let Some(token) = iter.next() {
+        if flags.contains(&token.as_str()) {
+            return iter.next().map(|sql| sql.trim().to_string()).filter(|s| !s.is_empty());
+        }
+        if let Some(flag) = flags.iter().find(|flag| token.starts_with(&format!("{}=", flag))) {
+            let sql = token[flag.len() + 1..].trim();
+            return (!sql.is_empty()).then(|| sql.to_string());
+        }
+    }
+
+    // Heredoc: everything between the delimiter line and its closer
+    if let Some(body) = heredoc_body(raw) {
+        return Some(body);
+    }
+
+    // Statement positional (sqlite3 db.sqlite "SELECT ...")
+    if *positional_index != usize::MAX {
+        let positionals: Vec<&String> = tokens
+            .iter()
+            .skip(1)
+            .filter(|token| !token.starts_with('-'))
+            .collect();
+        if let Some(sql) = positionals.get(*positional_index) {
+            let sql = sql.trim();
+            if !sql.is_empty() {
+                return Some(sql.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+/// The leading SQL verb (lowercased), for grouping database activity.
+pub fn sql_verb(sql: &str) -> Option<&'static str> {
+    const VERBS: &[&str] = &[
+        "select", "insert", "update", "delete", "alter", "create", "drop", "truncate",
+        "grant", "revoke", "vacuum", "explain", "copy", "begin", "commit", "rollback",
+    ];
+    let first = sql.split_whitespace().next()?.to_lowercase();
+    VERBS.iter().find(|verb| **verb == first).copied()
+}
+
+/// Splits a command line into tokens, honoring single and double quotes
+/// (quotes are stripped from the result).
+fn shell_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            None => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// The body of a `<<EOF ... EOF` heredoc, when the recorded command
+/// kept its newlines.
+fn heredoc_body(raw: &str) -> Option<String> {
+    let marker = raw.find("<<")?;
+    let delimiter = raw[marker + 2..]
+        .trim_start_matches('-')
+        .split_whitespace()
+        .next()?
+        .trim_matches(|c| c == '\'' || c == '"')
+        .to_string();
+    let mut lines = raw.lines();
+    lines.next()?; // the invocation line
+    let body: Vec<&str> = lines.take_while(|line| line.trim() != delimiter).collect();
+    let body = body.join("\n").trim().to_string();
+    (!body.is_empty()).then_some(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_inline_and_positional_sql() {
+        assert_eq!(
+            extract_sql("psql mydb -c 'ALTER TABLE users ADD email TEXT'").as_deref(),
+            Some("ALTER TABLE users ADD email TEXT")
+        );
+        assert_eq!(
+            extract_sql("mysql --execute=\"SELECT 1\" app").as_deref(),
+            Some("SELECT 1")
+        );
+        assert_eq!(
+            extract_sql("sqlite3 app.db 'select count(*) from users'").as_deref(),
+            Some("select count(*) from users")
+        );
+        assert!(extract_sql("psql mydb").is_none());
+        assert!(extract_sql("grep -c pattern file").is_none());
+    }
+
+    #[test]
+    fn extracts_heredoc_body() {
+        let raw = "psql mydb <<EOF\nALTER TABLE users\n  ADD email TEXT;\nEOF";
+        assert_eq!(
+            extract_sql(raw).as_deref(),
+            Some("ALTER TABLE users\n  ADD email TEXT;")
+        );
+    }
+
+    #[test]
+    fn verb_grouping() {
+        assert_eq!(sql_verb("ALTER TABLE x ADD y"), Some("alter"));
+        assert_eq!(sql_verb("select 1"), Some("select"));
+        assert_eq!(sql_verb(".tables"), None);
+    }
+}
@@ -0,0 +1,57 @@
+//! Terminal visualization primitives
+//!
+//! Unicode-block rendering shared by the stats views: sparklines for
+//! per-day trends and intensity glyphs for the activity heatmap. Pure
+//! string construction — callers own layout and labels.
+
+/// Eight-level bar glyphs, lowest first.
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Five-level heat glyphs: none, then light through peak.
+const HEAT_LEVELS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// Renders values as a sparkline scaled to the series maximum. An
+/// all-zero (or empty) series renders as a flat baseline.
+pub fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().copied().fold(0.0_f64, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if max <= 0.0 || *value <= 0.0 {
+                SPARK_LEVELS[0]
+            } else {
+                let level = ((value / max) * 7.0).round() as usize;
+                SPARK_LEVELS[level.min(7)]
+            }
+        })
+        .collect()
+}
+
+/// The heat glyph for one cell, scaled to the grid maximum. Zero is
+/// always the "none" glyph; any activity gets at least the lightest.
+pub fn heat_glyph(value: u64, max: u64) -> char {
+    if value == 0 || max == 0 {
+        return HEAT_LEVELS[0];
+    }
+    let level = ((value as f64 / max as f64) * 4.0).ceil() as usize;
+    HEAT_LEVELS[level.clamp(1, 4)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparkline_scales_to_the_maximum() {
+        assert_eq!(sparkline(&[0.0, 5.0, 10.0]), "▁▅█");
+        assert_eq!(sparkline(&[0.0, 0.0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn heat_glyphs_distinguish_none_from_light() {
+        assert_eq!(heat_glyph(0, 10), '·');
+        assert_eq!(heat_glyph(1, 10), '░');
+        assert_eq!(heat_glyph(10, 10), '█');
+    }
+}
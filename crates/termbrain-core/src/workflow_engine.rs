@@ -0,0 +1,179 @@
+//! Workflow step evaluation: `{{variable}}` substitution and
+//! exit-code conditionals. Actually spawning processes is the CLI's job.
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::domain::entities::StepCondition;
+
+/// Replaces every `{{name}}` placeholder in `template` with its value
+/// from `vars`. Unknown placeholders are an error — running a deploy
+/// workflow with `{{branch}}` left literal is worse than stopping.
+pub fn substitute_vars(template: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            // Unterminated braces: leave literally, shell may want them
+            out.push_str(&rest[start..]);
+            return Ok(out);
+        };
+        let name = after[..end].trim();
+        match vars.get(name) {
+            Some(value) => out.push_str(value),
+            None => anyhow::bail!(
+                "Workflow variable '{}' is not set — pass --var {}=<value>",
+                name,
+                name
+            ),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Names of every `{{placeholder}}` in a template, in order of first use.
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let name = after[..end].trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Turns a detected command sequence into a draft workflow: consecutive
+/// duplicate commands collapse into one step and a name is generated
+/// from the first and last commands. Returns `None` when fewer than two
+/// distinct steps remain — a one-command "workflow" is just an alias.
+pub fn draft_from_sequence(
+    sequence: &[String],
+) -> Option<(String, Vec<crate::domain::entities::WorkflowStep>)> {
+    let mut commands: Vec<&String> = Vec::new();
+    for command in sequence {
+        if commands.last() != Some(&command) {
+            commands.push(command);
+        }
+    }
+    if commands.len() < 2 {
+        return None;
+    }
+
+    let name = format!(
+        "{}-then-{}",
+        name_fragment(commands[0]),
+        name_fragment(commands[commands.len() - 1])
+    );
+    let steps = commands
+        .iter()
+        .enumerate()
+        .map(|(index, command)| crate::domain::entities::WorkflowStep {
+            order: index as u32 + 1,
+            command: (*command).clone(),
+            description: None,
+            expected_outcome: None,
+            env: Default::default(),
+            when: Default::default(),
+            confirm: false,
+            timeout_secs: None,
+        })
+        .collect();
+    Some((name, steps))
+}
+
+/// Kebab-cased leading words of a command, for generated names.
+fn name_fragment(command: &str) -> String {
+    command
+        .split_whitespace()
+        .take(2)
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Whether a step should run, given whether every step so far succeeded.
+pub fn should_run(when: StepCondition, all_succeeded: bool) -> bool {
+    match when {
+        StepCondition::OnSuccess => all_succeeded,
+        StepCondition::OnFailure => !all_succeeded,
+        StepCondition::Always => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_substitutes_known_vars() {
+        let result =
+            substitute_vars("git push origin {{branch}}", &vars(&[("branch", "main")])).unwrap();
+        assert_eq!(result, "git push origin main");
+
+        // Whitespace inside braces is tolerated
+        let result = substitute_vars("echo {{ branch }}", &vars(&[("branch", "dev")])).unwrap();
+        assert_eq!(result, "echo dev");
+    }
+
+    #[test]
+    fn test_unknown_var_is_an_error() {
+        let err = substitute_vars("deploy {{env}}", &vars(&[])).unwrap_err();
+        assert!(err.to_string().contains("'env'"));
+    }
+
+    #[test]
+    fn test_placeholders_listed_once_in_order() {
+        assert_eq!(
+            placeholders("scp {{file}} {{host}}:{{file}}"),
+            vec!["file".to_string(), "host".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_draft_dedups_and_names() {
+        let sequence: Vec<String> = ["git add .", "git add .", "git commit", "git push"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (name, steps) = draft_from_sequence(&sequence).unwrap();
+        assert_eq!(name, "git-add-then-git-push");
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].command, "git add .");
+        assert_eq!(steps[2].order, 3);
+
+        // A single repeated command is not a workflow
+        let repeated: Vec<String> = vec!["ls".to_string(), "ls".to_string()];
+        assert!(draft_from_sequence(&repeated).is_none());
+    }
+
+    #[test]
+    fn test_should_run_conditions() {
+        assert!(should_run(StepCondition::OnSuccess, true));
+        assert!(!should_run(StepCondition::OnSuccess, false));
+        assert!(should_run(StepCondition::OnFailure, false));
+        assert!(!should_run(StepCondition::OnFailure, true));
+        assert!(should_run(StepCondition::Always, false));
+    }
+}
@@ -0,0 +1,151 @@
+//! Decayed working-set model
+//!
+//! Tracks which projects (directories) and tools the user is actively
+//! working with right now. Every recorded command contributes a weight
+//! that decays exponentially with age, so last month's big refactor no
+//! longer drowns out this week's work. Suggestion and digest surfaces
+//! filter to the working set by default to reduce noise.
+
+use crate::domain::entities::Command;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Half-life of a command's contribution to working-set weights.
+const HALF_LIFE_DAYS: f64 = 7.0;
+
+/// Minimum share of total decayed weight a directory or tool needs to
+/// count as part of the working set.
+const MIN_WEIGHT_SHARE: f64 = 0.02;
+
+/// The user's currently-active directories and tools, with decayed
+/// weights normalized to sum to 1.0 per dimension.
+#[derive(Debug, Clone, Default)]
+pub struct WorkingSet {
+    directories: HashMap<String, f64>,
+    tools: HashMap<String, f64>,
+}
+
+impl WorkingSet {
+    /// Builds the working set from history, decaying each command's
+    /// contribution by its age relative to `now`.
+    pub fn from_commands(commands: &[Command], now: DateTime<Utc>) -> Self {
+        let mut directories: HashMap<String, f64> = HashMap::new();
+        let mut tools: HashMap<String, f64> = HashMap::new();
+
+        for cmd in commands {
+            let age_days = (now - cmd.timestamp).num_seconds() as f64 / 86_400.0;
+            let weight = 0.5_f64.powf(age_days.max(0.0) / HALF_LIFE_DAYS);
+
+            *directories.entry(cmd.working_directory.clone()).or_insert(0.0) += weight;
+            *tools.entry(cmd.parsed_command.clone()).or_insert(0.0) += weight;
+        }
+
+        normalize(&mut directories);
+        normalize(&mut tools);
+
+        // Drop entries below the share threshold: they are history, not
+        // the working set.
+        directories.retain(|_, w| *w >= MIN_WEIGHT_SHARE);
+        tools.retain(|_, w| *w >= MIN_WEIGHT_SHARE);
+
+        Self { directories, tools }
+    }
+
+    /// Whether a command belongs to the current working set, i.e. its
+    /// directory or tool still carries meaningful decayed weight.
+    pub fn contains(&self, command: &Command) -> bool {
+        self.directories.contains_key(&command.working_directory)
+            || self.tools.contains_key(&command.parsed_command)
+    }
+
+    /// Active directories, most heavily weighted first.
+    pub fn directories(&self) -> Vec<(&str, f64)> {
+        sorted_entries(&self.directories)
+    }
+
+    /// Active tools, most heavily weighted first.
+    pub fn tools(&self) -> Vec<(&str, f64)> {
+        sorted_entries(&self.tools)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.directories.is_empty() && self.tools.is_empty()
+    }
+}
+
+fn normalize(weights: &mut HashMap<String, f64>) {
+    let total: f64 = weights.values().sum();
+    if total > 0.0 {
+        for weight in weights.values_mut() {
+            *weight /= total;
+        }
+    }
+}
+
+fn sorted_entries(weights: &HashMap<String, f64>) -> Vec<(&str, f64)> {
+    let mut entries: Vec<(&str, f64)> = weights.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+    entries.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CommandMetadata;
+    use chrono::Duration;
+
+    fn command_at(raw: &str, dir: &str, age_days: i64, now: DateTime<Utc>) -> Command {
+        Command {
+            id: uuid::Uuid::new_v4(),
+            raw: raw.to_string(),
+            parsed_command: raw.split_whitespace().next().unwrap().to_string(),
+            arguments: Vec::new(),
+            working_directory: dir.to_string(),
+            exit_code: 0,
+            duration_ms: 0,
+            timestamp: now - Duration::days(age_days),
+            session_id: "test".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
+            metadata: CommandMetadata {
+                shell: "bash".to_string(),
+                user: "test".to_string(),
+                hostname: "test".to_string(),
+                terminal: "xterm".to_string(),
+                environment: std::collections::HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_recent_work_dominates_old_work() {
+        let now = Utc::now();
+        let mut commands = Vec::new();
+        for _ in 0..5 {
+            commands.push(command_at("cargo build", "/work/new-project", 0, now));
+        }
+        for _ in 0..20 {
+            commands.push(command_at("make all", "/work/old-project", 90, now));
+        }
+
+        let working_set = WorkingSet::from_commands(&commands, now);
+        let dirs = working_set.directories();
+
+        assert_eq!(dirs[0].0, "/work/new-project");
+        assert!(working_set.contains(&command_at("cargo test", "/work/new-project", 0, now)));
+    }
+
+    #[test]
+    fn test_stale_context_falls_out_of_working_set() {
+        let now = Utc::now();
+        let mut commands = vec![command_at("vim notes.txt", "/tmp/stale", 120, now)];
+        for _ in 0..50 {
+            commands.push(command_at("git status", "/work/active", 1, now));
+        }
+
+        let working_set = WorkingSet::from_commands(&commands, now);
+
+        assert!(!working_set.contains(&command_at("vim notes.txt", "/tmp/stale", 120, now)));
+    }
+}
@@ -1,5 +1,6 @@
 //! Storage implementations for TermBrain
 
 pub mod sqlite;
+pub mod vault;
 
 pub use sqlite::SqliteStorage;
\ No newline at end of file
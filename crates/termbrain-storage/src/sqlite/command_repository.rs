@@ -4,37 +4,216 @@ use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sqlx::{SqlitePool, Row};
-use termbrain_core::domain::{Command, CommandRepository, CommandMetadata};
+use termbrain_core::domain::{Command, CommandFilter, CommandRepository, CommandMetadata, HybridWeights, UserScope};
 use uuid::Uuid;
 use std::collections::HashMap;
 
+use super::vector_index::VectorIndex;
+use crate::vault::{self, VaultCipher};
+
+/// Column list shared by every read query.
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, raw, parsed_command, arguments, working_directory,
+           exit_code, duration_ms, timestamp, session_id, source,
+           shell, user, hostname, terminal, environment, extras,
+           logical_clock
+    FROM commands
+"#;
+
 pub struct SqliteCommandRepository {
     pool: SqlitePool,
+    scope: UserScope,
+    vector_index: VectorIndex,
+    cipher: Option<VaultCipher>,
 }
 
 impl SqliteCommandRepository {
+    /// Creates an unscoped repository that sees every user's rows.
+    /// Prefer [`SqliteCommandRepository::with_scope`] on shared backends.
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            scope: UserScope::Team,
+            vector_index: VectorIndex::default(),
+            cipher: None,
+        }
+    }
+
+    /// Creates a repository restricted to `scope`. The filter is applied
+    /// inside every query, not in display code.
+    pub fn with_scope(pool: SqlitePool, scope: UserScope) -> Self {
+        Self {
+            pool,
+            scope,
+            vector_index: VectorIndex::default(),
+            cipher: None,
+        }
+    }
+
+    /// Selects how `search_by_vector` ranks embeddings.
+    pub fn with_vector_index(mut self, vector_index: VectorIndex) -> Self {
+        self.vector_index = vector_index;
+        self
+    }
+
+    /// Enables at-rest encryption of `raw` and `arguments` with an
+    /// unlocked vault key. Reads decrypt transparently; without a
+    /// cipher, encrypted rows surface as locked placeholders.
+    pub fn with_cipher(mut self, cipher: VaultCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Encrypts a field value when the vault is unlocked; otherwise
+    /// stores it as-is.
+    fn protect_field(&self, value: &str) -> Result<String> {
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt_field(value),
+            None => Ok(value.to_string()),
+        }
+    }
+
+    /// Reverses `protect_field` on read. Encrypted values without an
+    /// unlocked key come back as `None` so callers can substitute a
+    /// locked placeholder instead of failing the whole query.
+    fn expose_field(&self, stored: String) -> Result<Option<String>> {
+        if !vault::is_encrypted(&stored) {
+            return Ok(Some(stored));
+        }
+        match &self.cipher {
+            Some(cipher) => Ok(Some(cipher.decrypt_field(&stored)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// SQL fragment enforcing the user scope. `has_where` controls
+    /// whether the fragment continues an existing WHERE clause.
+    fn scope_sql(&self, has_where: bool) -> &'static str {
+        match self.scope {
+            UserScope::Team => "",
+            UserScope::User(_) => {
+                if has_where {
+                    " AND user = ? "
+                } else {
+                    " WHERE user = ? "
+                }
+            }
+        }
+    }
+
+    fn scoped_user(&self) -> Option<&str> {
+        match &self.scope {
+            UserScope::Team => None,
+            UserScope::User(user) => Some(user),
+        }
+    }
+
+    /// Exact cosine ranking computed in Rust; fine up to a few thousand
+    /// commands.
+    async fn search_by_vector_flat(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<Command>> {
+        use termbrain_core::embedding::{cosine_similarity, decode_vector};
+
+        let sql = format!(
+            r#"
+            SELECT c.id, c.raw, c.parsed_command, c.arguments, c.working_directory,
+                   c.exit_code, c.duration_ms, c.timestamp, c.session_id, c.source,
+                   c.shell, c.user, c.hostname, c.terminal, c.environment, c.extras,
+                   c.logical_clock, e.vector
+            FROM commands c
+            JOIN embeddings e ON e.command_id = c.id{}
+            "#,
+            match self.scope {
+                UserScope::Team => "",
+                UserScope::User(_) => " WHERE c.user = ?",
+            }
+        );
+
+        let mut query_builder = sqlx::query(&sql);
+        if let Some(user) = self.scoped_user() {
+            query_builder = query_builder.bind(user);
+        }
+
+        let rows = query_builder.fetch_all(&self.pool).await?;
+
+        let mut scored: Vec<(f32, Command)> = rows
+            .into_iter()
+            .map(|row| {
+                let vector = decode_vector(row.get::<Vec<u8>, _>("vector").as_slice());
+                let score = cosine_similarity(query_vector, &vector);
+                Ok((score, self.row_to_command(row)?))
+            })
+            .collect::<Result<_>>()?;
+
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+
+        Ok(scored.into_iter().map(|(_, cmd)| cmd).collect())
+    }
+
+    /// Cosine distance evaluated inside SQLite by sqlite-vec, so only
+    /// `limit` rows ever cross into Rust. All stored vectors must share
+    /// the query's dimension (one embedding model per database).
+    async fn search_by_vector_sqlite_vec(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<Command>> {
+        use termbrain_core::embedding::encode_vector;
+
+        let sql = format!(
+            r#"
+            SELECT c.id, c.raw, c.parsed_command, c.arguments, c.working_directory,
+                   c.exit_code, c.duration_ms, c.timestamp, c.session_id, c.source,
+                   c.shell, c.user, c.hostname, c.terminal, c.environment, c.extras,
+                   c.logical_clock
+            FROM commands c
+            JOIN embeddings e ON e.command_id = c.id{}
+            ORDER BY vec_distance_cosine(e.vector, ?)
+            LIMIT ?
+            "#,
+            match self.scope {
+                UserScope::Team => "",
+                UserScope::User(_) => " WHERE c.user = ?",
+            }
+        );
+
+        let mut query_builder = sqlx::query(&sql);
+        if let Some(user) = self.scoped_user() {
+            query_builder = query_builder.bind(user);
+        }
+        query_builder = query_builder
+            .bind(encode_vector(query_vector))
+            .bind(limit as i64);
+
+        let rows = query_builder.fetch_all(&self.pool).await?;
+        rows.into_iter().map(|row| self.row_to_command(row)).collect()
     }
 }
 
 #[async_trait]
 impl CommandRepository for SqliteCommandRepository {
     async fn save(&self, command: &Command) -> Result<()> {
-        let arguments_json = serde_json::to_string(&command.arguments)?;
+        let raw = self.protect_field(&command.raw)?;
+        let arguments_json = self.protect_field(&serde_json::to_string(&command.arguments)?)?;
         let environment_json = serde_json::to_string(&command.metadata.environment)?;
-        
+        let extras_json = serde_json::to_string(&command.extras)?;
+
         sqlx::query(
             r#"
             INSERT INTO commands (
-                id, raw, parsed_command, arguments, working_directory, 
-                exit_code, duration_ms, timestamp, session_id,
-                shell, user, hostname, terminal, environment
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                id, raw, parsed_command, arguments, working_directory,
+                exit_code, duration_ms, timestamp, session_id, source,
+                shell, user, hostname, terminal, environment, extras,
+                logical_clock
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
             "#,
         )
         .bind(command.id.to_string())
-        .bind(&command.raw)
+        .bind(&raw)
         .bind(&command.parsed_command)
         .bind(&arguments_json)
         .bind(&command.working_directory)
@@ -42,196 +221,449 @@ impl CommandRepository for SqliteCommandRepository {
         .bind(command.duration_ms as i64)
         .bind(command.timestamp.to_rfc3339())
         .bind(&command.session_id)
+        .bind(&command.source)
         .bind(&command.metadata.shell)
         .bind(&command.metadata.user)
         .bind(&command.metadata.hostname)
         .bind(&command.metadata.terminal)
         .bind(&environment_json)
+        .bind(&extras_json)
+        .bind(&command.logical_clock)
         .execute(&self.pool)
         .await?;
-        
+
+        Ok(())
+    }
+
+    async fn save_batch(&self, commands: &[Command]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for command in commands {
+            let raw = self.protect_field(&command.raw)?;
+            let arguments_json = self.protect_field(&serde_json::to_string(&command.arguments)?)?;
+            let environment_json = serde_json::to_string(&command.metadata.environment)?;
+            let extras_json = serde_json::to_string(&command.extras)?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO commands (
+                    id, raw, parsed_command, arguments, working_directory,
+                    exit_code, duration_ms, timestamp, session_id, source,
+                    shell, user, hostname, terminal, environment, extras,
+                    logical_clock
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+                "#,
+            )
+            .bind(command.id.to_string())
+            .bind(&raw)
+            .bind(&command.parsed_command)
+            .bind(&arguments_json)
+            .bind(&command.working_directory)
+            .bind(command.exit_code)
+            .bind(command.duration_ms as i64)
+            .bind(command.timestamp.to_rfc3339())
+            .bind(&command.session_id)
+            .bind(&command.source)
+            .bind(&command.metadata.shell)
+            .bind(&command.metadata.user)
+            .bind(&command.metadata.hostname)
+            .bind(&command.metadata.terminal)
+            .bind(&environment_json)
+            .bind(&extras_json)
+            .bind(&command.logical_clock)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
         Ok(())
     }
-    
+
     async fn find_by_id(&self, id: &Uuid) -> Result<Option<Command>> {
-        let result = sqlx::query(
-            r#"
-            SELECT id, raw, parsed_command, arguments, working_directory,
-                   exit_code, duration_ms, timestamp, session_id,
-                   shell, user, hostname, terminal, environment
-            FROM commands 
-            WHERE id = ?1
-            "#,
-        )
-        .bind(id.to_string())
-        .fetch_optional(&self.pool)
-        .await?;
-        
-        Ok(result.map(|row| self.row_to_command(row)).transpose()?)
+        let sql = format!("{} WHERE id = ?{}", SELECT_COLUMNS, self.scope_sql(true));
+
+        let mut query = sqlx::query(&sql).bind(id.to_string());
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        let result = query.fetch_optional(&self.pool).await?;
+
+        result.map(|row| self.row_to_command(row)).transpose()
     }
-    
+
     async fn find_by_session(&self, session_id: &str) -> Result<Vec<Command>> {
-        let results = sqlx::query(
-            r#"
-            SELECT id, raw, parsed_command, arguments, working_directory,
-                   exit_code, duration_ms, timestamp, session_id,
-                   shell, user, hostname, terminal, environment
-            FROM commands 
-            WHERE session_id = ?1
-            ORDER BY timestamp DESC
-            "#,
-        )
-        .bind(session_id)
-        .fetch_all(&self.pool)
-        .await?;
-        
+        let sql = format!(
+            "{} WHERE session_id = ?{} ORDER BY timestamp DESC",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+
+        let mut query = sqlx::query(&sql).bind(session_id);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        let results = query.fetch_all(&self.pool).await?;
+
         self.rows_to_commands(results)
     }
-    
+
     async fn find_recent(&self, limit: usize) -> Result<Vec<Command>> {
-        let results = sqlx::query(
-            r#"
-            SELECT id, raw, parsed_command, arguments, working_directory,
-                   exit_code, duration_ms, timestamp, session_id,
-                   shell, user, hostname, terminal, environment
-            FROM commands 
-            ORDER BY timestamp DESC 
-            LIMIT ?1
-            "#,
-        )
-        .bind(limit as i64)
-        .fetch_all(&self.pool)
-        .await?;
-        
+        let sql = format!(
+            "{}{} ORDER BY timestamp DESC LIMIT ?",
+            SELECT_COLUMNS,
+            self.scope_sql(false)
+        );
+
+        let mut query = sqlx::query(&sql);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+        query = query.bind(limit as i64);
+
+        let results = query.fetch_all(&self.pool).await?;
+
         self.rows_to_commands(results)
     }
-    
+
     async fn find_by_pattern(&self, pattern: &str) -> Result<Vec<Command>> {
-        let results = sqlx::query(
-            r#"
-            SELECT id, raw, parsed_command, arguments, working_directory,
-                   exit_code, duration_ms, timestamp, session_id,
-                   shell, user, hostname, terminal, environment
-            FROM commands 
-            WHERE raw LIKE ?1
-            ORDER BY timestamp DESC
-            "#,
-        )
-        .bind(format!("%{}%", pattern))
-        .fetch_all(&self.pool)
-        .await?;
-        
+        let sql = format!(
+            "{} WHERE raw LIKE ?{} ORDER BY timestamp DESC",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+
+        let mut query = sqlx::query(&sql).bind(format!("%{}%", pattern));
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        let results = query.fetch_all(&self.pool).await?;
+
         self.rows_to_commands(results)
     }
-    
+
     async fn find_by_directory(&self, directory: &str) -> Result<Vec<Command>> {
-        let results = sqlx::query(
-            r#"
-            SELECT id, raw, parsed_command, arguments, working_directory,
-                   exit_code, duration_ms, timestamp, session_id,
-                   shell, user, hostname, terminal, environment
-            FROM commands 
-            WHERE working_directory = ?1
-            ORDER BY timestamp DESC
-            "#,
-        )
-        .bind(directory)
-        .fetch_all(&self.pool)
-        .await?;
-        
+        let sql = format!(
+            "{} WHERE working_directory = ?{} ORDER BY timestamp DESC",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+
+        let mut query = sqlx::query(&sql).bind(directory);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        let results = query.fetch_all(&self.pool).await?;
+
         self.rows_to_commands(results)
     }
-    
+
+    async fn find_by_extra(&self, key: &str, value: &str) -> Result<Vec<Command>> {
+        // json_extract returns the raw value, so both string and
+        // numeric extras compare against their textual form
+        let sql = format!(
+            "{} WHERE json_extract(extras, '$.' || ?) = ?{} ORDER BY timestamp DESC",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+
+        let mut query = sqlx::query(&sql).bind(key).bind(value);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        let results = query.fetch_all(&self.pool).await?;
+
+        self.rows_to_commands(results)
+    }
+
+    async fn update_extras_batch(
+        &self,
+        updates: &[(Uuid, std::collections::HashMap<String, serde_json::Value>)],
+    ) -> Result<()> {
+        let sql = format!(
+            "UPDATE commands SET extras = ? WHERE id = ?{}",
+            self.scope_sql(true)
+        );
+
+        let mut tx = self.pool.begin().await?;
+        for (id, extras) in updates {
+            let extras_json = serde_json::to_string(extras)?;
+            let mut query = sqlx::query(&sql).bind(extras_json).bind(id.to_string());
+            if let Some(user) = self.scoped_user() {
+                query = query.bind(user);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
     async fn find_by_time_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<Command>> {
-        let results = sqlx::query(
+        let sql = format!(
+            "{} WHERE timestamp >= ? AND timestamp <= ?{} ORDER BY timestamp DESC",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+
+        let mut query = sqlx::query(&sql)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        let results = query.fetch_all(&self.pool).await?;
+
+        self.rows_to_commands(results)
+    }
+
+    async fn find_by_time_range_paged(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<Command>> {
+        let sql = format!(
+            "{} WHERE timestamp >= ? AND timestamp <= ?{} ORDER BY timestamp ASC, id ASC LIMIT ? OFFSET ?",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+
+        let mut query = sqlx::query(&sql)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+        query = query.bind(limit as i64).bind(offset as i64);
+
+        let results = query.fetch_all(&self.pool).await?;
+
+        self.rows_to_commands(results)
+    }
+
+    async fn find_filtered_paged(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        filter: &CommandFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<Command>> {
+        let mut sql = format!(
+            "{} WHERE timestamp >= ? AND timestamp <= ?{}",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+
+        if filter.directory.is_some() {
+            // The directory itself plus everything below it
+            sql.push_str(" AND (working_directory = ? OR working_directory LIKE ? || '/%')");
+        }
+        let category_tools = match &filter.semantic_type {
+            Some(category) if category == "other" => {
+                // "other" is everything the classifier doesn't list
+                let all: Vec<_> = termbrain_core::privacy::CATEGORY_TOOLS
+                    .iter()
+                    .flat_map(|(_, tools)| tools.iter().copied())
+                    .collect();
+                sql.push_str(&format!(
+                    " AND parsed_command NOT IN ({})",
+                    vec!["?"; all.len()].join(",")
+                ));
+                Some(all)
+            }
+            Some(category) => {
+                let tools = termbrain_core::privacy::category_tools(category)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown semantic type '{}'", category))?
+                    .to_vec();
+                sql.push_str(&format!(
+                    " AND parsed_command IN ({})",
+                    vec!["?"; tools.len()].join(",")
+                ));
+                Some(tools)
+            }
+            None => None,
+        };
+        if filter.ai_agent.is_some() {
+            sql.push_str(" AND source = 'wrap' AND json_extract(extras, '$.agent') = ?");
+        }
+        if filter.exit_code.is_some() {
+            sql.push_str(" AND exit_code = ?");
+        }
+        sql.push_str(" ORDER BY timestamp ASC, id ASC LIMIT ? OFFSET ?");
+
+        let mut query = sqlx::query(&sql)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+        if let Some(directory) = &filter.directory {
+            let trimmed = directory.trim_end_matches('/').to_string();
+            query = query.bind(trimmed.clone()).bind(trimmed);
+        }
+        if let Some(tools) = category_tools {
+            for tool in tools {
+                query = query.bind(tool);
+            }
+        }
+        if let Some(agent) = &filter.ai_agent {
+            query = query.bind(agent);
+        }
+        if let Some(exit_code) = filter.exit_code {
+            query = query.bind(exit_code);
+        }
+        query = query.bind(limit as i64).bind(offset as i64);
+
+        let results = query.fetch_all(&self.pool).await?;
+
+        self.rows_to_commands(results)
+    }
+
+    async fn find_neighbors(&self, anchor: &Command, before: usize, after: usize) -> Result<Vec<Command>> {
+        // (timestamp, id) breaks ties between commands recorded in the
+        // same millisecond, matching the paged export ordering
+        let before_sql = format!(
+            "{} WHERE session_id = ? AND (timestamp, id) < (?, ?){} ORDER BY timestamp DESC, id DESC LIMIT ?",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+        let after_sql = format!(
+            "{} WHERE session_id = ? AND (timestamp, id) > (?, ?){} ORDER BY timestamp ASC, id ASC LIMIT ?",
+            SELECT_COLUMNS,
+            self.scope_sql(true)
+        );
+
+        let mut neighbors = Vec::new();
+        for (sql, limit) in [(&before_sql, before), (&after_sql, after)] {
+            let mut query = sqlx::query(sql)
+                .bind(&anchor.session_id)
+                .bind(anchor.timestamp.to_rfc3339())
+                .bind(anchor.id.to_string());
+            if let Some(user) = self.scoped_user() {
+                query = query.bind(user);
+            }
+            query = query.bind(limit as i64);
+
+            neighbors.push(self.rows_to_commands(query.fetch_all(&self.pool).await?)?);
+        }
+
+        let (mut older, newer) = (neighbors.remove(0), neighbors.remove(0));
+        older.reverse();
+        older.extend(newer);
+        Ok(older)
+    }
+
+    async fn duration_percentile(&self, parsed_command: &str, duration_ms: u64) -> Result<Option<f64>> {
+        let sql = format!(
             r#"
-            SELECT id, raw, parsed_command, arguments, working_directory,
-                   exit_code, duration_ms, timestamp, session_id,
-                   shell, user, hostname, terminal, environment
-            FROM commands 
-            WHERE timestamp >= ?1 AND timestamp <= ?2
-            ORDER BY timestamp DESC
+            SELECT COUNT(*) AS total,
+                   SUM(CASE WHEN duration_ms <= ? THEN 1 ELSE 0 END) AS not_slower
+            FROM commands
+            WHERE parsed_command = ? AND duration_ms > 0{}
             "#,
-        )
-        .bind(start.to_rfc3339())
-        .bind(end.to_rfc3339())
-        .fetch_all(&self.pool)
-        .await?;
-        
-        self.rows_to_commands(results)
+            self.scope_sql(true)
+        );
+
+        let mut query = sqlx::query(&sql)
+            .bind(duration_ms as i64)
+            .bind(parsed_command);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        let row = query.fetch_one(&self.pool).await?;
+        let total: i64 = row.get("total");
+        // A percentile against fewer than a handful of runs is noise
+        if total < 5 {
+            return Ok(None);
+        }
+        let not_slower: i64 = row.get("not_slower");
+        Ok(Some(not_slower as f64 / total as f64 * 100.0))
     }
-    
+
     async fn delete_by_id(&self, id: &Uuid) -> Result<()> {
-        sqlx::query(r#"DELETE FROM commands WHERE id = ?1"#)
-            .bind(id.to_string())
-            .execute(&self.pool)
-            .await?;
-        
+        let sql = format!("DELETE FROM commands WHERE id = ?{}", self.scope_sql(true));
+
+        let mut query = sqlx::query(&sql).bind(id.to_string());
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        query.execute(&self.pool).await?;
+
         Ok(())
     }
-    
+
     async fn search(&self, query: &str, limit: usize, directory: Option<&str>, since: Option<DateTime<Utc>>) -> Result<Vec<Command>> {
-        let mut sql = r#"
-            SELECT id, raw, parsed_command, arguments, working_directory,
-                   exit_code, duration_ms, timestamp, session_id,
-                   shell, user, hostname, terminal, environment
-            FROM commands 
-            WHERE raw LIKE ?
-        "#.to_string();
-        
+        let mut sql = format!("{} WHERE raw LIKE ?", SELECT_COLUMNS);
+
         if directory.is_some() {
             sql.push_str(" AND working_directory = ?");
         }
-        
+
         if since.is_some() {
             sql.push_str(" AND timestamp >= ?");
         }
-        
+
+        sql.push_str(self.scope_sql(true));
         sql.push_str(" ORDER BY timestamp DESC LIMIT ?");
-        
+
         let search_term = format!("%{}%", query);
         let mut query_builder = sqlx::query(&sql);
         query_builder = query_builder.bind(&search_term);
-        
+
         if let Some(dir) = directory {
             query_builder = query_builder.bind(dir);
         }
-        
+
         if let Some(since_time) = since {
             query_builder = query_builder.bind(since_time.to_rfc3339());
         }
-        
+
+        if let Some(user) = self.scoped_user() {
+            query_builder = query_builder.bind(user);
+        }
+
         query_builder = query_builder.bind(limit as i64);
-        
+
         let results = query_builder.fetch_all(&self.pool).await?;
-        
+
         self.rows_to_commands(results)
     }
-    
+
     async fn search_semantic(&self, query: &str, limit: usize) -> Result<Vec<Command>> {
         // TODO: Implement proper semantic search with sqlite-vec
         // For now, we'll use a more sophisticated text search that looks for
         // similar commands based on keywords
-        
+
         let keywords: Vec<&str> = query.split_whitespace().collect();
         const MAX_KEYWORDS: usize = 10;
-        
+
         if keywords.len() > MAX_KEYWORDS {
             return Err(anyhow::anyhow!(
-                "Too many keywords in search query. Maximum {} keywords supported", 
+                "Too many keywords in search query. Maximum {} keywords supported",
                 MAX_KEYWORDS
             ));
         }
-        
+
         let patterns: Vec<String> = keywords.iter()
             .map(|keyword| format!("%{}%", keyword))
             .collect();
-        
-        let sql = r#"
+
+        let sql = format!(r#"
             WITH keyword_matches AS (
                 SELECT id, raw, parsed_command, arguments, working_directory,
-                       exit_code, duration_ms, timestamp, session_id,
-                       shell, user, hostname, terminal, environment,
+                       exit_code, duration_ms, timestamp, session_id, source,
+                       shell, user, hostname, terminal, environment, extras,
+                       logical_clock,
                        (CASE WHEN raw LIKE ?1 THEN 1 ELSE 0 END +
                         CASE WHEN raw LIKE ?2 THEN 1 ELSE 0 END +
                         CASE WHEN raw LIKE ?3 THEN 1 ELSE 0 END +
@@ -243,19 +675,24 @@ impl CommandRepository for SqliteCommandRepository {
                         CASE WHEN raw LIKE ?9 THEN 1 ELSE 0 END +
                         CASE WHEN raw LIKE ?10 THEN 1 ELSE 0 END) as match_count
                 FROM commands
-                WHERE raw LIKE ?1 OR raw LIKE ?2 OR raw LIKE ?3 OR 
-                      raw LIKE ?4 OR raw LIKE ?5 OR raw LIKE ?6 OR
-                      raw LIKE ?7 OR raw LIKE ?8 OR raw LIKE ?9 OR 
-                      raw LIKE ?10
+                WHERE (raw LIKE ?1 OR raw LIKE ?2 OR raw LIKE ?3 OR
+                       raw LIKE ?4 OR raw LIKE ?5 OR raw LIKE ?6 OR
+                       raw LIKE ?7 OR raw LIKE ?8 OR raw LIKE ?9 OR
+                       raw LIKE ?10){}
             )
             SELECT * FROM keyword_matches
             WHERE match_count > 0
             ORDER BY match_count DESC, timestamp DESC
             LIMIT ?11
-        "#;
-        
-        let mut query_builder = sqlx::query(sql);
-        
+        "#,
+            match self.scope {
+                UserScope::Team => "",
+                UserScope::User(_) => " AND user = ?12",
+            }
+        );
+
+        let mut query_builder = sqlx::query(&sql);
+
         for i in 0..MAX_KEYWORDS {
             if i < patterns.len() {
                 query_builder = query_builder.bind(&patterns[i]);
@@ -263,36 +700,132 @@ impl CommandRepository for SqliteCommandRepository {
                 query_builder = query_builder.bind("__IMPOSSIBLE_PATTERN__");
             }
         }
-        
+
+        query_builder = query_builder.bind(limit as i64);
+
+        if let Some(user) = self.scoped_user() {
+            query_builder = query_builder.bind(user);
+        }
+
+        let results = query_builder.fetch_all(&self.pool).await?;
+
+        self.rows_to_commands(results)
+    }
+
+    async fn search_fulltext(&self, query: &str, limit: usize) -> Result<Vec<Command>> {
+        // The query string is passed straight through so FTS5 syntax
+        // ("docker run", deploy*) keeps working; bm25 ranks best first.
+        let sql = format!(
+            r#"
+            SELECT c.id, c.raw, c.parsed_command, c.arguments, c.working_directory,
+                   c.exit_code, c.duration_ms, c.timestamp, c.session_id, c.source,
+                   c.shell, c.user, c.hostname, c.terminal, c.environment, c.extras,
+                   c.logical_clock
+            FROM commands_fts
+            JOIN commands c ON c.rowid = commands_fts.rowid
+            WHERE commands_fts MATCH ?{}
+            ORDER BY bm25(commands_fts)
+            LIMIT ?
+            "#,
+            match self.scope {
+                UserScope::Team => "",
+                UserScope::User(_) => " AND c.user = ?",
+            }
+        );
+
+        let mut query_builder = sqlx::query(&sql).bind(query);
+        if let Some(user) = self.scoped_user() {
+            query_builder = query_builder.bind(user);
+        }
         query_builder = query_builder.bind(limit as i64);
-        
+
         let results = query_builder.fetch_all(&self.pool).await?;
-        
+
         self.rows_to_commands(results)
     }
 
+    async fn search_by_vector(&self, query_vector: &[f32], limit: usize) -> Result<Vec<Command>> {
+        match self.vector_index {
+            VectorIndex::Flat => self.search_by_vector_flat(query_vector, limit).await,
+            VectorIndex::SqliteVec => self.search_by_vector_sqlite_vec(query_vector, limit).await,
+        }
+    }
+
+    async fn search_hybrid(&self, query: &str, limit: usize, weights: &HybridWeights) -> Result<Vec<Command>> {
+        // Constant from the original RRF paper; dampens the influence of
+        // top-ranked results so a single strategy can't dominate the fusion.
+        const RRF_K: f32 = 60.0;
+
+        // Fetch more candidates than requested from each strategy so that
+        // commands ranked moderately by both can still surface after fusion.
+        let candidate_limit = limit * 3;
+
+        let keyword_results = self.search(query, candidate_limit, None, None).await?;
+        let semantic_results = self.search_semantic(query, candidate_limit).await?;
+
+        let mut scores: HashMap<Uuid, f32> = HashMap::new();
+        let mut commands: HashMap<Uuid, Command> = HashMap::new();
+
+        for (rank, cmd) in keyword_results.into_iter().enumerate() {
+            *scores.entry(cmd.id).or_insert(0.0) += weights.keyword / (RRF_K + rank as f32 + 1.0);
+            commands.entry(cmd.id).or_insert(cmd);
+        }
+
+        for (rank, cmd) in semantic_results.into_iter().enumerate() {
+            *scores.entry(cmd.id).or_insert(0.0) += weights.semantic / (RRF_K + rank as f32 + 1.0);
+            commands.entry(cmd.id).or_insert(cmd);
+        }
+
+        let mut ranked: Vec<(Uuid, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(limit);
+
+        Ok(ranked.into_iter()
+            .filter_map(|(id, _)| commands.remove(&id))
+            .collect())
+    }
+
     async fn count(&self) -> Result<usize> {
-        let result = sqlx::query(r#"SELECT COUNT(*) as count FROM commands"#)
-            .fetch_one(&self.pool)
-            .await?;
-        
+        let sql = format!(
+            "SELECT COUNT(*) as count FROM commands{}",
+            self.scope_sql(false)
+        );
+
+        let mut query = sqlx::query(&sql);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        let result = query.fetch_one(&self.pool).await?;
+
         Ok(result.get::<i64, _>("count") as usize)
     }
 }
 
 impl SqliteCommandRepository {
     fn row_to_command(&self, row: sqlx::sqlite::SqliteRow) -> Result<Command> {
-        let arguments_json: String = row.get("arguments");
         let environment_json: String = row.get("environment");
         let timestamp_str: String = row.get("timestamp");
-        
-        let arguments: Vec<String> = serde_json::from_str(&arguments_json)?;
+
+        // Vault-encrypted fields decrypt transparently while unlocked;
+        // while locked they surface as placeholders rather than failing
+        // every read in the result set
+        let raw = self
+            .expose_field(row.get("raw"))?
+            .unwrap_or_else(|| "[vault locked]".to_string());
+        let arguments: Vec<String> = match self.expose_field(row.get("arguments"))? {
+            Some(json) => serde_json::from_str(&json)?,
+            None => Vec::new(),
+        };
+
         let environment: HashMap<String, String> = serde_json::from_str(&environment_json)?;
+        let extras: HashMap<String, serde_json::Value> =
+            serde_json::from_str(&row.get::<String, _>("extras"))?;
         let timestamp = DateTime::parse_from_rfc3339(&timestamp_str)?.with_timezone(&Utc);
-        
+
         Ok(Command {
             id: Uuid::parse_str(row.get("id"))?,
-            raw: row.get("raw"),
+            raw,
             parsed_command: row.get("parsed_command"),
             arguments,
             working_directory: row.get("working_directory"),
@@ -300,6 +833,9 @@ impl SqliteCommandRepository {
             duration_ms: row.get::<i64, _>("duration_ms") as u64,
             timestamp,
             session_id: row.get("session_id"),
+            source: row.get("source"),
+            extras,
+            logical_clock: row.get("logical_clock"),
             metadata: CommandMetadata {
                 shell: row.get("shell"),
                 user: row.get("user"),
@@ -309,7 +845,7 @@ impl SqliteCommandRepository {
             },
         })
     }
-    
+
     fn rows_to_commands(&self, rows: Vec<sqlx::sqlite::SqliteRow>) -> Result<Vec<Command>> {
         rows.into_iter()
             .map(|row| self.row_to_command(row))
@@ -321,56 +857,324 @@ impl SqliteCommandRepository {
 mod tests {
     use super::*;
     use sqlx::sqlite::SqlitePoolOptions;
-    use tempfile::NamedTempFile;
 
     async fn setup_test_db() -> Result<SqlitePool> {
-        let temp_file = NamedTempFile::new()?;
-        let db_path = temp_file.path().to_str().unwrap();
+        crate::sqlite::vector_index::register_vec_extension();
         let pool = SqlitePoolOptions::new()
-            .connect(&format!("sqlite:{}", db_path))
+            .max_connections(1)
+            .connect("sqlite::memory:")
             .await?;
-        
+
         // Create schema
-        sqlx::query(include_str!("../../../../migrations/001_initial.sql"))
-            .execute(&pool)
-            .await?;
-        
+        crate::sqlite::connection::apply_migrations(&pool).await?;
+
         Ok(pool)
     }
 
-    #[tokio::test]
-    async fn test_save_and_find_command() -> Result<()> {
-        let pool = setup_test_db().await?;
-        let repo = SqliteCommandRepository::new(pool);
-        
-        let command = Command {
+    fn test_command(raw: &str, user: &str) -> Command {
+        Command {
             id: Uuid::new_v4(),
-            raw: "git status".to_string(),
-            parsed_command: "git".to_string(),
-            arguments: vec!["status".to_string()],
+            raw: raw.to_string(),
+            parsed_command: raw.split_whitespace().next().unwrap().to_string(),
+            arguments: raw.split_whitespace().skip(1).map(String::from).collect(),
             working_directory: "/home/test".to_string(),
             exit_code: 0,
             duration_ms: 100,
             timestamp: Utc::now(),
             session_id: "test-session".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
             metadata: CommandMetadata {
                 shell: "bash".to_string(),
-                user: "testuser".to_string(),
+                user: user.to_string(),
                 hostname: "testhost".to_string(),
                 terminal: "xterm".to_string(),
                 environment: HashMap::new(),
             },
-        };
-        
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_and_find_command() -> Result<()> {
+        let pool = setup_test_db().await?;
+        let repo = SqliteCommandRepository::new(pool);
+
+        let command = test_command("git status", "testuser");
+
         repo.save(&command).await?;
-        
+
         let found = repo.find_by_id(&command.id).await?;
         assert!(found.is_some());
-        
+
         let found_cmd = found.unwrap();
         assert_eq!(found_cmd.raw, command.raw);
         assert_eq!(found_cmd.exit_code, command.exit_code);
-        
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_merges_rankings() -> Result<()> {
+        let pool = setup_test_db().await?;
+        let repo = SqliteCommandRepository::new(pool);
+
+        for raw in ["git push origin main", "git status", "docker push registry"] {
+            repo.save(&test_command(raw, "testuser")).await?;
+        }
+
+        let results = repo.search_hybrid("git push", 10, &HybridWeights::default()).await?;
+
+        // Matched by both keywords, so it must fuse to the top.
+        assert_eq!(results[0].raw, "git push origin main");
+        assert!(results.iter().any(|cmd| cmd.raw == "docker push registry"));
+
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_fulltext_search_supports_phrases_and_prefixes() -> Result<()> {
+        let pool = setup_test_db().await?;
+        let repo = SqliteCommandRepository::new(pool);
+
+        for raw in [
+            "docker run -it ubuntu bash",
+            "docker build -t app .",
+            "kubectl run debug --image=busybox",
+        ] {
+            repo.save(&test_command(raw, "testuser")).await?;
+        }
+
+        // Phrase query only matches the adjacent words
+        let results = repo.search_fulltext("\"docker run\"", 10).await?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].raw, "docker run -it ubuntu bash");
+
+        // Prefix query matches both docker commands
+        let results = repo.search_fulltext("dock*", 10).await?;
+        assert_eq!(results.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fulltext_index_tracks_deletes() -> Result<()> {
+        let pool = setup_test_db().await?;
+        let repo = SqliteCommandRepository::new(pool);
+
+        let command = test_command("terraform apply", "testuser");
+        repo.save(&command).await?;
+        assert_eq!(repo.search_fulltext("terraform", 10).await?.len(), 1);
+
+        repo.delete_by_id(&command.id).await?;
+        assert!(repo.search_fulltext("terraform", 10).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vector_search_ranks_by_cosine_similarity() -> Result<()> {
+        use termbrain_core::embedding::encode_vector;
+
+        let pool = setup_test_db().await?;
+        let repo = SqliteCommandRepository::new(pool.clone());
+
+        let close = test_command("kubectl get pods", "testuser");
+        let far = test_command("cargo fmt", "testuser");
+        repo.save(&close).await?;
+        repo.save(&far).await?;
+
+        for (cmd, vector) in [(&close, vec![1.0_f32, 0.1]), (&far, vec![0.0_f32, 1.0])] {
+            sqlx::query(
+                "INSERT INTO embeddings (command_id, vector, model, created_at) VALUES (?1, ?2, 'test', ?3)",
+            )
+            .bind(cmd.id.to_string())
+            .bind(encode_vector(vector.as_slice()))
+            .bind(Utc::now().to_rfc3339())
+            .execute(&pool)
+            .await?;
+        }
+
+        let results = repo.search_by_vector(&[1.0, 0.0], 10).await?;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].raw, "kubectl get pods");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_vec_index_matches_flat_ranking() -> Result<()> {
+        use termbrain_core::embedding::encode_vector;
+
+        let pool = setup_test_db().await?;
+        let flat = SqliteCommandRepository::new(pool.clone());
+        let vec_indexed =
+            SqliteCommandRepository::new(pool.clone()).with_vector_index(VectorIndex::SqliteVec);
+
+        let close = test_command("kubectl get pods", "testuser");
+        let far = test_command("cargo fmt", "testuser");
+        flat.save(&close).await?;
+        flat.save(&far).await?;
+
+        for (cmd, vector) in [(&close, vec![1.0_f32, 0.1]), (&far, vec![0.0_f32, 1.0])] {
+            sqlx::query(
+                "INSERT INTO embeddings (command_id, vector, model, created_at) VALUES (?1, ?2, 'test', ?3)",
+            )
+            .bind(cmd.id.to_string())
+            .bind(encode_vector(vector.as_slice()))
+            .bind(Utc::now().to_rfc3339())
+            .execute(&pool)
+            .await?;
+        }
+
+        let in_sql = vec_indexed.search_by_vector(&[1.0, 0.0], 10).await?;
+        let in_rust = flat.search_by_vector(&[1.0, 0.0], 10).await?;
+        assert_eq!(
+            in_sql.iter().map(|c| &c.raw).collect::<Vec<_>>(),
+            in_rust.iter().map(|c| &c.raw).collect::<Vec<_>>(),
+        );
+        assert_eq!(in_sql[0].raw, "kubectl get pods");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_scope_hides_other_users() -> Result<()> {
+        let pool = setup_test_db().await?;
+
+        let unscoped = SqliteCommandRepository::new(pool.clone());
+        unscoped.save(&test_command("git status", "alice")).await?;
+        unscoped.save(&test_command("git push", "bob")).await?;
+
+        let alice = SqliteCommandRepository::with_scope(
+            pool.clone(),
+            UserScope::User("alice".to_string()),
+        );
+
+        assert_eq!(alice.count().await?, 1);
+        let recent = alice.find_recent(10).await?;
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].metadata.user, "alice");
+
+        // Scoped search can't see bob's rows either
+        let results = alice.search("git", 10, None, None).await?;
+        assert_eq!(results.len(), 1);
+
+        // Team scope sees everything
+        let team = SqliteCommandRepository::with_scope(pool, UserScope::Team);
+        assert_eq!(team.count().await?, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_by_extra_matches_stored_values() -> Result<()> {
+        let pool = setup_test_db().await?;
+        let repo = SqliteCommandRepository::new(pool);
+
+        let mut tagged = test_command("kubectl get pods", "testuser");
+        tagged.extras.insert(
+            "k8s_context".to_string(),
+            serde_json::Value::String("staging".to_string()),
+        );
+        repo.save(&tagged).await?;
+        repo.save(&test_command("git status", "testuser")).await?;
+
+        let results = repo.find_by_extra("k8s_context", "staging").await?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].raw, "kubectl get pods");
+        assert_eq!(
+            results[0].extras["k8s_context"],
+            serde_json::Value::String("staging".to_string())
+        );
+
+        assert!(repo.find_by_extra("k8s_context", "prod").await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_neighbors_stays_in_session_order() -> Result<()> {
+        let pool = setup_test_db().await?;
+        let repo = SqliteCommandRepository::new(pool);
+
+        let base = Utc::now();
+        let mut anchor = None;
+        for (offset, raw) in ["cd app", "cargo build", "cargo test", "git push"]
+            .iter()
+            .enumerate()
+        {
+            let mut command = test_command(raw, "testuser");
+            command.timestamp = base + chrono::Duration::seconds(offset as i64);
+            if *raw == "cargo build" {
+                anchor = Some(command.clone());
+            }
+            repo.save(&command).await?;
+        }
+        // A different session must not leak in
+        let mut other = test_command("rm -rf /tmp/x", "testuser");
+        other.session_id = "other-session".to_string();
+        other.timestamp = base + chrono::Duration::seconds(1);
+        repo.save(&other).await?;
+
+        let neighbors = repo.find_neighbors(&anchor.unwrap(), 3, 1).await?;
+        let raws: Vec<&str> = neighbors.iter().map(|c| c.raw.as_str()).collect();
+        assert_eq!(raws, vec!["cd app", "cargo test"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duration_percentile_needs_history() -> Result<()> {
+        let pool = setup_test_db().await?;
+        let repo = SqliteCommandRepository::new(pool);
+
+        // Too few runs: no percentile
+        repo.save(&test_command("cargo build", "testuser")).await?;
+        assert!(repo.duration_percentile("cargo", 100).await?.is_none());
+
+        for duration in [100, 200, 300, 400, 500] {
+            let mut command = test_command("cargo build", "testuser");
+            command.duration_ms = duration;
+            repo.save(&command).await?;
+        }
+
+        let slow = repo.duration_percentile("cargo", 500).await?.unwrap();
+        let fast = repo.duration_percentile("cargo", 100).await?.unwrap();
+        assert_eq!(slow, 100.0);
+        assert!(fast < slow);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vault_cipher_roundtrips_and_locks() -> Result<()> {
+        use crate::vault::{VaultCipher, VaultKey};
+
+        let pool = setup_test_db().await?;
+        let key = VaultKey::from_bytes([9u8; 32]);
+
+        let unlocked = SqliteCommandRepository::new(pool.clone())
+            .with_cipher(VaultCipher::new(&key));
+        let command = test_command("curl -u admin:hunter2 internal.example", "testuser");
+        unlocked.save(&command).await?;
+
+        // Ciphertext on disk, plaintext through the unlocked repository
+        let stored: String = sqlx::query_scalar("SELECT raw FROM commands")
+            .fetch_one(&pool)
+            .await?;
+        assert!(crate::vault::is_encrypted(&stored));
+
+        let found = unlocked.find_by_id(&command.id).await?.unwrap();
+        assert_eq!(found.raw, command.raw);
+        assert_eq!(found.arguments, command.arguments);
+
+        // A locked repository sees placeholders, not secrets
+        let locked = SqliteCommandRepository::new(pool);
+        let found = locked.find_by_id(&command.id).await?.unwrap();
+        assert_eq!(found.raw, "[vault locked]");
+        assert!(found.arguments.is_empty());
+
+        Ok(())
+    }
+}
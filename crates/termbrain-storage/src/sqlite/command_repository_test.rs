@@ -50,6 +50,9 @@ mod tests {
             duration_ms: 100,
             timestamp: Utc::now(),
             session_id: "test-session".to_string(),
+            source: "shell-hook".to_string(),
+            extras: std::collections::HashMap::new(),
+            logical_clock: None,
             metadata: CommandMetadata {
                 shell: "bash".to_string(),
                 user: "testuser".to_string(),
@@ -103,6 +106,9 @@ mod tests {
                 duration_ms: 100,
                 timestamp: Utc::now(),
                 session_id: format!("session-{}", i),
+                source: "shell-hook".to_string(),
+                extras: std::collections::HashMap::new(),
+                logical_clock: None,
                 metadata: CommandMetadata {
                     shell: "bash".to_string(),
                     user: "testuser".to_string(),
@@ -142,6 +148,9 @@ mod tests {
                 duration_ms: 100,
                 timestamp: Utc::now(),
                 session_id: "test-session".to_string(),
+                source: "shell-hook".to_string(),
+                extras: std::collections::HashMap::new(),
+                logical_clock: None,
                 metadata: CommandMetadata {
                     shell: "bash".to_string(),
                     user: "testuser".to_string(),
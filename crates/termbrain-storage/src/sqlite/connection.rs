@@ -1,48 +1,258 @@
 //! SQLite connection pool management
 
 use anyhow::Result;
-use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    SqlitePool,
+};
 use std::path::Path;
 
+/// Schema migrations applied in order by `ensure_schema`. Every
+/// statement must be idempotent (IF NOT EXISTS) since the full list is
+/// re-run on startup.
+pub(crate) const MIGRATIONS: &[&str] = &[
+    include_str!("../../../../migrations/001_initial.sql"),
+    include_str!("../../../../migrations/002_analytics_views.sql"),
+    include_str!("../../../../migrations/003_tool_versions.sql"),
+    include_str!("../../../../migrations/004_env_changes.sql"),
+    include_str!("../../../../migrations/005_diagnoses.sql"),
+    include_str!("../../../../migrations/006_fts.sql"),
+    include_str!("../../../../migrations/007_intentions.sql"),
+    include_str!("../../../../migrations/008_issue_refs.sql"),
+    include_str!("../../../../migrations/009_embeddings.sql"),
+    include_str!("../../../../migrations/010_activity_refs.sql"),
+    include_str!("../../../../migrations/011_command_source.sql"),
+    include_str!("../../../../migrations/012_extras.sql"),
+    include_str!("../../../../migrations/013_vault.sql"),
+    include_str!("../../../../migrations/014_project_rollups.sql"),
+    include_str!("../../../../migrations/015_experiments.sql"),
+    include_str!("../../../../migrations/016_workflow_runs.sql"),
+    include_str!("../../../../migrations/017_workflow_run_context.sql"),
+    include_str!("../../../../migrations/018_import_ledger.sql"),
+    include_str!("../../../../migrations/019_logical_clock.sql"),
+    include_str!("../../../../migrations/020_integrity_chain.sql"),
+    include_str!("../../../../migrations/021_projects.sql"),
+    include_str!("../../../../migrations/022_session_intentions.sql"),
+    include_str!("../../../../migrations/023_undo_snapshots.sql"),
+    include_str!("../../../../migrations/024_session_extras.sql"),
+    include_str!("../../../../migrations/025_session_parent.sql"),
+    include_str!("../../../../migrations/026_devices.sql"),
+    include_str!("../../../../migrations/027_classification_overrides.sql"),
+    include_str!("../../../../migrations/028_command_transitions.sql"),
+    include_str!("../../../../migrations/029_project_identity.sql"),
+];
+
+/// Applies all schema migrations to a pool, converting a database
+/// written by the pre-workspace binary first if one is found.
+pub(crate) async fn apply_migrations(pool: &SqlitePool) -> Result<()> {
+    let legacy = stash_legacy_commands(pool).await?;
+    for migration in MIGRATIONS {
+        if let Err(e) = sqlx::query(migration).execute(pool).await {
+            // SQLite has no ADD COLUMN IF NOT EXISTS; a duplicate column
+            // error just means that migration already ran
+            if e.to_string().contains("duplicate column name") {
+                continue;
+            }
+            return Err(e.into());
+        }
+    }
+    if legacy {
+        convert_legacy_commands(pool).await?;
+    }
+    Ok(())
+}
+
+/// Detects the legacy `commands` layout (the pre-workspace binary's
+/// `command`/`directory`/`semantic_type` columns) and renames the table
+/// aside so the migrations can create the current schema. Returns true
+/// when a legacy table was stashed.
+async fn stash_legacy_commands(pool: &SqlitePool) -> Result<bool> {
+    use sqlx::Row;
+
+    let columns = sqlx::query("PRAGMA table_info(commands)")
+        .fetch_all(pool)
+        .await?;
+    if columns.is_empty() {
+        return Ok(false);
+    }
+    let names: Vec<String> = columns.iter().map(|row| row.get("name")).collect();
+    if names.iter().any(|name| name == "raw") || !names.iter().any(|name| name == "command") {
+        return Ok(false);
+    }
+
+    // The legacy indexes keep their names across the rename and would
+    // shadow the new schema's CREATE INDEX IF NOT EXISTS
+    sqlx::query("DROP INDEX IF EXISTS idx_commands_timestamp")
+        .execute(pool)
+        .await?;
+    sqlx::query("DROP INDEX IF EXISTS idx_commands_directory")
+        .execute(pool)
+        .await?;
+    sqlx::query("ALTER TABLE commands RENAME TO commands_legacy")
+        .execute(pool)
+        .await?;
+    Ok(true)
+}
+
+/// Copies stashed legacy rows into the current schema. Unix-second
+/// timestamps become RFC 3339, the leading word is split out as
+/// `parsed_command`, and `semantic_type` survives in extras. The
+/// stashed table is kept as a backup; re-running is a no-op thanks to
+/// INSERT OR IGNORE.
+async fn convert_legacy_commands(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        "INSERT OR IGNORE INTO commands
+            (id, raw, parsed_command, arguments, working_directory, exit_code,
+             duration_ms, timestamp, session_id, shell, user, hostname,
+             terminal, environment, source, extras)
+         SELECT id,
+                command,
+                CASE WHEN instr(command, ' ') > 0
+                     THEN substr(command, 1, instr(command, ' ') - 1)
+                     ELSE command END,
+                '[]',
+                directory,
+                exit_code,
+                COALESCE(duration_ms, 0),
+                strftime('%Y-%m-%dT%H:%M:%S+00:00', timestamp, 'unixepoch'),
+                COALESCE(session_id, 'legacy'),
+                '', '', '', '', '{}',
+                'import:legacy',
+                CASE WHEN semantic_type IS NULL THEN '{}'
+                     ELSE json_object('semantic_type', semantic_type) END
+         FROM commands_legacy",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
 pub struct SqliteStorage {
     pool: SqlitePool,
 }
 
 impl SqliteStorage {
     pub async fn new(database_path: impl AsRef<Path>) -> Result<Self> {
-        let database_url = format!("sqlite:{}", database_path.as_ref().display());
-        
+        super::vector_index::register_vec_extension();
+        let database_path = database_path.as_ref();
+        // First run on a fresh machine: the data directory and database
+        // file don't exist yet, and SQLite won't create either on its own
+        if let Some(parent) = database_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
         let pool = SqlitePoolOptions::new()
             .max_connections(5)
-            .connect(&database_url)
+            .connect_with(options)
             .await?;
-        
+
         Ok(Self { pool })
     }
     
     pub async fn in_memory() -> Result<Self> {
+        super::vector_index::register_vec_extension();
         let pool = SqlitePoolOptions::new()
             .max_connections(1)
             .connect("sqlite::memory:")
             .await?;
         
         // Create schema
-        sqlx::query(include_str!("../../../../migrations/001_initial.sql"))
-            .execute(&pool)
-            .await?;
-        
+        apply_migrations(&pool).await?;
+
         Ok(Self { pool })
     }
-    
+
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
-    
+
     pub async fn ensure_schema(&self) -> Result<()> {
-        // Create schema directly without migrations
-        sqlx::query(include_str!("../../../../migrations/001_initial.sql"))
-            .execute(&self.pool)
-            .await?;
-        Ok(())
+        apply_migrations(&self.pool).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::Row;
+
+    #[tokio::test]
+    async fn test_new_creates_missing_database_and_directory() {
+        // Cold start: nothing under HOME exists yet, and the first
+        // command (tb teach, a shell-hook record) must not fail with
+        // "unable to open database file"
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("termbrain.db");
+
+        let storage = SqliteStorage::new(&path).await.unwrap();
+        storage.ensure_schema().await.unwrap();
+        assert!(path.exists());
+
+        let count: i64 = sqlx::query("SELECT COUNT(*) AS n FROM commands")
+            .fetch_one(storage.pool())
+            .await
+            .unwrap()
+            .get("n");
+        assert_eq!(count, 0);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_legacy_schema_converted() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        // The pre-workspace binary's layout
+        sqlx::query(
+            "CREATE TABLE commands (
+                id TEXT PRIMARY KEY,
+                timestamp INTEGER NOT NULL,
+                command TEXT NOT NULL,
+                directory TEXT NOT NULL,
+                exit_code INTEGER NOT NULL,
+                duration_ms INTEGER,
+                session_id TEXT,
+                semantic_type TEXT
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO commands VALUES
+                ('a', 1700000000, 'git push origin main', '/home/me', 0, 1200, 's1', 'version_control'),
+                ('b', 1700000100, 'ls', '/tmp', 0, NULL, NULL, NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        apply_migrations(&pool).await.unwrap();
+
+        let row = sqlx::query("SELECT * FROM commands WHERE id = 'a'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("raw"), "git push origin main");
+        assert_eq!(row.get::<String, _>("parsed_command"), "git");
+        assert_eq!(row.get::<String, _>("source"), "import:legacy");
+        assert!(row.get::<String, _>("timestamp").starts_with("2023-11-14T"));
+        let extras: serde_json::Value =
+            serde_json::from_str(&row.get::<String, _>("extras")).unwrap();
+        assert_eq!(extras["semantic_type"], "version_control");
+
+        // Idempotent: a second run neither errors nor duplicates
+        apply_migrations(&pool).await.unwrap();
+        let count: i64 = sqlx::query("SELECT COUNT(*) AS n FROM commands")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .get("n");
+        assert_eq!(count, 2);
+    }
+}
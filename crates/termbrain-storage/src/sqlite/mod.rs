@@ -2,6 +2,18 @@
 
 mod command_repository;
 mod connection;
+mod project_repository;
+mod session_repository;
+mod stats_repository;
+mod vector_index;
+mod workflow_repository;
+mod workflow_run_repository;
 
 pub use connection::SqliteStorage;
-pub use command_repository::SqliteCommandRepository;
\ No newline at end of file
+pub use command_repository::SqliteCommandRepository;
+pub use project_repository::SqliteProjectRepository;
+pub use session_repository::SqliteSessionRepository;
+pub use stats_repository::SqliteStatsRepository;
+pub use vector_index::VectorIndex;
+pub use workflow_repository::SqliteWorkflowRepository;
+pub use workflow_run_repository::SqliteWorkflowRunRepository;
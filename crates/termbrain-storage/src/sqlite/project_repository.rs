@@ -0,0 +1,134 @@
+//! SQLite-backed project storage
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use termbrain_core::domain::entities::Project;
+use termbrain_core::domain::repositories::ProjectRepository;
+use uuid::Uuid;
+
+pub struct SqliteProjectRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteProjectRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_project(row: &sqlx::sqlite::SqliteRow) -> Result<Project> {
+        Ok(Project {
+            id: Uuid::parse_str(&row.get::<String, _>("id"))?,
+            name: row.get("name"),
+            root: row.get("root"),
+            marker: row.get("marker"),
+            remote: row.get("remote"),
+            tags: serde_json::from_str(&row.get::<String, _>("tags")).unwrap_or_default(),
+            first_seen: DateTime::parse_from_rfc3339(&row.get::<String, _>("first_seen"))?
+                .with_timezone(&Utc),
+            last_seen: DateTime::parse_from_rfc3339(&row.get::<String, _>("last_seen"))?
+                .with_timezone(&Utc),
+        })
+    }
+}
+
+#[async_trait]
+impl ProjectRepository for SqliteProjectRepository {
+    async fn upsert(&self, project: &Project) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO projects (id, name, root, marker, remote, tags, first_seen, last_seen)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(root) DO UPDATE SET
+                 name = excluded.name,
+                 marker = excluded.marker,
+                 remote = COALESCE(excluded.remote, projects.remote),
+                 last_seen = excluded.last_seen",
+        )
+        .bind(project.id.to_string())
+        .bind(&project.name)
+        .bind(&project.root)
+        .bind(&project.marker)
+        .bind(&project.remote)
+        .bind(serde_json::to_string(&project.tags)?)
+        .bind(project.first_seen.to_rfc3339())
+        .bind(project.last_seen.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_all(&self) -> Result<Vec<Project>> {
+        let rows = sqlx::query("SELECT * FROM projects ORDER BY last_seen DESC")
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(Self::row_to_project).collect()
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Vec<Project>> {
+        let rows = sqlx::query("SELECT * FROM projects WHERE name = ? ORDER BY last_seen DESC")
+            .bind(name)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(Self::row_to_project).collect()
+    }
+
+    async fn find_by_remote(&self, remote: &str) -> Result<Vec<Project>> {
+        let rows = sqlx::query("SELECT * FROM projects WHERE remote = ? ORDER BY last_seen DESC")
+            .bind(remote)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(Self::row_to_project).collect()
+    }
+
+    async fn update(&self, project: &Project) -> Result<()> {
+        sqlx::query("UPDATE projects SET name = ?2, remote = ?3, tags = ?4 WHERE id = ?1")
+            .bind(project.id.to_string())
+            .bind(&project.name)
+            .bind(&project.remote)
+            .bind(serde_json::to_string(&project.tags)?)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::SqliteStorage;
+
+    #[tokio::test]
+    async fn test_upsert_is_keyed_by_root() {
+        let storage = SqliteStorage::in_memory().await.unwrap();
+        let repo = SqliteProjectRepository::new(storage.pool().clone());
+
+        let mut project = Project {
+            id: Uuid::new_v4(),
+            name: "termbrain".to_string(),
+            root: "/home/dev/termbrain".to_string(),
+            marker: ".git".to_string(),
+            remote: None,
+            tags: vec!["oss".to_string()],
+            first_seen: Utc::now(),
+            last_seen: Utc::now(),
+        };
+        repo.upsert(&project).await.unwrap();
+
+        // Seeing the same root again refreshes instead of duplicating,
+        // and auto-discovery never clobbers manual tags or a known remote
+        project.id = Uuid::new_v4();
+        project.marker = "Cargo.toml".to_string();
+        project.tags = Vec::new();
+        repo.upsert(&project).await.unwrap();
+
+        let all = repo.find_all().await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].marker, "Cargo.toml");
+        assert_eq!(all[0].tags, vec!["oss".to_string()]);
+
+        let named = repo.find_by_name("termbrain").await.unwrap();
+        assert_eq!(named.len(), 1);
+        assert!(repo.find_by_name("other").await.unwrap().is_empty());
+    }
+}
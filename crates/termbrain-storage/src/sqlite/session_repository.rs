@@ -0,0 +1,145 @@
+//! SQLite-backed session storage
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use termbrain_core::domain::entities::Session;
+use termbrain_core::domain::repositories::SessionRepository;
+
+pub struct SqliteSessionRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteSessionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_session(row: &sqlx::sqlite::SqliteRow) -> Result<Session> {
+        let end_time: Option<String> = row.get("end_time");
+        Ok(Session {
+            id: row.get("id"),
+            parent_id: row.get("parent_id"),
+            start_time: DateTime::parse_from_rfc3339(&row.get::<String, _>("start_time"))?
+                .with_timezone(&Utc),
+            end_time: end_time
+                .map(|t| DateTime::parse_from_rfc3339(&t).map(|t| t.with_timezone(&Utc)))
+                .transpose()?,
+            shell: row.get("shell"),
+            terminal: row.get("terminal"),
+            extras: serde_json::from_str(&row.get::<String, _>("extras")).unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SqliteSessionRepository {
+    async fn create(&self, session: &Session) -> Result<()> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO sessions (id, parent_id, start_time, end_time, shell, terminal, extras)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .bind(&session.id)
+        .bind(&session.parent_id)
+        .bind(session.start_time.to_rfc3339())
+        .bind(session.end_time.map(|t| t.to_rfc3339()))
+        .bind(&session.shell)
+        .bind(&session.terminal)
+        .bind(serde_json::to_string(&session.extras)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn update(&self, session: &Session) -> Result<()> {
+        sqlx::query(
+            "UPDATE sessions SET parent_id = ?2, end_time = ?3, shell = ?4, terminal = ?5, extras = ?6
+             WHERE id = ?1",
+        )
+        .bind(&session.id)
+        .bind(&session.parent_id)
+        .bind(session.end_time.map(|t| t.to_rfc3339()))
+        .bind(&session.shell)
+        .bind(&session.terminal)
+        .bind(serde_json::to_string(&session.extras)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Session>> {
+        let row = sqlx::query("SELECT * FROM sessions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(Self::row_to_session).transpose()
+    }
+
+    async fn find_active(&self) -> Result<Option<Session>> {
+        let row =
+            sqlx::query("SELECT * FROM sessions WHERE end_time IS NULL ORDER BY start_time DESC LIMIT 1")
+                .fetch_optional(&self.pool)
+                .await?;
+        row.as_ref().map(Self::row_to_session).transpose()
+    }
+
+    async fn find_recent(&self, limit: usize) -> Result<Vec<Session>> {
+        let rows = sqlx::query("SELECT * FROM sessions ORDER BY start_time DESC LIMIT ?")
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(Self::row_to_session).collect()
+    }
+
+    async fn close(&self, id: &str) -> Result<()> {
+        sqlx::query("UPDATE sessions SET end_time = ? WHERE id = ? AND end_time IS NULL")
+            .bind(Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::SqliteStorage;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let storage = SqliteStorage::in_memory().await.unwrap();
+        let repo = SqliteSessionRepository::new(storage.pool().clone());
+
+        let session = Session {
+            id: "1756500000-1234".to_string(),
+            parent_id: None,
+            start_time: Utc::now(),
+            end_time: None,
+            shell: "zsh".to_string(),
+            terminal: "iTerm.app".to_string(),
+            extras: HashMap::new(),
+        };
+        repo.create(&session).await.unwrap();
+
+        // Re-running the start hook in the same tab must not reset the session
+        repo.create(&session).await.unwrap();
+        assert_eq!(repo.find_recent(10).await.unwrap().len(), 1);
+        assert_eq!(
+            repo.find_active().await.unwrap().unwrap().id,
+            session.id
+        );
+
+        repo.close(&session.id).await.unwrap();
+        assert!(repo.find_active().await.unwrap().is_none());
+        assert!(repo
+            .find_by_id(&session.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .end_time
+            .is_some());
+    }
+}
@@ -0,0 +1,246 @@
+//! SQLite implementation of StatsRepository
+//!
+//! Every method aggregates in SQL — nothing materializes Command rows,
+//! so `tb stats` stays fast no matter how large the history grows.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use termbrain_core::domain::{CommandUsage, DailyActivity, PeriodStats, StatsRepository, UserScope};
+
+/// `strftime('%w')` weekday numbers, Sunday first.
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+pub struct SqliteStatsRepository {
+    pool: SqlitePool,
+    scope: UserScope,
+}
+
+impl SqliteStatsRepository {
+    /// Creates an unscoped repository that aggregates every user's rows.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            scope: UserScope::Team,
+        }
+    }
+
+    /// Restricts every aggregate to one user's rows.
+    pub fn with_scope(pool: SqlitePool, scope: UserScope) -> Self {
+        Self { pool, scope }
+    }
+
+    /// SQL fragment continuing a WHERE clause with the user scope.
+    fn scope_sql(&self) -> &'static str {
+        match self.scope {
+            UserScope::Team => "",
+            UserScope::User(_) => " AND user = ? ",
+        }
+    }
+
+    fn scoped_user(&self) -> Option<&str> {
+        match &self.scope {
+            UserScope::Team => None,
+            UserScope::User(user) => Some(user),
+        }
+    }
+
+    /// The given percentile of `duration_ms` over timed commands in the
+    /// window, via ORDER BY + OFFSET (SQLite has no percentile
+    /// function).
+    async fn duration_percentile(
+        &self,
+        start: &str,
+        end: &str,
+        percent: u32,
+    ) -> Result<Option<u64>> {
+        let window = format!(
+            "FROM commands WHERE timestamp >= ? AND timestamp <= ? AND duration_ms > 0{}",
+            self.scope_sql()
+        );
+        let sql = format!(
+            "SELECT duration_ms {window} ORDER BY duration_ms
+             LIMIT 1 OFFSET (SELECT (COUNT(*) - 1) * {percent} / 100 {window})"
+        );
+
+        let mut query = sqlx::query_scalar::<_, i64>(&sql)
+            .bind(start)
+            .bind(end);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+        query = query.bind(start).bind(end);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        Ok(query
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|duration| duration as u64))
+    }
+}
+
+#[async_trait]
+impl StatsRepository for SqliteStatsRepository {
+    async fn period_stats(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<PeriodStats> {
+        let (start, end) = (start.to_rfc3339(), end.to_rfc3339());
+
+        let sql = format!(
+            "SELECT COUNT(*) AS total,
+                    COUNT(DISTINCT parsed_command) AS unique_commands,
+                    COALESCE(AVG(CASE WHEN exit_code = 0 THEN 100.0 ELSE 0.0 END), 0.0) AS success_rate
+             FROM commands WHERE timestamp >= ? AND timestamp <= ?{}",
+            self.scope_sql()
+        );
+        let mut query = sqlx::query(&sql).bind(&start).bind(&end);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+        let row = query.fetch_one(&self.pool).await?;
+        let total: i64 = row.get("total");
+        let unique_commands: i64 = row.get("unique_commands");
+        let success_rate: f64 = row.get("success_rate");
+
+        if total == 0 {
+            return Ok(PeriodStats::default());
+        }
+
+        let hours_sql = format!(
+            "SELECT CAST(strftime('%H', timestamp) AS INTEGER) AS hour, COUNT(*) AS n
+             FROM commands WHERE timestamp >= ? AND timestamp <= ?{}
+             GROUP BY hour ORDER BY n DESC LIMIT 3",
+            self.scope_sql()
+        );
+        let mut query = sqlx::query(&hours_sql).bind(&start).bind(&end);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+        let busiest_hours = query
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| (row.get::<i64, _>("hour") as u8, row.get::<i64, _>("n") as u64))
+            .collect();
+
+        let days_sql = format!(
+            "SELECT CAST(strftime('%w', timestamp) AS INTEGER) AS weekday, COUNT(*) AS n
+             FROM commands WHERE timestamp >= ? AND timestamp <= ?{}
+             GROUP BY weekday ORDER BY n DESC LIMIT 3",
+            self.scope_sql()
+        );
+        let mut query = sqlx::query(&days_sql).bind(&start).bind(&end);
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+        let busiest_days = query
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| {
+                let weekday = row.get::<i64, _>("weekday") as usize;
+                (
+                    WEEKDAYS.get(weekday).copied().unwrap_or("?").to_string(),
+                    row.get::<i64, _>("n") as u64,
+                )
+            })
+            .collect();
+
+        Ok(PeriodStats {
+            total_commands: total as u64,
+            unique_commands: unique_commands as u64,
+            success_rate,
+            duration_p50_ms: self.duration_percentile(&start, &end, 50).await?,
+            duration_p95_ms: self.duration_percentile(&start, &end, 95).await?,
+            busiest_hours,
+            busiest_days,
+        })
+    }
+
+    async fn top_commands(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<CommandUsage>> {
+        let sql = format!(
+            "SELECT parsed_command, COUNT(*) AS n, SUM(CASE WHEN exit_code = 0 THEN 1 ELSE 0 END) AS ok
+             FROM commands WHERE timestamp >= ? AND timestamp <= ?{}
+             GROUP BY parsed_command ORDER BY n DESC LIMIT ?",
+            self.scope_sql()
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+        query = query.bind(limit as i64);
+
+        Ok(query
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| CommandUsage {
+                parsed_command: row.get("parsed_command"),
+                count: row.get::<i64, _>("n") as u64,
+                successes: row.get::<i64, _>("ok") as u64,
+            })
+            .collect())
+    }
+
+    async fn per_user(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<(String, u64)>> {
+        let sql = format!(
+            "SELECT user, COUNT(*) AS n
+             FROM commands WHERE timestamp >= ? AND timestamp <= ?{}
+             GROUP BY user ORDER BY n DESC",
+            self.scope_sql()
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        Ok(query
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| (row.get("user"), row.get::<i64, _>("n") as u64))
+            .collect())
+    }
+
+    async fn daily_activity(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<DailyActivity>> {
+        let sql = format!(
+            "SELECT date(timestamp) AS day,
+                    COUNT(*) AS n,
+                    SUM(CASE WHEN exit_code != 0 THEN 1 ELSE 0 END) AS failed
+             FROM commands WHERE timestamp >= ? AND timestamp <= ?{}
+             GROUP BY day ORDER BY day",
+            self.scope_sql()
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        if let Some(user) = self.scoped_user() {
+            query = query.bind(user);
+        }
+
+        Ok(query
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| DailyActivity {
+                date: row.get("day"),
+                commands: row.get::<i64, _>("n") as u64,
+                failures: row.get::<i64, _>("failed") as u64,
+            })
+            .collect())
+    }
+}
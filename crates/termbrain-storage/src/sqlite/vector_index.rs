@@ -0,0 +1,46 @@
+//! Vector index selection for semantic search
+//!
+//! `search_by_vector` can rank stored embeddings two ways: decode the
+//! BLOBs and compute cosine similarity in Rust (`Flat`), or push the
+//! distance computation into SQLite via the sqlite-vec extension
+//! (`SqliteVec`), which stays fast well past 100k commands.
+
+use std::sync::Once;
+
+/// How stored embeddings are ranked against a query vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VectorIndex {
+    /// Exact cosine similarity computed in Rust over all vectors.
+    #[default]
+    Flat,
+    /// `vec_distance_cosine` evaluated inside SQLite by sqlite-vec.
+    SqliteVec,
+}
+
+impl VectorIndex {
+    /// Parses a config value; unknown names fall back to `Flat`.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "sqlite-vec" => Self::SqliteVec,
+            _ => Self::Flat,
+        }
+    }
+}
+
+static REGISTER_VEC: Once = Once::new();
+
+/// Registers sqlite-vec as an auto extension so every new connection
+/// gets the `vec_*` SQL functions. Must run before a pool connects;
+/// safe to call repeatedly.
+pub(crate) fn register_vec_extension() {
+    REGISTER_VEC.call_once(|| unsafe {
+        libsqlite3_sys::sqlite3_auto_extension(Some(std::mem::transmute::<
+            *const (),
+            unsafe extern "C" fn(
+                *mut libsqlite3_sys::sqlite3,
+                *mut *mut std::os::raw::c_char,
+                *const libsqlite3_sys::sqlite3_api_routines,
+            ) -> std::os::raw::c_int,
+        >(sqlite_vec::sqlite3_vec_init as *const ())));
+    });
+}
@@ -0,0 +1,150 @@
+//! SQLite-backed workflow storage
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use termbrain_core::domain::entities::{Workflow, WorkflowStep};
+use termbrain_core::domain::repositories::WorkflowRepository;
+use uuid::Uuid;
+
+pub struct SqliteWorkflowRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteWorkflowRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_workflow(row: &sqlx::sqlite::SqliteRow) -> Result<Workflow> {
+        let steps: Vec<WorkflowStep> = serde_json::from_str(&row.get::<String, _>("steps"))?;
+        Ok(Workflow {
+            id: Uuid::parse_str(&row.get::<String, _>("id"))?,
+            name: row.get("name"),
+            description: row.get("description"),
+            steps,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?
+                .with_timezone(&Utc),
+            usage_count: row.get::<i64, _>("usage_count") as u32,
+        })
+    }
+}
+
+#[async_trait]
+impl WorkflowRepository for SqliteWorkflowRepository {
+    async fn save(&self, workflow: &Workflow) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO workflows (id, name, description, steps, created_at, updated_at, usage_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .bind(workflow.id.to_string())
+        .bind(&workflow.name)
+        .bind(&workflow.description)
+        .bind(serde_json::to_string(&workflow.steps)?)
+        .bind(workflow.created_at.to_rfc3339())
+        .bind(workflow.updated_at.to_rfc3339())
+        .bind(workflow.usage_count as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn update(&self, workflow: &Workflow) -> Result<()> {
+        sqlx::query(
+            "UPDATE workflows SET name = ?2, description = ?3, steps = ?4, updated_at = ?5, usage_count = ?6
+             WHERE id = ?1",
+        )
+        .bind(workflow.id.to_string())
+        .bind(&workflow.name)
+        .bind(&workflow.description)
+        .bind(serde_json::to_string(&workflow.steps)?)
+        .bind(Utc::now().to_rfc3339())
+        .bind(workflow.usage_count as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &Uuid) -> Result<Option<Workflow>> {
+        let row = sqlx::query("SELECT * FROM workflows WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(Self::row_to_workflow).transpose()
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Workflow>> {
+        let row = sqlx::query("SELECT * FROM workflows WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(Self::row_to_workflow).transpose()
+    }
+
+    async fn find_all(&self) -> Result<Vec<Workflow>> {
+        let rows = sqlx::query("SELECT * FROM workflows ORDER BY usage_count DESC, name")
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(Self::row_to_workflow).collect()
+    }
+
+    async fn increment_usage(&self, id: &Uuid) -> Result<()> {
+        sqlx::query("UPDATE workflows SET usage_count = usage_count + 1, updated_at = ?2 WHERE id = ?1")
+            .bind(id.to_string())
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_by_id(&self, id: &Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM workflows WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::SqliteStorage;
+
+    #[tokio::test]
+    async fn test_save_and_find_roundtrip() {
+        let storage = SqliteStorage::in_memory().await.unwrap();
+        let repo = SqliteWorkflowRepository::new(storage.pool().clone());
+
+        let workflow = Workflow {
+            id: Uuid::new_v4(),
+            name: "deploy".to_string(),
+            description: "test".to_string(),
+            steps: vec![WorkflowStep {
+                order: 1,
+                command: "cargo build".to_string(),
+                description: None,
+                expected_outcome: None,
+                env: Default::default(),
+                when: Default::default(),
+                confirm: false,
+                timeout_secs: None,
+            }],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            usage_count: 0,
+        };
+        repo.save(&workflow).await.unwrap();
+
+        let loaded = repo.find_by_name("deploy").await.unwrap().unwrap();
+        assert_eq!(loaded.id, workflow.id);
+        assert_eq!(loaded.steps.len(), 1);
+
+        repo.increment_usage(&workflow.id).await.unwrap();
+        let loaded = repo.find_by_id(&workflow.id).await.unwrap().unwrap();
+        assert_eq!(loaded.usage_count, 1);
+    }
+}
@@ -0,0 +1,150 @@
+//! SQLite-backed workflow run history
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use termbrain_core::domain::entities::WorkflowRunStep;
+use termbrain_core::domain::repositories::{WorkflowRunRepository, WorkflowRunStats};
+use uuid::Uuid;
+
+pub struct SqliteWorkflowRunRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteWorkflowRunRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_step(row: &sqlx::sqlite::SqliteRow) -> Result<WorkflowRunStep> {
+        Ok(WorkflowRunStep {
+            id: Uuid::parse_str(&row.get::<String, _>("id"))?,
+            run_id: Uuid::parse_str(&row.get::<String, _>("run_id"))?,
+            workflow_name: row.get("workflow_name"),
+            step_order: row.get::<i64, _>("step_order") as u32,
+            command: row.get("command"),
+            status: row.get("status"),
+            exit_code: row.get("exit_code"),
+            duration_ms: row.get("duration_ms"),
+            user: row.get("user"),
+            session_id: row.get("session_id"),
+            started_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("started_at"))?
+                .with_timezone(&Utc),
+        })
+    }
+}
+
+#[async_trait]
+impl WorkflowRunRepository for SqliteWorkflowRunRepository {
+    async fn record_step(&self, step: &WorkflowRunStep) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO workflow_runs (id, run_id, workflow_name, step_order, command, status, exit_code, duration_ms, user, session_id, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )
+        .bind(step.id.to_string())
+        .bind(step.run_id.to_string())
+        .bind(&step.workflow_name)
+        .bind(step.step_order as i64)
+        .bind(&step.command)
+        .bind(&step.status)
+        .bind(step.exit_code)
+        .bind(step.duration_ms)
+        .bind(&step.user)
+        .bind(&step.session_id)
+        .bind(step.started_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn history(&self, workflow: &str, runs: usize) -> Result<Vec<WorkflowRunStep>> {
+        // Pick the newest N runs first, then pull their steps in order
+        let rows = sqlx::query(
+            "SELECT * FROM workflow_runs
+             WHERE run_id IN (
+                 SELECT run_id FROM workflow_runs WHERE workflow_name = ?1
+                 GROUP BY run_id ORDER BY MIN(started_at) DESC LIMIT ?2
+             )
+             ORDER BY started_at DESC, step_order ASC",
+        )
+        .bind(workflow)
+        .bind(runs as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_step).collect()
+    }
+
+    async fn stats(&self) -> Result<HashMap<String, WorkflowRunStats>> {
+        let rows = sqlx::query(
+            "SELECT workflow_name,
+                    COUNT(DISTINCT run_id) AS runs,
+                    COUNT(DISTINCT CASE WHEN status IN ('failed', 'timeout') THEN run_id END) AS failed_runs
+             FROM workflow_runs
+             GROUP BY workflow_name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let runs = row.get::<i64, _>("runs") as usize;
+                let failed = row.get::<i64, _>("failed_runs") as usize;
+                (
+                    row.get::<String, _>("workflow_name"),
+                    WorkflowRunStats {
+                        runs,
+                        successful: runs - failed,
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::SqliteStorage;
+
+    fn step(workflow: &str, run_id: Uuid, order: u32, status: &str) -> WorkflowRunStep {
+        WorkflowRunStep {
+            id: Uuid::new_v4(),
+            run_id,
+            workflow_name: workflow.to_string(),
+            step_order: order,
+            command: format!("step-{}", order),
+            status: status.to_string(),
+            exit_code: if status == "ok" { Some(0) } else { Some(1) },
+            duration_ms: Some(10),
+            user: Some("test".to_string()),
+            session_id: None,
+            started_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_and_stats_group_by_run() {
+        let storage = SqliteStorage::in_memory().await.unwrap();
+        let repo = SqliteWorkflowRunRepository::new(storage.pool().clone());
+
+        let good_run = Uuid::new_v4();
+        repo.record_step(&step("deploy", good_run, 1, "ok")).await.unwrap();
+        repo.record_step(&step("deploy", good_run, 2, "ok")).await.unwrap();
+
+        let bad_run = Uuid::new_v4();
+        repo.record_step(&step("deploy", bad_run, 1, "failed")).await.unwrap();
+
+        let history = repo.history("deploy", 10).await.unwrap();
+        assert_eq!(history.len(), 3);
+
+        let stats = repo.stats().await.unwrap();
+        let deploy = &stats["deploy"];
+        assert_eq!(deploy.runs, 2);
+        assert_eq!(deploy.successful, 1);
+        assert_eq!(deploy.success_rate(), Some(0.5));
+    }
+}
@@ -0,0 +1,146 @@
+//! At-rest encryption for sensitive command fields
+//!
+//! Opt-in application-level encryption: with the vault unlocked, the
+//! repository encrypts `raw` and `arguments` with XChaCha20-Poly1305
+//! before they hit SQLite and decrypts them transparently on read. The
+//! key is derived from a passphrase with PBKDF2-HMAC-SHA256; the salt
+//! and a verifier live in the `vault_meta` table so a wrong passphrase
+//! is rejected up front. Rows written before the vault existed stay
+//! plaintext and keep working — the ciphertext prefix tells them apart.
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::XChaCha20Poly1305;
+use sha2::Sha256;
+
+/// Marks an encrypted field value; everything after it is
+/// base64(nonce || ciphertext). Versioned so the format can evolve.
+const CIPHERTEXT_PREFIX: &str = "enc:v1:";
+
+/// PBKDF2-HMAC-SHA256 rounds for passphrase key derivation.
+pub const KDF_ITERATIONS: u32 = 600_000;
+
+/// Known plaintext encrypted into `vault_meta` at setup; decrypting it
+/// successfully proves a candidate key is correct.
+const VERIFIER_PLAINTEXT: &str = "termbrain-vault-v1";
+
+/// A 256-bit vault key.
+#[derive(Clone)]
+pub struct VaultKey([u8; 32]);
+
+impl VaultKey {
+    /// Derives a key from a passphrase and per-database salt.
+    pub fn derive(passphrase: &str, salt: &[u8]) -> Self {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, KDF_ITERATIONS, &mut key);
+        Self(key)
+    }
+
+    /// Wraps an already-derived key (e.g. read back from the unlock
+    /// cache between invocations).
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// Encrypts and decrypts individual field values.
+#[derive(Clone)]
+pub struct VaultCipher {
+    cipher: XChaCha20Poly1305,
+}
+
+impl VaultCipher {
+    pub fn new(key: &VaultKey) -> Self {
+        Self {
+            cipher: XChaCha20Poly1305::new(key.as_bytes().into()),
+        }
+    }
+
+    /// Encrypts a field value with a fresh random nonce.
+    pub fn encrypt_field(&self, plaintext: &str) -> Result<String> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", CIPHERTEXT_PREFIX, BASE64.encode(payload)))
+    }
+
+    /// Decrypts a field previously produced by `encrypt_field`. Fails
+    /// on a wrong key or tampered ciphertext; plaintext fields should
+    /// be screened with `is_encrypted` first.
+    pub fn decrypt_field(&self, stored: &str) -> Result<String> {
+        let encoded = stored
+            .strip_prefix(CIPHERTEXT_PREFIX)
+            .ok_or_else(|| anyhow::anyhow!("Not an encrypted field"))?;
+        let payload = BASE64.decode(encoded)?;
+        if payload.len() < 24 {
+            anyhow::bail!("Encrypted field is truncated");
+        }
+        let (nonce, ciphertext) = payload.split_at(24);
+        let plaintext = self
+            .cipher
+            .decrypt(nonce.into(), ciphertext)
+            .map_err(|_| anyhow::anyhow!("Decryption failed: wrong key or corrupted data"))?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+    /// Produces the verifier stored in `vault_meta` at setup.
+    pub fn make_verifier(&self) -> Result<String> {
+        self.encrypt_field(VERIFIER_PLAINTEXT)
+    }
+
+    /// Checks this cipher's key against a stored verifier.
+    pub fn check_verifier(&self, verifier: &str) -> bool {
+        self.decrypt_field(verifier)
+            .map(|p| p == VERIFIER_PLAINTEXT)
+            .unwrap_or(false)
+    }
+}
+
+/// True if a stored field value is vault ciphertext.
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(CIPHERTEXT_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip_and_prefix() -> Result<()> {
+        let key = VaultKey::from_bytes([7u8; 32]);
+        let cipher = VaultCipher::new(&key);
+
+        let stored = cipher.encrypt_field("curl -H 'Authorization: Bearer sk-123'")?;
+        assert!(is_encrypted(&stored));
+        assert!(!is_encrypted("git status"));
+        assert_eq!(
+            cipher.decrypt_field(&stored)?,
+            "curl -H 'Authorization: Bearer sk-123'"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrong_key_fails_verifier() -> Result<()> {
+        let cipher = VaultCipher::new(&VaultKey::from_bytes([1u8; 32]));
+        let verifier = cipher.make_verifier()?;
+        assert!(cipher.check_verifier(&verifier));
+
+        let wrong = VaultCipher::new(&VaultKey::from_bytes([2u8; 32]));
+        assert!(!wrong.check_verifier(&verifier));
+        assert!(wrong.decrypt_field(&verifier).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+-- Derived analytics views powering stats/trends without repeated
+-- full-table scans in application code. Plain views are cheap to keep
+-- current; materialization can come later if profiles show the need.
+
+-- Commands per day with success/failure split
+CREATE VIEW IF NOT EXISTS daily_command_counts AS
+SELECT substr(timestamp, 1, 10) AS day,
+       COUNT(*) AS total,
+       SUM(CASE WHEN exit_code = 0 THEN 1 ELSE 0 END) AS succeeded,
+       SUM(CASE WHEN exit_code != 0 THEN 1 ELSE 0 END) AS failed
+FROM commands
+GROUP BY day;
+
+-- Failure rate per tool
+CREATE VIEW IF NOT EXISTS per_tool_failure_rates AS
+SELECT parsed_command AS tool,
+       COUNT(*) AS total,
+       SUM(CASE WHEN exit_code != 0 THEN 1 ELSE 0 END) AS failures,
+       ROUND(100.0 * SUM(CASE WHEN exit_code != 0 THEN 1 ELSE 0 END) / COUNT(*), 1) AS failure_rate
+FROM commands
+GROUP BY parsed_command;
+
+-- Activity rollup per working directory
+CREATE VIEW IF NOT EXISTS directory_rollups AS
+SELECT working_directory AS directory,
+       COUNT(*) AS total,
+       COUNT(DISTINCT session_id) AS sessions,
+       MIN(timestamp) AS first_seen,
+       MAX(timestamp) AS last_seen,
+       SUM(CASE WHEN exit_code != 0 THEN 1 ELSE 0 END) AS failures
+FROM commands
+GROUP BY working_directory;
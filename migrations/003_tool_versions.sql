@@ -0,0 +1,11 @@
+-- Periodic snapshots of tool versions per host, used to correlate
+-- failures with upgrades
+CREATE TABLE IF NOT EXISTS tool_versions (
+    id TEXT PRIMARY KEY,
+    tool TEXT NOT NULL,
+    version TEXT NOT NULL,
+    hostname TEXT NOT NULL,
+    recorded_at TEXT NOT NULL -- ISO 8601 string
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_versions_tool ON tool_versions(tool, hostname, recorded_at);
@@ -0,0 +1,12 @@
+-- Environment change events (package installs, dotfile edits) used to
+-- correlate failures with changes to the machine
+CREATE TABLE IF NOT EXISTS env_changes (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL, -- package-install | package-remove | dotfile-edit
+    description TEXT NOT NULL,
+    command_id TEXT NOT NULL,
+    hostname TEXT NOT NULL,
+    occurred_at TEXT NOT NULL -- ISO 8601 string
+);
+
+CREATE INDEX IF NOT EXISTS idx_env_changes_occurred ON env_changes(occurred_at);
@@ -0,0 +1,11 @@
+-- Structured diagnoses produced by `tb diagnose`, kept with the
+-- command they explain for later reference.
+CREATE TABLE IF NOT EXISTS diagnoses (
+    id TEXT PRIMARY KEY,
+    command_id TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    layers TEXT NOT NULL, -- JSON object, one entry per diagnosis layer
+    created_at TEXT NOT NULL -- ISO 8601 string
+);
+
+CREATE INDEX IF NOT EXISTS idx_diagnoses_command ON diagnoses(command_id);
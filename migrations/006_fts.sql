@@ -0,0 +1,23 @@
+-- FTS5 full-text index over command text, kept in sync with the
+-- commands table via triggers. The rebuild at the end is idempotent and
+-- backfills histories that predate this migration.
+CREATE VIRTUAL TABLE IF NOT EXISTS commands_fts USING fts5(
+    raw,
+    content='commands',
+    content_rowid='rowid'
+);
+
+CREATE TRIGGER IF NOT EXISTS commands_fts_insert AFTER INSERT ON commands BEGIN
+    INSERT INTO commands_fts(rowid, raw) VALUES (new.rowid, new.raw);
+END;
+
+CREATE TRIGGER IF NOT EXISTS commands_fts_delete AFTER DELETE ON commands BEGIN
+    INSERT INTO commands_fts(commands_fts, rowid, raw) VALUES ('delete', old.rowid, old.raw);
+END;
+
+CREATE TRIGGER IF NOT EXISTS commands_fts_update AFTER UPDATE OF raw ON commands BEGIN
+    INSERT INTO commands_fts(commands_fts, rowid, raw) VALUES ('delete', old.rowid, old.raw);
+    INSERT INTO commands_fts(rowid, raw) VALUES (new.rowid, new.raw);
+END;
+
+INSERT INTO commands_fts(commands_fts) VALUES ('rebuild');
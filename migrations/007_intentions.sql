@@ -0,0 +1,15 @@
+-- Intentions: what the user is currently trying to do, set manually
+-- via `tb intend` or automatically from branch checkouts.
+CREATE TABLE IF NOT EXISTS intentions (
+    id TEXT PRIMARY KEY,
+    text TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT 'manual', -- 'manual' | 'branch'
+    branch TEXT,
+    directory TEXT,
+    status TEXT NOT NULL DEFAULT 'active', -- 'active' | 'closed'
+    created_at TEXT NOT NULL, -- ISO 8601 string
+    closed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_intentions_status ON intentions(status);
+CREATE INDEX IF NOT EXISTS idx_intentions_branch ON intentions(branch);
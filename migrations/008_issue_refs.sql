@@ -0,0 +1,12 @@
+-- Structured references from terminal activity to issue tracker keys
+-- (Jira ABC-123 / GitHub #456), extracted on record.
+CREATE TABLE IF NOT EXISTS issue_refs (
+    id TEXT PRIMARY KEY,
+    issue_key TEXT NOT NULL,
+    kind TEXT NOT NULL, -- 'commit' | 'branch' | 'command' | 'intention'
+    source_id TEXT NOT NULL, -- command or intention id
+    context TEXT NOT NULL, -- the text the key was found in
+    occurred_at TEXT NOT NULL -- ISO 8601 string
+);
+
+CREATE INDEX IF NOT EXISTS idx_issue_refs_key ON issue_refs(issue_key);
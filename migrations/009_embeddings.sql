@@ -0,0 +1,8 @@
+-- Persisted command embeddings so semantic search only embeds the
+-- query. Vectors are little-endian f32 BLOBs.
+CREATE TABLE IF NOT EXISTS embeddings (
+    command_id TEXT PRIMARY KEY,
+    vector BLOB NOT NULL,
+    model TEXT NOT NULL,
+    created_at TEXT NOT NULL -- ISO 8601 string
+);
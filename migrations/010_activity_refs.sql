@@ -0,0 +1,14 @@
+-- Git/GitHub activity references attached to recorded commands.
+-- A row links a command to the commit it pushed or the PR it touched,
+-- so terminal activity can be reported per PR or per commit.
+CREATE TABLE IF NOT EXISTS activity_refs (
+    id TEXT PRIMARY KEY,
+    command_id TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('commit', 'pr')),
+    reference TEXT NOT NULL,
+    branch TEXT,
+    occurred_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_refs_reference ON activity_refs(reference);
+CREATE INDEX IF NOT EXISTS idx_activity_refs_command ON activity_refs(command_id);
@@ -0,0 +1,5 @@
+-- Provenance of each command record: how it entered the database
+-- (shell-hook, wrap, import:zsh, ingest:ci, api, synthetic).
+ALTER TABLE commands ADD COLUMN source TEXT NOT NULL DEFAULT 'shell-hook';
+
+CREATE INDEX IF NOT EXISTS idx_commands_source ON commands(source);
@@ -0,0 +1,4 @@
+-- Arbitrary structured data stashed by plugins and integrations
+-- (e.g. k8s context, PR number), stored as a JSON object.
+ALTER TABLE commands ADD COLUMN extras TEXT NOT NULL DEFAULT '{}';
+ALTER TABLE sessions ADD COLUMN extras TEXT NOT NULL DEFAULT '{}';
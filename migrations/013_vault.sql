@@ -0,0 +1,11 @@
+-- At-rest encryption metadata: the key-derivation salt and a verifier
+-- ciphertext used to reject wrong passphrases. Single row; the key
+-- itself is never stored here.
+CREATE TABLE IF NOT EXISTS vault_meta (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    salt BLOB NOT NULL,
+    verifier TEXT NOT NULL,
+    kdf TEXT NOT NULL DEFAULT 'pbkdf2-sha256',
+    iterations INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
@@ -0,0 +1,14 @@
+-- Activity rollup per project identity (normalized git remote URL,
+-- recorded under the "project" extras key). Unlike directory_rollups,
+-- clones of the same repository in different paths aggregate together.
+CREATE VIEW IF NOT EXISTS project_rollups AS
+SELECT json_extract(extras, '$.project') AS project,
+       COUNT(*) AS total,
+       COUNT(DISTINCT working_directory) AS directories,
+       COUNT(DISTINCT session_id) AS sessions,
+       MIN(timestamp) AS first_seen,
+       MAX(timestamp) AS last_seen,
+       SUM(CASE WHEN exit_code != 0 THEN 1 ELSE 0 END) AS failures
+FROM commands
+WHERE json_extract(extras, '$.project') IS NOT NULL
+GROUP BY project;
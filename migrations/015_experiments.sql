@@ -0,0 +1,8 @@
+-- Self-run A/B experiments: a named time window whose usage is
+-- compared against the equally long window before it.
+CREATE TABLE IF NOT EXISTS experiments (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    started_at TEXT NOT NULL,
+    ended_at TEXT -- NULL while the experiment is running
+);
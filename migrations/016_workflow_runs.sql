@@ -0,0 +1,15 @@
+-- Per-step results of workflow executions, so runs can be audited
+-- ("which step failed last Tuesday?") and step reliability tracked.
+CREATE TABLE IF NOT EXISTS workflow_runs (
+    id TEXT PRIMARY KEY,
+    run_id TEXT NOT NULL,        -- groups the steps of one invocation
+    workflow_name TEXT NOT NULL,
+    step_order INTEGER NOT NULL,
+    command TEXT NOT NULL,       -- after variable substitution
+    status TEXT NOT NULL,        -- 'ok', 'failed', 'skipped', 'timeout'
+    exit_code INTEGER,           -- NULL for skipped/timed-out steps
+    duration_ms INTEGER,
+    started_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_runs_name ON workflow_runs(workflow_name, started_at);
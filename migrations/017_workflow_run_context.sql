@@ -0,0 +1,3 @@
+-- Who and which session triggered a workflow run.
+ALTER TABLE workflow_runs ADD COLUMN user TEXT;
+ALTER TABLE workflow_runs ADD COLUMN session_id TEXT;
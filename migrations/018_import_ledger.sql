@@ -0,0 +1,8 @@
+-- Fingerprints of everything ever imported, per source, so re-running
+-- an import is a no-op even after the original rows were pruned.
+CREATE TABLE IF NOT EXISTS import_ledger (
+    source TEXT NOT NULL,       -- e.g. 'import:zsh', 'import:atuin'
+    fingerprint TEXT NOT NULL,  -- sha256 of command text + timestamp + host
+    imported_at TEXT NOT NULL,
+    PRIMARY KEY (source, fingerprint)
+);
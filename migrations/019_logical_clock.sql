@@ -0,0 +1,3 @@
+-- Hybrid logical clock stamp: zero-padded wall-ms + counter + device,
+-- so string order is a stable total order across merged devices.
+ALTER TABLE commands ADD COLUMN logical_clock TEXT;
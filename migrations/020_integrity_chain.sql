@@ -0,0 +1,9 @@
+-- Tamper-evident hash chain over recorded commands (tb verify). Each
+-- link's hash covers the previous link's hash plus the record content,
+-- so rewriting any past record breaks every hash after it.
+CREATE TABLE IF NOT EXISTS integrity_chain (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    command_id TEXT NOT NULL,
+    hash TEXT NOT NULL,          -- sha256(previous hash + record content)
+    created_at TEXT NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Projects detected from build/vcs marker files (Cargo.toml,
+-- package.json, .git), keyed by root path. Commands are grouped into a
+-- project by working_directory prefix against these roots.
+CREATE TABLE IF NOT EXISTS projects (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,          -- root directory basename
+    root TEXT NOT NULL UNIQUE,
+    marker TEXT NOT NULL,        -- which marker file identified the root
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_projects_name ON projects(name);
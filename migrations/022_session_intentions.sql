@@ -0,0 +1,9 @@
+-- Intentions inherited by AI sessions: when an agent session starts
+-- while a human intention is active, the link is recorded here so the
+-- session carries the "why" it was invoked.
+CREATE TABLE IF NOT EXISTS session_intentions (
+    session_id TEXT NOT NULL,
+    intention_id TEXT NOT NULL,
+    attached_at TEXT NOT NULL,
+    PRIMARY KEY (session_id, intention_id)
+);
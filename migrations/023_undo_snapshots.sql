@@ -0,0 +1,14 @@
+-- Pre-flight working-tree snapshots taken just before history-rewriting
+-- or file-deleting commands (tb snapshot, surfaced by tb undo-hint).
+-- The stash ref comes from `git stash create`, which stores a dangling
+-- commit without touching the working tree or the stash list.
+CREATE TABLE IF NOT EXISTS undo_snapshots (
+    id TEXT PRIMARY KEY,
+    command TEXT NOT NULL,       -- the risky command about to run
+    directory TEXT NOT NULL,
+    branch TEXT,
+    head_commit TEXT,            -- short hash of HEAD at snapshot time
+    stash_ref TEXT,              -- NULL when the tree was clean
+    files_changed INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
@@ -0,0 +1,3 @@
+-- Structured data stashed on sessions by plugins and integrations,
+-- mirroring the extras column on commands.
+ALTER TABLE sessions ADD COLUMN extras TEXT NOT NULL DEFAULT '{}';
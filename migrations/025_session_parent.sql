@@ -0,0 +1,3 @@
+-- Parent link for nested sessions: a zsh started inside bash, or a tmux
+-- pane, records which session spawned it so views can stitch the tree.
+ALTER TABLE sessions ADD COLUMN parent_id TEXT;
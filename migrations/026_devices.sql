@@ -0,0 +1,8 @@
+-- Device registry: friendly labels and lifecycle for the hostnames seen
+-- in history. Hosts appear automatically via commands.hostname; this
+-- table only stores what the user says about them.
+CREATE TABLE IF NOT EXISTS devices (
+    hostname TEXT PRIMARY KEY,
+    label TEXT,
+    retired INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,11 @@
+-- User corrections to classifier output, recorded by `tb reclassify`.
+-- Classifiers look here before falling back to their heuristics: a
+-- semantic-type correction is keyed by program, a project correction by
+-- directory, an agent correction by session.
+CREATE TABLE IF NOT EXISTS classification_overrides (
+    kind TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    corrected_at TEXT NOT NULL,
+    PRIMARY KEY (kind, key)
+);
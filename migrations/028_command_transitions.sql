@@ -0,0 +1,14 @@
+-- Markov transition counts over recorded history, updated
+-- incrementally on every record. context is the previous one or two
+-- parsed commands ("git" or "cd git"); scope is 'global' or a working
+-- directory, so predictions can prefer directory-local habits.
+CREATE TABLE IF NOT EXISTS command_transitions (
+    scope TEXT NOT NULL,
+    context TEXT NOT NULL,
+    next TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL, -- ISO 8601 string
+    PRIMARY KEY (scope, context, next)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transitions_context ON command_transitions(context);
@@ -0,0 +1,7 @@
+-- First-class project identity: the normalized git remote links clones
+-- of one repository across root paths, and tags support manual
+-- curation via `tb projects tag`.
+ALTER TABLE projects ADD COLUMN remote TEXT;
+ALTER TABLE projects ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+
+CREATE INDEX IF NOT EXISTS idx_projects_remote ON projects(remote);
@@ -5,7 +5,13 @@
 # Configuration
 export TERMBRAIN_ENABLED="${TERMBRAIN_ENABLED:-1}"
 export TERMBRAIN_AUTO_RECORD="${TERMBRAIN_AUTO_RECORD:-1}"
-export TERMBRAIN_SESSION_ID="${TERMBRAIN_SESSION_ID:-$(date +%s)-$$}"
+# Each shell gets its own session; an inherited session id means this
+# shell is nested (zsh inside bash, a tmux pane) and becomes the parent
+if [[ "$TERMBRAIN_SESSION_PID" != "$$" ]]; then
+    [[ -n "$TERMBRAIN_SESSION_ID" ]] && export TERMBRAIN_PARENT_SESSION_ID="$TERMBRAIN_SESSION_ID"
+    export TERMBRAIN_SESSION_ID="$(date +%s)-$$"
+    export TERMBRAIN_SESSION_PID="$$"
+fi
 
 # Check if termbrain CLI is available
 if ! command -v tb >/dev/null 2>&1; then
@@ -43,10 +49,11 @@ _termbrain_record_command() {
     # Record the command asynchronously
     # Use printf to safely escape the command and directory
     (
-        tb record -- "$last_command" \
+        tb record \
             --exit-code "$exit_code" \
             --directory "$(realpath -- "$PWD" 2>/dev/null || printf '%s' "$PWD")" \
             ${duration_ms:+--duration "$duration_ms"} \
+            -- "$last_command" \
             >/dev/null 2>&1 &
     )
     
@@ -55,7 +62,40 @@ _termbrain_record_command() {
 
 # Function to record command start time
 _termbrain_pre_command() {
-    [[ "$TERMBRAIN_ENABLED" == "1" ]] && export TERMBRAIN_COMMAND_START_TIME=$(date +%s%3N)
+    [[ "$TERMBRAIN_ENABLED" == "1" ]] || return 0
+    # The DEBUG trap also fires for PROMPT_COMMAND; don't let our own
+    # post-command hook clobber the real command's start time
+    case "$BASH_COMMAND" in
+        _termbrain_*) return 0 ;;
+    esac
+    export TERMBRAIN_COMMAND_START_TIME=$(date +%s%3N)
+
+    # Pre-flight snapshot before destructive commands (a no-op unless
+    # undo_snapshots is enabled in the termbrain config)
+    case "$BASH_COMMAND" in
+        rm\ *|git\ rebase*|git\ reset*|git\ clean*|git\ checkout*|git\ stash\ drop*|git\ stash\ pop*)
+            tb snapshot -- "$BASH_COMMAND" >/dev/null 2>&1
+            ;;
+    esac
+
+    # Pre-exec guard (opt-in): returning non-zero here makes extdebug
+    # skip the command. Only top-level commands are checked — with
+    # extdebug the DEBUG trap also fires inside functions, including
+    # our own hooks.
+    if [[ "$TERMBRAIN_GUARD" == "1" && ${#FUNCNAME[@]} -le 1 ]]; then
+        case "$BASH_COMMAND" in
+            tb\ *|termbrain\ *|_termbrain_*) ;;
+            *)
+                tb guard -- "$BASH_COMMAND" </dev/tty || return 1
+                ;;
+        esac
+    fi
+    return 0
+}
+
+# Function to close the session when the shell exits
+_termbrain_session_end() {
+    [[ "$TERMBRAIN_ENABLED" == "1" ]] && tb sessions end >/dev/null 2>&1
 }
 
 # Set up command recording
@@ -63,14 +103,22 @@ if [[ "$TERMBRAIN_ENABLED" == "1" ]]; then
     # Use DEBUG trap for pre-command hook (Bash 4.0+)
     if [[ ${BASH_VERSION%%.*} -ge 4 ]]; then
         trap '_termbrain_pre_command' DEBUG
+        # Dangerous-command guard: with extdebug, a non-zero return from
+        # the DEBUG trap stops the command, so 'tb guard' can block or
+        # ask before anything matching the policy runs
+        [[ "$TERMBRAIN_GUARD" == "1" ]] && shopt -s extdebug
     fi
-    
+
     # Set up post-command hook
     if [[ -n "$TERMBRAIN_ORIGINAL_PROMPT_COMMAND" ]]; then
         PROMPT_COMMAND="_termbrain_record_command; $TERMBRAIN_ORIGINAL_PROMPT_COMMAND"
     else
         PROMPT_COMMAND="_termbrain_record_command"
     fi
+
+    # Session lifecycle: open this tab's session now, close it on exit
+    ( tb sessions start >/dev/null 2>&1 & )
+    trap '_termbrain_session_end' EXIT
 fi
 
 # Utility functions for manual control
@@ -104,4 +152,32 @@ if command -v tb >/dev/null 2>&1 && command -v complete >/dev/null 2>&1; then
     complete -W "record search history statistics patterns workflow export install interactive status help" tb
 fi
 
-echo "TermBrain shell integration loaded (Bash)"
\ No newline at end of file
+echo "TermBrain shell integration loaded (Bash)"
+# Ctrl-R: termbrain-powered history search
+# The picker draws on the terminal and prints the selection to stdout,
+# which replaces the current prompt line.
+_termbrain_ctrl_r() {
+    local selected
+    selected=$(tb search --interactive "$READLINE_LINE" 2>/dev/tty)
+    if [[ -n "$selected" ]]; then
+        READLINE_LINE="$selected"
+        READLINE_POINT=${#READLINE_LINE}
+    fi
+}
+if [[ $- == *i* ]]; then
+    bind -x '"\C-r": _termbrain_ctrl_r'
+fi
+
+# Daily digest (opt-in): set TERMBRAIN_DIGEST=1 to print one precomputed
+# insight per day at shell startup. Only reads the cache written by
+# 'tb digest generate' — no tb invocation, so startup stays fast.
+if [[ -n "$TERMBRAIN_DIGEST" && -f "$HOME/.termbrain/digest.txt" ]]; then
+    _tb_digest_stamp="$HOME/.termbrain/digest-shown"
+    _tb_today=$(date +%Y-%m-%d)
+    if [[ "$(head -n1 "$HOME/.termbrain/digest.txt" 2>/dev/null)" == "$_tb_today" \
+          && "$(cat "$_tb_digest_stamp" 2>/dev/null)" != "$_tb_today" ]]; then
+        tail -n +2 "$HOME/.termbrain/digest.txt"
+        echo "$_tb_today" > "$_tb_digest_stamp"
+    fi
+    unset _tb_digest_stamp _tb_today
+fi
@@ -0,0 +1,22 @@
+# TermBrain autosuggestions for Fish
+# Source this after the main termbrain.fish integration (or drop it in
+# ~/.config/fish/conf.d/).
+#
+# Fish's own autosuggestions can't be fed from an external command, so
+# this binds Ctrl-S to complete the current buffer from your recorded
+# history: the best full command extending what you've typed, ranked by
+# how often it succeeded in the current directory. With an empty buffer
+# it inserts the predicted next command instead (see 'tb predict').
+
+function _termbrain_suggest_inline
+    set -l suggested (tb suggest-inline -- (commandline -b) 2>/dev/null)
+    if test -n "$suggested"
+        commandline -r -- $suggested
+        commandline -f end-of-line
+    end
+    commandline -f repaint
+end
+bind \cs _termbrain_suggest_inline
+
+# Complete 'tb suggest-inline' itself so it stays discoverable
+complete -c tb -n '__fish_use_subcommand' -x -a 'suggest-inline' -d 'Print one history-based completion for the buffer'
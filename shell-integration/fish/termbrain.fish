@@ -4,7 +4,13 @@
 # Configuration
 set -gx TERMBRAIN_ENABLED (test -n "$TERMBRAIN_ENABLED"; and echo $TERMBRAIN_ENABLED; or echo "1")
 set -gx TERMBRAIN_AUTO_RECORD (test -n "$TERMBRAIN_AUTO_RECORD"; and echo $TERMBRAIN_AUTO_RECORD; or echo "1")
-set -gx TERMBRAIN_SESSION_ID (test -n "$TERMBRAIN_SESSION_ID"; and echo $TERMBRAIN_SESSION_ID; or echo (date +%s)"-"(echo %self))
+# Each shell gets its own session; an inherited session id means this
+# shell is nested (fish inside bash, a tmux pane) and becomes the parent
+if test "$TERMBRAIN_SESSION_PID" != "$fish_pid"
+    test -n "$TERMBRAIN_SESSION_ID"; and set -gx TERMBRAIN_PARENT_SESSION_ID $TERMBRAIN_SESSION_ID
+    set -gx TERMBRAIN_SESSION_ID (date +%s)"-"$fish_pid
+    set -gx TERMBRAIN_SESSION_PID $fish_pid
+end
 
 # Check if termbrain CLI is available
 if not command -v tb >/dev/null 2>&1
@@ -40,15 +46,17 @@ function _termbrain_record_command --on-event fish_postexec
     # Use -- to prevent command injection and safely handle paths
     begin
         if test -n "$duration_ms"
-            tb record -- "$last_command" \
+            tb record \
                 --exit-code "$exit_code" \
                 --directory (realpath -- "$PWD" 2>/dev/null; or echo "$PWD") \
                 --duration "$duration_ms" \
+                -- "$last_command" \
                 >/dev/null 2>&1 &
         else
-            tb record -- "$last_command" \
+            tb record \
                 --exit-code "$exit_code" \
                 --directory (realpath -- "$PWD" 2>/dev/null; or echo "$PWD") \
+                -- "$last_command" \
                 >/dev/null 2>&1 &
         end
     end
@@ -58,7 +66,25 @@ end
 
 # Function to record command start time
 function _termbrain_pre_command --on-event fish_preexec
-    test "$TERMBRAIN_ENABLED" = "1"; and set -gx TERMBRAIN_COMMAND_START_TIME (date +%s%3N)
+    test "$TERMBRAIN_ENABLED" = "1"; or return 0
+    set -gx TERMBRAIN_COMMAND_START_TIME (date +%s%3N)
+
+    # Pre-flight snapshot before destructive commands (a no-op unless
+    # undo_snapshots is enabled in the termbrain config)
+    switch "$argv[1]"
+        case 'rm *' 'git rebase*' 'git reset*' 'git clean*' 'git checkout*' 'git stash drop*' 'git stash pop*'
+            tb snapshot -- "$argv[1]" >/dev/null 2>&1
+    end
+end
+
+# Function to close the session when the shell exits
+function _termbrain_session_end --on-event fish_exit
+    test "$TERMBRAIN_ENABLED" = "1"; and tb sessions end >/dev/null 2>&1
+end
+
+# Session lifecycle: open this tab's session now
+if test "$TERMBRAIN_ENABLED" = "1"
+    tb sessions start >/dev/null 2>&1 &
 end
 
 # Utility functions for manual control
@@ -107,4 +133,27 @@ if command -v tb >/dev/null 2>&1
     complete -c tb -l format -x -a 'table json csv plain' -d 'Output format'
 end
 
-echo "TermBrain shell integration loaded (Fish)"
\ No newline at end of file
+echo "TermBrain shell integration loaded (Fish)"
+# Ctrl-R: termbrain-powered history search
+# The picker draws on the terminal and prints the selection to stdout,
+# which replaces the current prompt line.
+function _termbrain_ctrl_r
+    set -l selected (tb search --interactive (commandline) 2>/dev/tty)
+    if test -n "$selected"
+        commandline -r -- $selected
+    end
+    commandline -f repaint
+end
+bind \cr _termbrain_ctrl_r
+
+# Daily digest (opt-in): set TERMBRAIN_DIGEST=1 to print one precomputed
+# insight per day at shell startup. Only reads the cache written by
+# 'tb digest generate' — no tb invocation, so startup stays fast.
+if test -n "$TERMBRAIN_DIGEST"; and test -f "$HOME/.termbrain/digest.txt"
+    set -l _tb_today (date +%Y-%m-%d)
+    if test (head -n1 "$HOME/.termbrain/digest.txt" 2>/dev/null) = "$_tb_today"
+        and test "$(cat "$HOME/.termbrain/digest-shown" 2>/dev/null)" != "$_tb_today"
+        tail -n +2 "$HOME/.termbrain/digest.txt"
+        echo "$_tb_today" > "$HOME/.termbrain/digest-shown"
+    end
+end
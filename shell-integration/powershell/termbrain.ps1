@@ -0,0 +1,93 @@
+# TermBrain shell integration for PowerShell (Windows PowerShell and pwsh)
+# This file should be dot-sourced from your $PROFILE
+
+# Configuration
+if (-not $env:TERMBRAIN_ENABLED) { $env:TERMBRAIN_ENABLED = "1" }
+if (-not $env:TERMBRAIN_AUTO_RECORD) { $env:TERMBRAIN_AUTO_RECORD = "1" }
+# Each shell gets its own session; an inherited session id means this
+# shell is nested (pwsh inside another shell) and becomes the parent
+if ($env:TERMBRAIN_SESSION_PID -ne "$PID") {
+    if ($env:TERMBRAIN_SESSION_ID) { $env:TERMBRAIN_PARENT_SESSION_ID = $env:TERMBRAIN_SESSION_ID }
+    $env:TERMBRAIN_SESSION_ID = "$([DateTimeOffset]::Now.ToUnixTimeSeconds())-$PID"
+    $env:TERMBRAIN_SESSION_PID = "$PID"
+}
+
+# Check if termbrain CLI is available
+if (-not (Get-Command tb -ErrorAction SilentlyContinue)) {
+    Write-Warning "termbrain CLI (tb) not found in PATH"
+    return
+}
+
+# PowerShell has no preexec hook, so the before/after protocol runs from
+# the prompt function: each prompt records the history entry added since
+# the previous one. Get-History carries start and end times, so duration
+# capture needs no separate pre-command timestamp.
+$script:TermbrainLastHistoryId = (Get-History -Count 1).Id
+
+function global:_TermbrainRecordLastCommand {
+    if ($env:TERMBRAIN_ENABLED -ne "1" -or $env:TERMBRAIN_AUTO_RECORD -ne "1") { return }
+
+    $entry = Get-History -Count 1
+    if (-not $entry -or $entry.Id -eq $script:TermbrainLastHistoryId) { return }
+    $script:TermbrainLastHistoryId = $entry.Id
+
+    $command = $entry.CommandLine
+    # Skip empty commands or termbrain commands
+    if (-not $command) { return }
+    if ($command -match '^(tb|termbrain)\s') { return }
+
+    $exitCode = if ($?) { 0 } elseif ($null -ne $LASTEXITCODE) { $LASTEXITCODE } else { 1 }
+    $durationMs = [int64]($entry.EndExecutionTime - $entry.StartExecutionTime).TotalMilliseconds
+
+    # Record the command asynchronously
+    Start-Process -NoNewWindow -FilePath tb -ArgumentList @(
+        "record",
+        "--exit-code", $exitCode,
+        "--directory", (Get-Location).Path,
+        "--duration", $durationMs,
+        "--", $command
+    ) -RedirectStandardOutput ([System.IO.Path]::GetTempFileName()) -ErrorAction SilentlyContinue
+}
+
+# Chain onto the existing prompt so themes keep working
+$script:TermbrainOriginalPrompt = $function:prompt
+function global:prompt {
+    _TermbrainRecordLastCommand
+    & $script:TermbrainOriginalPrompt
+}
+
+# Session lifecycle: open this tab's session now, close it on exit
+if ($env:TERMBRAIN_ENABLED -eq "1") {
+    Start-Process -NoNewWindow -FilePath tb -ArgumentList @("sessions", "start") `
+        -RedirectStandardOutput ([System.IO.Path]::GetTempFileName()) -ErrorAction SilentlyContinue
+    Register-EngineEvent -SourceIdentifier PowerShell.Exiting -SupportEvent -Action {
+        tb sessions end *> $null
+    } | Out-Null
+}
+
+# Utility functions for manual control
+function global:Enable-Termbrain {
+    $env:TERMBRAIN_ENABLED = "1"
+    Write-Host "TermBrain recording enabled"
+}
+
+function global:Disable-Termbrain {
+    $env:TERMBRAIN_ENABLED = "0"
+    Write-Host "TermBrain recording disabled"
+}
+
+function global:Get-TermbrainStatus {
+    Write-Host "TermBrain Status:"
+    Write-Host "  Enabled: $env:TERMBRAIN_ENABLED"
+    Write-Host "  Auto-record: $env:TERMBRAIN_AUTO_RECORD"
+    Write-Host "  Session ID: $env:TERMBRAIN_SESSION_ID"
+    Write-Host "  PWD: $((Get-Location).Path)"
+    Write-Host "  PowerShell Version: $($PSVersionTable.PSVersion)"
+}
+
+# Aliases for convenience
+Set-Alias -Name tbs -Value Get-TermbrainStatus -Scope Global
+Set-Alias -Name tbe -Value Enable-Termbrain -Scope Global
+Set-Alias -Name tbd -Value Disable-Termbrain -Scope Global
+
+Write-Host "TermBrain shell integration loaded (PowerShell)"